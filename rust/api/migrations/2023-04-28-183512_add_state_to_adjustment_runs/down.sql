@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE adjustment_runs DROP COLUMN state;
+DROP TYPE adjustment_run_state;
\ No newline at end of file
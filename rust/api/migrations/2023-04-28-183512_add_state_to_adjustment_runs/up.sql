@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TYPE adjustment_run_state AS ENUM (
+  'running',
+  'paused',
+  'cancelled',
+  'finished',
+  'failed'
+);
+ALTER TABLE adjustment_runs
+ADD COLUMN state adjustment_run_state NOT NULL DEFAULT 'running';
+UPDATE adjustment_runs
+SET state = 'finished'
+WHERE result_individual_id IS NOT NULL;
\ No newline at end of file
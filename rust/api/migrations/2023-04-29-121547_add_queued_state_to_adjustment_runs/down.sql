@@ -0,0 +1,17 @@
+-- This file should undo anything in `up.sql`
+UPDATE adjustment_runs
+SET state = 'cancelled'
+WHERE state = 'queued';
+ALTER TYPE adjustment_run_state RENAME TO adjustment_run_state_old;
+CREATE TYPE adjustment_run_state AS ENUM (
+  'running',
+  'paused',
+  'cancelled',
+  'finished',
+  'failed'
+);
+ALTER TABLE adjustment_runs
+ALTER COLUMN state DROP DEFAULT,
+ALTER COLUMN state TYPE adjustment_run_state USING state::text::adjustment_run_state,
+ALTER COLUMN state SET DEFAULT 'running';
+DROP TYPE adjustment_run_state_old;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TYPE adjustment_run_state ADD VALUE 'queued' BEFORE 'running';
\ No newline at end of file
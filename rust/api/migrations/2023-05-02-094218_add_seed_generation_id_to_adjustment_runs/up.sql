@@ -0,0 +1,6 @@
+-- Your SQL goes here
+ALTER TABLE adjustment_runs
+ADD COLUMN seed_generation_id INTEGER DEFAULT NULL;
+ALTER TABLE adjustment_runs
+ADD FOREIGN KEY (seed_generation_id) REFERENCES adjustment_generations(id) ON DELETE
+SET NULL;
\ No newline at end of file
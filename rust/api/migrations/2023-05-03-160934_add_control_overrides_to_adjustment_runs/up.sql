@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE adjustment_runs
+ADD COLUMN control_overrides JSONB DEFAULT NULL;
\ No newline at end of file
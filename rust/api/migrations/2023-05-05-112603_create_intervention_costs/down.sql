@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE adjustment_individuals DROP COLUMN cost;
+ALTER TABLE adjustment_runs DROP COLUMN minimize_cost;
+ALTER TABLE adjustment_runs DROP COLUMN budget;
+DROP TABLE connection_intervention_costs;
+DROP TABLE concept_intervention_costs;
\ No newline at end of file
@@ -0,0 +1,19 @@
+-- Your SQL goes here
+CREATE TABLE concept_intervention_costs (
+  concept_id INTEGER NOT NULL PRIMARY KEY,
+  FOREIGN KEY (concept_id) REFERENCES concepts(id) ON DELETE CASCADE,
+  linear_cost DOUBLE PRECISION NOT NULL DEFAULT 0,
+  fixed_cost DOUBLE PRECISION NOT NULL DEFAULT 0
+);
+CREATE TABLE connection_intervention_costs (
+  connection_id INTEGER NOT NULL PRIMARY KEY,
+  FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE,
+  linear_cost DOUBLE PRECISION NOT NULL DEFAULT 0,
+  fixed_cost DOUBLE PRECISION NOT NULL DEFAULT 0
+);
+ALTER TABLE adjustment_runs
+ADD COLUMN budget DOUBLE PRECISION DEFAULT NULL;
+ALTER TABLE adjustment_runs
+ADD COLUMN minimize_cost BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE adjustment_individuals
+ADD COLUMN cost DOUBLE PRECISION NOT NULL DEFAULT 0;
\ No newline at end of file
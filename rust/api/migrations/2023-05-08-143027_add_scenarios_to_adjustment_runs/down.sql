@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE adjustment_scenario_errors;
+ALTER TABLE adjustment_runs DROP COLUMN scenario_aggregation_type;
+ALTER TABLE adjustment_runs DROP COLUMN scenarios;
+DROP TYPE scenario_aggregation_type;
\ No newline at end of file
@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TYPE scenario_aggregation_type AS ENUM ('worst_case', 'mean');
+ALTER TABLE adjustment_runs
+ADD COLUMN scenarios JSONB DEFAULT NULL;
+ALTER TABLE adjustment_runs
+ADD COLUMN scenario_aggregation_type scenario_aggregation_type NOT NULL DEFAULT 'worst_case';
+CREATE TABLE adjustment_scenario_errors (
+  id SERIAL PRIMARY KEY,
+  adjustment_individual_id INTEGER NOT NULL,
+  FOREIGN KEY (adjustment_individual_id) REFERENCES adjustment_individuals(id) ON DELETE CASCADE,
+  scenario_name VARCHAR(255) NOT NULL,
+  error DOUBLE PRECISION NOT NULL
+);
\ No newline at end of file
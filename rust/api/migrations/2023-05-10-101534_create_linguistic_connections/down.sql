@@ -0,0 +1,22 @@
+-- This file should undo anything in `up.sql`
+DELETE FROM project_plugins
+WHERE plugin_name = 'Linguistic Connections';
+DELETE FROM plugins
+WHERE name = 'Linguistic Connections';
+DROP TABLE connection_linguistic_terms;
+DROP TABLE linguistic_terms;
+DROP TABLE linguistic_term_sets;
+DROP TYPE defuzzification_method_type;
+DROP TYPE membership_function_type;
+UPDATE projects
+SET connection_value_type = 'from_minus_one_to_one'
+WHERE connection_value_type = 'linguistic';
+ALTER TYPE connection_value_type RENAME TO connection_value_type_old;
+CREATE TYPE connection_value_type AS ENUM ('symbolic', 'from_minus_one_to_one');
+ALTER TABLE plugins
+ALTER COLUMN connection_value_type TYPE connection_value_type USING connection_value_type::text::connection_value_type;
+ALTER TABLE projects
+ALTER COLUMN connection_value_type DROP DEFAULT,
+ALTER COLUMN connection_value_type TYPE connection_value_type USING connection_value_type::text::connection_value_type,
+ALTER COLUMN connection_value_type SET DEFAULT 'symbolic';
+DROP TYPE connection_value_type_old;
\ No newline at end of file
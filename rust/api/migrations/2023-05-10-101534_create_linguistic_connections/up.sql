@@ -0,0 +1,28 @@
+-- Your SQL goes here
+ALTER TYPE connection_value_type ADD VALUE 'linguistic';
+CREATE TYPE membership_function_type AS ENUM ('triangular', 'trapezoidal');
+CREATE TYPE defuzzification_method_type AS ENUM ('centroid', 'mean_of_maxima');
+CREATE TABLE linguistic_term_sets (
+  project_id INTEGER NOT NULL PRIMARY KEY,
+  FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+  defuzzification_method defuzzification_method_type NOT NULL DEFAULT 'centroid'
+);
+CREATE TABLE linguistic_terms (
+  id SERIAL PRIMARY KEY,
+  project_id INTEGER NOT NULL,
+  FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+  name VARCHAR(255) NOT NULL,
+  membership_function_type membership_function_type NOT NULL,
+  a DOUBLE PRECISION NOT NULL,
+  b DOUBLE PRECISION NOT NULL,
+  c DOUBLE PRECISION NOT NULL,
+  d DOUBLE PRECISION NOT NULL,
+  UNIQUE (project_id, name)
+);
+CREATE TABLE connection_linguistic_terms (
+  id SERIAL PRIMARY KEY,
+  connection_id INTEGER NOT NULL,
+  FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE,
+  linguistic_term_id INTEGER NOT NULL,
+  FOREIGN KEY (linguistic_term_id) REFERENCES linguistic_terms(id)
+);
\ No newline at end of file
@@ -0,0 +1,6 @@
+-- Your SQL goes here
+CREATE TABLE connection_lags (
+  connection_id INTEGER NOT NULL PRIMARY KEY,
+  FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE,
+  lag INTEGER NOT NULL DEFAULT 0 CHECK (lag >= 0)
+);
\ No newline at end of file
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE adjustment_connection_function_values;
+DROP TABLE connection_functions;
+DROP TYPE connection_function_type;
\ No newline at end of file
@@ -0,0 +1,23 @@
+-- Your SQL goes here
+CREATE TYPE connection_function_type AS ENUM (
+  'linear',
+  'threshold',
+  'saturation',
+  'piecewise_linear'
+);
+CREATE TABLE connection_functions (
+  connection_id INTEGER NOT NULL PRIMARY KEY,
+  FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE,
+  function_type connection_function_type NOT NULL DEFAULT 'linear',
+  parameter DOUBLE PRECISION NOT NULL DEFAULT 0,
+  points JSONB NOT NULL DEFAULT '[]',
+  is_control BOOLEAN NOT NULL DEFAULT FALSE
+);
+CREATE TABLE adjustment_connection_function_values (
+  id SERIAL PRIMARY KEY,
+  adjustment_individual_id INTEGER NOT NULL,
+  FOREIGN KEY (adjustment_individual_id) REFERENCES adjustment_individuals(id) ON DELETE CASCADE,
+  connection_id INTEGER NOT NULL,
+  FOREIGN KEY (connection_id) REFERENCES connections(id) ON DELETE CASCADE,
+  parameter DOUBLE PRECISION NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,20 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE adjustment_runs DROP COLUMN time_step;
+DROP TABLE concept_decay_rates;
+UPDATE concept_dynamic_models
+SET dynamic_model_type = NULL
+WHERE dynamic_model_type = 'continuous';
+DELETE FROM adjustment_runs
+WHERE dynamic_model_type = 'continuous';
+ALTER TYPE dynamic_model_type RENAME TO dynamic_model_type_old;
+CREATE TYPE dynamic_model_type AS ENUM (
+  'delta_delta',
+  'delta_value',
+  'value_delta',
+  'value_value'
+);
+ALTER TABLE concept_dynamic_models
+ALTER COLUMN dynamic_model_type TYPE dynamic_model_type USING dynamic_model_type::text::dynamic_model_type;
+ALTER TABLE adjustment_runs
+ALTER COLUMN dynamic_model_type TYPE dynamic_model_type USING dynamic_model_type::text::dynamic_model_type;
+DROP TYPE dynamic_model_type_old;
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+ALTER TYPE dynamic_model_type ADD VALUE 'continuous';
+CREATE TABLE concept_decay_rates (
+  concept_id INTEGER NOT NULL PRIMARY KEY,
+  FOREIGN KEY (concept_id) REFERENCES concepts(id) ON DELETE CASCADE,
+  decay_rate DOUBLE PRECISION NOT NULL DEFAULT 1 CHECK (decay_rate >= 0)
+);
+ALTER TABLE adjustment_runs
+ADD COLUMN time_step DOUBLE PRECISION DEFAULT NULL;
\ No newline at end of file
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE adjustment_runs DROP COLUMN stability_condition;
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- Your SQL goes here
+ALTER TABLE adjustment_runs
+ADD COLUMN stability_condition JSONB DEFAULT NULL;
\ No newline at end of file
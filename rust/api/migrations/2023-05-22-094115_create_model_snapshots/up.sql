@@ -0,0 +1,14 @@
+-- Your SQL goes here
+CREATE TABLE model_snapshots (
+  id SERIAL PRIMARY KEY,
+  project_id INTEGER NOT NULL,
+  FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+  model_copy_id INTEGER NOT NULL,
+  FOREIGN KEY (model_copy_id) REFERENCES model_copies(id) ON DELETE CASCADE,
+  creator_id INTEGER NOT NULL,
+  FOREIGN KEY (creator_id) REFERENCES users(id) ON DELETE CASCADE,
+  name VARCHAR(255) NOT NULL,
+  description TEXT NOT NULL,
+  is_automatic BOOLEAN NOT NULL DEFAULT FALSE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
\ No newline at end of file
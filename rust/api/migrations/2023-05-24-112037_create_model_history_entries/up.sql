@@ -0,0 +1,13 @@
+-- Your SQL goes here
+CREATE TABLE model_history_entries (
+  id SERIAL PRIMARY KEY,
+  project_id INTEGER NOT NULL,
+  FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+  user_id INTEGER NOT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+  name VARCHAR(255) NOT NULL,
+  previous_state JSONB NOT NULL,
+  next_state JSONB NOT NULL,
+  is_undone BOOLEAN NOT NULL DEFAULT FALSE,
+  created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
\ No newline at end of file
@@ -32,4 +32,14 @@ INSERT INTO plugin_dependencies (dependent_plugin_name, dependency_plugin_name)
 VALUES (
     'Connection Constraints',
     'Control Connections'
+  );
+INSERT INTO plugin_dependencies (dependent_plugin_name, dependency_plugin_name)
+VALUES (
+    'Intervention Costs',
+    'Control Concepts'
+  );
+INSERT INTO plugin_dependencies (dependent_plugin_name, dependency_plugin_name)
+VALUES (
+    'Intervention Costs',
+    'Control Connections'
   );
\ No newline at end of file
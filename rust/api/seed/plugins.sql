@@ -64,6 +64,66 @@ INSERT INTO plugins (
     concept_value_type,
     connection_value_type
   )
+VALUES (
+    'Intervention Costs',
+    'The plugin adds the ability to set costs of changing control concepts and connections.',
+    null,
+    null
+  );
+INSERT INTO plugins (
+    name,
+    description,
+    concept_value_type,
+    connection_value_type
+  )
+VALUES (
+    'Linguistic Connections',
+    'The plugin adds the ability to set connection values with linguistic terms.',
+    null,
+    'linguistic'
+  );
+INSERT INTO plugins (
+    name,
+    description,
+    concept_value_type,
+    connection_value_type
+  )
+VALUES (
+    'Connection Lags',
+    'The plugin adds the ability to set time lags of connections.',
+    null,
+    null
+  );
+INSERT INTO plugins (
+    name,
+    description,
+    concept_value_type,
+    connection_value_type
+  )
+VALUES (
+    'Connection Functions',
+    'The plugin adds the ability to set nonlinear response functions of connections.',
+    null,
+    null
+  );
+INSERT INTO plugins (
+    name,
+    description,
+    concept_value_type,
+    connection_value_type
+  )
+VALUES (
+    'Concept Decay Rates',
+    'The plugin adds the ability to set decay rates of concepts for continuous-time dynamics model.',
+    null,
+    null
+  );
+INSERT INTO plugins (
+    name,
+    description,
+    concept_value_type,
+    connection_value_type
+  )
 VALUES (
     'Adjustment With Genetic Algorithms',
     'Structural and parametric adjustment of fuzzy cognitive models based on genetic algorithms.',
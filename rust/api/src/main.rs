@@ -1,10 +1,15 @@
 #[macro_use]
 extern crate rocket;
 use dotenvy::dotenv;
+use fuzzy_cognitive_model_api::db;
 use fuzzy_cognitive_model_api::locale::LocaleFairing;
+use fuzzy_cognitive_model_api::plugins::adjustment::services::adjustment_worker_services;
+use fuzzy_cognitive_model_api::plugins::adjustment::{AdjustmentRunControls, AdjustmentWorkers};
 use fuzzy_cognitive_model_api::plugins::{
-    AdjustmentPlugin, ConceptConstraintsPlugin, ConnectionConstraintsPlugin, ControlConceptsPlugin,
-    ControlConnectionsPlugin, PluginsFairing, TargetConceptsPlugin,
+    AdjustmentPlugin, ConceptConstraintsPlugin, ConceptDecayRatesPlugin,
+    ConnectionConstraintsPlugin, ConnectionFunctionsPlugin, ConnectionLagsPlugin,
+    ControlConceptsPlugin, ControlConnectionsPlugin, InterventionCostsPlugin,
+    LinguisticConnectionsPlugin, PluginsFairing, TargetConceptsPlugin,
 };
 use fuzzy_cognitive_model_api::response;
 use fuzzy_cognitive_model_api::routes::MountRoutes;
@@ -43,9 +48,25 @@ fn rocket() -> _ {
     );
 
     let storage = Storage::new();
+    let adjustment_run_controls = AdjustmentRunControls::new();
+    let adjustment_workers = AdjustmentWorkers::new(
+        utils::get_env_or("ADJUSTMENT_MAX_WORKERS", "2")
+            .parse()
+            .expect("Invalid ADJUSTMENT_MAX_WORKERS"),
+        utils::get_env_or("ADJUSTMENT_PROJECT_QUOTA", "1")
+            .parse()
+            .expect("Invalid ADJUSTMENT_PROJECT_QUOTA"),
+        utils::get_env_or("ADJUSTMENT_USER_QUOTA", "1")
+            .parse()
+            .expect("Invalid ADJUSTMENT_USER_QUOTA"),
+    );
+    adjustment_worker_services::fail_stale_adjustment_runs(&mut db::establish_connection())
+        .expect("Error failing stale adjustment runs");
 
     rocket::build()
         .manage(storage)
+        .manage(adjustment_run_controls)
+        .manage(adjustment_workers)
         .mount_routes("/api/v1")
         .register(
             "/api/v1",
@@ -64,6 +85,11 @@ fn rocket() -> _ {
         .attach(ControlConnectionsPlugin)
         .attach(ConceptConstraintsPlugin)
         .attach(ConnectionConstraintsPlugin)
+        .attach(InterventionCostsPlugin)
+        .attach(LinguisticConnectionsPlugin)
+        .attach(ConnectionLagsPlugin)
+        .attach(ConnectionFunctionsPlugin)
+        .attach(ConceptDecayRatesPlugin)
         .attach(AdjustmentPlugin)
         .attach(web_socket_listener)
 }
@@ -1,7 +1,8 @@
 use crate::schema::{
-    concepts, connections, email_confirmations, model_copies, password_resets, permissions,
-    plugin_dependencies, plugins, project_plugins, project_user_permissions, project_user_statuses,
-    project_users, projects, sessions, users,
+    concepts, connections, email_confirmations, model_copies, model_history_entries,
+    model_snapshots, model_templates, password_resets, permissions, plugin_dependencies, plugins,
+    project_plugins, project_user_permissions, project_user_statuses, project_users, projects,
+    sessions, users,
 };
 use chrono::{DateTime, Utc};
 use diesel::{Associations, Identifiable, Queryable};
@@ -60,7 +61,7 @@ pub struct Session {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Queryable, Identifiable, Clone)]
+#[derive(Queryable, Identifiable, Clone, Serialize, Deserialize)]
 pub struct Project {
     pub id: i32,
     pub name: String,
@@ -160,7 +161,7 @@ pub enum ConceptValueType {
     FromZeroToOne,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(table_name = concepts, belongs_to(Project))]
 pub struct Concept {
     pub id: i32,
@@ -182,9 +183,10 @@ pub struct Concept {
 pub enum ConnectionValueType {
     Symbolic,
     FromMinusOneToOne,
+    Linguistic,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(belongs_to(Project))]
 #[diesel(belongs_to(Concept, foreign_key = source_id))]
 #[diesel(belongs_to(Concept, foreign_key = target_id))]
@@ -199,10 +201,51 @@ pub struct Connection {
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(table_name = model_copies)]
 pub struct ModelCopy {
     pub id: i32,
     pub project_id: i32,
     pub model: Value,
 }
+
+#[derive(Queryable, Identifiable, Associations)]
+#[diesel(belongs_to(Project))]
+#[diesel(belongs_to(User))]
+#[diesel(table_name = model_history_entries)]
+pub struct ModelHistoryEntry {
+    pub id: i32,
+    pub project_id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub previous_state: Value,
+    pub next_state: Value,
+    pub is_undone: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Queryable, Identifiable, Associations)]
+#[diesel(belongs_to(Project))]
+#[diesel(belongs_to(ModelCopy))]
+#[diesel(belongs_to(User, foreign_key = creator_id))]
+pub struct ModelSnapshot {
+    pub id: i32,
+    pub project_id: i32,
+    pub model_copy_id: i32,
+    pub creator_id: i32,
+    pub name: String,
+    pub description: String,
+    pub is_automatic: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Queryable, Identifiable, Associations)]
+#[diesel(belongs_to(User))]
+pub struct ModelTemplate {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub description: String,
+    pub fragment: Value,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,15 +1,25 @@
 pub mod adjustment;
 pub mod concept_constraints;
+pub mod concept_decay_rates;
 pub mod connection_constraints;
+pub mod connection_functions;
+pub mod connection_lags;
 pub mod control_concepts;
 pub mod control_connections;
+pub mod intervention_costs;
+pub mod linguistic_connections;
 pub mod target_concepts;
 
 pub use adjustment::AdjustmentPlugin;
 pub use concept_constraints::ConceptConstraintsPlugin;
+pub use concept_decay_rates::ConceptDecayRatesPlugin;
 pub use connection_constraints::ConnectionConstraintsPlugin;
+pub use connection_functions::ConnectionFunctionsPlugin;
+pub use connection_lags::ConnectionLagsPlugin;
 pub use control_concepts::ControlConceptsPlugin;
 pub use control_connections::ControlConnectionsPlugin;
+pub use intervention_costs::InterventionCostsPlugin;
+pub use linguistic_connections::LinguisticConnectionsPlugin;
 pub use target_concepts::TargetConceptsPlugin;
 
 use crate::models::Project;
@@ -35,6 +45,9 @@ pub trait Plugin {
     fn uninstall(&self, _: &mut PgConnection, project: Project) -> ServiceResult<Project> {
         Ok(project)
     }
+    fn sync_model(&self, _: &mut PgConnection, project: Project) -> ServiceResult<Project> {
+        Ok(project)
+    }
 }
 
 #[derive(Clone)]
@@ -99,6 +112,26 @@ impl Plugins {
             String::from("Connection Constraints"),
             Arc::new(Mutex::new(Box::new(ConnectionConstraintsPlugin))),
         );
+        plugins.insert(
+            String::from("Intervention Costs"),
+            Arc::new(Mutex::new(Box::new(InterventionCostsPlugin))),
+        );
+        plugins.insert(
+            String::from("Linguistic Connections"),
+            Arc::new(Mutex::new(Box::new(LinguisticConnectionsPlugin))),
+        );
+        plugins.insert(
+            String::from("Connection Lags"),
+            Arc::new(Mutex::new(Box::new(ConnectionLagsPlugin))),
+        );
+        plugins.insert(
+            String::from("Connection Functions"),
+            Arc::new(Mutex::new(Box::new(ConnectionFunctionsPlugin))),
+        );
+        plugins.insert(
+            String::from("Concept Decay Rates"),
+            Arc::new(Mutex::new(Box::new(ConceptDecayRatesPlugin))),
+        );
         plugins.insert(
             String::from("Adjustment With Genetic Algorithms"),
             Arc::new(Mutex::new(Box::new(AdjustmentPlugin))),
@@ -115,7 +148,7 @@ impl Plugins {
 }
 
 pub struct Emitter<R, E> {
-    listeners: Vec<Mutex<Box<dyn Fn(R, E) -> ServiceResult<R> + Send>>>,
+    listeners: Vec<Mutex<Box<dyn Fn(&mut PgConnection, R, E) -> ServiceResult<R> + Send>>>,
 }
 
 impl<R, E> Emitter<R, E>
@@ -127,12 +160,15 @@ where
             listeners: Vec::new(),
         }
     }
-    pub fn on(&mut self, callback: impl Fn(R, E) -> ServiceResult<R> + Send + 'static) -> () {
+    pub fn on(
+        &mut self,
+        callback: impl Fn(&mut PgConnection, R, E) -> ServiceResult<R> + Send + 'static,
+    ) -> () {
         self.listeners.push(Mutex::new(Box::new(callback)));
     }
-    pub fn emit(&self, mut result: R, extra: E) -> ServiceResult<R> {
+    pub fn emit(&self, conn: &mut PgConnection, mut result: R, extra: E) -> ServiceResult<R> {
         for callback in &self.listeners {
-            result = callback.lock().unwrap()(result, extra.clone())?;
+            result = callback.lock().unwrap()(conn, result, extra.clone())?;
         }
         Ok(result)
     }
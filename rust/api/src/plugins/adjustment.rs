@@ -13,6 +13,9 @@ use rocket::{Data, Request};
 use services::concept_dynamic_model_services;
 use std::sync::Arc;
 
+pub use services::adjustment_control_services::AdjustmentRunControls;
+pub use services::adjustment_worker_services::AdjustmentWorkers;
+
 pub struct AdjustmentPlugin;
 
 impl Plugin for AdjustmentPlugin {
@@ -27,6 +30,10 @@ impl Plugin for AdjustmentPlugin {
         concept_dynamic_model_services::delete_project_concept_dynamic_models(conn, project.id)?;
         Ok(project)
     }
+    fn sync_model(&self, conn: &mut PgConnection, project: Project) -> ServiceResult<Project> {
+        concept_dynamic_model_services::create_project_concept_dynamic_models(conn, project.id)?;
+        Ok(project)
+    }
 }
 
 #[rocket::async_trait]
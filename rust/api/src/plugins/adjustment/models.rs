@@ -1,12 +1,14 @@
 use crate::schema::adjustment_runs;
 use crate::schema::{
-    adjustment_concept_values, adjustment_connection_values, adjustment_generations,
-    adjustment_individuals, concept_dynamic_models,
+    adjustment_concept_values, adjustment_connection_function_values, adjustment_connection_values,
+    adjustment_generations, adjustment_individuals, adjustment_scenario_errors,
+    concept_dynamic_models,
 };
 use chrono::{DateTime, Utc};
 use diesel::{Identifiable, Queryable};
 use rocket::serde::{Deserialize, Serialize};
 use schemars::JsonSchema;
+use serde_json::Value;
 
 #[derive(
     Debug,
@@ -25,16 +27,41 @@ pub enum DynamicModelType {
     DeltaValue,
     ValueDelta,
     ValueValue,
+    Continuous,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(
+    Debug, Clone, PartialEq, diesel_derive_enum::DbEnum, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[ExistingTypePath = "crate::schema::sql_types::AdjustmentRunState"]
+pub enum AdjustmentRunState {
+    Queued,
+    Running,
+    Paused,
+    Cancelled,
+    Finished,
+    Failed,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, diesel_derive_enum::DbEnum, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[ExistingTypePath = "crate::schema::sql_types::ScenarioAggregationType"]
+pub enum ScenarioAggregationType {
+    WorstCase,
+    Mean,
+}
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(primary_key(concept_id), belongs_to(Concept))]
 pub struct ConceptDynamicModel {
     pub concept_id: i32,
     pub dynamic_model_type: Option<DynamicModelType>,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(belongs_to(Project))]
 #[diesel(belongs_to(ModelCopy))]
 pub struct AdjustmentRun {
@@ -53,9 +80,18 @@ pub struct AdjustmentRun {
     pub error: f64,
     pub created_at: DateTime<Utc>,
     pub result_individual_id: Option<i32>,
+    pub state: AdjustmentRunState,
+    pub seed_generation_id: Option<i32>,
+    pub control_overrides: Option<Value>,
+    pub budget: Option<f64>,
+    pub minimize_cost: bool,
+    pub scenarios: Option<Value>,
+    pub scenario_aggregation_type: ScenarioAggregationType,
+    pub time_step: Option<f64>,
+    pub stability_condition: Option<Value>,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(belongs_to(AdjustmentRun))]
 pub struct AdjustmentGeneration {
     pub id: i32,
@@ -64,7 +100,7 @@ pub struct AdjustmentGeneration {
     pub error: f64,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(belongs_to(AdjustmentGeneration))]
 pub struct AdjustmentIndividual {
     pub id: i32,
@@ -72,9 +108,10 @@ pub struct AdjustmentIndividual {
     pub number: i32,
     pub time: i32,
     pub error: f64,
+    pub cost: f64,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(belongs_to(AdjustmentChromosome))]
 #[diesel(belongs_to(Concept))]
 pub struct AdjustmentConceptValue {
@@ -84,7 +121,7 @@ pub struct AdjustmentConceptValue {
     pub value: f64,
 }
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(belongs_to(AdjustmentChromosome))]
 #[diesel(belongs_to(Connection))]
 pub struct AdjustmentConnectionValue {
@@ -93,3 +130,22 @@ pub struct AdjustmentConnectionValue {
     pub connection_id: i32,
     pub value: f64,
 }
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(belongs_to(AdjustmentIndividual))]
+#[diesel(belongs_to(Connection))]
+pub struct AdjustmentConnectionFunctionValue {
+    pub id: i32,
+    pub adjustment_individual_id: i32,
+    pub connection_id: i32,
+    pub parameter: f64,
+}
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(belongs_to(AdjustmentIndividual))]
+pub struct AdjustmentScenarioError {
+    pub id: i32,
+    pub adjustment_individual_id: i32,
+    pub scenario_name: String,
+    pub error: f64,
+}
@@ -1,20 +1,32 @@
 use super::models::DynamicModelType;
 use super::services::{
-    adjustment_out_services, adjustment_services, concept_dynamic_model_services,
+    adjustment_apply_services, adjustment_control_services, adjustment_out_services,
+    adjustment_services, adjustment_worker_services, concept_dynamic_model_services,
+    explanation_services, interval_simulation_services, inverse_solution_services,
+    model_validation_services, stability_analysis_services,
 };
 use super::types::{
     AdjustmentGenerationOutType, AdjustmentGenerationsInType, AdjustmentInType,
     AdjustmentIndividualInType, AdjustmentIndividualOutType, AdjustmentRunActionType,
-    AdjustmentRunOutType, AdjustmentRunsInType, ConceptDynamicModelOutType,
+    AdjustmentRunOutType, AdjustmentRunsInType, ConceptDynamicModelOutType, ContributionOutType,
+    DiagnosticOutType, ExplanationInType, IntervalSimulationInType, IntervalSimulationStepOutType,
+    InverseSolutionInType, InverseSolutionOutType, StabilityAnalysisInType,
+    StabilityAnalysisOutType,
 };
+use super::{AdjustmentRunControls, AdjustmentWorkers};
 use crate::db;
 use crate::locale::Locale;
 use crate::models::User;
 use crate::plugins::Plugins;
+use crate::request::IfMatch;
 use crate::response::{PathResult, ToPathResult};
-use crate::types::{IntervalInType, ModelActionType, PaginationInType, PaginationOutType};
+use crate::types::{
+    IntervalInType, ModelActionType, ModelElementsChangeOutType, PaginationInType,
+    PaginationOutType,
+};
 use crate::web_socket::{WebSocketAdjustmentRunService, WebSocketModelService};
 use rocket::serde::json::Json;
+use rocket::State;
 use rocket_okapi::openapi;
 
 /// Change concept concept dynamic model type
@@ -27,16 +39,20 @@ use rocket_okapi::openapi;
 pub async fn change_dynamic_model_type(
     concept_id: i32,
     dynamic_model_type: Json<Option<DynamicModelType>>,
+    if_match: IfMatch,
     user: User,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ConceptDynamicModelOutType>> {
     let conn = &mut db::establish_connection();
     concept_dynamic_model_services::change_dynamic_model_type(
         conn,
+        plugins,
         model_service,
         &user,
         concept_id,
         dynamic_model_type.into_inner(),
+        if_match.0,
     )
     .await
     .to_path_result()
@@ -56,17 +72,188 @@ pub async fn adjust(
     locale: &Locale,
     plugins: &Plugins,
     adjustment_run_service: WebSocketAdjustmentRunService,
+    adjustment_run_controls: &State<AdjustmentRunControls>,
+    adjustment_workers: &State<AdjustmentWorkers>,
 ) -> PathResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
-    let conn = db::establish_connection();
+    let conn = &mut db::establish_connection();
     adjustment_services::adjust(
         conn,
         plugins,
         adjustment_run_service,
+        adjustment_run_controls,
+        adjustment_workers,
+        &user,
+        locale,
+        project_id,
+        adjustment_in.into_inner(),
+    )
+    .await
+    .to_path_result()
+}
+
+/// Validate fuzzy cognitive model and adjustment settings before running genetic algorithm
+#[openapi(tag = "adjustment")]
+#[post(
+    "/projects/<project_id>/validate_adjustment",
+    format = "json",
+    data = "<adjustment_in>"
+)]
+pub fn validate_adjustment(
+    project_id: i32,
+    adjustment_in: Json<AdjustmentInType>,
+    user: User,
+    locale: &Locale,
+    plugins: &Plugins,
+) -> PathResult<Vec<DiagnosticOutType>> {
+    let conn = &mut db::establish_connection();
+    model_validation_services::validate_adjustment(
+        conn,
+        plugins,
         &user,
         locale,
         project_id,
         adjustment_in.into_inner(),
     )
+    .to_path_result()
+}
+
+/// Simulate fuzzy cognitive model with interval values and get lower and upper envelopes of concept values
+#[openapi(tag = "adjustment")]
+#[post(
+    "/projects/<project_id>/simulate_intervals",
+    format = "json",
+    data = "<interval_simulation_in>"
+)]
+pub fn simulate_intervals(
+    project_id: i32,
+    interval_simulation_in: Json<IntervalSimulationInType>,
+    user: User,
+    plugins: &Plugins,
+) -> PathResult<Vec<IntervalSimulationStepOutType>> {
+    let conn = &mut db::establish_connection();
+    interval_simulation_services::simulate_intervals(
+        conn,
+        plugins,
+        &user,
+        project_id,
+        interval_simulation_in.into_inner(),
+    )
+    .to_path_result()
+}
+
+/// Analyze spectral properties of update matrix of dynamics model and check whether fuzzy cognitive model is guaranteed to converge
+#[openapi(tag = "adjustment")]
+#[post(
+    "/projects/<project_id>/analyze_stability",
+    format = "json",
+    data = "<stability_analysis_in>"
+)]
+pub fn analyze_stability(
+    project_id: i32,
+    stability_analysis_in: Json<StabilityAnalysisInType>,
+    user: User,
+    plugins: &Plugins,
+) -> PathResult<StabilityAnalysisOutType> {
+    let conn = &mut db::establish_connection();
+    stability_analysis_services::analyze_stability(
+        conn,
+        plugins,
+        &user,
+        project_id,
+        stability_analysis_in.into_inner(),
+    )
+    .to_path_result()
+}
+
+/// Find control concept values of minimal norm reaching target values at model time or the constraint preventing it
+#[openapi(tag = "adjustment")]
+#[post(
+    "/projects/<project_id>/solve_inverse",
+    format = "json",
+    data = "<inverse_solution_in>"
+)]
+pub fn solve_inverse(
+    project_id: i32,
+    inverse_solution_in: Json<InverseSolutionInType>,
+    user: User,
+    plugins: &Plugins,
+) -> PathResult<InverseSolutionOutType> {
+    let conn = &mut db::establish_connection();
+    inverse_solution_services::solve_inverse(
+        conn,
+        plugins,
+        &user,
+        project_id,
+        inverse_solution_in.into_inner(),
+    )
+    .to_path_result()
+}
+
+/// Pause adjustment run
+#[openapi(tag = "adjustment")]
+#[patch("/adjustment_runs/<adjustment_run_id>/pause")]
+pub async fn pause_adjustment(
+    adjustment_run_id: i32,
+    user: User,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    adjustment_run_controls: &State<AdjustmentRunControls>,
+    adjustment_workers: &State<AdjustmentWorkers>,
+) -> PathResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
+    let conn = &mut db::establish_connection();
+    adjustment_control_services::pause_adjustment(
+        conn,
+        adjustment_run_controls,
+        adjustment_workers,
+        adjustment_run_service,
+        &user,
+        adjustment_run_id,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Resume paused adjustment run
+#[openapi(tag = "adjustment")]
+#[patch("/adjustment_runs/<adjustment_run_id>/resume")]
+pub async fn resume_adjustment(
+    adjustment_run_id: i32,
+    user: User,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    adjustment_run_controls: &State<AdjustmentRunControls>,
+    adjustment_workers: &State<AdjustmentWorkers>,
+) -> PathResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
+    let conn = &mut db::establish_connection();
+    adjustment_control_services::resume_adjustment(
+        conn,
+        adjustment_run_controls,
+        adjustment_workers,
+        adjustment_run_service,
+        &user,
+        adjustment_run_id,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Cancel adjustment run saving the best individual found so far
+#[openapi(tag = "adjustment")]
+#[patch("/adjustment_runs/<adjustment_run_id>/cancel")]
+pub async fn cancel_adjustment(
+    adjustment_run_id: i32,
+    user: User,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    adjustment_run_controls: &State<AdjustmentRunControls>,
+    adjustment_workers: &State<AdjustmentWorkers>,
+) -> PathResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
+    let conn = &mut db::establish_connection();
+    adjustment_control_services::cancel_adjustment(
+        conn,
+        adjustment_run_controls,
+        adjustment_workers,
+        adjustment_run_service,
+        &user,
+        adjustment_run_id,
+    )
     .await
     .to_path_result()
 }
@@ -79,6 +266,25 @@ pub fn get_adjustment_run(adjustment_run_id: i32, user: User) -> PathResult<Adju
     adjustment_out_services::get_adjustment_run(conn, &user, adjustment_run_id).to_path_result()
 }
 
+/// Get position of queued adjustment run in the adjustment queue
+#[openapi(tag = "adjustment")]
+#[get("/adjustment_runs/<adjustment_run_id>/queue_position")]
+pub async fn get_adjustment_run_queue_position(
+    adjustment_run_id: i32,
+    user: User,
+    adjustment_workers: &State<AdjustmentWorkers>,
+) -> PathResult<Option<i32>> {
+    let conn = &mut db::establish_connection();
+    adjustment_worker_services::get_adjustment_run_queue_position(
+        conn,
+        adjustment_workers,
+        &user,
+        adjustment_run_id,
+    )
+    .await
+    .to_path_result()
+}
+
 /// Get adjustment runs
 #[openapi(tag = "adjustment")]
 #[get("/projects/<project_id>/adjustment_runs?<adjustment_runs_in..>")]
@@ -184,3 +390,52 @@ pub fn get_adjustment_individuals(
     )
     .to_path_result()
 }
+
+/// Explain values of target concepts reached by adjustment individual with ranked contribution trees
+#[openapi(tag = "adjustment")]
+#[post(
+    "/adjustment_individuals/<adjustment_individual_id>/explain",
+    format = "json",
+    data = "<explanation_in>"
+)]
+pub fn explain_adjustment_individual(
+    adjustment_individual_id: i32,
+    explanation_in: Json<ExplanationInType>,
+    user: User,
+    plugins: &Plugins,
+) -> PathResult<Vec<ContributionOutType>> {
+    let conn = &mut db::establish_connection();
+    explanation_services::explain_adjustment_individual(
+        conn,
+        plugins,
+        &user,
+        adjustment_individual_id,
+        explanation_in.into_inner(),
+    )
+    .to_path_result()
+}
+
+/// Apply concept and connection values of adjustment individual to the model
+#[openapi(tag = "adjustment")]
+#[post("/adjustment_individuals/<adjustment_individual_id>/apply?<force>")]
+pub async fn apply_adjustment_individual(
+    adjustment_individual_id: i32,
+    force: Option<bool>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ModelElementsChangeOutType>> {
+    let conn = &mut db::establish_connection();
+    adjustment_apply_services::apply_adjustment_individual(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        adjustment_individual_id,
+        force.unwrap_or(false),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
@@ -1,5 +1,13 @@
+pub mod adjustment_apply_services;
+pub mod adjustment_control_services;
 pub mod adjustment_out_services;
 pub mod adjustment_save_result_services;
 pub mod adjustment_services;
+pub mod adjustment_worker_services;
 pub mod concept_dynamic_model_services;
+pub mod explanation_services;
+pub mod interval_simulation_services;
+pub mod inverse_solution_services;
+pub mod model_validation_services;
 pub mod permission_services;
+pub mod stability_analysis_services;
@@ -0,0 +1,280 @@
+use super::adjustment_out_services;
+use crate::models::{Project, User};
+use crate::plugins::adjustment::types::AdjustmentIndividualOutType;
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{concepts, connection_functions, connections};
+use crate::services::{
+    model_history_services, model_services, model_snapshot_services, permission_services,
+    project_services,
+};
+use crate::types::{
+    ModelActionType, ModelDiffOutType, ModelElementsChangeOutType, ModelElementsType, ModelOutType,
+};
+use crate::web_socket::WebSocketModelService;
+use crate::{conflict_error, forbidden_error};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use std::collections::HashSet;
+
+const LAYOUT_FIELDS: [&str; 2] = ["xPosition", "yPosition"];
+
+pub async fn apply_adjustment_individual(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    adjustment_individual_id: i32,
+    force: bool,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelElementsChangeOutType>> {
+    let project = adjustment_out_services::find_project_by_adjustment_individual_id(
+        conn,
+        adjustment_individual_id,
+    )
+    .to_service_result_find(String::from("adjustment_individual_not_found_error"))?;
+    if !plugins
+        .plugins
+        .get("Adjustment With Genetic Algorithms")
+        .unwrap()
+        .lock()
+        .unwrap()
+        .is_enabled(conn, project.id)?
+    {
+        return forbidden_error!("adjustment_plugin_is_not_enabled_error");
+    }
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    let adjustment_run = adjustment_out_services::find_adjustment_run_by_individual_id(
+        conn,
+        adjustment_individual_id,
+    )
+    .to_service_result()?;
+    let adjustment_individual = AdjustmentIndividualOutType::from_individual(
+        conn,
+        adjustment_out_services::find_adjustment_individual_by_id(conn, adjustment_individual_id)
+            .to_service_result()?,
+    )?;
+    let model_copy = model_services::get_model_copy(conn, user, adjustment_run.model_copy_id)?;
+    let (project, next_state) = conn.transaction(|conn| {
+        apply_adjustment_individual_values(
+            conn,
+            plugins,
+            user,
+            project,
+            &model_copy,
+            &adjustment_individual,
+            force,
+        )
+    })?;
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("applyAdjustmentIndividual"),
+        ModelElementsChangeOutType {
+            concepts: next_state.concepts,
+            connections: next_state.connections,
+            deleted_concept_ids: vec![],
+            deleted_connection_ids: vec![],
+        },
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+fn apply_adjustment_individual_values(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project: Project,
+    model_copy: &ModelOutType,
+    adjustment_individual: &AdjustmentIndividualOutType,
+    force: bool,
+) -> ServiceResult<(Project, ModelElementsType)> {
+    let model = model_services::get_model(conn, plugins, user, project.id)?;
+    let model_diff = get_written_elements_diff(
+        model_snapshot_services::diff_models(model_copy, &model),
+        adjustment_individual,
+    );
+    if !force && has_changes(&model_diff) {
+        return conflict_error!(
+            "adjustment_apply_conflict_error",
+            serde_json::to_value(model_diff).unwrap()
+        );
+    }
+    // Elements deleted after the adjustment run are skipped
+    let concept_values = adjustment_individual
+        .concept_values
+        .iter()
+        .filter(|concept_value| {
+            model
+                .concepts
+                .iter()
+                .any(|concept_out| concept_out.id == concept_value.concept_id)
+        })
+        .collect::<Vec<_>>();
+    let connection_values = adjustment_individual
+        .connection_values
+        .iter()
+        .filter(|connection_value| {
+            model
+                .connections
+                .iter()
+                .any(|connection_out| connection_out.id == connection_value.connection_id)
+        })
+        .collect::<Vec<_>>();
+    let connection_function_values = adjustment_individual
+        .connection_function_values
+        .iter()
+        .filter(|connection_function_value| {
+            model
+                .connections
+                .iter()
+                .any(|connection_out| connection_out.id == connection_function_value.connection_id)
+        })
+        .collect::<Vec<_>>();
+    for concept_value in &concept_values {
+        model_services::check_concept_value(&project, Some(concept_value.value))?;
+    }
+    for connection_value in &connection_values {
+        model_services::check_connection_value(&project, connection_value.value)?;
+    }
+    let concept_ids = concept_values
+        .iter()
+        .map(|concept_value| concept_value.concept_id)
+        .collect::<Vec<_>>();
+    let mut connection_ids = connection_values
+        .iter()
+        .map(|connection_value| connection_value.connection_id)
+        .chain(
+            connection_function_values
+                .iter()
+                .map(|connection_function_value| connection_function_value.connection_id),
+        )
+        .collect::<Vec<_>>();
+    connection_ids.sort();
+    connection_ids.dedup();
+    model_snapshot_services::save_automatic_model_snapshot(
+        conn,
+        plugins,
+        user,
+        &project,
+        "applyAdjustmentIndividual",
+    )?;
+    let previous_state = model_history_services::get_model_elements(
+        conn,
+        plugins,
+        &project,
+        &concept_ids,
+        &connection_ids,
+    )?;
+    let updated_at = Utc::now();
+    for concept_value in &concept_values {
+        diesel::update(concepts::table)
+            .filter(concepts::id.eq(concept_value.concept_id))
+            .set((
+                concepts::value.eq(concept_value.value),
+                concepts::updated_at.eq(updated_at),
+            ))
+            .execute(conn)?;
+    }
+    for connection_value in &connection_values {
+        diesel::update(connections::table)
+            .filter(connections::id.eq(connection_value.connection_id))
+            .set((
+                connections::value.eq(connection_value.value),
+                connections::updated_at.eq(updated_at),
+            ))
+            .execute(conn)?;
+    }
+    for connection_function_value in &connection_function_values {
+        diesel::update(connection_functions::table)
+            .filter(connection_functions::connection_id.eq(connection_function_value.connection_id))
+            .set(connection_functions::parameter.eq(connection_function_value.parameter))
+            .execute(conn)?;
+        diesel::update(connections::table)
+            .filter(connections::id.eq(connection_function_value.connection_id))
+            .set(connections::updated_at.eq(updated_at))
+            .execute(conn)?;
+    }
+    let project = project_services::update_project(conn, project.id, updated_at)?;
+    let next_state = model_history_services::get_model_elements(
+        conn,
+        plugins,
+        &project,
+        &concept_ids,
+        &connection_ids,
+    )?;
+    model_history_services::record_model_action(
+        conn,
+        user,
+        &project,
+        "applyAdjustmentIndividual",
+        previous_state,
+        next_state.clone(),
+    )?;
+    Ok((project, next_state))
+}
+
+// Only the elements the individual writes can conflict, and their layout is not written
+fn get_written_elements_diff(
+    model_diff: ModelDiffOutType,
+    adjustment_individual: &AdjustmentIndividualOutType,
+) -> ModelDiffOutType {
+    let concept_ids = adjustment_individual
+        .concept_values
+        .iter()
+        .map(|concept_value| concept_value.concept_id)
+        .collect::<HashSet<_>>();
+    let connection_ids = adjustment_individual
+        .connection_values
+        .iter()
+        .map(|connection_value| connection_value.connection_id)
+        .chain(
+            adjustment_individual
+                .connection_function_values
+                .iter()
+                .map(|connection_function_value| connection_function_value.connection_id),
+        )
+        .collect::<HashSet<_>>();
+    ModelDiffOutType {
+        added_concepts: vec![],
+        removed_concepts: model_diff
+            .removed_concepts
+            .into_iter()
+            .filter(|concept_out| concept_ids.contains(&concept_out.id))
+            .collect(),
+        changed_concepts: model_diff
+            .changed_concepts
+            .into_iter()
+            .filter(|concept_diff| concept_ids.contains(&concept_diff.source.id))
+            .filter_map(|mut concept_diff| {
+                concept_diff
+                    .fields
+                    .retain(|field| !LAYOUT_FIELDS.contains(&field.name.as_str()));
+                (!concept_diff.fields.is_empty()).then_some(concept_diff)
+            })
+            .collect(),
+        added_connections: vec![],
+        removed_connections: model_diff
+            .removed_connections
+            .into_iter()
+            .filter(|connection_out| connection_ids.contains(&connection_out.id))
+            .collect(),
+        changed_connections: model_diff
+            .changed_connections
+            .into_iter()
+            .filter(|connection_diff| connection_ids.contains(&connection_diff.source.id))
+            .collect(),
+    }
+}
+
+fn has_changes(model_diff: &ModelDiffOutType) -> bool {
+    !model_diff.added_concepts.is_empty()
+        || !model_diff.removed_concepts.is_empty()
+        || !model_diff.changed_concepts.is_empty()
+        || !model_diff.added_connections.is_empty()
+        || !model_diff.removed_connections.is_empty()
+        || !model_diff.changed_connections.is_empty()
+}
@@ -0,0 +1,239 @@
+use super::super::models::{AdjustmentRun, AdjustmentRunState};
+use super::super::types::{AdjustmentRunActionType, AdjustmentRunOutType};
+use super::adjustment_worker_services::AdjustmentWorkers;
+use super::{adjustment_out_services, permission_services};
+use crate::models::User;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::adjustment_runs;
+use crate::validation_error;
+use crate::web_socket::WebSocketAdjustmentRunService;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use rocket::tokio::sync::watch::{self, Receiver, Sender};
+use rocket::tokio::sync::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct AdjustmentRunControls {
+    senders: Arc<Mutex<HashMap<i32, Sender<AdjustmentRunState>>>>,
+}
+
+impl AdjustmentRunControls {
+    pub fn new() -> Self {
+        Self {
+            senders: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+    pub async fn register(&self, adjustment_run_id: i32) -> Receiver<AdjustmentRunState> {
+        let (sender, receiver) = watch::channel(AdjustmentRunState::Running);
+        self.senders.lock().await.insert(adjustment_run_id, sender);
+        receiver
+    }
+    pub async fn unregister(&self, adjustment_run_id: i32) -> Option<AdjustmentRunState> {
+        self.senders
+            .lock()
+            .await
+            .remove(&adjustment_run_id)
+            .map(|sender| sender.borrow().clone())
+    }
+}
+
+pub async fn pause_adjustment(
+    conn: &mut PgConnection,
+    adjustment_run_controls: &AdjustmentRunControls,
+    adjustment_workers: &AdjustmentWorkers,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    user: &User,
+    adjustment_run_id: i32,
+) -> ServiceResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
+    let adjustment_run = find_controlled_adjustment_run(conn, user, adjustment_run_id)?;
+    if adjustment_run.state != AdjustmentRunState::Running {
+        return validation_error!("adjustment_run_is_not_running_error");
+    }
+    let adjustment_run_action = change_adjustment_run_state(
+        conn,
+        adjustment_run_controls,
+        adjustment_run_service.clone(),
+        adjustment_run,
+        AdjustmentRunState::Paused,
+        "pauseAdjustment",
+    )
+    .await?;
+    adjustment_workers
+        .set_paused(
+            adjustment_run_service,
+            adjustment_run_controls.clone(),
+            adjustment_run_id,
+            true,
+        )
+        .await;
+    Ok(adjustment_run_action)
+}
+
+pub async fn resume_adjustment(
+    conn: &mut PgConnection,
+    adjustment_run_controls: &AdjustmentRunControls,
+    adjustment_workers: &AdjustmentWorkers,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    user: &User,
+    adjustment_run_id: i32,
+) -> ServiceResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
+    let adjustment_run = find_controlled_adjustment_run(conn, user, adjustment_run_id)?;
+    if adjustment_run.state != AdjustmentRunState::Paused {
+        return validation_error!("adjustment_run_is_not_paused_error");
+    }
+    let adjustment_run_action = change_adjustment_run_state(
+        conn,
+        adjustment_run_controls,
+        adjustment_run_service.clone(),
+        adjustment_run,
+        AdjustmentRunState::Running,
+        "resumeAdjustment",
+    )
+    .await?;
+    adjustment_workers
+        .set_paused(
+            adjustment_run_service,
+            adjustment_run_controls.clone(),
+            adjustment_run_id,
+            false,
+        )
+        .await;
+    Ok(adjustment_run_action)
+}
+
+pub async fn cancel_adjustment(
+    conn: &mut PgConnection,
+    adjustment_run_controls: &AdjustmentRunControls,
+    adjustment_workers: &AdjustmentWorkers,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    user: &User,
+    adjustment_run_id: i32,
+) -> ServiceResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
+    let adjustment_run = find_controlled_adjustment_run(conn, user, adjustment_run_id)?;
+    if adjustment_run.state != AdjustmentRunState::Queued
+        && adjustment_run.state != AdjustmentRunState::Running
+        && adjustment_run.state != AdjustmentRunState::Paused
+    {
+        return validation_error!("adjustment_run_is_not_active_error");
+    }
+    let is_queued = adjustment_run.state == AdjustmentRunState::Queued
+        && adjustment_workers.remove_queued(adjustment_run_id).await;
+    let adjustment_run_action = change_adjustment_run_state(
+        conn,
+        adjustment_run_controls,
+        adjustment_run_service.clone(),
+        adjustment_run,
+        AdjustmentRunState::Cancelled,
+        "cancelAdjustment",
+    )
+    .await?;
+    if is_queued {
+        adjustment_run_controls.unregister(adjustment_run_id).await;
+        adjustment_workers
+            .dispatch(adjustment_run_service, adjustment_run_controls.clone())
+            .await;
+    }
+    Ok(adjustment_run_action)
+}
+
+pub async fn finish_adjustment(
+    conn: &mut PgConnection,
+    adjustment_run_controls: &AdjustmentRunControls,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    project_id: i32,
+    adjustment_run_id: i32,
+    is_failed: bool,
+) -> ServiceResult<()> {
+    let last_state = adjustment_run_controls.unregister(adjustment_run_id).await;
+    let (state, action_name) = if is_failed {
+        (AdjustmentRunState::Failed, "adjustmentFailed")
+    } else if last_state == Some(AdjustmentRunState::Cancelled) {
+        (AdjustmentRunState::Cancelled, "adjustmentCancelled")
+    } else {
+        (AdjustmentRunState::Finished, "adjustmentFinished")
+    };
+    let adjustment_run =
+        set_adjustment_run_state(conn, adjustment_run_id, state).to_service_result()?;
+    let adjustment_run_out = AdjustmentRunOutType::from_adjustment_run(conn, adjustment_run)?;
+    let adjustment_run_action = AdjustmentRunActionType::new(
+        project_id,
+        adjustment_run_id,
+        String::from(action_name),
+        adjustment_run_out,
+    );
+    adjustment_run_service.notify(adjustment_run_action).await;
+    Ok(())
+}
+
+pub async fn wait_while_paused(control: &mut Receiver<AdjustmentRunState>) -> bool {
+    loop {
+        let state = control.borrow().clone();
+        match state {
+            AdjustmentRunState::Paused => {
+                if control.changed().await.is_err() {
+                    return false;
+                }
+            }
+            AdjustmentRunState::Cancelled => return false,
+            _ => return true,
+        }
+    }
+}
+
+pub fn set_adjustment_run_state(
+    conn: &mut PgConnection,
+    adjustment_run_id: i32,
+    state: AdjustmentRunState,
+) -> QueryResult<AdjustmentRun> {
+    diesel::update(adjustment_runs::table)
+        .filter(adjustment_runs::id.eq(adjustment_run_id))
+        .set(adjustment_runs::state.eq(state))
+        .get_result::<AdjustmentRun>(conn)
+}
+
+fn find_controlled_adjustment_run(
+    conn: &mut PgConnection,
+    user: &User,
+    adjustment_run_id: i32,
+) -> ServiceResult<AdjustmentRun> {
+    let project =
+        adjustment_out_services::find_project_by_adjustment_run_id(conn, adjustment_run_id)
+            .to_service_result_find(String::from("adjustment_run_not_found_error"))?;
+    permission_services::can_adjust(conn, &project, user.id)?;
+    adjustment_out_services::find_adjustment_run_by_id(conn, adjustment_run_id)
+        .to_service_result_find(String::from("adjustment_run_not_found_error"))
+}
+
+async fn change_adjustment_run_state(
+    conn: &mut PgConnection,
+    adjustment_run_controls: &AdjustmentRunControls,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    adjustment_run: AdjustmentRun,
+    state: AdjustmentRunState,
+    action_name: &str,
+) -> ServiceResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
+    let senders = adjustment_run_controls.senders.lock().await;
+    let sender = match senders.get(&adjustment_run.id) {
+        Some(sender) => sender,
+        None => return validation_error!("adjustment_run_is_not_active_error"),
+    };
+    sender.send_replace(state.clone());
+    let adjustment_run =
+        set_adjustment_run_state(conn, adjustment_run.id, state).to_service_result()?;
+    drop(senders);
+    let project_id = adjustment_run.project_id;
+    let adjustment_run_id = adjustment_run.id;
+    let adjustment_run_out = AdjustmentRunOutType::from_adjustment_run(conn, adjustment_run)?;
+    let adjustment_run_action = AdjustmentRunActionType::new(
+        project_id,
+        adjustment_run_id,
+        String::from(action_name),
+        adjustment_run_out,
+    );
+    adjustment_run_service
+        .notify(adjustment_run_action.clone())
+        .await;
+    Ok(adjustment_run_action)
+}
@@ -1,10 +1,11 @@
 use super::super::models::{
-    AdjustmentConceptValue, AdjustmentConnectionValue, AdjustmentGeneration, AdjustmentIndividual,
-    AdjustmentRun,
+    AdjustmentConceptValue, AdjustmentConnectionFunctionValue, AdjustmentConnectionValue,
+    AdjustmentGeneration, AdjustmentIndividual, AdjustmentRun, AdjustmentScenarioError,
 };
 use super::super::types::{
-    AdjustmentConceptValueOutType, AdjustmentConnectionValueOutType, AdjustmentGenerationOutType,
-    AdjustmentRunOutType, StopConditionType,
+    AdjustmentConceptValueOutType, AdjustmentConnectionFunctionValueOutType,
+    AdjustmentConnectionValueOutType, AdjustmentGenerationOutType, AdjustmentRunOutType,
+    AdjustmentScenarioErrorOutType, StopConditionType,
 };
 use crate::filter_date_time;
 use crate::models::{Project, User};
@@ -14,8 +15,9 @@ use crate::plugins::adjustment::types::{
 };
 use crate::response::{ServiceResult, ToServiceResult};
 use crate::schema::{
-    adjustment_concept_values, adjustment_connection_values, adjustment_generations,
-    adjustment_individuals, adjustment_runs, projects,
+    adjustment_concept_values, adjustment_connection_function_values, adjustment_connection_values,
+    adjustment_generations, adjustment_individuals, adjustment_runs, adjustment_scenario_errors,
+    projects,
 };
 use crate::services::{permission_services, project_services};
 use crate::types::{IntervalInType, PaginationInType, PaginationOutType};
@@ -167,6 +169,17 @@ pub fn find_project_by_adjustment_run_id(
         .get_result::<Project>(conn)
 }
 
+pub fn find_adjustment_run_by_individual_id(
+    conn: &mut PgConnection,
+    adjustment_individual_id: i32,
+) -> QueryResult<AdjustmentRun> {
+    adjustment_individuals::table
+        .inner_join(adjustment_generations::table.inner_join(adjustment_runs::table))
+        .filter(adjustment_individuals::id.eq(adjustment_individual_id))
+        .select(adjustment_runs::all_columns)
+        .get_result::<AdjustmentRun>(conn)
+}
+
 pub fn find_adjustment_generation_by_id(
     conn: &mut PgConnection,
     adjustment_generation_id: i32,
@@ -228,6 +241,64 @@ fn find_connection_values(
         .get_results::<AdjustmentConnectionValue>(conn)
 }
 
+fn find_connection_function_values(
+    conn: &mut PgConnection,
+    individual_ids: &[i32],
+) -> QueryResult<Vec<AdjustmentConnectionFunctionValue>> {
+    adjustment_connection_function_values::table
+        .filter(
+            adjustment_connection_function_values::adjustment_individual_id.eq_any(individual_ids),
+        )
+        .order(adjustment_connection_function_values::id)
+        .get_results::<AdjustmentConnectionFunctionValue>(conn)
+}
+
+fn take_connection_function_values(
+    connection_function_values: &mut Vec<AdjustmentConnectionFunctionValue>,
+    individual_id: i32,
+) -> Vec<AdjustmentConnectionFunctionValueOutType> {
+    let mut connection_function_out_values = vec![];
+    let mut index = 0;
+    while index < connection_function_values.len() {
+        if connection_function_values[index].adjustment_individual_id == individual_id {
+            connection_function_out_values.push(AdjustmentConnectionFunctionValueOutType::from(
+                connection_function_values.remove(index),
+            ));
+        } else {
+            index += 1;
+        }
+    }
+    connection_function_out_values
+}
+
+fn find_scenario_errors(
+    conn: &mut PgConnection,
+    individual_ids: &[i32],
+) -> QueryResult<Vec<AdjustmentScenarioError>> {
+    adjustment_scenario_errors::table
+        .filter(adjustment_scenario_errors::adjustment_individual_id.eq_any(individual_ids))
+        .order(adjustment_scenario_errors::id)
+        .get_results::<AdjustmentScenarioError>(conn)
+}
+
+fn take_scenario_errors(
+    scenario_errors: &mut Vec<AdjustmentScenarioError>,
+    individual_id: i32,
+) -> Vec<AdjustmentScenarioErrorOutType> {
+    let mut scenario_out_errors = vec![];
+    let mut index = 0;
+    while index < scenario_errors.len() {
+        if scenario_errors[index].adjustment_individual_id == individual_id {
+            scenario_out_errors.push(AdjustmentScenarioErrorOutType::from(
+                scenario_errors.remove(index),
+            ));
+        } else {
+            index += 1;
+        }
+    }
+    scenario_out_errors
+}
+
 impl AdjustmentRunOutType {
     pub fn from_adjustment_run(
         conn: &mut PgConnection,
@@ -235,33 +306,56 @@ impl AdjustmentRunOutType {
     ) -> ServiceResult<Self> {
         let result_individual = match adjustment_run.result_individual_id {
             Some(result_individual_id) => {
-                let (id, number, time, error, generation_id, generation_number, generation_error) =
-                    adjustment_individuals::table
-                        .inner_join(adjustment_generations::table)
-                        .filter(adjustment_individuals::id.eq(result_individual_id))
-                        .select((
-                            adjustment_individuals::id,
-                            adjustment_individuals::number,
-                            adjustment_individuals::time,
-                            adjustment_individuals::error,
-                            adjustment_generations::id,
-                            adjustment_generations::number,
-                            adjustment_generations::error,
-                        ))
-                        .get_result::<(i32, i32, i32, f64, i32, i32, f64)>(conn)
-                        .to_service_result()?;
+                let (
+                    id,
+                    number,
+                    time,
+                    error,
+                    cost,
+                    generation_id,
+                    generation_number,
+                    generation_error,
+                ) = adjustment_individuals::table
+                    .inner_join(adjustment_generations::table)
+                    .filter(adjustment_individuals::id.eq(result_individual_id))
+                    .select((
+                        adjustment_individuals::id,
+                        adjustment_individuals::number,
+                        adjustment_individuals::time,
+                        adjustment_individuals::error,
+                        adjustment_individuals::cost,
+                        adjustment_generations::id,
+                        adjustment_generations::number,
+                        adjustment_generations::error,
+                    ))
+                    .get_result::<(i32, i32, i32, f64, f64, i32, i32, f64)>(conn)
+                    .to_service_result()?;
                 let concept_values = Self::get_concept_values(conn, result_individual_id)?;
                 let connection_values = Self::get_connection_values(conn, result_individual_id)?;
+                let mut connection_function_values =
+                    find_connection_function_values(conn, &[result_individual_id])
+                        .to_service_result()?;
+                let mut scenario_errors =
+                    find_scenario_errors(conn, &[result_individual_id]).to_service_result()?;
                 Some(AdjustmentIndividualGenerationOutType {
                     id,
                     number,
                     time,
                     error,
+                    cost,
                     generation_id,
                     generation_number,
                     generation_error,
                     concept_values,
                     connection_values,
+                    connection_function_values: take_connection_function_values(
+                        &mut connection_function_values,
+                        result_individual_id,
+                    ),
+                    scenario_errors: take_scenario_errors(
+                        &mut scenario_errors,
+                        result_individual_id,
+                    ),
                 })
             }
             None => None,
@@ -284,6 +378,10 @@ impl AdjustmentRunOutType {
         let mut concept_values = find_concept_values(conn, &individual_ids).to_service_result()?;
         let mut connection_values =
             find_connection_values(conn, &individual_ids).to_service_result()?;
+        let mut connection_function_values =
+            find_connection_function_values(conn, &individual_ids).to_service_result()?;
+        let mut scenario_errors =
+            find_scenario_errors(conn, &individual_ids).to_service_result()?;
         let mut result = vec![];
         for adjustment_run in adjustment_runs {
             let result_individual = match &adjustment_run.result_individual_id {
@@ -299,6 +397,7 @@ impl AdjustmentRunOutType {
                         number,
                         time,
                         error,
+                        cost,
                         generation_id,
                         generation_number,
                         generation_error,
@@ -332,11 +431,20 @@ impl AdjustmentRunOutType {
                         number,
                         time,
                         error,
+                        cost,
                         generation_id,
                         generation_number,
                         generation_error,
                         concept_values: concept_out_values,
                         connection_values: connection_out_values,
+                        connection_function_values: take_connection_function_values(
+                            &mut connection_function_values,
+                            *result_individual_id,
+                        ),
+                        scenario_errors: take_scenario_errors(
+                            &mut scenario_errors,
+                            *result_individual_id,
+                        ),
                     })
                 }
                 None => None,
@@ -348,7 +456,7 @@ impl AdjustmentRunOutType {
     fn find_individuals(
         conn: &mut PgConnection,
         individual_ids: &[i32],
-    ) -> QueryResult<Vec<(i32, i32, i32, f64, i32, i32, f64)>> {
+    ) -> QueryResult<Vec<(i32, i32, i32, f64, f64, i32, i32, f64)>> {
         adjustment_individuals::table
             .inner_join(adjustment_generations::table)
             .filter(adjustment_individuals::id.eq_any(individual_ids))
@@ -357,11 +465,12 @@ impl AdjustmentRunOutType {
                 adjustment_individuals::number,
                 adjustment_individuals::time,
                 adjustment_individuals::error,
+                adjustment_individuals::cost,
                 adjustment_generations::id,
                 adjustment_generations::number,
                 adjustment_generations::error,
             ))
-            .get_results::<(i32, i32, i32, f64, i32, i32, f64)>(conn)
+            .get_results::<(i32, i32, i32, f64, f64, i32, i32, f64)>(conn)
     }
     fn get_concept_values(
         conn: &mut PgConnection,
@@ -434,6 +543,21 @@ impl From<(AdjustmentRun, Option<AdjustmentIndividualGenerationOutType>)> for Ad
                 error: adjustment_run.error,
             },
             created_at: adjustment_run.created_at,
+            state: adjustment_run.state,
+            seed_generation_id: adjustment_run.seed_generation_id,
+            control_overrides: adjustment_run
+                .control_overrides
+                .map(|control_overrides| serde_json::from_value(control_overrides).unwrap()),
+            budget: adjustment_run.budget,
+            minimize_cost: adjustment_run.minimize_cost,
+            scenarios: adjustment_run
+                .scenarios
+                .map(|scenarios| serde_json::from_value(scenarios).unwrap()),
+            scenario_aggregation_type: adjustment_run.scenario_aggregation_type,
+            time_step: adjustment_run.time_step,
+            stability_condition: adjustment_run
+                .stability_condition
+                .map(|stability_condition| serde_json::from_value(stability_condition).unwrap()),
             result_individual,
         }
     }
@@ -450,7 +574,7 @@ impl From<AdjustmentGeneration> for AdjustmentGenerationOutType {
 }
 
 impl AdjustmentIndividualOutType {
-    fn from_individual(
+    pub fn from_individual(
         conn: &mut PgConnection,
         adjustment_individual: AdjustmentIndividual,
     ) -> ServiceResult<Self> {
@@ -458,11 +582,17 @@ impl AdjustmentIndividualOutType {
             find_concept_values(conn, &vec![adjustment_individual.id]).to_service_result()?;
         let connection_values =
             find_connection_values(conn, &vec![adjustment_individual.id]).to_service_result()?;
+        let connection_function_values =
+            find_connection_function_values(conn, &vec![adjustment_individual.id])
+                .to_service_result()?;
+        let scenario_errors =
+            find_scenario_errors(conn, &vec![adjustment_individual.id]).to_service_result()?;
         Ok(Self {
             id: adjustment_individual.id,
             number: adjustment_individual.number,
             time: adjustment_individual.time,
             error: adjustment_individual.error,
+            cost: adjustment_individual.cost,
             concept_values: concept_values
                 .into_iter()
                 .map(AdjustmentConceptValueOutType::from)
@@ -471,6 +601,14 @@ impl AdjustmentIndividualOutType {
                 .into_iter()
                 .map(AdjustmentConnectionValueOutType::from)
                 .collect(),
+            connection_function_values: connection_function_values
+                .into_iter()
+                .map(AdjustmentConnectionFunctionValueOutType::from)
+                .collect(),
+            scenario_errors: scenario_errors
+                .into_iter()
+                .map(AdjustmentScenarioErrorOutType::from)
+                .collect(),
         })
     }
     fn from_individuals(
@@ -484,6 +622,10 @@ impl AdjustmentIndividualOutType {
         let mut concept_values = find_concept_values(conn, &individual_ids).to_service_result()?;
         let mut connection_values =
             find_connection_values(conn, &individual_ids).to_service_result()?;
+        let mut connection_function_values =
+            find_connection_function_values(conn, &individual_ids).to_service_result()?;
+        let mut scenario_errors =
+            find_scenario_errors(conn, &individual_ids).to_service_result()?;
         let mut result = vec![];
         for adjustment_individual in adjustment_individuals {
             let concept_value_indices = concept_values
@@ -515,8 +657,17 @@ impl AdjustmentIndividualOutType {
                 number: adjustment_individual.number,
                 time: adjustment_individual.time,
                 error: adjustment_individual.error,
+                cost: adjustment_individual.cost,
                 concept_values: concept_out_values,
                 connection_values: connection_out_values,
+                connection_function_values: take_connection_function_values(
+                    &mut connection_function_values,
+                    adjustment_individual.id,
+                ),
+                scenario_errors: take_scenario_errors(
+                    &mut scenario_errors,
+                    adjustment_individual.id,
+                ),
             })
         }
         Ok(result)
@@ -542,3 +693,23 @@ impl From<AdjustmentConnectionValue> for AdjustmentConnectionValueOutType {
         }
     }
 }
+
+impl From<AdjustmentConnectionFunctionValue> for AdjustmentConnectionFunctionValueOutType {
+    fn from(adjustment_connection_function_value: AdjustmentConnectionFunctionValue) -> Self {
+        Self {
+            id: adjustment_connection_function_value.id,
+            connection_id: adjustment_connection_function_value.connection_id,
+            parameter: adjustment_connection_function_value.parameter,
+        }
+    }
+}
+
+impl From<AdjustmentScenarioError> for AdjustmentScenarioErrorOutType {
+    fn from(adjustment_scenario_error: AdjustmentScenarioError) -> Self {
+        Self {
+            id: adjustment_scenario_error.id,
+            scenario_name: adjustment_scenario_error.scenario_name,
+            error: adjustment_scenario_error.error,
+        }
+    }
+}
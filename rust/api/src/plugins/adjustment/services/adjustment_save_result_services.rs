@@ -5,8 +5,8 @@ use crate::plugins::adjustment::models::{AdjustmentIndividual, AdjustmentRun};
 use crate::plugins::adjustment::types::{AdjustmentGenerationOutType, AdjustmentRunActionType};
 use crate::response::{AppError, ServiceResult, ToServiceResult};
 use crate::schema::{
-    adjustment_concept_values, adjustment_connection_values, adjustment_generations,
-    adjustment_individuals, adjustment_runs,
+    adjustment_concept_values, adjustment_connection_function_values, adjustment_connection_values,
+    adjustment_generations, adjustment_individuals, adjustment_runs, adjustment_scenario_errors,
 };
 use crate::web_socket::WebSocketAdjustmentRunService;
 use diesel::prelude::*;
@@ -81,6 +81,11 @@ impl SaveResult<(), AppError> for SaveResultServer {
                                 .as_ref()
                                 .unwrap()
                                 .error),
+                            adjustment_individuals::cost.eq(individual
+                                .fitness
+                                .as_ref()
+                                .unwrap()
+                                .cost),
                         ))
                         .get_result::<AdjustmentIndividual>(conn)?;
                     individual.id = Some(adjustment_individual.id);
@@ -104,6 +109,28 @@ impl SaveResult<(), AppError> for SaveResultServer {
                             ))
                             .execute(conn)?;
                     }
+                    for (connection_id, parameter) in &individual.function_parameters {
+                        diesel::insert_into(adjustment_connection_function_values::table)
+                            .values((
+                                adjustment_connection_function_values::adjustment_individual_id
+                                    .eq(adjustment_individual.id),
+                                adjustment_connection_function_values::connection_id
+                                    .eq(connection_id),
+                                adjustment_connection_function_values::parameter.eq(parameter),
+                            ))
+                            .execute(conn)?;
+                    }
+                    for scenario_error in &individual.fitness.as_ref().unwrap().scenario_errors {
+                        diesel::insert_into(adjustment_scenario_errors::table)
+                            .values((
+                                adjustment_scenario_errors::adjustment_individual_id
+                                    .eq(adjustment_individual.id),
+                                adjustment_scenario_errors::scenario_name
+                                    .eq(&scenario_error.scenario_name),
+                                adjustment_scenario_errors::error.eq(scenario_error.error),
+                            ))
+                            .execute(conn)?;
+                    }
                 }
                 Ok(adjustment_generation)
             })
@@ -1,41 +1,74 @@
-use super::super::models::{AdjustmentRun, DynamicModelType};
-use super::super::types::{AdjustmentInType, AdjustmentRunActionErrorType, AdjustmentRunOutType};
+use super::super::models::{
+    AdjustmentGeneration, AdjustmentRun, AdjustmentRunState, DynamicModelType,
+    ScenarioAggregationType,
+};
+use super::super::types::{
+    AdjustmentInType, AdjustmentRunActionErrorType, AdjustmentRunOutType, AdjustmentSeedInType,
+    ControlOverrideType, ControlOverridesType, ControlRangeType, ScenarioType,
+    StabilityConditionType,
+};
+use super::adjustment_control_services::{self, AdjustmentRunControls};
 use super::adjustment_save_result_services::SaveResultServer;
+use super::adjustment_worker_services::{self, AdjustmentJob, AdjustmentWorkers};
+use super::model_validation_services;
 use super::permission_services;
+use super::stability_analysis_services;
+use crate::db;
 use crate::forbidden_error;
 use crate::locale::Locale;
 use crate::models::User;
 use crate::plugins::adjustment::types::AdjustmentRunActionType;
+use crate::plugins::concept_decay_rates::services as concept_decay_rates_services;
+use crate::plugins::connection_functions::services as connection_functions_services;
+use crate::plugins::connection_functions::types::FunctionPointType;
+use crate::plugins::connection_lags::services as connection_lags_services;
+use crate::plugins::intervention_costs::models::{
+    ConceptInterventionCost, ConnectionInterventionCost,
+};
+use crate::plugins::intervention_costs::services as intervention_costs_services;
 use crate::plugins::Plugins;
 use crate::response::{AppError, ServiceResult, ToServiceResult};
 use crate::schema::{
-    adjustment_runs, concept_constraints, concept_dynamic_models, concepts, connection_constraints,
-    connections, control_concepts, control_connections, target_concepts,
+    adjustment_concept_values, adjustment_connection_function_values, adjustment_connection_values,
+    adjustment_generations, adjustment_individuals, adjustment_runs, concept_constraints,
+    concept_dynamic_models, concepts, connection_constraints, connections, control_concepts,
+    control_connections, target_concepts,
+};
+use crate::services::plugins_data_services::{
+    get_plugin_data, ConstraintData, DecayRateData, DynamicModelData, FunctionData,
+    InterventionCostData, IsControlData, LagData, TargetData,
 };
 use crate::services::{model_services, project_services};
+use crate::types::{ConceptOutType, ConnectionOutType, ModelOutType};
+use crate::validation_error;
 use crate::web_socket::WebSocketAdjustmentRunService;
 use diesel::prelude::*;
 use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use diesel::PgConnection;
 use fuzzy_cognitive_model_common::adjustment::{
-    AdjustmentInput, AdjustmentModel, Concept, Connection, Constraint, DynamicModel, StopCondition,
-    TargetValue,
+    AdjustmentInput, AdjustmentModel, Concept, Connection, Constraint, DynamicModel, Individual,
+    InterventionCost, Scenario, ScenarioAggregation, StopCondition, TargetValue,
+    MAX_CONNECTION_LAG,
 };
+use fuzzy_cognitive_model_common::connection_function::ConnectionFunction as Function;
+use fuzzy_cognitive_model_common::stability::Activation;
 use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 
 pub async fn adjust(
-    mut conn: PgConnection,
+    conn: &mut PgConnection,
     plugins: &Plugins,
     adjustment_run_service: WebSocketAdjustmentRunService,
+    adjustment_run_controls: &AdjustmentRunControls,
+    adjustment_workers: &AdjustmentWorkers,
     user: &User,
     locale: &Locale,
     project_id: i32,
     adjustment_in: AdjustmentInType,
 ) -> ServiceResult<AdjustmentRunActionType<AdjustmentRunOutType>> {
-    let project = project_services::find_project_by_id(&mut conn, project_id)
+    let project = project_services::find_project_by_id(conn, project_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
     if !plugins
         .plugins
@@ -43,21 +76,23 @@ pub async fn adjust(
         .unwrap()
         .lock()
         .unwrap()
-        .is_enabled(&mut conn, project_id)?
+        .is_enabled(conn, project_id)?
     {
         return forbidden_error!("adjustment_plugin_is_not_enabled_error");
     }
-    permission_services::can_adjust(&mut conn, &project, user.id)?;
-    let model_copy = model_services::save_model_copy(&mut conn, plugins, user, project_id)?;
-    let adjustment_model = get_adjustment_model(&mut conn, project_id, adjustment_in)?;
-    let adjustment_run = create_adjustment_run(
-        &mut conn,
+    permission_services::can_adjust(conn, &project, user.id)?;
+    let diagnostics = model_validation_services::get_diagnostics(
+        conn,
         project_id,
-        model_copy.id,
-        &adjustment_model.adjustment_input,
+        &adjustment_in,
+        &locale.get_locale(),
     )?;
+    model_validation_services::check_diagnostics(&diagnostics)?;
+    let (adjustment_run, adjustment_model) = conn.transaction(|conn| {
+        create_adjustment_run_with_model(conn, plugins, user, project_id, adjustment_in)
+    })?;
     let adjustment_run_id = adjustment_run.id;
-    let adjustment_run_out = AdjustmentRunOutType::from_adjustment_run(&mut conn, adjustment_run)?;
+    let adjustment_run_out = AdjustmentRunOutType::from_adjustment_run(conn, adjustment_run)?;
     let adjustment_run_action = AdjustmentRunActionType::new(
         project.id,
         adjustment_run_id,
@@ -67,26 +102,57 @@ pub async fn adjust(
     adjustment_run_service
         .notify(adjustment_run_action.clone())
         .await;
-    let locale = locale.get_locale();
-    rocket::tokio::spawn(run_adjust(
-        conn,
-        adjustment_run_service,
-        adjustment_model,
-        project_id,
-        adjustment_run_id,
-        locale,
-    ));
+    let control = adjustment_run_controls.register(adjustment_run_id).await;
+    adjustment_workers
+        .submit(
+            adjustment_run_service,
+            adjustment_run_controls.clone(),
+            AdjustmentJob {
+                project_id,
+                user_id: user.id,
+                adjustment_run_id,
+                adjustment_model,
+                control,
+                locale: locale.get_locale(),
+            },
+        )
+        .await;
     Ok(adjustment_run_action)
 }
 
-async fn run_adjust(
-    conn: PgConnection,
+pub async fn run_adjust(
     adjustment_run_service: WebSocketAdjustmentRunService,
-    mut adjustment_model: AdjustmentModel,
-    project_id: i32,
-    adjustment_run_id: i32,
-    locale: String,
+    adjustment_run_controls: AdjustmentRunControls,
+    job: AdjustmentJob,
 ) -> () {
+    let AdjustmentJob {
+        project_id,
+        adjustment_run_id,
+        mut adjustment_model,
+        mut control,
+        locale,
+        ..
+    } = job;
+    let mut conn = db::establish_connection();
+    if let Err(app_error) = adjustment_worker_services::start_adjustment_run(
+        &mut conn,
+        adjustment_run_service.clone(),
+        project_id,
+        adjustment_run_id,
+    )
+    .await
+    {
+        return notify_error(
+            &mut conn,
+            adjustment_run_service,
+            adjustment_run_controls,
+            app_error,
+            project_id,
+            adjustment_run_id,
+            locale,
+        )
+        .await;
+    }
     let adjustment_run_service_copy = adjustment_run_service.clone();
     let mut save_result = SaveResultServer {
         conn,
@@ -96,11 +162,16 @@ async fn run_adjust(
     adjustment_model.start();
     let mut run_next = true;
     while run_next {
+        if !adjustment_control_services::wait_while_paused(&mut control).await {
+            break;
+        }
         run_next = match adjustment_model.next(&mut save_result).await {
             Ok(run_next) => run_next,
             Err(app_error) => {
                 return notify_error(
+                    &mut save_result.conn,
                     adjustment_run_service_copy,
+                    adjustment_run_controls,
                     app_error,
                     project_id,
                     adjustment_run_id,
@@ -109,11 +180,12 @@ async fn run_adjust(
                 .await;
             }
         };
-        rocket::tokio::task::yield_now().await;
     }
     if let Err(app_error) = adjustment_model.finish(&mut save_result).await {
-        notify_error(
+        return notify_error(
+            &mut save_result.conn,
             adjustment_run_service_copy,
+            adjustment_run_controls,
             app_error,
             project_id,
             adjustment_run_id,
@@ -121,10 +193,21 @@ async fn run_adjust(
         )
         .await;
     }
+    let _ = adjustment_control_services::finish_adjustment(
+        &mut save_result.conn,
+        &adjustment_run_controls,
+        adjustment_run_service_copy,
+        project_id,
+        adjustment_run_id,
+        false,
+    )
+    .await;
 }
 
 async fn notify_error(
+    conn: &mut PgConnection,
     adjustment_run_service: WebSocketAdjustmentRunService,
+    adjustment_run_controls: AdjustmentRunControls,
     app_error: AppError,
     project_id: i32,
     adjustment_run_id: i32,
@@ -140,6 +223,15 @@ async fn notify_error(
     adjustment_run_service
         .notify_error(model_action_error)
         .await;
+    let _ = adjustment_control_services::finish_adjustment(
+        conn,
+        &adjustment_run_controls,
+        adjustment_run_service,
+        project_id,
+        adjustment_run_id,
+        true,
+    )
+    .await;
 }
 
 fn get_adjustment_model(
@@ -147,12 +239,23 @@ fn get_adjustment_model(
     project_id: i32,
     adjustment_in: AdjustmentInType,
 ) -> ServiceResult<AdjustmentModel> {
-    let concepts = get_concepts(conn, project_id)?;
+    let control_overrides = adjustment_in.control_overrides.clone().unwrap_or_default();
+    let concepts =
+        apply_concept_overrides(get_concepts(conn, project_id)?, &control_overrides.concepts)?;
     let concepts_map = HashMap::from_iter(
         concepts
             .iter()
             .map(|concept| (concept.id, Arc::clone(concept))),
     );
+    if let Some(scenarios) = &adjustment_in.scenarios {
+        check_scenarios(scenarios, &concepts_map)?;
+    }
+    if let Some(time_step) = adjustment_in.time_step {
+        check_time_step(time_step)?;
+    }
+    if let Some(stability_condition) = &adjustment_in.stability_condition {
+        stability_analysis_services::check_stability_condition(stability_condition)?;
+    }
     let control_concepts = concepts
         .iter()
         .filter(|concept| concept.is_control)
@@ -168,7 +271,10 @@ fn get_adjustment_model(
         .filter(|concept| !concept.is_control && !concept.is_target)
         .cloned()
         .collect();
-    let connections = get_connections(conn, project_id)?;
+    let connections = apply_connection_overrides(
+        get_connections(conn, project_id)?,
+        &control_overrides.connections,
+    )?;
     let connections_map = HashMap::from_iter(
         connections
             .iter()
@@ -190,10 +296,242 @@ fn get_adjustment_model(
     ))
 }
 
+fn apply_concept_overrides(
+    concepts: Vec<Arc<Concept>>,
+    control_overrides: &[ControlOverrideType],
+) -> ServiceResult<Vec<Arc<Concept>>> {
+    for control_override in control_overrides {
+        if !concepts
+            .iter()
+            .any(|concept| concept.id == control_override.id && concept.is_control)
+        {
+            return validation_error!("concept_not_control_error");
+        }
+        check_control_override(control_override, 0.0)?;
+    }
+    concepts
+        .into_iter()
+        .map(|concept| {
+            match control_overrides
+                .iter()
+                .find(|control_override| control_override.id == concept.id)
+            {
+                Some(control_override) => {
+                    let mut concept = Arc::try_unwrap(concept).ok().unwrap();
+                    if let Some(pinned_value) = control_override.pinned_value {
+                        concept.value = pinned_value;
+                        concept.is_control = false;
+                    } else if control_override.is_excluded {
+                        concept.is_control = false;
+                    } else if let Some(range) = &control_override.range {
+                        concept.constraint =
+                            Some(narrow_constraint(concept.constraint.take(), range)?);
+                    }
+                    Ok(Arc::new(concept))
+                }
+                None => Ok(concept),
+            }
+        })
+        .collect()
+}
+
+fn apply_connection_overrides(
+    connections: Vec<Arc<Connection>>,
+    control_overrides: &[ControlOverrideType],
+) -> ServiceResult<Vec<Arc<Connection>>> {
+    for control_override in control_overrides {
+        if !connections
+            .iter()
+            .any(|connection| connection.id == control_override.id && connection.is_control)
+        {
+            return validation_error!("connection_not_control_error");
+        }
+        check_control_override(control_override, -1.0)?;
+    }
+    connections
+        .into_iter()
+        .map(|connection| {
+            match control_overrides
+                .iter()
+                .find(|control_override| control_override.id == connection.id)
+            {
+                Some(control_override) => {
+                    let mut connection = Arc::try_unwrap(connection).ok().unwrap();
+                    if let Some(pinned_value) = control_override.pinned_value {
+                        connection.value = pinned_value;
+                        connection.is_control = false;
+                    } else if control_override.is_excluded {
+                        connection.is_control = false;
+                    } else if let Some(range) = &control_override.range {
+                        connection.constraint =
+                            Some(narrow_constraint(connection.constraint.take(), range)?);
+                    }
+                    Ok(Arc::new(connection))
+                }
+                None => Ok(connection),
+            }
+        })
+        .collect()
+}
+
+fn check_control_override(
+    control_override: &ControlOverrideType,
+    min_value: f64,
+) -> ServiceResult<()> {
+    if let Some(pinned_value) = control_override.pinned_value {
+        if pinned_value < min_value || pinned_value > 1.0 {
+            return validation_error!(
+                "control_override_value_error",
+                value = pinned_value,
+                range = format!("[{}; 1]", min_value)
+            );
+        }
+    }
+    if let Some(range) = &control_override.range {
+        if range.min_value < min_value || range.max_value > 1.0 {
+            return generate_control_range_error(range);
+        }
+    }
+    Ok(())
+}
+
+fn narrow_constraint(
+    constraint: Option<Constraint>,
+    range: &ControlRangeType,
+) -> ServiceResult<Constraint> {
+    let mut narrowed_constraint = Constraint {
+        min_value: range.min_value,
+        include_min_value: range.include_min_value,
+        max_value: range.max_value,
+        include_max_value: range.include_max_value,
+    };
+    if let Some(constraint) = constraint {
+        if constraint.min_value > narrowed_constraint.min_value
+            || constraint.min_value == narrowed_constraint.min_value
+                && !constraint.include_min_value
+        {
+            narrowed_constraint.min_value = constraint.min_value;
+            narrowed_constraint.include_min_value = constraint.include_min_value;
+        }
+        if constraint.max_value < narrowed_constraint.max_value
+            || constraint.max_value == narrowed_constraint.max_value
+                && !constraint.include_max_value
+        {
+            narrowed_constraint.max_value = constraint.max_value;
+            narrowed_constraint.include_max_value = constraint.include_max_value;
+        }
+    }
+    if narrowed_constraint.min_value > narrowed_constraint.max_value
+        || narrowed_constraint.min_value == narrowed_constraint.max_value
+            && !(narrowed_constraint.include_min_value && narrowed_constraint.include_max_value)
+    {
+        return generate_control_range_error(range);
+    }
+    Ok(narrowed_constraint)
+}
+
+fn generate_control_range_error<T>(range: &ControlRangeType) -> ServiceResult<T> {
+    let range = format!(
+        "{}{}; {}{}",
+        if range.include_min_value { "[" } else { "(" },
+        range.min_value,
+        range.max_value,
+        if range.include_max_value { "]" } else { ")" },
+    );
+    validation_error!("control_override_range_error", range = range)
+}
+
+pub fn check_time_step(time_step: f64) -> ServiceResult<()> {
+    if time_step <= 0.0 || time_step > 1.0 {
+        let time_step = time_step.to_string();
+        return validation_error!("time_step_error", time_step = &time_step);
+    }
+    Ok(())
+}
+
+fn check_scenarios(
+    scenarios: &[ScenarioType],
+    concepts_map: &HashMap<i32, Arc<Concept>>,
+) -> ServiceResult<()> {
+    for (index, scenario) in scenarios.iter().enumerate() {
+        if scenarios[..index]
+            .iter()
+            .any(|other_scenario| other_scenario.name == scenario.name)
+        {
+            let name = scenario.name.to_owned();
+            return validation_error!("scenario_name_duplication_error", name = &name);
+        }
+        for (value_index, concept_value) in scenario.concept_values.iter().enumerate() {
+            let concept = match concepts_map.get(&concept_value.concept_id) {
+                Some(concept) => concept,
+                None => return validation_error!("concept_not_found_error"),
+            };
+            // Control values are searched for and target values are measured by the adjustment
+            if concept.is_control || concept.is_target {
+                return validation_error!("scenario_concept_type_error");
+            }
+            if scenario.concept_values[..value_index]
+                .iter()
+                .any(|other_value| other_value.concept_id == concept_value.concept_id)
+            {
+                let name = scenario.name.to_owned();
+                return validation_error!("scenario_concept_duplication_error", name = &name);
+            }
+            if concept_value.value < 0.0 || concept_value.value > 1.0 {
+                return validation_error!(
+                    "scenario_concept_value_error",
+                    value = concept_value.value
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn create_adjustment_run_with_model(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    adjustment_in: AdjustmentInType,
+) -> ServiceResult<(AdjustmentRun, AdjustmentModel)> {
+    let seed = adjustment_in.seed.clone();
+    let control_overrides = adjustment_in.control_overrides.clone();
+    let scenarios = adjustment_in.scenarios.clone();
+    let stability_condition = adjustment_in.stability_condition.clone();
+    let mut adjustment_model = get_adjustment_model(conn, project_id, adjustment_in)?;
+    let seed_generation_id = match seed {
+        Some(seed) => {
+            let (seed_generation_id, seed_individuals) =
+                get_seed_individuals(conn, project_id, seed)?;
+            adjustment_model.seed_individuals = seed_individuals;
+            Some(seed_generation_id)
+        }
+        None => None,
+    };
+    // The model copy is saved only after the request has passed validation
+    let model_copy = model_services::save_model_copy(conn, plugins, user, project_id)?;
+    let adjustment_run = create_adjustment_run(
+        conn,
+        project_id,
+        model_copy.id,
+        seed_generation_id,
+        control_overrides,
+        scenarios,
+        stability_condition,
+        &adjustment_model.adjustment_input,
+    )?;
+    Ok((adjustment_run, adjustment_model))
+}
+
 fn create_adjustment_run(
     conn: &mut PgConnection,
     project_id: i32,
     model_copy_id: i32,
+    seed_generation_id: Option<i32>,
+    control_overrides: Option<ControlOverridesType>,
+    scenarios: Option<Vec<ScenarioType>>,
+    stability_condition: Option<StabilityConditionType>,
     adjustment_input: &AdjustmentInput,
 ) -> ServiceResult<AdjustmentRun> {
     diesel::insert_into(adjustment_runs::table)
@@ -214,12 +552,153 @@ fn create_adjustment_run(
             adjustment_runs::max_without_improvements
                 .eq(&adjustment_input.stop_condition.max_without_improvements),
             adjustment_runs::error.eq(&adjustment_input.stop_condition.error),
+            adjustment_runs::budget.eq(&adjustment_input.budget),
+            adjustment_runs::minimize_cost.eq(&adjustment_input.minimize_cost),
+            adjustment_runs::state.eq(AdjustmentRunState::Queued),
+            adjustment_runs::seed_generation_id.eq(seed_generation_id),
+            adjustment_runs::control_overrides.eq(control_overrides
+                .map(|control_overrides| serde_json::to_value(control_overrides).unwrap())),
+            adjustment_runs::scenarios
+                .eq(scenarios.map(|scenarios| serde_json::to_value(scenarios).unwrap())),
+            adjustment_runs::scenario_aggregation_type.eq(ScenarioAggregationType::from(
+                adjustment_input.scenario_aggregation.clone(),
+            )),
+            adjustment_runs::time_step.eq(&adjustment_input.time_step),
+            adjustment_runs::stability_condition.eq(stability_condition
+                .map(|stability_condition| serde_json::to_value(stability_condition).unwrap())),
         ))
         .get_result::<AdjustmentRun>(conn)
         .to_service_result()
 }
 
-fn get_concepts(conn: &mut PgConnection, project_id: i32) -> ServiceResult<Vec<Arc<Concept>>> {
+fn get_seed_individuals(
+    conn: &mut PgConnection,
+    project_id: i32,
+    seed: AdjustmentSeedInType,
+) -> ServiceResult<(i32, Vec<Individual>)> {
+    let adjustment_run = match adjustment_runs::table
+        .filter(adjustment_runs::id.eq(seed.adjustment_run_id))
+        .filter(adjustment_runs::project_id.eq(project_id))
+        .first::<AdjustmentRun>(conn)
+        .optional()
+        .to_service_result()?
+    {
+        Some(adjustment_run) => adjustment_run,
+        None => return validation_error!("adjustment_run_not_found_error"),
+    };
+    let generation_query = adjustment_generations::table
+        .filter(adjustment_generations::adjustment_run_id.eq(adjustment_run.id))
+        .into_boxed();
+    let generation_query = match seed.generation_id {
+        Some(generation_id) => {
+            generation_query.filter(adjustment_generations::id.eq(generation_id))
+        }
+        None => generation_query.order(adjustment_generations::number.desc()),
+    };
+    let adjustment_generation = match generation_query
+        .first::<AdjustmentGeneration>(conn)
+        .optional()
+        .to_service_result()?
+    {
+        Some(adjustment_generation) => adjustment_generation,
+        None if seed.generation_id.is_some() => {
+            return validation_error!("adjustment_generation_not_found_error")
+        }
+        None => return validation_error!("adjustment_run_has_no_generations_error"),
+    };
+    let individual_ids = adjustment_individuals::table
+        .filter(adjustment_individuals::adjustment_generation_id.eq(adjustment_generation.id))
+        .order(adjustment_individuals::number)
+        .select(adjustment_individuals::id)
+        .get_results::<i32>(conn)
+        .to_service_result()?;
+    let mut individuals = individual_ids
+        .iter()
+        .map(|id| {
+            (
+                *id,
+                Individual {
+                    id: None,
+                    concepts: HashMap::new(),
+                    connections: HashMap::new(),
+                    function_parameters: HashMap::new(),
+                    fitness: None,
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    for (individual_id, concept_id, value) in adjustment_concept_values::table
+        .filter(adjustment_concept_values::adjustment_individual_id.eq_any(&individual_ids))
+        .select((
+            adjustment_concept_values::adjustment_individual_id,
+            adjustment_concept_values::concept_id,
+            adjustment_concept_values::value,
+        ))
+        .get_results::<(i32, i32, f64)>(conn)
+        .to_service_result()?
+    {
+        individuals
+            .get_mut(&individual_id)
+            .unwrap()
+            .concepts
+            .insert(concept_id, value);
+    }
+    for (individual_id, connection_id, value) in adjustment_connection_values::table
+        .filter(adjustment_connection_values::adjustment_individual_id.eq_any(&individual_ids))
+        .select((
+            adjustment_connection_values::adjustment_individual_id,
+            adjustment_connection_values::connection_id,
+            adjustment_connection_values::value,
+        ))
+        .get_results::<(i32, i32, f64)>(conn)
+        .to_service_result()?
+    {
+        individuals
+            .get_mut(&individual_id)
+            .unwrap()
+            .connections
+            .insert(connection_id, value);
+    }
+    for (individual_id, connection_id, parameter) in adjustment_connection_function_values::table
+        .filter(
+            adjustment_connection_function_values::adjustment_individual_id.eq_any(&individual_ids),
+        )
+        .select((
+            adjustment_connection_function_values::adjustment_individual_id,
+            adjustment_connection_function_values::connection_id,
+            adjustment_connection_function_values::parameter,
+        ))
+        .get_results::<(i32, i32, f64)>(conn)
+        .to_service_result()?
+    {
+        individuals
+            .get_mut(&individual_id)
+            .unwrap()
+            .function_parameters
+            .insert(connection_id, parameter);
+    }
+    let individuals = individual_ids
+        .iter()
+        .map(|id| individuals.remove(id).unwrap())
+        .collect::<Vec<_>>();
+    Ok((adjustment_generation.id, individuals))
+}
+
+pub fn get_concepts(conn: &mut PgConnection, project_id: i32) -> ServiceResult<Vec<Arc<Concept>>> {
+    let concept_intervention_costs = HashMap::<i32, ConceptInterventionCost>::from_iter(
+        intervention_costs_services::find_project_concept_intervention_costs(conn, project_id)
+            .to_service_result()?
+            .into_iter()
+            .map(|cost| (cost.concept_id, cost)),
+    );
+    let concept_decay_rates = HashMap::<i32, f64>::from_iter(
+        concept_decay_rates_services::find_project_concept_decay_rates(conn, project_id)
+            .to_service_result()?
+            .into_iter()
+            .map(|concept_decay_rate| {
+                (concept_decay_rate.concept_id, concept_decay_rate.decay_rate)
+            }),
+    );
     let concepts = concepts::table
         .inner_join(control_concepts::table)
         .inner_join(target_concepts::table)
@@ -305,6 +784,13 @@ fn get_concepts(conn: &mut PgConnection, project_id: i32) -> ServiceResult<Vec<A
                     target_value,
                     constraint,
                     dynamic_model: dynamic_model_type.map(DynamicModel::from),
+                    cost: concept_intervention_costs
+                        .get(&id)
+                        .map(|cost| InterventionCost {
+                            linear_cost: cost.linear_cost,
+                            fixed_cost: cost.fixed_cost,
+                        }),
+                    decay_rate: concept_decay_rates.get(&id).cloned(),
                 })
             },
         )
@@ -312,10 +798,40 @@ fn get_concepts(conn: &mut PgConnection, project_id: i32) -> ServiceResult<Vec<A
     Ok(concepts)
 }
 
-fn get_connections(
+pub fn get_connections(
     conn: &mut PgConnection,
     project_id: i32,
 ) -> ServiceResult<Vec<Arc<Connection>>> {
+    let connection_intervention_costs = HashMap::<i32, ConnectionInterventionCost>::from_iter(
+        intervention_costs_services::find_project_connection_intervention_costs(conn, project_id)
+            .to_service_result()?
+            .into_iter()
+            .map(|cost| (cost.connection_id, cost)),
+    );
+    let connection_lags = HashMap::<i32, i32>::from_iter(
+        connection_lags_services::find_project_connection_lags(conn, project_id)
+            .to_service_result()?
+            .into_iter()
+            .map(|connection_lag| (connection_lag.connection_id, connection_lag.lag)),
+    );
+    // Lags may come from an imported bundle or a snapshot, so they are checked again
+    for lag in connection_lags.values() {
+        check_connection_lag(*lag)?;
+    }
+    let connection_functions = HashMap::<i32, (Function, bool)>::from_iter(
+        connection_functions_services::find_project_connection_functions(conn, project_id)
+            .to_service_result()?
+            .into_iter()
+            .map(|connection_function| {
+                (
+                    connection_function.connection_id,
+                    (
+                        Function::from(&connection_function),
+                        connection_function.is_control,
+                    ),
+                )
+            }),
+    );
     let connections = connections::table
         .inner_join(control_connections::table)
         .inner_join(connection_constraints::table)
@@ -365,6 +881,21 @@ fn get_connections(
                     target_id,
                     is_control,
                     constraint,
+                    cost: connection_intervention_costs
+                        .get(&id)
+                        .map(|cost| InterventionCost {
+                            linear_cost: cost.linear_cost,
+                            fixed_cost: cost.fixed_cost,
+                        }),
+                    lag: connection_lags.get(&id).cloned().unwrap_or(0),
+                    function: connection_functions
+                        .get(&id)
+                        .map(|(function, _)| function.clone())
+                        .unwrap_or_default(),
+                    is_function_control: connection_functions
+                        .get(&id)
+                        .map(|(_, is_control)| *is_control)
+                        .unwrap_or(false),
                 })
             },
         )
@@ -372,6 +903,135 @@ fn get_connections(
     Ok(connections)
 }
 
+/// Concepts and connections of the model copy saved for the adjustment run,
+/// with the control overrides of the run applied.
+pub fn get_adjustment_run_model(
+    conn: &mut PgConnection,
+    adjustment_run: &AdjustmentRun,
+) -> ServiceResult<(Vec<Arc<Concept>>, Vec<Arc<Connection>>)> {
+    let model_copy = model_services::find_model_copy_by_id(conn, adjustment_run.model_copy_id)
+        .to_service_result_find(String::from("model_copy_not_found_error"))?;
+    let model_out = serde_json::from_value::<ModelOutType>(model_copy.model).unwrap();
+    let control_overrides = adjustment_run
+        .control_overrides
+        .clone()
+        .map(|control_overrides| {
+            serde_json::from_value::<ControlOverridesType>(control_overrides).unwrap()
+        })
+        .unwrap_or_default();
+    let concepts = apply_concept_overrides(
+        model_out
+            .concepts
+            .iter()
+            .map(get_model_copy_concept)
+            .collect(),
+        &control_overrides.concepts,
+    )?;
+    let connections = apply_connection_overrides(
+        model_out
+            .connections
+            .iter()
+            .map(get_model_copy_connection)
+            .collect::<ServiceResult<Vec<_>>>()?,
+        &control_overrides.connections,
+    )?;
+    Ok((concepts, connections))
+}
+
+fn get_model_copy_concept(concept_out: &ConceptOutType) -> Arc<Concept> {
+    let plugins_data = &concept_out.plugins_data;
+    let target_data = get_plugin_data::<TargetData>(plugins_data, "targetConcepts")
+        .filter(|target_data| target_data.is_target);
+    let constraint_data = get_plugin_data::<ConstraintData>(plugins_data, "conceptConstraints")
+        .filter(|constraint_data| constraint_data.has_constraint);
+    Arc::new(Concept {
+        id: concept_out.id,
+        value: concept_out.value.unwrap_or(0.0),
+        is_control: get_plugin_data::<IsControlData>(plugins_data, "controlConcepts")
+            .map(|is_control_data| is_control_data.is_control)
+            .unwrap_or(false),
+        is_target: target_data.is_some(),
+        target_value: target_data.map(|target_data| TargetValue {
+            min_value: target_data.min_value,
+            include_min_value: target_data.include_min_value,
+            max_value: target_data.max_value,
+            include_max_value: target_data.include_max_value,
+        }),
+        constraint: constraint_data.map(|constraint_data| Constraint {
+            min_value: constraint_data.min_value,
+            include_min_value: constraint_data.include_min_value,
+            max_value: constraint_data.max_value,
+            include_max_value: constraint_data.include_max_value,
+        }),
+        dynamic_model: get_plugin_data::<DynamicModelData>(plugins_data, "adjustment")
+            .and_then(|dynamic_model_data| dynamic_model_data.dynamic_model_type)
+            .map(DynamicModel::from),
+        cost: get_plugin_data::<InterventionCostData>(plugins_data, "interventionCosts").map(
+            |cost_data| InterventionCost {
+                linear_cost: cost_data.linear_cost,
+                fixed_cost: cost_data.fixed_cost,
+            },
+        ),
+        decay_rate: get_plugin_data::<DecayRateData>(plugins_data, "conceptDecayRates")
+            .map(|decay_rate_data| decay_rate_data.decay_rate),
+    })
+}
+
+fn get_model_copy_connection(connection_out: &ConnectionOutType) -> ServiceResult<Arc<Connection>> {
+    let plugins_data = &connection_out.plugins_data;
+    let constraint_data = get_plugin_data::<ConstraintData>(plugins_data, "connectionConstraints")
+        .filter(|constraint_data| constraint_data.has_constraint);
+    let function_data = get_plugin_data::<FunctionData>(plugins_data, "connectionFunctions");
+    let lag = get_plugin_data::<LagData>(plugins_data, "connectionLags")
+        .map(|lag_data| lag_data.lag)
+        .unwrap_or(0);
+    check_connection_lag(lag)?;
+    Ok(Arc::new(Connection {
+        id: connection_out.id,
+        value: connection_out.value,
+        source_id: connection_out.source_id,
+        target_id: connection_out.target_id,
+        is_control: get_plugin_data::<IsControlData>(plugins_data, "controlConnections")
+            .map(|is_control_data| is_control_data.is_control)
+            .unwrap_or(false),
+        constraint: constraint_data.map(|constraint_data| Constraint {
+            min_value: constraint_data.min_value,
+            include_min_value: constraint_data.include_min_value,
+            max_value: constraint_data.max_value,
+            include_max_value: constraint_data.include_max_value,
+        }),
+        cost: get_plugin_data::<InterventionCostData>(plugins_data, "interventionCosts").map(
+            |cost_data| InterventionCost {
+                linear_cost: cost_data.linear_cost,
+                fixed_cost: cost_data.fixed_cost,
+            },
+        ),
+        lag,
+        function: function_data
+            .as_ref()
+            .map(|function_data| {
+                connection_functions_services::get_function(
+                    &function_data.function_type,
+                    function_data.parameter,
+                    &serde_json::from_value::<Vec<FunctionPointType>>(function_data.points.clone())
+                        .unwrap_or_default(),
+                )
+            })
+            .unwrap_or_default(),
+        is_function_control: function_data
+            .map(|function_data| function_data.is_control)
+            .unwrap_or(false),
+    }))
+}
+
+fn check_connection_lag(lag: i32) -> ServiceResult<()> {
+    if lag < 0 || lag > MAX_CONNECTION_LAG {
+        let max_lag = MAX_CONNECTION_LAG.to_string();
+        return validation_error!("connection_lag_max_error", max_lag = &max_lag);
+    }
+    Ok(())
+}
+
 impl From<DynamicModelType> for DynamicModel {
     fn from(dynamic_model_type: DynamicModelType) -> Self {
         match dynamic_model_type {
@@ -379,6 +1039,7 @@ impl From<DynamicModelType> for DynamicModel {
             DynamicModelType::DeltaValue => Self::DeltaValue,
             DynamicModelType::ValueDelta => Self::ValueDelta,
             DynamicModelType::ValueValue => Self::ValueValue,
+            DynamicModelType::Continuous => Self::Continuous,
         }
     }
 }
@@ -390,6 +1051,44 @@ impl From<DynamicModel> for DynamicModelType {
             DynamicModel::DeltaValue => Self::DeltaValue,
             DynamicModel::ValueDelta => Self::ValueDelta,
             DynamicModel::ValueValue => Self::ValueValue,
+            DynamicModel::Continuous => Self::Continuous,
+        }
+    }
+}
+
+impl From<ScenarioAggregationType> for ScenarioAggregation {
+    fn from(scenario_aggregation_type: ScenarioAggregationType) -> Self {
+        match scenario_aggregation_type {
+            ScenarioAggregationType::WorstCase => Self::WorstCase,
+            ScenarioAggregationType::Mean => Self::Mean,
+        }
+    }
+}
+
+impl From<ScenarioAggregation> for ScenarioAggregationType {
+    fn from(scenario_aggregation: ScenarioAggregation) -> Self {
+        match scenario_aggregation {
+            ScenarioAggregation::WorstCase => Self::WorstCase,
+            ScenarioAggregation::Mean => Self::Mean,
+        }
+    }
+}
+
+impl From<ScenarioType> for Scenario {
+    fn from(scenario: ScenarioType) -> Self {
+        let mut initial_values = HashMap::new();
+        let mut clamped_values = HashMap::new();
+        for concept_value in scenario.concept_values {
+            if concept_value.is_clamped {
+                clamped_values.insert(concept_value.concept_id, concept_value.value);
+            } else {
+                initial_values.insert(concept_value.concept_id, concept_value.value);
+            }
+        }
+        Self {
+            name: scenario.name,
+            initial_values,
+            clamped_values,
         }
     }
 }
@@ -409,6 +1108,20 @@ impl From<AdjustmentInType> for AdjustmentInput {
                 max_without_improvements: adjustment_in.stop_condition.max_without_improvements,
                 error: adjustment_in.stop_condition.error,
             },
+            budget: adjustment_in.budget,
+            minimize_cost: adjustment_in.minimize_cost.unwrap_or(false),
+            scenarios: adjustment_in
+                .scenarios
+                .unwrap_or_default()
+                .into_iter()
+                .map(Scenario::from)
+                .collect(),
+            scenario_aggregation: adjustment_in
+                .scenario_aggregation_type
+                .map(ScenarioAggregation::from)
+                .unwrap_or_default(),
+            time_step: adjustment_in.time_step,
+            stability_activation: adjustment_in.stability_condition.map(Activation::from),
         }
     }
 }
@@ -0,0 +1,313 @@
+use super::super::models::{AdjustmentRun, AdjustmentRunState};
+use super::super::types::{AdjustmentRunActionType, AdjustmentRunOutType};
+use super::adjustment_control_services::{self, AdjustmentRunControls};
+use super::{adjustment_out_services, adjustment_services, permission_services};
+use crate::db;
+use crate::models::User;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::adjustment_runs;
+use crate::web_socket::WebSocketAdjustmentRunService;
+use diesel::prelude::*;
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::adjustment::AdjustmentModel;
+use rocket::tokio::runtime::{Builder, Runtime};
+use rocket::tokio::sync::watch::Receiver;
+use rocket::tokio::sync::Mutex;
+use std::collections::VecDeque;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::thread;
+
+pub struct AdjustmentJob {
+    pub project_id: i32,
+    pub user_id: i32,
+    pub adjustment_run_id: i32,
+    pub adjustment_model: AdjustmentModel,
+    pub control: Receiver<AdjustmentRunState>,
+    pub locale: String,
+}
+
+struct RunningJob {
+    project_id: i32,
+    user_id: i32,
+    adjustment_run_id: i32,
+    is_paused: bool,
+}
+
+struct AdjustmentWorkersState {
+    queue: VecDeque<AdjustmentJob>,
+    running: Vec<RunningJob>,
+}
+
+/// Bounded pool of dedicated threads for CPU-bound adjustment runs.
+/// Runs that exceed the global limit or the per-project/per-user quotas wait in a FIFO queue.
+/// Paused runs do not hold a slot, so queued runs may start while they are paused.
+#[derive(Clone)]
+pub struct AdjustmentWorkers {
+    state: Arc<Mutex<AdjustmentWorkersState>>,
+    max_workers: usize,
+    project_quota: usize,
+    user_quota: usize,
+}
+
+impl AdjustmentWorkers {
+    pub fn new(max_workers: usize, project_quota: usize, user_quota: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(AdjustmentWorkersState {
+                queue: VecDeque::new(),
+                running: vec![],
+            })),
+            max_workers,
+            project_quota,
+            user_quota,
+        }
+    }
+    pub async fn submit(
+        &self,
+        adjustment_run_service: WebSocketAdjustmentRunService,
+        adjustment_run_controls: AdjustmentRunControls,
+        job: AdjustmentJob,
+    ) -> () {
+        self.state.lock().await.queue.push_back(job);
+        self.dispatch(adjustment_run_service, adjustment_run_controls)
+            .await;
+    }
+    pub async fn remove_queued(&self, adjustment_run_id: i32) -> bool {
+        let mut state = self.state.lock().await;
+        match state
+            .queue
+            .iter()
+            .position(|job| job.adjustment_run_id == adjustment_run_id)
+        {
+            Some(index) => {
+                state.queue.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+    pub async fn set_paused(
+        &self,
+        adjustment_run_service: WebSocketAdjustmentRunService,
+        adjustment_run_controls: AdjustmentRunControls,
+        adjustment_run_id: i32,
+        is_paused: bool,
+    ) {
+        if let Some(running_job) = self
+            .state
+            .lock()
+            .await
+            .running
+            .iter_mut()
+            .find(|running_job| running_job.adjustment_run_id == adjustment_run_id)
+        {
+            running_job.is_paused = is_paused;
+        }
+        self.dispatch(adjustment_run_service, adjustment_run_controls)
+            .await;
+    }
+    pub async fn get_queue_position(&self, adjustment_run_id: i32) -> Option<i32> {
+        self.state
+            .lock()
+            .await
+            .queue
+            .iter()
+            .position(|job| job.adjustment_run_id == adjustment_run_id)
+            .map(|index| index as i32 + 1)
+    }
+    pub async fn dispatch(
+        &self,
+        adjustment_run_service: WebSocketAdjustmentRunService,
+        adjustment_run_controls: AdjustmentRunControls,
+    ) -> () {
+        let mut state = self.state.lock().await;
+        let mut started = vec![];
+        let mut index = 0;
+        while index < state.queue.len() && count_active(&state.running) < self.max_workers {
+            if self.can_start(&state, &state.queue[index]) {
+                let job = state.queue.remove(index).unwrap();
+                state.running.push(RunningJob {
+                    project_id: job.project_id,
+                    user_id: job.user_id,
+                    adjustment_run_id: job.adjustment_run_id,
+                    is_paused: false,
+                });
+                started.push(job);
+            } else {
+                index += 1;
+            }
+        }
+        let queue_positions = state
+            .queue
+            .iter()
+            .enumerate()
+            .map(|(index, job)| (job.project_id, job.adjustment_run_id, index as i32 + 1))
+            .collect::<Vec<_>>();
+        drop(state);
+        for job in started {
+            self.spawn(
+                adjustment_run_service.clone(),
+                adjustment_run_controls.clone(),
+                job,
+            );
+        }
+        for (project_id, adjustment_run_id, queue_position) in queue_positions {
+            adjustment_run_service
+                .notify(AdjustmentRunActionType::new(
+                    project_id,
+                    adjustment_run_id,
+                    String::from("adjustmentQueuePosition"),
+                    queue_position,
+                ))
+                .await;
+        }
+    }
+    fn can_start(&self, state: &AdjustmentWorkersState, job: &AdjustmentJob) -> bool {
+        let project_runs = state
+            .running
+            .iter()
+            .filter(|running_job| {
+                !running_job.is_paused && running_job.project_id == job.project_id
+            })
+            .count();
+        let user_runs = state
+            .running
+            .iter()
+            .filter(|running_job| !running_job.is_paused && running_job.user_id == job.user_id)
+            .count();
+        project_runs < self.project_quota && user_runs < self.user_quota
+    }
+    fn spawn(
+        &self,
+        adjustment_run_service: WebSocketAdjustmentRunService,
+        adjustment_run_controls: AdjustmentRunControls,
+        job: AdjustmentJob,
+    ) {
+        let adjustment_workers = self.clone();
+        thread::spawn(move || {
+            let project_id = job.project_id;
+            let adjustment_run_id = job.adjustment_run_id;
+            let is_panicked = panic::catch_unwind(AssertUnwindSafe(|| {
+                build_runtime().block_on(adjustment_services::run_adjust(
+                    adjustment_run_service.clone(),
+                    adjustment_run_controls.clone(),
+                    job,
+                ))
+            }))
+            .is_err();
+            // A panicked run must still release its worker slot and must not stay running
+            if is_panicked {
+                let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+                    build_runtime().block_on(fail_adjustment_run(
+                        adjustment_run_service.clone(),
+                        adjustment_run_controls.clone(),
+                        project_id,
+                        adjustment_run_id,
+                    ))
+                }));
+            }
+            build_runtime().block_on(adjustment_workers.complete(
+                adjustment_run_service,
+                adjustment_run_controls,
+                adjustment_run_id,
+            ));
+        });
+    }
+    async fn complete(
+        &self,
+        adjustment_run_service: WebSocketAdjustmentRunService,
+        adjustment_run_controls: AdjustmentRunControls,
+        adjustment_run_id: i32,
+    ) -> () {
+        self.state
+            .lock()
+            .await
+            .running
+            .retain(|running_job| running_job.adjustment_run_id != adjustment_run_id);
+        self.dispatch(adjustment_run_service, adjustment_run_controls)
+            .await;
+    }
+}
+
+/// Runs queued or running before a restart have lost their worker, so they are marked as failed.
+pub fn fail_stale_adjustment_runs(conn: &mut PgConnection) -> QueryResult<usize> {
+    diesel::update(adjustment_runs::table)
+        .filter(adjustment_runs::state.eq_any(vec![
+            AdjustmentRunState::Queued,
+            AdjustmentRunState::Running,
+            AdjustmentRunState::Paused,
+        ]))
+        .set(adjustment_runs::state.eq(AdjustmentRunState::Failed))
+        .execute(conn)
+}
+
+pub async fn get_adjustment_run_queue_position(
+    conn: &mut PgConnection,
+    adjustment_workers: &AdjustmentWorkers,
+    user: &User,
+    adjustment_run_id: i32,
+) -> ServiceResult<Option<i32>> {
+    let project =
+        adjustment_out_services::find_project_by_adjustment_run_id(conn, adjustment_run_id)
+            .to_service_result_find(String::from("adjustment_run_not_found_error"))?;
+    permission_services::can_view_project(conn, &project, user)?;
+    Ok(adjustment_workers
+        .get_queue_position(adjustment_run_id)
+        .await)
+}
+
+pub async fn start_adjustment_run(
+    conn: &mut PgConnection,
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    project_id: i32,
+    adjustment_run_id: i32,
+) -> ServiceResult<()> {
+    let adjustment_run = diesel::update(adjustment_runs::table)
+        .filter(adjustment_runs::id.eq(adjustment_run_id))
+        .filter(adjustment_runs::state.eq(AdjustmentRunState::Queued))
+        .set(adjustment_runs::state.eq(AdjustmentRunState::Running))
+        .get_result::<AdjustmentRun>(conn)
+        .optional()
+        .to_service_result()?;
+    if let Some(adjustment_run) = adjustment_run {
+        let adjustment_run_out = AdjustmentRunOutType::from_adjustment_run(conn, adjustment_run)?;
+        adjustment_run_service
+            .notify(AdjustmentRunActionType::new(
+                project_id,
+                adjustment_run_id,
+                String::from("adjustmentStarted"),
+                adjustment_run_out,
+            ))
+            .await;
+    }
+    Ok(())
+}
+
+async fn fail_adjustment_run(
+    adjustment_run_service: WebSocketAdjustmentRunService,
+    adjustment_run_controls: AdjustmentRunControls,
+    project_id: i32,
+    adjustment_run_id: i32,
+) {
+    let mut conn = db::establish_connection();
+    let _ = adjustment_control_services::finish_adjustment(
+        &mut conn,
+        &adjustment_run_controls,
+        adjustment_run_service,
+        project_id,
+        adjustment_run_id,
+        true,
+    )
+    .await;
+}
+
+fn count_active(running: &[RunningJob]) -> usize {
+    running
+        .iter()
+        .filter(|running_job| !running_job.is_paused)
+        .count()
+}
+
+fn build_runtime() -> Runtime {
+    Builder::new_current_thread().enable_all().build().unwrap()
+}
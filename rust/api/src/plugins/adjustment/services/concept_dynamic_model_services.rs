@@ -1,15 +1,14 @@
 use super::super::models::{ConceptDynamicModel, DynamicModelType};
 use super::super::types::ConceptDynamicModelOutType;
 use super::super::Plugins;
-use crate::db;
 use crate::models::{Concept, User};
 use crate::plugins::Plugin;
 use crate::response::{ServiceResult, ToServiceResult};
 use crate::schema::{concept_dynamic_models, concepts, projects};
-use crate::services::{model_services, permission_services};
+use crate::services::{model_history_services, model_services, permission_services};
 use crate::types::{ConceptOutType, ModelActionType};
 use crate::web_socket::WebSocketModelService;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::PgConnection;
 use serde_json::{json, Value};
@@ -23,8 +22,7 @@ pub fn handle_get_model(
         .get_model_emitter
         .lock()
         .unwrap()
-        .on(move |mut model_out, _| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut model_out, _| {
             if !plugin
                 .lock()
                 .unwrap()
@@ -54,8 +52,7 @@ pub fn handle_add_concept(
         .add_concept_emitter
         .lock()
         .unwrap()
-        .on(move |mut concept_out, project| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut concept_out, project| {
             if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
                 return Ok(concept_out);
             }
@@ -78,6 +75,7 @@ pub fn create_project_concept_dynamic_models(
                 .map(|concept| concept_dynamic_models::concept_id.eq(concept.id))
                 .collect::<Vec<_>>(),
         )
+        .on_conflict_do_nothing()
         .get_results::<ConceptDynamicModel>(conn)
         .to_service_result()
 }
@@ -113,27 +111,47 @@ pub fn create_concept_dynamic_model(
 
 pub async fn change_dynamic_model_type(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     concept_id: i32,
     dynamic_model_type: Option<DynamicModelType>,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ConceptDynamicModelOutType>> {
     let project = model_services::find_project_by_concept_id(conn, concept_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
     permission_services::can_change_model(conn, &project, user.id)?;
     let concept_dynamic_model = find_concept_dynamic_model_by_id(conn, concept_id)
         .to_service_result_find(String::from("concept_dynamic_model_not_found_error"))?;
-    let (concept_dynamic_model, concept, project) = conn
-        .transaction(|conn| {
+    let (concept_dynamic_model, concept, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            model_services::check_concept_version(
+                conn,
+                plugins,
+                &project,
+                concept_id,
+                expected_version,
+            )?;
+            let previous_state =
+                model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
             let concept_dynamic_model = diesel::update(concept_dynamic_models::table)
                 .filter(concept_dynamic_models::concept_id.eq(concept_dynamic_model.concept_id))
                 .set(concept_dynamic_models::dynamic_model_type.eq(dynamic_model_type))
                 .get_result::<ConceptDynamicModel>(conn)?;
             let (concept, project) =
                 model_services::update_concept(conn, concept_id, project.id, Utc::now())?;
+            let next_state =
+                model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeDynamicModelType",
+                previous_state,
+                next_state,
+            )?;
             Ok((concept_dynamic_model, concept, project))
-        })
-        .to_service_result()?;
+        })?;
     let concept_dynamic_model_out =
         ConceptDynamicModelOutType::from((concept_dynamic_model, concept));
     let model_action = ModelActionType::new(
@@ -0,0 +1,174 @@
+use super::super::models::AdjustmentRun;
+use super::super::types::{ContributionOutType, ExplanationInType, ScenarioType};
+use super::{adjustment_out_services, adjustment_services};
+use crate::forbidden_error;
+use crate::models::User;
+use crate::plugins::adjustment::types::AdjustmentIndividualOutType;
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::services::permission_services;
+use crate::validation_error;
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::adjustment::{
+    Contribution, DynamicModel, Scenario, TimeSimulation,
+};
+use std::collections::HashMap;
+
+const DEFAULT_MAX_DEPTH: i32 = 3;
+const MAX_DEPTH: i32 = 10;
+
+pub fn explain_adjustment_individual(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    adjustment_individual_id: i32,
+    explanation_in: ExplanationInType,
+) -> ServiceResult<Vec<ContributionOutType>> {
+    let project = adjustment_out_services::find_project_by_adjustment_individual_id(
+        conn,
+        adjustment_individual_id,
+    )
+    .to_service_result_find(String::from("adjustment_individual_not_found_error"))?;
+    if !plugins
+        .plugins
+        .get("Adjustment With Genetic Algorithms")
+        .unwrap()
+        .lock()
+        .unwrap()
+        .is_enabled(conn, project.id)?
+    {
+        return forbidden_error!("adjustment_plugin_is_not_enabled_error");
+    }
+    permission_services::can_view_project(conn, &project, user)?;
+    let adjustment_run = adjustment_out_services::find_adjustment_run_by_individual_id(
+        conn,
+        adjustment_individual_id,
+    )
+    .to_service_result()?;
+    let adjustment_individual = AdjustmentIndividualOutType::from_individual(
+        conn,
+        adjustment_out_services::find_adjustment_individual_by_id(conn, adjustment_individual_id)
+            .to_service_result()?,
+    )?;
+    let time = explanation_in.time.unwrap_or(adjustment_individual.time);
+    if time < 1 || time > adjustment_run.max_model_time {
+        let time = time.to_string();
+        let range = format!("[1; {}]", adjustment_run.max_model_time);
+        return validation_error!("explanation_time_error", time = &time, range = &range);
+    }
+    let max_depth = explanation_in.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+    if max_depth < 0 || max_depth > MAX_DEPTH {
+        let max_depth = max_depth.to_string();
+        let range = format!("[0; {}]", MAX_DEPTH);
+        return validation_error!(
+            "explanation_max_depth_error",
+            max_depth = &max_depth,
+            range = &range
+        );
+    }
+    // The run was adjusted on its model copy, so later model changes do not affect the explanation
+    let (concepts, connections) =
+        adjustment_services::get_adjustment_run_model(conn, &adjustment_run)?;
+    let scenario = get_scenario(&adjustment_run, explanation_in.scenario_name)?;
+    let mut state =
+        HashMap::<i32, f64>::from_iter(concepts.iter().map(|concept| (concept.id, concept.value)));
+    if let Some(scenario) = &scenario {
+        for (concept_id, value) in scenario
+            .initial_values
+            .iter()
+            .chain(scenario.clamped_values.iter())
+        {
+            state.insert(*concept_id, *value);
+        }
+    }
+    for concept_value in &adjustment_individual.concept_values {
+        state.insert(concept_value.concept_id, concept_value.value);
+    }
+    let mut time_simulation = TimeSimulation::new(
+        adjustment_run.max_model_time,
+        HashMap::from_iter(concepts.iter().map(|concept| (concept.id, concept.clone()))),
+        HashMap::from_iter(
+            connections
+                .into_iter()
+                .map(|connection| (connection.id, connection)),
+        ),
+        concepts
+            .iter()
+            .filter(|concept| concept.is_target)
+            .cloned()
+            .collect(),
+        DynamicModel::from(adjustment_run.dynamic_model_type),
+        state,
+        HashMap::from_iter(
+            adjustment_individual
+                .connection_values
+                .iter()
+                .map(|connection_value| (connection_value.connection_id, connection_value.value)),
+        ),
+    );
+    time_simulation.set_function_parameters(HashMap::from_iter(
+        adjustment_individual
+            .connection_function_values
+            .iter()
+            .map(|function_value| (function_value.connection_id, function_value.parameter)),
+    ));
+    if let Some(time_step) = adjustment_run.time_step {
+        time_simulation.set_time_step(time_step);
+    }
+    if let Some(scenario) = scenario {
+        time_simulation.clamp(scenario.clamped_values);
+    }
+    Ok(time_simulation
+        .explain(
+            time,
+            max_depth as usize,
+            explanation_in.min_contribution.unwrap_or(0.0),
+        )
+        .into_iter()
+        .map(ContributionOutType::from)
+        .collect())
+}
+
+fn get_scenario(
+    adjustment_run: &AdjustmentRun,
+    scenario_name: Option<String>,
+) -> ServiceResult<Option<Scenario>> {
+    let scenarios = adjustment_run
+        .scenarios
+        .clone()
+        .map(|scenarios| serde_json::from_value::<Vec<ScenarioType>>(scenarios).unwrap())
+        .unwrap_or_default();
+    match scenario_name {
+        Some(scenario_name) => match scenarios
+            .into_iter()
+            .find(|scenario| scenario.name == scenario_name)
+        {
+            Some(scenario) => Ok(Some(Scenario::from(scenario))),
+            None => validation_error!(
+                "explanation_scenario_not_found_error",
+                name = &scenario_name
+            ),
+        },
+        None if !scenarios.is_empty() => validation_error!("explanation_scenario_required_error"),
+        None => Ok(None),
+    }
+}
+
+impl From<Contribution> for ContributionOutType {
+    fn from(contribution: Contribution) -> Self {
+        Self {
+            concept_id: contribution.concept_id,
+            connection_id: contribution.connection_id,
+            time: contribution.time,
+            value: contribution.value,
+            input: contribution.input,
+            contribution: contribution.contribution,
+            is_control: contribution.is_control,
+            children: contribution
+                .children
+                .into_iter()
+                .map(ContributionOutType::from)
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use super::super::types::{
+    IntervalSimulationInType, IntervalSimulationStepOutType, ValueIntervalType,
+};
+use super::adjustment_services;
+use crate::forbidden_error;
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::services::{permission_services, project_services};
+use crate::validation_error;
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::adjustment::DynamicModel;
+use fuzzy_cognitive_model_common::interval::{self, Interval, IntervalTimeSimulation};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+pub fn simulate_intervals(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    interval_simulation_in: IntervalSimulationInType,
+) -> ServiceResult<Vec<IntervalSimulationStepOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    if !plugins
+        .plugins
+        .get("Adjustment With Genetic Algorithms")
+        .unwrap()
+        .lock()
+        .unwrap()
+        .is_enabled(conn, project_id)?
+    {
+        return forbidden_error!("adjustment_plugin_is_not_enabled_error");
+    }
+    permission_services::can_view_project(conn, &project, user)?;
+    if interval_simulation_in.max_model_time < 1 {
+        return validation_error!("max_model_time_error");
+    }
+    let concepts_map = HashMap::from_iter(
+        adjustment_services::get_concepts(conn, project_id)?
+            .into_iter()
+            .map(|concept| (concept.id, concept)),
+    );
+    let connections_map = HashMap::from_iter(
+        adjustment_services::get_connections(conn, project_id)?
+            .into_iter()
+            .map(|connection| (connection.id, connection)),
+    );
+    let concept_intervals = get_intervals(
+        &interval_simulation_in.concept_intervals,
+        &concepts_map,
+        0.0,
+        "concept_not_found_error",
+    )?;
+    let connection_intervals = get_intervals(
+        &interval_simulation_in.connection_intervals,
+        &connections_map,
+        -1.0,
+        "connection_not_found_error",
+    )?;
+    let concepts = interval::get_concept_intervals(&concepts_map, &concept_intervals);
+    let connections = interval::get_connection_intervals(&connections_map, &connection_intervals);
+    let interval_time_simulation = IntervalTimeSimulation::new(
+        interval_simulation_in.max_model_time,
+        concepts_map,
+        connections_map,
+        DynamicModel::from(interval_simulation_in.dynamic_model_type),
+        concepts,
+        connections,
+    );
+    let mut steps = vec![IntervalSimulationStepOutType::from((
+        interval_time_simulation.get_current_time(),
+        interval_time_simulation.get_state(),
+    ))];
+    for data in interval_time_simulation {
+        steps.push(IntervalSimulationStepOutType::from((data.time, data.state)));
+    }
+    Ok(steps)
+}
+
+fn get_intervals<T>(
+    value_intervals: &[ValueIntervalType],
+    elements_map: &HashMap<i32, Arc<T>>,
+    min_value: f64,
+    not_found_error: &'static str,
+) -> ServiceResult<HashMap<i32, Interval>> {
+    let mut intervals = HashMap::new();
+    for value_interval in value_intervals {
+        if !elements_map.contains_key(&value_interval.id) {
+            return validation_error!(not_found_error);
+        }
+        if value_interval.lower_value > value_interval.upper_value
+            || value_interval.lower_value < min_value
+            || value_interval.upper_value > 1.0
+        {
+            let interval = format!(
+                "[{}; {}]",
+                value_interval.lower_value, value_interval.upper_value
+            );
+            let range = format!("[{}; 1]", min_value);
+            return validation_error!("value_interval_error", interval = interval, range = range);
+        }
+        intervals.insert(
+            value_interval.id,
+            Interval::new(value_interval.lower_value, value_interval.upper_value),
+        );
+    }
+    Ok(intervals)
+}
+
+impl From<(i32, HashMap<i32, Interval>)> for IntervalSimulationStepOutType {
+    fn from((time, state): (i32, HashMap<i32, Interval>)) -> Self {
+        let mut concepts = state
+            .into_iter()
+            .map(|(id, interval)| ValueIntervalType {
+                id,
+                lower_value: interval.lower,
+                upper_value: interval.upper,
+            })
+            .collect::<Vec<_>>();
+        concepts.sort_by_key(|concept| concept.id);
+        Self { time, concepts }
+    }
+}
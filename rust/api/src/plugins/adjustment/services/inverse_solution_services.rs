@@ -0,0 +1,120 @@
+use super::super::types::{
+    BindingConstraintKindType, BindingConstraintOutType, InverseConceptValueOutType,
+    InverseSolutionInType, InverseSolutionOutType, ValueIntervalType,
+};
+use super::adjustment_services;
+use crate::forbidden_error;
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::services::{permission_services, project_services};
+use crate::validation_error;
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::adjustment::DynamicModel;
+use fuzzy_cognitive_model_common::inverse::{
+    BindingConstraint, BindingConstraintKind, InverseSolution, InverseSolver,
+};
+use std::collections::HashMap;
+
+// The dynamics is unrolled over every model time, so the time is bounded
+const MAX_TIME: i32 = 100;
+
+pub fn solve_inverse(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    inverse_solution_in: InverseSolutionInType,
+) -> ServiceResult<InverseSolutionOutType> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    if !plugins
+        .plugins
+        .get("Adjustment With Genetic Algorithms")
+        .unwrap()
+        .lock()
+        .unwrap()
+        .is_enabled(conn, project_id)?
+    {
+        return forbidden_error!("adjustment_plugin_is_not_enabled_error");
+    }
+    permission_services::can_view_project(conn, &project, user)?;
+    let time = inverse_solution_in.time;
+    if time < 1 || time > MAX_TIME {
+        let time = time.to_string();
+        let range = format!("[1; {}]", MAX_TIME);
+        return validation_error!("inverse_time_error", time = &time, range = &range);
+    }
+    let concepts_map = HashMap::from_iter(
+        adjustment_services::get_concepts(conn, project_id)?
+            .into_iter()
+            .map(|concept| (concept.id, concept)),
+    );
+    let connections_map = HashMap::from_iter(
+        adjustment_services::get_connections(conn, project_id)?
+            .into_iter()
+            .map(|connection| (connection.id, connection)),
+    );
+    let inverse_solver = InverseSolver::new(
+        concepts_map,
+        connections_map,
+        DynamicModel::from(inverse_solution_in.dynamic_model_type),
+        time,
+    );
+    Ok(InverseSolutionOutType::from(inverse_solver.solve()))
+}
+
+impl From<InverseSolution> for InverseSolutionOutType {
+    fn from(inverse_solution: InverseSolution) -> Self {
+        let mut concepts = inverse_solution
+            .concepts
+            .into_iter()
+            .map(|(concept_id, value)| InverseConceptValueOutType { concept_id, value })
+            .collect::<Vec<_>>();
+        concepts.sort_by_key(|concept| concept.concept_id);
+        let mut feasible_region = inverse_solution
+            .feasible_region
+            .into_iter()
+            .map(|(id, interval)| ValueIntervalType {
+                id,
+                lower_value: interval.lower,
+                upper_value: interval.upper,
+            })
+            .collect::<Vec<_>>();
+        feasible_region.sort_by_key(|interval| interval.id);
+        Self {
+            is_supported: inverse_solution.is_supported,
+            is_feasible: inverse_solution.is_feasible,
+            concepts,
+            feasible_region,
+            error: inverse_solution.error,
+            binding_constraint: inverse_solution
+                .binding_constraint
+                .map(BindingConstraintOutType::from),
+        }
+    }
+}
+
+impl From<BindingConstraint> for BindingConstraintOutType {
+    fn from(binding_constraint: BindingConstraint) -> Self {
+        Self {
+            kind: BindingConstraintKindType::from(binding_constraint.kind),
+            concept_id: binding_constraint.concept_id,
+            time: binding_constraint.time,
+            violation: binding_constraint.violation,
+        }
+    }
+}
+
+impl From<BindingConstraintKind> for BindingConstraintKindType {
+    fn from(binding_constraint_kind: BindingConstraintKind) -> Self {
+        match binding_constraint_kind {
+            BindingConstraintKind::ControlMin => Self::ControlMin,
+            BindingConstraintKind::ControlMax => Self::ControlMax,
+            BindingConstraintKind::TargetMin => Self::TargetMin,
+            BindingConstraintKind::TargetMax => Self::TargetMax,
+            BindingConstraintKind::ActivationPiece => Self::ActivationPiece,
+            BindingConstraintKind::FunctionPiece => Self::FunctionPiece,
+        }
+    }
+}
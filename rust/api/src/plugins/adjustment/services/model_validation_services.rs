@@ -0,0 +1,291 @@
+use super::super::types::{
+    AdjustmentInType, DiagnosticOutType, DiagnosticSeverity, DiagnosticType,
+};
+use super::adjustment_services;
+use crate::forbidden_error;
+use crate::locale::Locale;
+use crate::models::{ConnectionValueType, User};
+use crate::plugins::Plugins;
+use crate::response::{AppError, ServiceResult, ToServiceResult};
+use crate::services::{permission_services, project_services};
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::adjustment::Constraint;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+pub fn validate_adjustment(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    locale: &Locale,
+    project_id: i32,
+    adjustment_in: AdjustmentInType,
+) -> ServiceResult<Vec<DiagnosticOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    if !plugins
+        .plugins
+        .get("Adjustment With Genetic Algorithms")
+        .unwrap()
+        .lock()
+        .unwrap()
+        .is_enabled(conn, project_id)?
+    {
+        return forbidden_error!("adjustment_plugin_is_not_enabled_error");
+    }
+    permission_services::can_view_project(conn, &project, user)?;
+    get_diagnostics(conn, project_id, &adjustment_in, &locale.get_locale())
+}
+
+pub fn get_diagnostics(
+    conn: &mut PgConnection,
+    project_id: i32,
+    adjustment_in: &AdjustmentInType,
+    locale: &str,
+) -> ServiceResult<Vec<DiagnosticOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    let concepts = adjustment_services::get_concepts(conn, project_id)?;
+    let connections = adjustment_services::get_connections(conn, project_id)?;
+    let mut diagnostics = get_settings_diagnostics(adjustment_in, locale);
+    let control_concept_ids = concepts
+        .iter()
+        .filter(|concept| concept.is_control)
+        .map(|concept| concept.id)
+        .collect::<Vec<_>>();
+    let control_connection_ids = connections
+        .iter()
+        .filter(|connection| connection.is_control || connection.is_function_control)
+        .map(|connection| connection.id)
+        .collect::<Vec<_>>();
+    if control_concept_ids.len() == 0 && control_connection_ids.len() == 0 {
+        diagnostics.push(DiagnosticOutType::new(
+            DiagnosticType::EmptyControls,
+            DiagnosticSeverity::Error,
+            t!("empty_controls_diagnostic", locale = locale),
+        ));
+    }
+    let target_concept_ids = concepts
+        .iter()
+        .filter(|concept| concept.is_target)
+        .map(|concept| concept.id)
+        .collect::<Vec<_>>();
+    if target_concept_ids.len() == 0 {
+        diagnostics.push(DiagnosticOutType::new(
+            DiagnosticType::EmptyTargets,
+            DiagnosticSeverity::Error,
+            t!("empty_targets_diagnostic", locale = locale),
+        ));
+    }
+    let linked_concept_ids = HashSet::<i32>::from_iter(
+        connections
+            .iter()
+            .flat_map(|connection| [connection.source_id, connection.target_id]),
+    );
+    for concept in &concepts {
+        if !linked_concept_ids.contains(&concept.id) {
+            diagnostics.push(
+                DiagnosticOutType::new(
+                    DiagnosticType::IsolatedConcept,
+                    DiagnosticSeverity::Warning,
+                    t!("isolated_concept_diagnostic", locale = locale),
+                )
+                .with_concept_ids(vec![concept.id]),
+            );
+        }
+    }
+    let mut target_connections = HashMap::<i32, Vec<i32>>::new();
+    for connection in &connections {
+        target_connections
+            .entry(connection.source_id)
+            .or_default()
+            .push(connection.target_id);
+    }
+    let mut reachable_concept_ids = HashSet::<i32>::new();
+    let mut queue = VecDeque::from_iter(
+        control_concept_ids.iter().cloned().chain(
+            connections
+                .iter()
+                .filter(|connection| connection.is_control || connection.is_function_control)
+                .map(|connection| connection.target_id),
+        ),
+    );
+    while let Some(concept_id) = queue.pop_front() {
+        if !reachable_concept_ids.insert(concept_id) {
+            continue;
+        }
+        if let Some(target_ids) = target_connections.get(&concept_id) {
+            queue.extend(target_ids);
+        }
+    }
+    if control_concept_ids.len() > 0 || control_connection_ids.len() > 0 {
+        for concept_id in &target_concept_ids {
+            if !reachable_concept_ids.contains(concept_id) {
+                diagnostics.push(
+                    DiagnosticOutType::new(
+                        DiagnosticType::UnreachableTarget,
+                        DiagnosticSeverity::Error,
+                        t!("unreachable_target_diagnostic", locale = locale),
+                    )
+                    .with_concept_ids(vec![*concept_id]),
+                );
+            }
+        }
+    }
+    for concept in &concepts {
+        if let Some(constraint) = &concept.constraint {
+            if !is_constraint_valid(constraint, 0.0, 1.0) {
+                let range = format_range(constraint);
+                diagnostics.push(
+                    DiagnosticOutType::new(
+                        DiagnosticType::ConceptConstraintOutOfRange,
+                        DiagnosticSeverity::Error,
+                        t!(
+                            "concept_constraint_out_of_range_diagnostic",
+                            locale = locale,
+                            range = &range
+                        ),
+                    )
+                    .with_concept_ids(vec![concept.id]),
+                );
+            }
+        }
+    }
+    let min_connection_value = match project.connection_value_type {
+        ConnectionValueType::Symbolic => 0.0,
+        ConnectionValueType::FromMinusOneToOne | ConnectionValueType::Linguistic => -1.0,
+    };
+    for connection in &connections {
+        if let Some(constraint) = &connection.constraint {
+            if !is_constraint_valid(constraint, min_connection_value, 1.0) {
+                let range = format_range(constraint);
+                diagnostics.push(
+                    DiagnosticOutType::new(
+                        DiagnosticType::ConnectionConstraintOutOfRange,
+                        DiagnosticSeverity::Error,
+                        t!(
+                            "connection_constraint_out_of_range_diagnostic",
+                            locale = locale,
+                            range = &range
+                        ),
+                    )
+                    .with_connection_ids(vec![connection.id]),
+                );
+            }
+        }
+    }
+    Ok(diagnostics)
+}
+
+pub fn check_diagnostics(diagnostics: &[DiagnosticOutType]) -> ServiceResult<()> {
+    let messages = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == DiagnosticSeverity::Error)
+        .map(|diagnostic| diagnostic.message.clone())
+        .collect::<Vec<_>>();
+    if messages.len() > 0 {
+        let message = messages.join(" ");
+        return Err(AppError::ValidationError(Box::new(move |_| {
+            message.clone()
+        })));
+    }
+    Ok(())
+}
+
+fn get_settings_diagnostics(
+    adjustment_in: &AdjustmentInType,
+    locale: &str,
+) -> Vec<DiagnosticOutType> {
+    let mut diagnostics = vec![];
+    if adjustment_in.max_model_time < 1 {
+        diagnostics.push(DiagnosticOutType::new(
+            DiagnosticType::InvalidModelTime,
+            DiagnosticSeverity::Error,
+            t!("max_model_time_diagnostic", locale = locale),
+        ));
+    }
+    if adjustment_in.min_model_time < 0
+        || adjustment_in.min_model_time > adjustment_in.max_model_time
+    {
+        diagnostics.push(DiagnosticOutType::new(
+            DiagnosticType::InvalidModelTime,
+            DiagnosticSeverity::Error,
+            t!("min_model_time_diagnostic", locale = locale),
+        ));
+    }
+    if adjustment_in.generation_size < 2 {
+        diagnostics.push(DiagnosticOutType::new(
+            DiagnosticType::InvalidGenerationSize,
+            DiagnosticSeverity::Error,
+            t!("generation_size_diagnostic", locale = locale),
+        ));
+    }
+    if adjustment_in.generation_save_interval < 1 {
+        diagnostics.push(DiagnosticOutType::new(
+            DiagnosticType::InvalidGenerationSaveInterval,
+            DiagnosticSeverity::Error,
+            t!("generation_save_interval_diagnostic", locale = locale),
+        ));
+    }
+    if adjustment_in.stop_condition.max_generations < 1
+        || adjustment_in.stop_condition.max_without_improvements < 1
+        || adjustment_in.stop_condition.error < 0.0
+    {
+        diagnostics.push(DiagnosticOutType::new(
+            DiagnosticType::InvalidStopCondition,
+            DiagnosticSeverity::Error,
+            t!("stop_condition_diagnostic", locale = locale),
+        ));
+    }
+    if adjustment_in.budget.map_or(false, |budget| budget < 0.0) {
+        diagnostics.push(DiagnosticOutType::new(
+            DiagnosticType::InvalidBudget,
+            DiagnosticSeverity::Error,
+            t!("budget_diagnostic", locale = locale),
+        ));
+    }
+    diagnostics
+}
+
+fn is_constraint_valid(constraint: &Constraint, min_value: f64, max_value: f64) -> bool {
+    constraint.min_value >= min_value
+        && constraint.max_value <= max_value
+        && constraint.get_min(min_value) <= constraint.get_max(max_value)
+}
+
+fn format_range(constraint: &Constraint) -> String {
+    format!(
+        "{}{}; {}{}",
+        if constraint.include_min_value {
+            "["
+        } else {
+            "("
+        },
+        constraint.min_value,
+        constraint.max_value,
+        if constraint.include_max_value {
+            "]"
+        } else {
+            ")"
+        }
+    )
+}
+
+impl DiagnosticOutType {
+    fn new(diagnostic_type: DiagnosticType, severity: DiagnosticSeverity, message: String) -> Self {
+        Self {
+            diagnostic_type,
+            severity,
+            message,
+            concept_ids: vec![],
+            connection_ids: vec![],
+        }
+    }
+    fn with_concept_ids(mut self, concept_ids: Vec<i32>) -> Self {
+        self.concept_ids = concept_ids;
+        self
+    }
+    fn with_connection_ids(mut self, connection_ids: Vec<i32>) -> Self {
+        self.connection_ids = connection_ids;
+        self
+    }
+}
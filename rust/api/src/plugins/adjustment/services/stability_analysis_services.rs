@@ -0,0 +1,97 @@
+use super::super::types::{
+    ActivationType, StabilityAnalysisInType, StabilityAnalysisOutType, StabilityConditionType,
+};
+use super::adjustment_services;
+use crate::forbidden_error;
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::services::{permission_services, project_services};
+use crate::validation_error;
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::adjustment::{DynamicModel, DEFAULT_TIME_STEP};
+use fuzzy_cognitive_model_common::stability::{Activation, StabilityAnalysis, WeightMatrix};
+use std::collections::HashMap;
+
+pub fn analyze_stability(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    stability_analysis_in: StabilityAnalysisInType,
+) -> ServiceResult<StabilityAnalysisOutType> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    if !plugins
+        .plugins
+        .get("Adjustment With Genetic Algorithms")
+        .unwrap()
+        .lock()
+        .unwrap()
+        .is_enabled(conn, project_id)?
+    {
+        return forbidden_error!("adjustment_plugin_is_not_enabled_error");
+    }
+    permission_services::can_view_project(conn, &project, user)?;
+    check_stability_condition(&stability_analysis_in.stability_condition)?;
+    if let Some(time_step) = stability_analysis_in.time_step {
+        adjustment_services::check_time_step(time_step)?;
+    }
+    let concepts_map = HashMap::from_iter(
+        adjustment_services::get_concepts(conn, project_id)?
+            .into_iter()
+            .map(|concept| (concept.id, concept)),
+    );
+    let connections_map = HashMap::from_iter(
+        adjustment_services::get_connections(conn, project_id)?
+            .into_iter()
+            .map(|connection| (connection.id, connection)),
+    );
+    let weight_matrix = WeightMatrix::new(
+        &concepts_map,
+        &connections_map,
+        &HashMap::new(),
+        &HashMap::new(),
+        &DynamicModel::from(stability_analysis_in.dynamic_model_type),
+        stability_analysis_in.time_step.unwrap_or(DEFAULT_TIME_STEP),
+    );
+    let stability_analysis =
+        weight_matrix.analyze(&Activation::from(stability_analysis_in.stability_condition));
+    Ok(StabilityAnalysisOutType::from(stability_analysis))
+}
+
+pub fn check_stability_condition(
+    stability_condition: &StabilityConditionType,
+) -> ServiceResult<()> {
+    if !Activation::from(stability_condition.clone()).is_valid() {
+        return validation_error!("activation_steepness_error");
+    }
+    Ok(())
+}
+
+impl From<StabilityConditionType> for Activation {
+    fn from(stability_condition: StabilityConditionType) -> Self {
+        let steepness = stability_condition.steepness.unwrap_or(1.0);
+        match stability_condition.activation_type {
+            ActivationType::Clamp => Self::Clamp,
+            ActivationType::Sigmoid => Self::Sigmoid { steepness },
+            ActivationType::Tanh => Self::Tanh { steepness },
+        }
+    }
+}
+
+impl From<StabilityAnalysis> for StabilityAnalysisOutType {
+    fn from(stability_analysis: StabilityAnalysis) -> Self {
+        Self {
+            spectral_radius: stability_analysis.spectral_radius,
+            spectral_norm: stability_analysis.spectral_norm,
+            frobenius_norm: stability_analysis.frobenius_norm,
+            row_sum_norm: stability_analysis.row_sum_norm,
+            column_sum_norm: stability_analysis.column_sum_norm,
+            lipschitz_constant: stability_analysis.lipschitz_constant,
+            contraction_factor: stability_analysis.contraction_factor,
+            is_continuous: stability_analysis.is_continuous,
+            is_contraction: stability_analysis.is_contraction,
+        }
+    }
+}
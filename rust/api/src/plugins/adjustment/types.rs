@@ -1,4 +1,4 @@
-use super::models::DynamicModelType;
+use super::models::{AdjustmentRunState, DynamicModelType, ScenarioAggregationType};
 use crate::request::DateTimeWrapper;
 use chrono::{DateTime, Utc};
 use rocket::serde::{Deserialize, Serialize};
@@ -48,6 +48,92 @@ pub struct AdjustmentInType {
     pub generation_save_interval: i32,
     /// Algorithm stop condition
     pub stop_condition: StopConditionType,
+    /// Population of a previous adjustment run to start from
+    pub seed: Option<AdjustmentSeedInType>,
+    /// Overrides of controls for this adjustment run only
+    pub control_overrides: Option<ControlOverridesType>,
+    /// Maximum total intervention cost of control changes
+    pub budget: Option<f64>,
+    /// Whether to search for the cheapest control values that hit the targets
+    pub minimize_cost: Option<bool>,
+    /// Scenarios of external conditions the control values have to work under
+    pub scenarios: Option<Vec<ScenarioType>>,
+    /// How to combine scenario errors, the worst case is used if it is absent
+    pub scenario_aggregation_type: Option<ScenarioAggregationType>,
+    /// Integration step of continuous-time dynamics model in range (0; 1], one is used if it is absent
+    pub time_step: Option<f64>,
+    /// Convergence condition individuals have to satisfy, individuals are not restricted if it is absent
+    pub stability_condition: Option<StabilityConditionType>,
+}
+
+/// Reference to a stored population used as the first generation of adjustment run
+#[derive(Clone, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdjustmentSeedInType {
+    /// Previous adjustment run identifier
+    pub adjustment_run_id: i32,
+    /// Adjustment generation identifier, the last saved generation of the run is used if it is absent
+    pub generation_id: Option<i32>,
+}
+
+/// Per-run overrides of control concepts and connections
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlOverridesType {
+    /// Control concept overrides
+    pub concepts: Vec<ControlOverrideType>,
+    /// Control connection overrides
+    pub connections: Vec<ControlOverrideType>,
+}
+
+/// Override of control concept or connection
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlOverrideType {
+    /// Concept or connection identifier
+    pub id: i32,
+    /// Whether to exclude control from adjustment keeping its model value
+    pub is_excluded: bool,
+    /// Value to pin control to
+    pub pinned_value: Option<f64>,
+    /// Narrowed range of control values
+    pub range: Option<ControlRangeType>,
+}
+
+/// Range of control values
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ControlRangeType {
+    /// Minimum control value
+    pub min_value: f64,
+    /// Whether to include minimum value in range
+    pub include_min_value: bool,
+    /// Maximum control value
+    pub max_value: f64,
+    /// Whether to include maximum value in range
+    pub include_max_value: bool,
+}
+
+/// Scenario of external conditions
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioType {
+    /// Scenario name
+    pub name: String,
+    /// Concept values of scenario
+    pub concept_values: Vec<ScenarioConceptValueType>,
+}
+
+/// Concept value of scenario
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioConceptValueType {
+    /// Concept identifier
+    pub concept_id: i32,
+    /// Concept value
+    pub value: f64,
+    /// Whether concept keeps the value during the whole simulation
+    pub is_clamped: bool,
 }
 
 /// Type of adjustment run
@@ -76,6 +162,24 @@ pub struct AdjustmentRunOutType {
     pub stop_condition: StopConditionType,
     /// Adjustment run creation time
     pub created_at: DateTime<Utc>,
+    /// Adjustment run state
+    pub state: AdjustmentRunState,
+    /// Identifier of adjustment generation used as the first generation
+    pub seed_generation_id: Option<i32>,
+    /// Overrides of controls used in adjustment run
+    pub control_overrides: Option<ControlOverridesType>,
+    /// Maximum total intervention cost of control changes
+    pub budget: Option<f64>,
+    /// Whether to search for the cheapest control values that hit the targets
+    pub minimize_cost: bool,
+    /// Scenarios of external conditions used in adjustment run
+    pub scenarios: Option<Vec<ScenarioType>>,
+    /// How scenario errors are combined
+    pub scenario_aggregation_type: ScenarioAggregationType,
+    /// Integration step of continuous-time dynamics model
+    pub time_step: Option<f64>,
+    /// Convergence condition individuals had to satisfy
+    pub stability_condition: Option<StabilityConditionType>,
     /// Resulting individual of adjustment run
     pub result_individual: Option<AdjustmentIndividualGenerationOutType>,
 }
@@ -104,10 +208,16 @@ pub struct AdjustmentIndividualOutType {
     pub time: i32,
     /// Adjustment individual error
     pub error: f64,
+    /// Total intervention cost of adjustment individual
+    pub cost: f64,
     /// Adjustment individual concept values
     pub concept_values: Vec<AdjustmentConceptValueOutType>,
     /// Adjustment individual connection values
     pub connection_values: Vec<AdjustmentConnectionValueOutType>,
+    /// Adjustment individual connection function parameters
+    pub connection_function_values: Vec<AdjustmentConnectionFunctionValueOutType>,
+    /// Adjustment individual errors in scenarios
+    pub scenario_errors: Vec<AdjustmentScenarioErrorOutType>,
 }
 
 /// Type of adjustment individual with generation information
@@ -122,6 +232,8 @@ pub struct AdjustmentIndividualGenerationOutType {
     pub time: i32,
     /// Adjustment individual error
     pub error: f64,
+    /// Total intervention cost of adjustment individual
+    pub cost: f64,
     /// Adjustment generation identifier
     pub generation_id: i32,
     /// Adjustment generation number
@@ -132,6 +244,10 @@ pub struct AdjustmentIndividualGenerationOutType {
     pub concept_values: Vec<AdjustmentConceptValueOutType>,
     /// Adjustment individual connection values
     pub connection_values: Vec<AdjustmentConnectionValueOutType>,
+    /// Adjustment individual connection function parameters
+    pub connection_function_values: Vec<AdjustmentConnectionFunctionValueOutType>,
+    /// Adjustment individual errors in scenarios
+    pub scenario_errors: Vec<AdjustmentScenarioErrorOutType>,
 }
 
 /// Type of adjustment concept value
@@ -158,6 +274,262 @@ pub struct AdjustmentConnectionValueOutType {
     pub value: f64,
 }
 
+/// Type of adjustment connection function parameter
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdjustmentConnectionFunctionValueOutType {
+    /// Adjustment connection function value identifier
+    pub id: i32,
+    /// Connection identifier
+    pub connection_id: i32,
+    /// Threshold or saturation level of connection function
+    pub parameter: f64,
+}
+
+/// Type of adjustment individual error in scenario
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AdjustmentScenarioErrorOutType {
+    /// Adjustment scenario error identifier
+    pub id: i32,
+    /// Scenario name
+    pub scenario_name: String,
+    /// Adjustment individual error in scenario
+    pub error: f64,
+}
+
+/// Input data of interval simulation of fuzzy cognitive model
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IntervalSimulationInType {
+    /// Maximum model time
+    pub max_model_time: i32,
+    /// Dynamics mode type
+    pub dynamic_model_type: DynamicModelType,
+    /// Explicit intervals of concept values, constraints or crisp values are used for other concepts
+    pub concept_intervals: Vec<ValueIntervalType>,
+    /// Explicit intervals of connection values, constraints or crisp values are used for other connections
+    pub connection_intervals: Vec<ValueIntervalType>,
+}
+
+/// Interval of concept or connection value
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueIntervalType {
+    /// Concept or connection identifier
+    pub id: i32,
+    /// Lower bound of value
+    pub lower_value: f64,
+    /// Upper bound of value
+    pub upper_value: f64,
+}
+
+/// Step of interval simulation of fuzzy cognitive model
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct IntervalSimulationStepOutType {
+    /// Model time
+    pub time: i32,
+    /// Lower and upper envelopes of concept values
+    pub concepts: Vec<ValueIntervalType>,
+}
+
+/// Type of diagnostic of fuzzy cognitive model or adjustment settings
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticType {
+    EmptyControls,
+    EmptyTargets,
+    IsolatedConcept,
+    UnreachableTarget,
+    ConceptConstraintOutOfRange,
+    ConnectionConstraintOutOfRange,
+    InvalidModelTime,
+    InvalidGenerationSize,
+    InvalidGenerationSaveInterval,
+    InvalidStopCondition,
+    InvalidBudget,
+}
+
+/// Severity of diagnostic, adjustment can not be started if there are errors
+#[derive(Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+/// Diagnostic of fuzzy cognitive model or adjustment settings
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticOutType {
+    /// Diagnostic type
+    pub diagnostic_type: DiagnosticType,
+    /// Diagnostic severity
+    pub severity: DiagnosticSeverity,
+    /// Localised diagnostic message
+    pub message: String,
+    /// Identifiers of concepts the diagnostic refers to
+    pub concept_ids: Vec<i32>,
+    /// Identifiers of connections the diagnostic refers to
+    pub connection_ids: Vec<i32>,
+}
+
+/// Input type for explaining values of target concepts reached by adjustment individual
+#[derive(Clone, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplanationInType {
+    /// Model time to explain, the time of adjustment individual is used if it is absent
+    pub time: Option<i32>,
+    /// Maximum depth of contribution tree, three is used if it is absent
+    pub max_depth: Option<i32>,
+    /// Minimum absolute contribution of source concept to be traced, zero is used if it is absent
+    pub min_contribution: Option<f64>,
+    /// Name of the adjustment run scenario to explain, required if the run has scenarios
+    pub scenario_name: Option<String>,
+}
+
+/// Contribution of concept to the input of its parent in contribution tree
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ContributionOutType {
+    /// Concept identifier
+    pub concept_id: i32,
+    /// Identifier of connection to parent concept, absent for target concepts
+    pub connection_id: Option<i32>,
+    /// Model time
+    pub time: i32,
+    /// Concept value at model time
+    pub value: f64,
+    /// Total input of concept from its source concepts
+    pub input: f64,
+    /// Connection value multiplied by concept value or delta, absent for target concepts
+    pub contribution: Option<f64>,
+    /// Whether concept is control
+    pub is_control: bool,
+    /// Contributions of source concepts ranked by absolute value
+    pub children: Vec<ContributionOutType>,
+}
+
+/// Activation function of fuzzy cognitive model
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationType {
+    Clamp,
+    Sigmoid,
+    Tanh,
+}
+
+/// Activation function the convergence condition of fuzzy cognitive model is checked for
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StabilityConditionType {
+    /// Activation function type
+    pub activation_type: ActivationType,
+    /// Steepness of sigmoid or hyperbolic tangent, one is used if it is absent
+    pub steepness: Option<f64>,
+}
+
+/// Type of stability analysis of fuzzy cognitive model
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StabilityAnalysisInType {
+    /// Activation function the convergence condition is checked for
+    pub stability_condition: StabilityConditionType,
+    /// Dynamics model type whose update rule is analyzed
+    pub dynamic_model_type: DynamicModelType,
+    /// Time step of continuous-time dynamics model, one is used if it is absent
+    pub time_step: Option<f64>,
+}
+
+/// Spectral properties of weight matrix and the convergence condition of fuzzy cognitive model
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StabilityAnalysisOutType {
+    /// Spectral radius of weight matrix
+    pub spectral_radius: f64,
+    /// Spectral norm of weight matrix
+    pub spectral_norm: f64,
+    /// Frobenius norm of weight matrix
+    pub frobenius_norm: f64,
+    /// Maximum absolute row sum norm of weight matrix
+    pub row_sum_norm: f64,
+    /// Maximum absolute column sum norm of weight matrix
+    pub column_sum_norm: f64,
+    /// Lipschitz constant of activation function
+    pub lipschitz_constant: f64,
+    /// Lipschitz constant of activation function multiplied by the smallest norm of weight matrix
+    pub contraction_factor: f64,
+    /// Whether response functions of all connections are continuous
+    pub is_continuous: bool,
+    /// Whether the model is a contraction mapping and converges to a unique fixed point
+    pub is_contraction: bool,
+}
+
+/// Input data of inverse solution of fuzzy cognitive model
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InverseSolutionInType {
+    /// Model time target values must be reached at
+    pub time: i32,
+    /// Dynamics mode type
+    pub dynamic_model_type: DynamicModelType,
+}
+
+/// Kind of constraint of inverse solution
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingConstraintKindType {
+    ControlMin,
+    ControlMax,
+    TargetMin,
+    TargetMax,
+    ActivationPiece,
+    FunctionPiece,
+}
+
+/// Constraint violated the most when target values can not be reached
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingConstraintOutType {
+    /// Constraint kind
+    pub kind: BindingConstraintKindType,
+    /// Concept identifier
+    pub concept_id: i32,
+    /// Model time
+    pub time: i32,
+    /// Constraint violation
+    pub violation: f64,
+}
+
+/// Control concept value of inverse solution
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InverseConceptValueOutType {
+    /// Concept identifier
+    pub concept_id: i32,
+    /// Concept value
+    pub value: f64,
+}
+
+/// Control concept values reaching target values of fuzzy cognitive model
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InverseSolutionOutType {
+    /// Whether the model can be solved, continuous dynamics model and control connections are not supported
+    pub is_supported: bool,
+    /// Whether control values reaching target values exist
+    pub is_feasible: bool,
+    /// Control values of minimal norm
+    pub concepts: Vec<InverseConceptValueOutType>,
+    /// Bounds of control values around the solution
+    pub feasible_region: Vec<ValueIntervalType>,
+    /// Target error of control values
+    pub error: f64,
+    /// Constraint violated the most when the solution is not feasible
+    pub binding_constraint: Option<BindingConstraintOutType>,
+}
+
 /// Input type for getting adjustment runs
 #[derive(FromForm, JsonSchema)]
 #[serde(rename_all = "camelCase")]
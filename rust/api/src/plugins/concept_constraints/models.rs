@@ -1,7 +1,8 @@
 use crate::schema::concept_constraints;
 use diesel::{Identifiable, Queryable};
+use rocket::serde::{Deserialize, Serialize};
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(primary_key(concept_id), belongs_to(Concept))]
 pub struct ConceptConstraint {
     pub concept_id: i32,
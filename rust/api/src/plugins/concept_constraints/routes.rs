@@ -2,6 +2,8 @@ use super::services;
 use super::types::{ConceptConstraintInChangeType, ConceptConstraintOutType};
 use crate::db;
 use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
 use crate::response::{PathResult, ToPathResult};
 use crate::types::ModelActionType;
 use crate::web_socket::WebSocketModelService;
@@ -18,16 +20,20 @@ use rocket_okapi::openapi;
 pub async fn change_concept_constraint(
     concept_id: i32,
     concept_constraint_in: Json<ConceptConstraintInChangeType>,
+    if_match: IfMatch,
     user: User,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ConceptConstraintOutType>> {
     let conn = &mut db::establish_connection();
     services::change_concept_constraint(
         conn,
+        plugins,
         model_service,
         &user,
         concept_id,
         concept_constraint_in.into_inner(),
+        if_match.0,
     )
     .await
     .to_path_result()
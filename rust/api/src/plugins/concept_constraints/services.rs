@@ -1,17 +1,16 @@
 use super::super::Plugins;
 use super::models::ConceptConstraint;
 use super::types::{ConceptConstraintInChangeType, ConceptConstraintOutType};
-use crate::db;
 use crate::models::{Concept, User};
 use crate::plugins::control_concepts::services as control_concepts_services;
 use crate::plugins::Plugin;
 use crate::response::{ServiceResult, ToServiceResult};
 use crate::schema::{concept_constraints, concepts, projects};
-use crate::services::{model_services, permission_services};
+use crate::services::{model_history_services, model_services, permission_services};
 use crate::types::{ConceptOutType, ModelActionType};
 use crate::validation_error;
 use crate::web_socket::WebSocketModelService;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::PgConnection;
 use serde_json::{json, Value};
@@ -25,8 +24,7 @@ pub fn handle_get_model(
         .get_model_emitter
         .lock()
         .unwrap()
-        .on(move |mut model_out, _| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut model_out, _| {
             if !plugin
                 .lock()
                 .unwrap()
@@ -55,8 +53,7 @@ pub fn handle_add_concept(
         .add_concept_emitter
         .lock()
         .unwrap()
-        .on(move |mut concept_out, project| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut concept_out, project| {
             if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
                 return Ok(concept_out);
             }
@@ -75,8 +72,7 @@ pub fn handle_change_concept_value(
         .change_concept_value_emitter
         .lock()
         .unwrap()
-        .on(move |value, extra| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, value, extra| {
             if !plugin.lock().unwrap().is_enabled(conn, extra.project.id)? {
                 return Ok(value);
             }
@@ -116,6 +112,7 @@ pub fn create_project_concept_constraints(
                 })
                 .collect::<Vec<_>>(),
         )
+        .on_conflict_do_nothing()
         .get_results::<ConceptConstraint>(conn)
         .to_service_result()
 }
@@ -157,10 +154,12 @@ pub fn create_concept_constraint(
 
 pub async fn change_concept_constraint(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     concept_id: i32,
     concept_constraint_in: ConceptConstraintInChangeType,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ConceptConstraintOutType>> {
     let project = model_services::find_project_by_concept_id(conn, concept_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
@@ -182,25 +181,40 @@ pub async fn change_concept_constraint(
     check_constraint_value(concept.value, &concept_constraint_in)?;
     let concept_constraint = find_concept_constraint_by_id(conn, concept_id)
         .to_service_result_find(String::from("concept_constraint_not_found_error"))?;
-    let (concept_constraint, concept, project) = conn
-        .transaction(|conn| {
-            let concept_constraint = diesel::update(concept_constraints::table)
-                .filter(concept_constraints::concept_id.eq(concept_constraint.concept_id))
-                .set((
-                    concept_constraints::has_constraint.eq(concept_constraint_in.has_constraint),
-                    concept_constraints::min_value.eq(concept_constraint_in.min_value),
-                    concept_constraints::include_min_value
-                        .eq(concept_constraint_in.include_min_value),
-                    concept_constraints::max_value.eq(concept_constraint_in.max_value),
-                    concept_constraints::include_max_value
-                        .eq(concept_constraint_in.include_max_value),
-                ))
-                .get_result::<ConceptConstraint>(conn)?;
-            let (concept, project) =
-                model_services::update_concept(conn, concept_id, project.id, Utc::now())?;
-            Ok((concept_constraint, concept, project))
-        })
-        .to_service_result()?;
+    let (concept_constraint, concept, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        model_services::check_concept_version(
+            conn,
+            plugins,
+            &project,
+            concept_id,
+            expected_version,
+        )?;
+        let previous_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        let concept_constraint = diesel::update(concept_constraints::table)
+            .filter(concept_constraints::concept_id.eq(concept_constraint.concept_id))
+            .set((
+                concept_constraints::has_constraint.eq(concept_constraint_in.has_constraint),
+                concept_constraints::min_value.eq(concept_constraint_in.min_value),
+                concept_constraints::include_min_value.eq(concept_constraint_in.include_min_value),
+                concept_constraints::max_value.eq(concept_constraint_in.max_value),
+                concept_constraints::include_max_value.eq(concept_constraint_in.include_max_value),
+            ))
+            .get_result::<ConceptConstraint>(conn)?;
+        let (concept, project) =
+            model_services::update_concept(conn, concept_id, project.id, Utc::now())?;
+        let next_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "changeConceptConstraint",
+            previous_state,
+            next_state,
+        )?;
+        Ok((concept_constraint, concept, project))
+    })?;
     let concept_constraint_out = ConceptConstraintOutType::from((concept_constraint, concept));
     let model_action = ModelActionType::new(
         &project,
@@ -0,0 +1,10 @@
+use crate::schema::concept_decay_rates;
+use diesel::{Identifiable, Queryable};
+use rocket::serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(primary_key(concept_id), belongs_to(Concept))]
+pub struct ConceptDecayRate {
+    pub concept_id: i32,
+    pub decay_rate: f64,
+}
@@ -0,0 +1,40 @@
+use super::services;
+use super::types::ConceptDecayRateOutType;
+use crate::db;
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
+use crate::response::{PathResult, ToPathResult};
+use crate::types::ModelActionType;
+use crate::web_socket::WebSocketModelService;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+/// Change concept decay rate
+#[openapi(tag = "concept decay rates")]
+#[patch(
+    "/concepts/<concept_id>/change_decay_rate",
+    format = "json",
+    data = "<decay_rate>"
+)]
+pub async fn change_concept_decay_rate(
+    concept_id: i32,
+    decay_rate: Json<f64>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ConceptDecayRateOutType>> {
+    let conn = &mut db::establish_connection();
+    services::change_concept_decay_rate(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        concept_id,
+        decay_rate.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
@@ -0,0 +1,206 @@
+use super::super::Plugins;
+use super::models::ConceptDecayRate;
+use super::types::ConceptDecayRateOutType;
+use crate::models::{Concept, User};
+use crate::plugins::Plugin;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{concept_decay_rates, concepts, projects};
+use crate::services::{model_history_services, model_services, permission_services};
+use crate::types::{ConceptOutType, ModelActionType};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+pub fn handle_get_model(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .get_model_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut model_out, _| {
+            if !plugin
+                .lock()
+                .unwrap()
+                .is_enabled(conn, model_out.project.id)?
+            {
+                return Ok(model_out);
+            }
+            let concept_decay_rates =
+                find_project_concept_decay_rates(conn, model_out.project.id).to_service_result()?;
+            for concept_out in model_out.concepts.iter_mut() {
+                let concept_decay_rate = concept_decay_rates
+                    .iter()
+                    .find(|cdr| cdr.concept_id == concept_out.id)
+                    .unwrap();
+                add_decay_rate(concept_out, &concept_decay_rate);
+            }
+            Ok(model_out)
+        })
+}
+
+pub fn handle_add_concept(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .add_concept_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut concept_out, project| {
+            if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
+                return Ok(concept_out);
+            }
+            let concept_decay_rate =
+                create_concept_decay_rate(conn, concept_out.id).to_service_result()?;
+            add_decay_rate(&mut concept_out, &concept_decay_rate);
+            Ok(concept_out)
+        });
+}
+
+pub fn create_project_concept_decay_rates(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<Vec<ConceptDecayRate>> {
+    let concepts = model_services::find_project_concepts(conn, project_id).to_service_result()?;
+    diesel::insert_into(concept_decay_rates::table)
+        .values(
+            &concepts
+                .into_iter()
+                .map(|concept| concept_decay_rates::concept_id.eq(concept.id))
+                .collect::<Vec<_>>(),
+        )
+        .on_conflict_do_nothing()
+        .get_results::<ConceptDecayRate>(conn)
+        .to_service_result()
+}
+
+pub fn delete_project_concept_decay_rates(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<()> {
+    let concepts = model_services::find_project_concepts(conn, project_id).to_service_result()?;
+    diesel::delete(
+        concept_decay_rates::table.filter(
+            concept_decay_rates::concept_id.eq_any(
+                concepts
+                    .into_iter()
+                    .map(|concept| concept.id)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    )
+    .execute(conn)
+    .to_service_result()?;
+    Ok(())
+}
+
+pub fn create_concept_decay_rate(
+    conn: &mut PgConnection,
+    concept_id: i32,
+) -> QueryResult<ConceptDecayRate> {
+    diesel::insert_into(concept_decay_rates::table)
+        .values(concept_decay_rates::concept_id.eq(concept_id))
+        .get_result::<ConceptDecayRate>(conn)
+}
+
+pub async fn change_concept_decay_rate(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    concept_id: i32,
+    decay_rate: f64,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ConceptDecayRateOutType>> {
+    let project = model_services::find_project_by_concept_id(conn, concept_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    let concept_decay_rate = find_concept_decay_rate_by_id(conn, concept_id)
+        .to_service_result_find(String::from("concept_decay_rate_not_found_error"))?;
+    if decay_rate < 0.0 {
+        return validation_error!("concept_decay_rate_negative_error");
+    }
+    let (concept_decay_rate, concept, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        model_services::check_concept_version(
+            conn,
+            plugins,
+            &project,
+            concept_id,
+            expected_version,
+        )?;
+        let previous_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        let concept_decay_rate = diesel::update(concept_decay_rates::table)
+            .filter(concept_decay_rates::concept_id.eq(concept_decay_rate.concept_id))
+            .set(concept_decay_rates::decay_rate.eq(decay_rate))
+            .get_result::<ConceptDecayRate>(conn)?;
+        let (concept, project) =
+            model_services::update_concept(conn, concept_id, project.id, Utc::now())?;
+        let next_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "changeConceptDecayRate",
+            previous_state,
+            next_state,
+        )?;
+        Ok((concept_decay_rate, concept, project))
+    })?;
+    let concept_decay_rate_out = ConceptDecayRateOutType::from((concept_decay_rate, concept));
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("changeConceptDecayRate"),
+        concept_decay_rate_out,
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub fn find_project_concept_decay_rates(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<Vec<ConceptDecayRate>> {
+    projects::table
+        .inner_join(concepts::table.inner_join(concept_decay_rates::table))
+        .select(concept_decay_rates::all_columns)
+        .filter(projects::id.eq(project_id))
+        .get_results::<ConceptDecayRate>(conn)
+}
+
+pub fn find_concept_decay_rate_by_id(
+    conn: &mut PgConnection,
+    concept_id: i32,
+) -> QueryResult<ConceptDecayRate> {
+    concept_decay_rates::table
+        .filter(concept_decay_rates::concept_id.eq(concept_id))
+        .first::<ConceptDecayRate>(conn)
+}
+
+fn add_decay_rate(concept_out: &mut ConceptOutType, concept_decay_rate: &ConceptDecayRate) -> () {
+    let plugins_data = match &mut concept_out.plugins_data {
+        Value::Object(plugins_data) => plugins_data,
+        _ => unreachable!(),
+    };
+    plugins_data
+        .entry("conceptDecayRates")
+        .or_insert(json!({ "decayRate": concept_decay_rate.decay_rate }));
+}
+
+impl From<(ConceptDecayRate, Concept)> for ConceptDecayRateOutType {
+    fn from((concept_decay_rate, concept): (ConceptDecayRate, Concept)) -> Self {
+        Self {
+            concept_id: concept_decay_rate.concept_id,
+            decay_rate: concept_decay_rate.decay_rate,
+            updated_at: concept.updated_at,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use rocket::serde::Serialize;
+use rocket_okapi::JsonSchema;
+
+/// Type of concept decay rate
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConceptDecayRateOutType {
+    /// Concept identifier
+    pub concept_id: i32,
+    /// Rate at which concept value decays in continuous-time dynamics model,
+    /// concepts without incoming connections keep their value
+    pub decay_rate: f64,
+    /// Concept update time
+    pub updated_at: DateTime<Utc>,
+}
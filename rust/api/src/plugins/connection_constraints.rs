@@ -26,6 +26,10 @@ impl Plugin for ConnectionConstraintsPlugin {
         services::delete_project_connection_constraints(conn, project.id)?;
         Ok(project)
     }
+    fn sync_model(&self, conn: &mut PgConnection, project: Project) -> ServiceResult<Project> {
+        services::create_project_connection_constraints(conn, project.id)?;
+        Ok(project)
+    }
 }
 
 #[rocket::async_trait]
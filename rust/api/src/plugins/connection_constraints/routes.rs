@@ -2,6 +2,8 @@ use super::services;
 use super::types::{ConnectionConstraintInChangeType, ConnectionConstraintOutType};
 use crate::db;
 use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
 use crate::response::{PathResult, ToPathResult};
 use crate::types::ModelActionType;
 use crate::web_socket::WebSocketModelService;
@@ -18,16 +20,20 @@ use rocket_okapi::openapi;
 pub async fn change_connection_constraint(
     connection_id: i32,
     connection_constraint_in: Json<ConnectionConstraintInChangeType>,
+    if_match: IfMatch,
     user: User,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ConnectionConstraintOutType>> {
     let conn = &mut db::establish_connection();
     services::change_connection_constraint(
         conn,
+        plugins,
         model_service,
         &user,
         connection_id,
         connection_constraint_in.into_inner(),
+        if_match.0,
     )
     .await
     .to_path_result()
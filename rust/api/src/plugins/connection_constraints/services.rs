@@ -1,17 +1,16 @@
 use super::super::Plugins;
 use super::models::ConnectionConstraint;
 use super::types::{ConnectionConstraintInChangeType, ConnectionConstraintOutType};
-use crate::db;
 use crate::models::{Connection, User};
 use crate::plugins::control_connections::services as control_connections_services;
 use crate::plugins::Plugin;
 use crate::response::{ServiceResult, ToServiceResult};
 use crate::schema::{connection_constraints, connections, projects};
-use crate::services::{model_services, permission_services};
+use crate::services::{model_history_services, model_services, permission_services};
 use crate::types::{ConnectionOutType, ModelActionType};
 use crate::validation_error;
 use crate::web_socket::WebSocketModelService;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::Connection as DieselConnection;
 use diesel::PgConnection;
@@ -26,8 +25,7 @@ pub fn handle_get_model(
         .get_model_emitter
         .lock()
         .unwrap()
-        .on(move |mut model_out, _| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut model_out, _| {
             if !plugin
                 .lock()
                 .unwrap()
@@ -57,8 +55,7 @@ pub fn handle_add_connection(
         .add_connection_emitter
         .lock()
         .unwrap()
-        .on(move |mut connection_out, project| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut connection_out, project| {
             if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
                 return Ok(connection_out);
             }
@@ -77,8 +74,7 @@ pub fn handle_change_connection_value(
         .change_connection_value_emitter
         .lock()
         .unwrap()
-        .on(move |value, extra| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, value, extra| {
             if !plugin.lock().unwrap().is_enabled(conn, extra.project.id)? {
                 return Ok(value);
             }
@@ -119,6 +115,7 @@ pub fn create_project_connection_constraints(
                 })
                 .collect::<Vec<_>>(),
         )
+        .on_conflict_do_nothing()
         .get_results::<ConnectionConstraint>(conn)
         .to_service_result()
 }
@@ -161,10 +158,12 @@ pub fn create_connection_constraint(
 
 pub async fn change_connection_constraint(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     connection_id: i32,
     connection_constraint_in: ConnectionConstraintInChangeType,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ConnectionConstraintOutType>> {
     let project = model_services::find_project_by_connection_id(conn, connection_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
@@ -186,8 +185,21 @@ pub async fn change_connection_constraint(
     check_constraint_value(connection.value, &connection_constraint_in)?;
     let connection_constraint = find_connection_constraint_by_id(conn, connection_id)
         .to_service_result_find(String::from("connection_constraint_not_found_error"))?;
-    let (connection_constraint, connection, project) = conn
-        .transaction(|conn| {
+    let (connection_constraint, connection, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            model_services::check_connection_version(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+                expected_version,
+            )?;
+            let previous_state = model_history_services::get_connection_elements(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+            )?;
             let connection_constraint = diesel::update(connection_constraints::table)
                 .filter(
                     connection_constraints::connection_id.eq(connection_constraint.connection_id),
@@ -205,9 +217,22 @@ pub async fn change_connection_constraint(
                 .get_result::<ConnectionConstraint>(conn)?;
             let (connection, project) =
                 model_services::update_connection(conn, connection_id, project.id, Utc::now())?;
+            let next_state = model_history_services::get_connection_elements(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+            )?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeConnectionConstraint",
+                previous_state,
+                next_state,
+            )?;
             Ok((connection_constraint, connection, project))
-        })
-        .to_service_result()?;
+        })?;
     let connection_constraint_out =
         ConnectionConstraintOutType::from((connection_constraint, connection));
     let model_action = ModelActionType::new(
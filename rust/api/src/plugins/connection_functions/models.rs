@@ -0,0 +1,27 @@
+use crate::schema::connection_functions;
+use diesel::{Identifiable, Queryable};
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+use serde_json::Value;
+
+#[derive(
+    Debug, Clone, PartialEq, diesel_derive_enum::DbEnum, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[ExistingTypePath = "crate::schema::sql_types::ConnectionFunctionType"]
+pub enum ConnectionFunctionType {
+    Linear,
+    Threshold,
+    Saturation,
+    PiecewiseLinear,
+}
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(primary_key(connection_id), belongs_to(Connection))]
+pub struct ConnectionFunction {
+    pub connection_id: i32,
+    pub function_type: ConnectionFunctionType,
+    pub parameter: f64,
+    pub points: Value,
+    pub is_control: bool,
+}
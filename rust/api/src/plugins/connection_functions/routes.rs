@@ -0,0 +1,40 @@
+use super::services;
+use super::types::{ConnectionFunctionInChangeType, ConnectionFunctionOutType};
+use crate::db;
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
+use crate::response::{PathResult, ToPathResult};
+use crate::types::ModelActionType;
+use crate::web_socket::WebSocketModelService;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+/// Change connection function
+#[openapi(tag = "connection functions")]
+#[patch(
+    "/connections/<connection_id>/change_function",
+    format = "json",
+    data = "<connection_function_in>"
+)]
+pub async fn change_connection_function(
+    connection_id: i32,
+    connection_function_in: Json<ConnectionFunctionInChangeType>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ConnectionFunctionOutType>> {
+    let conn = &mut db::establish_connection();
+    services::change_connection_function(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        connection_id,
+        connection_function_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
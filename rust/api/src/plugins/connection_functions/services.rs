@@ -0,0 +1,291 @@
+use super::super::Plugins;
+use super::models::{ConnectionFunction, ConnectionFunctionType};
+use super::types::{ConnectionFunctionInChangeType, ConnectionFunctionOutType, FunctionPointType};
+use crate::models::{Connection, User};
+use crate::plugins::Plugin;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{connection_functions, connections, projects};
+use crate::services::{model_history_services, model_services, permission_services};
+use crate::types::{ConnectionOutType, ModelActionType};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::connection_function::{
+    ConnectionFunction as Function, FunctionPoint,
+};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+pub fn handle_get_model(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .get_model_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut model_out, _| {
+            if !plugin
+                .lock()
+                .unwrap()
+                .is_enabled(conn, model_out.project.id)?
+            {
+                return Ok(model_out);
+            }
+            let connection_functions =
+                find_project_connection_functions(conn, model_out.project.id)
+                    .to_service_result()?;
+            for connection_out in model_out.connections.iter_mut() {
+                let connection_function = connection_functions
+                    .iter()
+                    .find(|cf| cf.connection_id == connection_out.id)
+                    .unwrap();
+                add_function(connection_out, &connection_function);
+            }
+            Ok(model_out)
+        })
+}
+
+pub fn handle_add_connection(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .add_connection_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut connection_out, project| {
+            if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
+                return Ok(connection_out);
+            }
+            let connection_function =
+                create_connection_function(conn, connection_out.id).to_service_result()?;
+            add_function(&mut connection_out, &connection_function);
+            Ok(connection_out)
+        });
+}
+
+pub fn create_project_connection_functions(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<Vec<ConnectionFunction>> {
+    let connections =
+        model_services::find_project_connections(conn, project_id).to_service_result()?;
+    diesel::insert_into(connection_functions::table)
+        .values(
+            &connections
+                .into_iter()
+                .map(|connection| connection_functions::connection_id.eq(connection.id))
+                .collect::<Vec<_>>(),
+        )
+        .on_conflict_do_nothing()
+        .get_results::<ConnectionFunction>(conn)
+        .to_service_result()
+}
+
+pub fn delete_project_connection_functions(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<()> {
+    let connections =
+        model_services::find_project_connections(conn, project_id).to_service_result()?;
+    diesel::delete(
+        connection_functions::table.filter(
+            connection_functions::connection_id.eq_any(
+                connections
+                    .into_iter()
+                    .map(|connection| connection.id)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    )
+    .execute(conn)
+    .to_service_result()?;
+    Ok(())
+}
+
+pub fn create_connection_function(
+    conn: &mut PgConnection,
+    connection_id: i32,
+) -> QueryResult<ConnectionFunction> {
+    diesel::insert_into(connection_functions::table)
+        .values(connection_functions::connection_id.eq(connection_id))
+        .get_result::<ConnectionFunction>(conn)
+}
+
+pub async fn change_connection_function(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    connection_id: i32,
+    connection_function_in: ConnectionFunctionInChangeType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ConnectionFunctionOutType>> {
+    let project = model_services::find_project_by_connection_id(conn, connection_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    let connection_function = find_connection_function_by_id(conn, connection_id)
+        .to_service_result_find(String::from("connection_function_not_found_error"))?;
+    check_connection_function(&connection_function_in)?;
+    let (connection_function, connection, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            model_services::check_connection_version(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+                expected_version,
+            )?;
+            let previous_state = model_history_services::get_connection_elements(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+            )?;
+            let connection_function = diesel::update(connection_functions::table)
+                .filter(connection_functions::connection_id.eq(connection_function.connection_id))
+                .set((
+                    connection_functions::function_type
+                        .eq(connection_function_in.function_type.clone()),
+                    connection_functions::parameter.eq(connection_function_in.parameter),
+                    connection_functions::points
+                        .eq(serde_json::to_value(&connection_function_in.points).unwrap()),
+                    connection_functions::is_control.eq(connection_function_in.is_control),
+                ))
+                .get_result::<ConnectionFunction>(conn)?;
+            let (connection, project) =
+                model_services::update_connection(conn, connection_id, project.id, Utc::now())?;
+            let next_state = model_history_services::get_connection_elements(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+            )?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeConnectionFunction",
+                previous_state,
+                next_state,
+            )?;
+            Ok((connection_function, connection, project))
+        })?;
+    let connection_function_out =
+        ConnectionFunctionOutType::from((connection_function, connection));
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("changeConnectionFunction"),
+        connection_function_out,
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub fn find_project_connection_functions(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<Vec<ConnectionFunction>> {
+    projects::table
+        .inner_join(connections::table.inner_join(connection_functions::table))
+        .select(connection_functions::all_columns)
+        .filter(projects::id.eq(project_id))
+        .get_results::<ConnectionFunction>(conn)
+}
+
+pub fn find_connection_function_by_id(
+    conn: &mut PgConnection,
+    connection_id: i32,
+) -> QueryResult<ConnectionFunction> {
+    connection_functions::table
+        .filter(connection_functions::connection_id.eq(connection_id))
+        .first::<ConnectionFunction>(conn)
+}
+
+fn check_connection_function(
+    connection_function_in: &ConnectionFunctionInChangeType,
+) -> ServiceResult<()> {
+    let function = get_function(
+        &connection_function_in.function_type,
+        connection_function_in.parameter,
+        &connection_function_in.points,
+    );
+    if !function.is_valid() {
+        return validation_error!("connection_function_parameters_error");
+    }
+    if connection_function_in.is_control && function.get_parameter().is_none() {
+        return validation_error!("connection_function_not_adjustable_error");
+    }
+    Ok(())
+}
+
+pub fn get_function(
+    function_type: &ConnectionFunctionType,
+    parameter: f64,
+    points: &[FunctionPointType],
+) -> Function {
+    match function_type {
+        ConnectionFunctionType::Linear => Function::Linear,
+        ConnectionFunctionType::Threshold => Function::Threshold {
+            threshold: parameter,
+        },
+        ConnectionFunctionType::Saturation => Function::Saturation {
+            saturation: parameter,
+        },
+        ConnectionFunctionType::PiecewiseLinear => Function::PiecewiseLinear {
+            points: points
+                .iter()
+                .map(|point| FunctionPoint {
+                    x: point.x,
+                    y: point.y,
+                })
+                .collect(),
+        },
+    }
+}
+
+fn add_function(
+    connection_out: &mut ConnectionOutType,
+    connection_function: &ConnectionFunction,
+) -> () {
+    let plugins_data = match &mut connection_out.plugins_data {
+        Value::Object(plugins_data) => plugins_data,
+        _ => unreachable!(),
+    };
+    plugins_data.entry("connectionFunctions").or_insert(json!({
+        "functionType": connection_function.function_type,
+        "parameter": connection_function.parameter,
+        "points": connection_function.points,
+        "isControl": connection_function.is_control
+    }));
+}
+
+impl From<&ConnectionFunction> for Function {
+    fn from(connection_function: &ConnectionFunction) -> Self {
+        let points =
+            serde_json::from_value::<Vec<FunctionPointType>>(connection_function.points.clone())
+                .unwrap_or_default();
+        get_function(
+            &connection_function.function_type,
+            connection_function.parameter,
+            &points,
+        )
+    }
+}
+
+impl From<(ConnectionFunction, Connection)> for ConnectionFunctionOutType {
+    fn from((connection_function, connection): (ConnectionFunction, Connection)) -> Self {
+        Self {
+            connection_id: connection_function.connection_id,
+            function_type: connection_function.function_type,
+            parameter: connection_function.parameter,
+            points: serde_json::from_value(connection_function.points).unwrap_or_default(),
+            is_control: connection_function.is_control,
+            updated_at: connection.updated_at,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use super::models::ConnectionFunctionType;
+use chrono::{DateTime, Utc};
+use rocket::serde::{Deserialize, Serialize};
+use rocket_okapi::JsonSchema;
+
+/// Type of connection function point
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionPointType {
+    /// Source concept value
+    pub x: f64,
+    /// Transformed source concept value
+    pub y: f64,
+}
+
+/// Type of connection function to change
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionFunctionInChangeType {
+    /// Connection function type
+    pub function_type: ConnectionFunctionType,
+    /// Threshold of absolute source value or saturation level in range [0; 1], ignored by other function types
+    pub parameter: f64,
+    /// Points of piecewise linear function ordered by x, ignored by other function types
+    pub points: Vec<FunctionPointType>,
+    /// Whether function parameter is adjusted by genetic algorithm
+    pub is_control: bool,
+}
+
+/// Type of connection function
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionFunctionOutType {
+    /// Connection identifier
+    pub connection_id: i32,
+    /// Connection function type
+    pub function_type: ConnectionFunctionType,
+    /// Threshold or saturation level
+    pub parameter: f64,
+    /// Points of piecewise linear function
+    pub points: Vec<FunctionPointType>,
+    /// Whether function parameter is adjusted by genetic algorithm
+    pub is_control: bool,
+    /// Connection update time
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,10 @@
+use crate::schema::connection_lags;
+use diesel::{Identifiable, Queryable};
+use rocket::serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(primary_key(connection_id), belongs_to(Connection))]
+pub struct ConnectionLag {
+    pub connection_id: i32,
+    pub lag: i32,
+}
@@ -0,0 +1,40 @@
+use super::services;
+use super::types::ConnectionLagOutType;
+use crate::db;
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
+use crate::response::{PathResult, ToPathResult};
+use crate::types::ModelActionType;
+use crate::web_socket::WebSocketModelService;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+/// Change connection lag
+#[openapi(tag = "connection lags")]
+#[patch(
+    "/connections/<connection_id>/change_lag",
+    format = "json",
+    data = "<lag>"
+)]
+pub async fn change_connection_lag(
+    connection_id: i32,
+    lag: Json<i32>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ConnectionLagOutType>> {
+    let conn = &mut db::establish_connection();
+    services::change_connection_lag(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        connection_id,
+        lag.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
@@ -0,0 +1,221 @@
+use super::super::Plugins;
+use super::models::ConnectionLag;
+use super::types::ConnectionLagOutType;
+use crate::models::{Connection, User};
+use crate::plugins::Plugin;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{connection_lags, connections, projects};
+use crate::services::{model_history_services, model_services, permission_services};
+use crate::types::{ConnectionOutType, ModelActionType};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::adjustment::MAX_CONNECTION_LAG;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+pub fn handle_get_model(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .get_model_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut model_out, _| {
+            if !plugin
+                .lock()
+                .unwrap()
+                .is_enabled(conn, model_out.project.id)?
+            {
+                return Ok(model_out);
+            }
+            let connection_lags =
+                find_project_connection_lags(conn, model_out.project.id).to_service_result()?;
+            for connection_out in model_out.connections.iter_mut() {
+                let connection_lag = connection_lags
+                    .iter()
+                    .find(|cl| cl.connection_id == connection_out.id)
+                    .unwrap();
+                add_lag(connection_out, &connection_lag);
+            }
+            Ok(model_out)
+        })
+}
+
+pub fn handle_add_connection(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .add_connection_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut connection_out, project| {
+            if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
+                return Ok(connection_out);
+            }
+            let connection_lag =
+                create_connection_lag(conn, connection_out.id).to_service_result()?;
+            add_lag(&mut connection_out, &connection_lag);
+            Ok(connection_out)
+        });
+}
+
+pub fn create_project_connection_lags(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<Vec<ConnectionLag>> {
+    let connections =
+        model_services::find_project_connections(conn, project_id).to_service_result()?;
+    diesel::insert_into(connection_lags::table)
+        .values(
+            &connections
+                .into_iter()
+                .map(|connection| connection_lags::connection_id.eq(connection.id))
+                .collect::<Vec<_>>(),
+        )
+        .on_conflict_do_nothing()
+        .get_results::<ConnectionLag>(conn)
+        .to_service_result()
+}
+
+pub fn delete_project_connection_lags(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<()> {
+    let connections =
+        model_services::find_project_connections(conn, project_id).to_service_result()?;
+    diesel::delete(
+        connection_lags::table.filter(
+            connection_lags::connection_id.eq_any(
+                connections
+                    .into_iter()
+                    .map(|connection| connection.id)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    )
+    .execute(conn)
+    .to_service_result()?;
+    Ok(())
+}
+
+pub fn create_connection_lag(
+    conn: &mut PgConnection,
+    connection_id: i32,
+) -> QueryResult<ConnectionLag> {
+    diesel::insert_into(connection_lags::table)
+        .values(connection_lags::connection_id.eq(connection_id))
+        .get_result::<ConnectionLag>(conn)
+}
+
+pub async fn change_connection_lag(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    connection_id: i32,
+    lag: i32,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ConnectionLagOutType>> {
+    let project = model_services::find_project_by_connection_id(conn, connection_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    let connection_lag = find_connection_lag_by_id(conn, connection_id)
+        .to_service_result_find(String::from("connection_lag_not_found_error"))?;
+    if lag < 0 {
+        return validation_error!("connection_lag_negative_error");
+    }
+    if lag > MAX_CONNECTION_LAG {
+        let max_lag = MAX_CONNECTION_LAG.to_string();
+        return validation_error!("connection_lag_max_error", max_lag = &max_lag);
+    }
+    let (connection_lag, connection, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        model_services::check_connection_version(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+            expected_version,
+        )?;
+        let previous_state = model_history_services::get_connection_elements(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+        )?;
+        let connection_lag = diesel::update(connection_lags::table)
+            .filter(connection_lags::connection_id.eq(connection_lag.connection_id))
+            .set(connection_lags::lag.eq(lag))
+            .get_result::<ConnectionLag>(conn)?;
+        let (connection, project) =
+            model_services::update_connection(conn, connection_id, project.id, Utc::now())?;
+        let next_state = model_history_services::get_connection_elements(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+        )?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "changeConnectionLag",
+            previous_state,
+            next_state,
+        )?;
+        Ok((connection_lag, connection, project))
+    })?;
+    let connection_lag_out = ConnectionLagOutType::from((connection_lag, connection));
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("changeConnectionLag"),
+        connection_lag_out,
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub fn find_project_connection_lags(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<Vec<ConnectionLag>> {
+    projects::table
+        .inner_join(connections::table.inner_join(connection_lags::table))
+        .select(connection_lags::all_columns)
+        .filter(projects::id.eq(project_id))
+        .get_results::<ConnectionLag>(conn)
+}
+
+pub fn find_connection_lag_by_id(
+    conn: &mut PgConnection,
+    connection_id: i32,
+) -> QueryResult<ConnectionLag> {
+    connection_lags::table
+        .filter(connection_lags::connection_id.eq(connection_id))
+        .first::<ConnectionLag>(conn)
+}
+
+fn add_lag(connection_out: &mut ConnectionOutType, connection_lag: &ConnectionLag) -> () {
+    let plugins_data = match &mut connection_out.plugins_data {
+        Value::Object(plugins_data) => plugins_data,
+        _ => unreachable!(),
+    };
+    plugins_data
+        .entry("connectionLags")
+        .or_insert(json!({ "lag": connection_lag.lag }));
+}
+
+impl From<(ConnectionLag, Connection)> for ConnectionLagOutType {
+    fn from((connection_lag, connection): (ConnectionLag, Connection)) -> Self {
+        Self {
+            connection_id: connection_lag.connection_id,
+            lag: connection_lag.lag,
+            updated_at: connection.updated_at,
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use rocket::serde::Serialize;
+use rocket_okapi::JsonSchema;
+
+/// Type of connection lag
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionLagOutType {
+    /// Connection identifier
+    pub connection_id: i32,
+    /// Number of time steps by which the source concept value is delayed
+    pub lag: i32,
+    /// Connection update time
+    pub updated_at: DateTime<Utc>,
+}
@@ -30,6 +30,10 @@ impl Plugin for ControlConceptsPlugin {
         services::delete_project_control_concepts(conn, project.id)?;
         Ok(project)
     }
+    fn sync_model(&self, conn: &mut PgConnection, project: Project) -> ServiceResult<Project> {
+        services::create_project_control_concepts(conn, project.id)?;
+        Ok(project)
+    }
 }
 
 #[rocket::async_trait]
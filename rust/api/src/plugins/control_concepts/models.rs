@@ -1,7 +1,8 @@
 use crate::schema::control_concepts;
 use diesel::{Identifiable, Queryable};
+use rocket::serde::{Deserialize, Serialize};
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(primary_key(concept_id), belongs_to(Concept))]
 pub struct ControlConcept {
     pub concept_id: i32,
@@ -2,6 +2,8 @@ use super::services;
 use super::types::ControlConceptOutType;
 use crate::db;
 use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
 use crate::response::{PathResult, ToPathResult};
 use crate::types::ModelActionType;
 use crate::web_socket::WebSocketModelService;
@@ -18,16 +20,20 @@ use rocket_okapi::openapi;
 pub async fn change_concept_is_control(
     concept_id: i32,
     is_control: Json<bool>,
+    if_match: IfMatch,
     user: User,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ControlConceptOutType>> {
     let conn = &mut db::establish_connection();
     services::set_is_control(
         conn,
+        plugins,
         model_service,
         &user,
         concept_id,
         is_control.into_inner(),
+        if_match.0,
     )
     .await
     .to_path_result()
@@ -1,7 +1,6 @@
 use super::super::Plugins;
 use super::models::ControlConcept;
 use super::types::ControlConceptOutType;
-use crate::db;
 use crate::models::{Concept, User};
 use crate::plugins::concept_constraints::models::ConceptConstraint;
 use crate::plugins::concept_constraints::services as concept_constraints_services;
@@ -9,11 +8,11 @@ use crate::plugins::target_concepts::services as target_concepts_services;
 use crate::plugins::Plugin;
 use crate::response::{ServiceResult, ToServiceResult};
 use crate::schema::{concept_constraints, concepts, control_concepts, projects};
-use crate::services::{model_services, permission_services};
+use crate::services::{model_history_services, model_services, permission_services};
 use crate::types::{ConceptOutType, ModelActionType};
 use crate::validation_error;
 use crate::web_socket::WebSocketModelService;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::PgConnection;
 use serde_json::{json, Value};
@@ -27,8 +26,7 @@ pub fn handle_get_model(
         .get_model_emitter
         .lock()
         .unwrap()
-        .on(move |mut model_out, _| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut model_out, _| {
             if !plugin
                 .lock()
                 .unwrap()
@@ -57,8 +55,7 @@ pub fn handle_add_concept(
         .add_concept_emitter
         .lock()
         .unwrap()
-        .on(move |mut concept_out, project| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut concept_out, project| {
             if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
                 return Ok(concept_out);
             }
@@ -81,6 +78,7 @@ pub fn create_project_control_concepts(
                 .map(|concept| control_concepts::concept_id.eq(concept.id))
                 .collect::<Vec<_>>(),
         )
+        .on_conflict_do_nothing()
         .get_results::<ControlConcept>(conn)
         .to_service_result()
 }
@@ -116,10 +114,12 @@ pub fn create_control_concept(
 
 pub async fn set_is_control(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     concept_id: i32,
     is_control: bool,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ControlConceptOutType>> {
     let project = model_services::find_project_by_concept_id(conn, concept_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
@@ -133,8 +133,17 @@ pub async fn set_is_control(
         concept_constraints_services::find_concept_constraint_by_id(conn, concept_id)
             .optional()
             .to_service_result()?;
-    let (control_concept, concept_constraint, concept, project) = conn
-        .transaction(|conn| {
+    let (control_concept, concept_constraint, concept, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            model_services::check_concept_version(
+                conn,
+                plugins,
+                &project,
+                concept_id,
+                expected_version,
+            )?;
+            let previous_state =
+                model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
             let control_concept = diesel::update(control_concepts::table)
                 .filter(control_concepts::concept_id.eq(control_concept.concept_id))
                 .set(control_concepts::is_control.eq(is_control))
@@ -157,9 +166,18 @@ pub async fn set_is_control(
             };
             let (concept, project) =
                 model_services::update_concept(conn, concept_id, project.id, Utc::now())?;
+            let next_state =
+                model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeControlConcept",
+                previous_state,
+                next_state,
+            )?;
             Ok((control_concept, concept_constraint, concept, project))
-        })
-        .to_service_result()?;
+        })?;
     let control_concept_out =
         ControlConceptOutType::from((control_concept, concept_constraint, concept));
     let model_action = ModelActionType::new(
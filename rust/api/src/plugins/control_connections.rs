@@ -26,6 +26,10 @@ impl Plugin for ControlConnectionsPlugin {
         services::delete_project_control_connections(conn, project.id)?;
         Ok(project)
     }
+    fn sync_model(&self, conn: &mut PgConnection, project: Project) -> ServiceResult<Project> {
+        services::create_project_control_connections(conn, project.id)?;
+        Ok(project)
+    }
 }
 
 #[rocket::async_trait]
@@ -1,7 +1,8 @@
 use crate::schema::control_connections;
 use diesel::{Identifiable, Queryable};
+use rocket::serde::{Deserialize, Serialize};
 
-#[derive(Queryable, Identifiable)]
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
 #[diesel(primary_key(connection_id), belongs_to(Connection))]
 pub struct ControlConnection {
     pub connection_id: i32,
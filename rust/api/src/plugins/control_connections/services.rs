@@ -1,17 +1,16 @@
 use super::super::Plugins;
 use super::models::ControlConnection;
 use super::types::ControlConnectionOutType;
-use crate::db;
 use crate::models::{Connection, User};
 use crate::plugins::connection_constraints::models::ConnectionConstraint;
 use crate::plugins::connection_constraints::services as connection_constraints_services;
 use crate::plugins::Plugin;
 use crate::response::{ServiceResult, ToServiceResult};
 use crate::schema::{connection_constraints, connections, control_connections, projects};
-use crate::services::{model_services, permission_services};
+use crate::services::{model_history_services, model_services, permission_services};
 use crate::types::{ConnectionOutType, ModelActionType};
 use crate::web_socket::WebSocketModelService;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::Connection as DieselConnection;
 use diesel::PgConnection;
@@ -26,8 +25,7 @@ pub fn handle_get_model(
         .get_model_emitter
         .lock()
         .unwrap()
-        .on(move |mut model_out, _| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut model_out, _| {
             if !plugin
                 .lock()
                 .unwrap()
@@ -56,8 +54,7 @@ pub fn handle_add_connection(
         .add_connection_emitter
         .lock()
         .unwrap()
-        .on(move |mut connection_out, project| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut connection_out, project| {
             if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
                 return Ok(connection_out);
             }
@@ -81,6 +78,7 @@ pub fn create_project_control_connections(
                 .map(|connection| control_connections::connection_id.eq(connection.id))
                 .collect::<Vec<_>>(),
         )
+        .on_conflict_do_nothing()
         .get_results::<ControlConnection>(conn)
         .to_service_result()
 }
@@ -117,10 +115,12 @@ pub fn create_control_connection(
 
 pub async fn set_is_control(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     connection_id: i32,
     is_control: bool,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ControlConnectionOutType>> {
     let project = model_services::find_project_by_connection_id(conn, connection_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
@@ -131,8 +131,21 @@ pub async fn set_is_control(
         connection_constraints_services::find_connection_constraint_by_id(conn, connection_id)
             .optional()
             .to_service_result()?;
-    let (control_connection, connection_constraint, connection, project) = conn
-        .transaction(|conn| {
+    let (control_connection, connection_constraint, connection, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            model_services::check_connection_version(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+                expected_version,
+            )?;
+            let previous_state = model_history_services::get_connection_elements(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+            )?;
             let control_connection = diesel::update(control_connections::table)
                 .filter(control_connections::connection_id.eq(control_connection.connection_id))
                 .set(control_connections::is_control.eq(is_control))
@@ -156,14 +169,27 @@ pub async fn set_is_control(
             };
             let (connection, project) =
                 model_services::update_connection(conn, connection_id, project.id, Utc::now())?;
+            let next_state = model_history_services::get_connection_elements(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+            )?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeControlConnection",
+                previous_state,
+                next_state,
+            )?;
             Ok((
                 control_connection,
                 connection_constraint,
                 connection,
                 project,
             ))
-        })
-        .to_service_result()?;
+        })?;
     let control_connection_out =
         ControlConnectionOutType::from((control_connection, connection_constraint, connection));
     let model_action = ModelActionType::new(
@@ -0,0 +1,19 @@
+use crate::schema::{concept_intervention_costs, connection_intervention_costs};
+use diesel::{Identifiable, Queryable};
+use rocket::serde::{Deserialize, Serialize};
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(primary_key(concept_id), belongs_to(Concept))]
+pub struct ConceptInterventionCost {
+    pub concept_id: i32,
+    pub linear_cost: f64,
+    pub fixed_cost: f64,
+}
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(primary_key(connection_id), belongs_to(Connection))]
+pub struct ConnectionInterventionCost {
+    pub connection_id: i32,
+    pub linear_cost: f64,
+    pub fixed_cost: f64,
+}
@@ -0,0 +1,71 @@
+use super::services;
+use super::types::{
+    ConceptInterventionCostOutType, ConnectionInterventionCostOutType, InterventionCostInChangeType,
+};
+use crate::db;
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
+use crate::response::{PathResult, ToPathResult};
+use crate::types::ModelActionType;
+use crate::web_socket::WebSocketModelService;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+/// Change concept intervention cost
+#[openapi(tag = "intervention cost")]
+#[patch(
+    "/concepts/<concept_id>/change_concept_intervention_cost",
+    format = "json",
+    data = "<intervention_cost_in>"
+)]
+pub async fn change_concept_intervention_cost(
+    concept_id: i32,
+    intervention_cost_in: Json<InterventionCostInChangeType>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ConceptInterventionCostOutType>> {
+    let conn = &mut db::establish_connection();
+    services::change_concept_intervention_cost(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        concept_id,
+        intervention_cost_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Change connection intervention cost
+#[openapi(tag = "intervention cost")]
+#[patch(
+    "/connections/<connection_id>/change_connection_intervention_cost",
+    format = "json",
+    data = "<intervention_cost_in>"
+)]
+pub async fn change_connection_intervention_cost(
+    connection_id: i32,
+    intervention_cost_in: Json<InterventionCostInChangeType>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ConnectionInterventionCostOutType>> {
+    let conn = &mut db::establish_connection();
+    services::change_connection_intervention_cost(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        connection_id,
+        intervention_cost_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
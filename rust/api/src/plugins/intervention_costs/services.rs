@@ -0,0 +1,457 @@
+use super::super::Plugins;
+use super::models::{ConceptInterventionCost, ConnectionInterventionCost};
+use super::types::{
+    ConceptInterventionCostOutType, ConnectionInterventionCostOutType, InterventionCostInChangeType,
+};
+use crate::models::{Concept, Connection, User};
+use crate::plugins::control_concepts::services as control_concepts_services;
+use crate::plugins::control_connections::services as control_connections_services;
+use crate::plugins::Plugin;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{
+    concept_intervention_costs, concepts, connection_intervention_costs, connections, projects,
+};
+use crate::services::{model_history_services, model_services, permission_services};
+use crate::types::{ConceptOutType, ConnectionOutType, ModelActionType};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+pub fn handle_get_model(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .get_model_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut model_out, _| {
+            if !plugin
+                .lock()
+                .unwrap()
+                .is_enabled(conn, model_out.project.id)?
+            {
+                return Ok(model_out);
+            }
+            let concept_intervention_costs =
+                find_project_concept_intervention_costs(conn, model_out.project.id)
+                    .to_service_result()?;
+            for concept_out in model_out.concepts.iter_mut() {
+                let concept_intervention_cost = concept_intervention_costs
+                    .iter()
+                    .find(|cic| cic.concept_id == concept_out.id)
+                    .unwrap();
+                add_concept_intervention_cost(concept_out, &concept_intervention_cost);
+            }
+            let connection_intervention_costs =
+                find_project_connection_intervention_costs(conn, model_out.project.id)
+                    .to_service_result()?;
+            for connection_out in model_out.connections.iter_mut() {
+                let connection_intervention_cost = connection_intervention_costs
+                    .iter()
+                    .find(|cic| cic.connection_id == connection_out.id)
+                    .unwrap();
+                add_connection_intervention_cost(connection_out, &connection_intervention_cost);
+            }
+            Ok(model_out)
+        })
+}
+
+pub fn handle_add_concept(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .add_concept_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut concept_out, project| {
+            if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
+                return Ok(concept_out);
+            }
+            let concept_intervention_cost =
+                create_concept_intervention_cost(conn, concept_out.id).to_service_result()?;
+            add_concept_intervention_cost(&mut concept_out, &concept_intervention_cost);
+            Ok(concept_out)
+        });
+}
+
+pub fn handle_add_connection(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .add_connection_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut connection_out, project| {
+            if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
+                return Ok(connection_out);
+            }
+            let connection_intervention_cost =
+                create_connection_intervention_cost(conn, connection_out.id).to_service_result()?;
+            add_connection_intervention_cost(&mut connection_out, &connection_intervention_cost);
+            Ok(connection_out)
+        });
+}
+
+pub fn create_project_intervention_costs(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<()> {
+    let concepts = model_services::find_project_concepts(conn, project_id).to_service_result()?;
+    diesel::insert_into(concept_intervention_costs::table)
+        .values(
+            &concepts
+                .into_iter()
+                .map(|concept| {
+                    (
+                        concept_intervention_costs::concept_id.eq(concept.id),
+                        concept_intervention_costs::linear_cost.eq(0.0),
+                        concept_intervention_costs::fixed_cost.eq(0.0),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .to_service_result()?;
+    let connections =
+        model_services::find_project_connections(conn, project_id).to_service_result()?;
+    diesel::insert_into(connection_intervention_costs::table)
+        .values(
+            &connections
+                .into_iter()
+                .map(|connection| {
+                    (
+                        connection_intervention_costs::connection_id.eq(connection.id),
+                        connection_intervention_costs::linear_cost.eq(0.0),
+                        connection_intervention_costs::fixed_cost.eq(0.0),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .to_service_result()?;
+    Ok(())
+}
+
+pub fn delete_project_intervention_costs(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<()> {
+    let concepts = model_services::find_project_concepts(conn, project_id).to_service_result()?;
+    diesel::delete(
+        concept_intervention_costs::table.filter(
+            concept_intervention_costs::concept_id.eq_any(
+                concepts
+                    .into_iter()
+                    .map(|concept| concept.id)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    )
+    .execute(conn)
+    .to_service_result()?;
+    let connections =
+        model_services::find_project_connections(conn, project_id).to_service_result()?;
+    diesel::delete(
+        connection_intervention_costs::table.filter(
+            connection_intervention_costs::connection_id.eq_any(
+                connections
+                    .into_iter()
+                    .map(|connection| connection.id)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    )
+    .execute(conn)
+    .to_service_result()?;
+    Ok(())
+}
+
+pub fn create_concept_intervention_cost(
+    conn: &mut PgConnection,
+    concept_id: i32,
+) -> QueryResult<ConceptInterventionCost> {
+    diesel::insert_into(concept_intervention_costs::table)
+        .values((
+            concept_intervention_costs::concept_id.eq(concept_id),
+            concept_intervention_costs::linear_cost.eq(0.0),
+            concept_intervention_costs::fixed_cost.eq(0.0),
+        ))
+        .get_result::<ConceptInterventionCost>(conn)
+}
+
+pub fn create_connection_intervention_cost(
+    conn: &mut PgConnection,
+    connection_id: i32,
+) -> QueryResult<ConnectionInterventionCost> {
+    diesel::insert_into(connection_intervention_costs::table)
+        .values((
+            connection_intervention_costs::connection_id.eq(connection_id),
+            connection_intervention_costs::linear_cost.eq(0.0),
+            connection_intervention_costs::fixed_cost.eq(0.0),
+        ))
+        .get_result::<ConnectionInterventionCost>(conn)
+}
+
+pub async fn change_concept_intervention_cost(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    concept_id: i32,
+    intervention_cost_in: InterventionCostInChangeType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ConceptInterventionCostOutType>> {
+    let project = model_services::find_project_by_concept_id(conn, concept_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    let control_concept_result =
+        control_concepts_services::find_control_concept_by_id(conn, concept_id)
+            .optional()
+            .to_service_result()?;
+    match control_concept_result {
+        Some(control_concept) => {
+            if !control_concept.is_control {
+                return validation_error!("concept_not_control_error");
+            }
+        }
+        None => return validation_error!("concept_not_control_error"),
+    };
+    check_intervention_cost(&intervention_cost_in)?;
+    let concept_intervention_cost = find_concept_intervention_cost_by_id(conn, concept_id)
+        .to_service_result_find(String::from("intervention_cost_not_found_error"))?;
+    let (concept_intervention_cost, concept, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            model_services::check_concept_version(
+                conn,
+                plugins,
+                &project,
+                concept_id,
+                expected_version,
+            )?;
+            let previous_state =
+                model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+            let concept_intervention_cost = diesel::update(concept_intervention_costs::table)
+                .filter(
+                    concept_intervention_costs::concept_id.eq(concept_intervention_cost.concept_id),
+                )
+                .set((
+                    concept_intervention_costs::linear_cost.eq(intervention_cost_in.linear_cost),
+                    concept_intervention_costs::fixed_cost.eq(intervention_cost_in.fixed_cost),
+                ))
+                .get_result::<ConceptInterventionCost>(conn)?;
+            let (concept, project) =
+                model_services::update_concept(conn, concept_id, project.id, Utc::now())?;
+            let next_state =
+                model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeConceptInterventionCost",
+                previous_state,
+                next_state,
+            )?;
+            Ok((concept_intervention_cost, concept, project))
+        })?;
+    let concept_intervention_cost_out =
+        ConceptInterventionCostOutType::from((concept_intervention_cost, concept));
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("changeConceptInterventionCost"),
+        concept_intervention_cost_out,
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub async fn change_connection_intervention_cost(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    connection_id: i32,
+    intervention_cost_in: InterventionCostInChangeType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ConnectionInterventionCostOutType>> {
+    let project = model_services::find_project_by_connection_id(conn, connection_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    let control_connection_result =
+        control_connections_services::find_control_connection_by_id(conn, connection_id)
+            .optional()
+            .to_service_result()?;
+    match control_connection_result {
+        Some(control_connection) => {
+            if !control_connection.is_control {
+                return validation_error!("connection_not_control_error");
+            }
+        }
+        None => return validation_error!("connection_not_control_error"),
+    };
+    check_intervention_cost(&intervention_cost_in)?;
+    let connection_intervention_cost = find_connection_intervention_cost_by_id(conn, connection_id)
+        .to_service_result_find(String::from("intervention_cost_not_found_error"))?;
+    let (connection_intervention_cost, connection, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            model_services::check_connection_version(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+                expected_version,
+            )?;
+            let previous_state = model_history_services::get_connection_elements(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+            )?;
+            let connection_intervention_cost = diesel::update(connection_intervention_costs::table)
+                .filter(
+                    connection_intervention_costs::connection_id
+                        .eq(connection_intervention_cost.connection_id),
+                )
+                .set((
+                    connection_intervention_costs::linear_cost.eq(intervention_cost_in.linear_cost),
+                    connection_intervention_costs::fixed_cost.eq(intervention_cost_in.fixed_cost),
+                ))
+                .get_result::<ConnectionInterventionCost>(conn)?;
+            let (connection, project) =
+                model_services::update_connection(conn, connection_id, project.id, Utc::now())?;
+            let next_state = model_history_services::get_connection_elements(
+                conn,
+                plugins,
+                &project,
+                connection_id,
+            )?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeConnectionInterventionCost",
+                previous_state,
+                next_state,
+            )?;
+            Ok((connection_intervention_cost, connection, project))
+        })?;
+    let connection_intervention_cost_out =
+        ConnectionInterventionCostOutType::from((connection_intervention_cost, connection));
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("changeConnectionInterventionCost"),
+        connection_intervention_cost_out,
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub fn find_project_concept_intervention_costs(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<Vec<ConceptInterventionCost>> {
+    projects::table
+        .inner_join(concepts::table.inner_join(concept_intervention_costs::table))
+        .select(concept_intervention_costs::all_columns)
+        .filter(projects::id.eq(project_id))
+        .get_results::<ConceptInterventionCost>(conn)
+}
+
+pub fn find_project_connection_intervention_costs(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<Vec<ConnectionInterventionCost>> {
+    projects::table
+        .inner_join(connections::table.inner_join(connection_intervention_costs::table))
+        .select(connection_intervention_costs::all_columns)
+        .filter(projects::id.eq(project_id))
+        .get_results::<ConnectionInterventionCost>(conn)
+}
+
+pub fn find_concept_intervention_cost_by_id(
+    conn: &mut PgConnection,
+    concept_id: i32,
+) -> QueryResult<ConceptInterventionCost> {
+    concept_intervention_costs::table
+        .filter(concept_intervention_costs::concept_id.eq(concept_id))
+        .first::<ConceptInterventionCost>(conn)
+}
+
+pub fn find_connection_intervention_cost_by_id(
+    conn: &mut PgConnection,
+    connection_id: i32,
+) -> QueryResult<ConnectionInterventionCost> {
+    connection_intervention_costs::table
+        .filter(connection_intervention_costs::connection_id.eq(connection_id))
+        .first::<ConnectionInterventionCost>(conn)
+}
+
+fn add_concept_intervention_cost(
+    concept_out: &mut ConceptOutType,
+    concept_intervention_cost: &ConceptInterventionCost,
+) -> () {
+    let plugins_data = match &mut concept_out.plugins_data {
+        Value::Object(plugins_data) => plugins_data,
+        _ => unreachable!(),
+    };
+    plugins_data.entry("interventionCosts").or_insert(json!({
+        "linearCost": concept_intervention_cost.linear_cost,
+        "fixedCost": concept_intervention_cost.fixed_cost
+    }));
+}
+
+fn add_connection_intervention_cost(
+    connection_out: &mut ConnectionOutType,
+    connection_intervention_cost: &ConnectionInterventionCost,
+) -> () {
+    let plugins_data = match &mut connection_out.plugins_data {
+        Value::Object(plugins_data) => plugins_data,
+        _ => unreachable!(),
+    };
+    plugins_data.entry("interventionCosts").or_insert(json!({
+        "linearCost": connection_intervention_cost.linear_cost,
+        "fixedCost": connection_intervention_cost.fixed_cost
+    }));
+}
+
+fn check_intervention_cost(
+    intervention_cost_in: &InterventionCostInChangeType,
+) -> ServiceResult<()> {
+    if intervention_cost_in.linear_cost < 0.0 || intervention_cost_in.fixed_cost < 0.0 {
+        return validation_error!("intervention_cost_negative_error");
+    }
+    Ok(())
+}
+
+impl From<(ConceptInterventionCost, Concept)> for ConceptInterventionCostOutType {
+    fn from((concept_intervention_cost, concept): (ConceptInterventionCost, Concept)) -> Self {
+        Self {
+            concept_id: concept_intervention_cost.concept_id,
+            linear_cost: concept_intervention_cost.linear_cost,
+            fixed_cost: concept_intervention_cost.fixed_cost,
+            updated_at: concept.updated_at,
+        }
+    }
+}
+
+impl From<(ConnectionInterventionCost, Connection)> for ConnectionInterventionCostOutType {
+    fn from(
+        (connection_intervention_cost, connection): (ConnectionInterventionCost, Connection),
+    ) -> Self {
+        Self {
+            connection_id: connection_intervention_cost.connection_id,
+            linear_cost: connection_intervention_cost.linear_cost,
+            fixed_cost: connection_intervention_cost.fixed_cost,
+            updated_at: connection.updated_at,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use rocket::serde::{Deserialize, Serialize};
+use rocket_okapi::JsonSchema;
+
+/// Type of intervention cost to change
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct InterventionCostInChangeType {
+    /// Cost per unit of control value change
+    pub linear_cost: f64,
+    /// Cost of any control value change
+    pub fixed_cost: f64,
+}
+
+/// Type of concept intervention cost
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConceptInterventionCostOutType {
+    /// Concept identifier
+    pub concept_id: i32,
+    /// Cost per unit of concept value change
+    pub linear_cost: f64,
+    /// Cost of any concept value change
+    pub fixed_cost: f64,
+    /// Concept update time
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Type of connection intervention cost
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionInterventionCostOutType {
+    /// Connection identifier
+    pub connection_id: i32,
+    /// Cost per unit of connection value change
+    pub linear_cost: f64,
+    /// Cost of any connection value change
+    pub fixed_cost: f64,
+    /// Connection update time
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,53 @@
+use crate::schema::{connection_linguistic_terms, linguistic_term_sets, linguistic_terms};
+use diesel::{Identifiable, Queryable};
+use rocket::serde::{Deserialize, Serialize};
+use schemars::JsonSchema;
+
+#[derive(
+    Debug, Clone, PartialEq, diesel_derive_enum::DbEnum, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[ExistingTypePath = "crate::schema::sql_types::MembershipFunctionType"]
+pub enum MembershipFunctionType {
+    Triangular,
+    Trapezoidal,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, diesel_derive_enum::DbEnum, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[ExistingTypePath = "crate::schema::sql_types::DefuzzificationMethodType"]
+pub enum DefuzzificationMethodType {
+    Centroid,
+    MeanOfMaxima,
+}
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(primary_key(project_id), belongs_to(Project))]
+pub struct LinguisticTermSet {
+    pub project_id: i32,
+    pub defuzzification_method: DefuzzificationMethodType,
+}
+
+#[derive(Queryable, Identifiable, Clone, Serialize, Deserialize)]
+#[diesel(belongs_to(Project))]
+pub struct LinguisticTerm {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub membership_function_type: MembershipFunctionType,
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+}
+
+#[derive(Queryable, Identifiable, Serialize, Deserialize)]
+#[diesel(belongs_to(Connection))]
+#[diesel(belongs_to(LinguisticTerm))]
+pub struct ConnectionLinguisticTerm {
+    pub id: i32,
+    pub connection_id: i32,
+    pub linguistic_term_id: i32,
+}
@@ -0,0 +1,156 @@
+use super::models::DefuzzificationMethodType;
+use super::services;
+use super::types::{
+    ConnectionLinguisticTermsInChangeType, ConnectionLinguisticTermsOutType,
+    DefuzzificationMethodOutChangeType, LinguisticTermInType, LinguisticTermOutChangeType,
+    LinguisticTermOutDeleteType, LinguisticTermOutType, LinguisticTermSetOutType,
+};
+use crate::db;
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
+use crate::response::{PathResult, ToPathResult};
+use crate::types::ModelActionType;
+use crate::web_socket::WebSocketModelService;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+/// Get project linguistic term set
+#[openapi(tag = "linguistic connections")]
+#[get("/projects/<project_id>/linguistic_terms")]
+pub fn get_linguistic_term_set(
+    project_id: i32,
+    user: User,
+) -> PathResult<LinguisticTermSetOutType> {
+    let conn = &mut db::establish_connection();
+    services::get_linguistic_term_set(conn, &user, project_id).to_path_result()
+}
+
+/// Create new linguistic term
+#[openapi(tag = "linguistic connections")]
+#[post(
+    "/projects/<project_id>/linguistic_term",
+    format = "json",
+    data = "<linguistic_term_in>"
+)]
+pub async fn create_linguistic_term(
+    project_id: i32,
+    linguistic_term_in: Json<LinguisticTermInType>,
+    if_match: IfMatch,
+    user: User,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<LinguisticTermOutType>> {
+    let conn = &mut db::establish_connection();
+    services::create_linguistic_term(
+        conn,
+        model_service,
+        &user,
+        project_id,
+        linguistic_term_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Change linguistic term
+#[openapi(tag = "linguistic connections")]
+#[put(
+    "/linguistic_terms/<linguistic_term_id>",
+    format = "json",
+    data = "<linguistic_term_in>"
+)]
+pub async fn change_linguistic_term(
+    linguistic_term_id: i32,
+    linguistic_term_in: Json<LinguisticTermInType>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<LinguisticTermOutChangeType>> {
+    let conn = &mut db::establish_connection();
+    services::change_linguistic_term(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        linguistic_term_id,
+        linguistic_term_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Delete linguistic term
+#[openapi(tag = "linguistic connections")]
+#[delete("/linguistic_terms/<linguistic_term_id>")]
+pub async fn delete_linguistic_term(
+    linguistic_term_id: i32,
+    if_match: IfMatch,
+    user: User,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<LinguisticTermOutDeleteType>> {
+    let conn = &mut db::establish_connection();
+    services::delete_linguistic_term(conn, model_service, &user, linguistic_term_id, if_match.0)
+        .await
+        .to_path_result()
+}
+
+/// Change project defuzzification method
+#[openapi(tag = "linguistic connections")]
+#[patch(
+    "/projects/<project_id>/change_defuzzification_method",
+    format = "json",
+    data = "<defuzzification_method>"
+)]
+pub async fn change_defuzzification_method(
+    project_id: i32,
+    defuzzification_method: Json<DefuzzificationMethodType>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<DefuzzificationMethodOutChangeType>> {
+    let conn = &mut db::establish_connection();
+    services::change_defuzzification_method(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        project_id,
+        defuzzification_method.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Change linguistic terms of connection, several terms are aggregated into one fuzzy weight
+#[openapi(tag = "linguistic connections")]
+#[patch(
+    "/connections/<connection_id>/change_connection_linguistic_terms",
+    format = "json",
+    data = "<connection_linguistic_terms_in>"
+)]
+pub async fn change_connection_linguistic_terms(
+    connection_id: i32,
+    connection_linguistic_terms_in: Json<ConnectionLinguisticTermsInChangeType>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ConnectionLinguisticTermsOutType>> {
+    let conn = &mut db::establish_connection();
+    services::change_connection_linguistic_terms(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        connection_id,
+        connection_linguistic_terms_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
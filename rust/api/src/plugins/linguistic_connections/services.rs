@@ -0,0 +1,754 @@
+use super::super::Plugins;
+use super::models::{
+    ConnectionLinguisticTerm, DefuzzificationMethodType, LinguisticTerm, LinguisticTermSet,
+    MembershipFunctionType,
+};
+use super::types::{
+    ConnectionLinguisticTermsInChangeType, ConnectionLinguisticTermsOutType,
+    DefuzzificationMethodOutChangeType, LinguisticTermInType, LinguisticTermOutChangeType,
+    LinguisticTermOutDeleteType, LinguisticTermOutType, LinguisticTermSetOutType,
+};
+use crate::models::{Connection, Project, User};
+use crate::plugins::Plugin;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{
+    connection_linguistic_terms, connections, linguistic_term_sets, linguistic_terms, projects,
+};
+use crate::services::{
+    model_history_services, model_services, permission_services, project_services,
+};
+use crate::types::{ConnectionOutType, ModelActionType};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use fuzzy_cognitive_model_common::linguistic::{DefuzzificationMethod, FuzzyNumber};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+pub fn handle_get_model(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .get_model_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut model_out, _| {
+            if !plugin
+                .lock()
+                .unwrap()
+                .is_enabled(conn, model_out.project.id)?
+            {
+                return Ok(model_out);
+            }
+            let connection_linguistic_terms =
+                find_project_connection_linguistic_terms(conn, model_out.project.id)
+                    .to_service_result()?;
+            for connection_out in model_out.connections.iter_mut() {
+                let linguistic_term_ids = connection_linguistic_terms
+                    .iter()
+                    .filter(|clt| clt.connection_id == connection_out.id)
+                    .map(|clt| clt.linguistic_term_id)
+                    .collect::<Vec<_>>();
+                add_connection_linguistic_terms(connection_out, &linguistic_term_ids);
+            }
+            Ok(model_out)
+        })
+}
+
+pub fn handle_add_connection(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .add_connection_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, mut connection_out, project| {
+            if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
+                return Ok(connection_out);
+            }
+            add_connection_linguistic_terms(&mut connection_out, &[]);
+            Ok(connection_out)
+        });
+}
+
+pub fn handle_change_connection_value(
+    plugins: &Plugins,
+    plugin: Arc<Mutex<Box<dyn Plugin + Sync + Send>>>,
+) -> () {
+    plugins
+        .change_connection_value_emitter
+        .lock()
+        .unwrap()
+        .on(move |conn, value, extra| {
+            if !plugin.lock().unwrap().is_enabled(conn, extra.project.id)? {
+                return Ok(value);
+            }
+            let connection_terms =
+                find_connection_linguistic_terms(conn, extra.connection_id).to_service_result()?;
+            if connection_terms.is_empty() {
+                return Ok(value);
+            }
+            let linguistic_term_set = find_linguistic_term_set_by_id(conn, extra.project.id)
+                .to_service_result_find(String::from("linguistic_term_set_not_found_error"))?;
+            Ok(get_connection_value(
+                &connection_terms,
+                &linguistic_term_set.defuzzification_method,
+            ))
+        });
+}
+
+pub fn create_project_linguistic_terms(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<()> {
+    diesel::insert_into(linguistic_term_sets::table)
+        .values((
+            linguistic_term_sets::project_id.eq(project_id),
+            linguistic_term_sets::defuzzification_method.eq(DefuzzificationMethodType::Centroid),
+        ))
+        .execute(conn)
+        .to_service_result()?;
+    let default_terms = [
+        (
+            "strong negative",
+            FuzzyNumber::trapezoidal(-1.0, -1.0, -0.75, -0.5),
+        ),
+        (
+            "medium negative",
+            FuzzyNumber::triangular(-0.75, -0.5, -0.25),
+        ),
+        ("weak negative", FuzzyNumber::triangular(-0.5, -0.25, 0.0)),
+        ("zero", FuzzyNumber::triangular(-0.25, 0.0, 0.25)),
+        ("weak positive", FuzzyNumber::triangular(0.0, 0.25, 0.5)),
+        ("medium positive", FuzzyNumber::triangular(0.25, 0.5, 0.75)),
+        (
+            "strong positive",
+            FuzzyNumber::trapezoidal(0.5, 0.75, 1.0, 1.0),
+        ),
+    ];
+    diesel::insert_into(linguistic_terms::table)
+        .values(
+            &default_terms
+                .iter()
+                .map(|(name, fuzzy_number)| {
+                    (
+                        linguistic_terms::project_id.eq(project_id),
+                        linguistic_terms::name.eq(*name),
+                        linguistic_terms::membership_function_type.eq(
+                            if fuzzy_number.is_triangular() {
+                                MembershipFunctionType::Triangular
+                            } else {
+                                MembershipFunctionType::Trapezoidal
+                            },
+                        ),
+                        linguistic_terms::a.eq(fuzzy_number.a),
+                        linguistic_terms::b.eq(fuzzy_number.b),
+                        linguistic_terms::c.eq(fuzzy_number.c),
+                        linguistic_terms::d.eq(fuzzy_number.d),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        )
+        .execute(conn)
+        .to_service_result()?;
+    Ok(())
+}
+
+pub fn delete_project_linguistic_terms(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> ServiceResult<()> {
+    let connections =
+        model_services::find_project_connections(conn, project_id).to_service_result()?;
+    diesel::delete(
+        connection_linguistic_terms::table.filter(
+            connection_linguistic_terms::connection_id.eq_any(
+                connections
+                    .into_iter()
+                    .map(|connection| connection.id)
+                    .collect::<Vec<_>>(),
+            ),
+        ),
+    )
+    .execute(conn)
+    .to_service_result()?;
+    diesel::delete(linguistic_terms::table.filter(linguistic_terms::project_id.eq(project_id)))
+        .execute(conn)
+        .to_service_result()?;
+    diesel::delete(
+        linguistic_term_sets::table.filter(linguistic_term_sets::project_id.eq(project_id)),
+    )
+    .execute(conn)
+    .to_service_result()?;
+    Ok(())
+}
+
+pub fn get_linguistic_term_set(
+    conn: &mut PgConnection,
+    user: &User,
+    project_id: i32,
+) -> ServiceResult<LinguisticTermSetOutType> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_view_project(conn, &project, user)?;
+    let linguistic_term_set = find_linguistic_term_set_by_id(conn, project_id)
+        .to_service_result_find(String::from("linguistic_term_set_not_found_error"))?;
+    let terms = find_project_linguistic_terms(conn, project_id).to_service_result()?;
+    Ok(LinguisticTermSetOutType {
+        project_id,
+        terms: terms
+            .into_iter()
+            .map(|linguistic_term| {
+                LinguisticTermOutType::from((
+                    linguistic_term,
+                    &linguistic_term_set.defuzzification_method,
+                ))
+            })
+            .collect(),
+        defuzzification_method: linguistic_term_set.defuzzification_method,
+    })
+}
+
+pub async fn create_linguistic_term(
+    conn: &mut PgConnection,
+    model_service: WebSocketModelService,
+    user: &User,
+    project_id: i32,
+    linguistic_term_in: LinguisticTermInType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<LinguisticTermOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    let linguistic_term_set = find_linguistic_term_set_by_id(conn, project_id)
+        .to_service_result_find(String::from("linguistic_term_set_not_found_error"))?;
+    let fuzzy_number = get_fuzzy_number(&linguistic_term_in)?;
+    let (linguistic_term, project) = conn
+        .transaction(|conn| {
+            let linguistic_term = diesel::insert_into(linguistic_terms::table)
+                .values((
+                    linguistic_terms::project_id.eq(project_id),
+                    linguistic_terms::name.eq(&linguistic_term_in.name),
+                    linguistic_terms::membership_function_type
+                        .eq(linguistic_term_in.membership_function_type.clone()),
+                    linguistic_terms::a.eq(fuzzy_number.a),
+                    linguistic_terms::b.eq(fuzzy_number.b),
+                    linguistic_terms::c.eq(fuzzy_number.c),
+                    linguistic_terms::d.eq(fuzzy_number.d),
+                ))
+                .get_result::<LinguisticTerm>(conn)?;
+            let project = project_services::update_project(conn, project_id, Utc::now())?;
+            Ok((linguistic_term, project))
+        })
+        .to_service_result_unique(String::from("linguistic_term_duplication_error"))?;
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("createLinguisticTerm"),
+        LinguisticTermOutType::from((linguistic_term, &linguistic_term_set.defuzzification_method)),
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub async fn change_linguistic_term(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    linguistic_term_id: i32,
+    linguistic_term_in: LinguisticTermInType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<LinguisticTermOutChangeType>> {
+    let project = find_project_by_linguistic_term_id(conn, linguistic_term_id)
+        .to_service_result_find(String::from("linguistic_term_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    let linguistic_term_set = find_linguistic_term_set_by_id(conn, project.id)
+        .to_service_result_find(String::from("linguistic_term_set_not_found_error"))?;
+    let fuzzy_number = get_fuzzy_number(&linguistic_term_in)?;
+    let connection_ids = connection_linguistic_terms::table
+        .filter(connection_linguistic_terms::linguistic_term_id.eq(linguistic_term_id))
+        .select(connection_linguistic_terms::connection_id)
+        .distinct()
+        .get_results::<i32>(conn)
+        .to_service_result()?;
+    let (linguistic_term, connections_out, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            let previous_state = model_history_services::get_model_elements(
+                conn,
+                plugins,
+                &project,
+                &[],
+                &connection_ids,
+            )?;
+            let linguistic_term = diesel::update(linguistic_terms::table)
+                .filter(linguistic_terms::id.eq(linguistic_term_id))
+                .set((
+                    linguistic_terms::name.eq(&linguistic_term_in.name),
+                    linguistic_terms::membership_function_type
+                        .eq(linguistic_term_in.membership_function_type.clone()),
+                    linguistic_terms::a.eq(fuzzy_number.a),
+                    linguistic_terms::b.eq(fuzzy_number.b),
+                    linguistic_terms::c.eq(fuzzy_number.c),
+                    linguistic_terms::d.eq(fuzzy_number.d),
+                ))
+                .get_result::<LinguisticTerm>(conn)
+                .to_service_result_unique(String::from("linguistic_term_duplication_error"))?;
+            let updated_at = Utc::now();
+            let connections_out = recalculate_connection_values(
+                conn,
+                &connection_ids,
+                &linguistic_term_set.defuzzification_method,
+                updated_at,
+            )?;
+            let project = project_services::update_project(conn, project.id, updated_at)?;
+            let next_state = model_history_services::get_model_elements(
+                conn,
+                plugins,
+                &project,
+                &[],
+                &connection_ids,
+            )?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeLinguisticTerm",
+                previous_state,
+                next_state,
+            )?;
+            Ok((linguistic_term, connections_out, project))
+        })?;
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("changeLinguisticTerm"),
+        LinguisticTermOutChangeType {
+            term: LinguisticTermOutType::from((
+                linguistic_term,
+                &linguistic_term_set.defuzzification_method,
+            )),
+            connections: connections_out,
+        },
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub async fn delete_linguistic_term(
+    conn: &mut PgConnection,
+    model_service: WebSocketModelService,
+    user: &User,
+    linguistic_term_id: i32,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<LinguisticTermOutDeleteType>> {
+    let project = find_project_by_linguistic_term_id(conn, linguistic_term_id)
+        .to_service_result_find(String::from("linguistic_term_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    let is_used = diesel::select(diesel::dsl::exists(
+        connection_linguistic_terms::table
+            .filter(connection_linguistic_terms::linguistic_term_id.eq(linguistic_term_id)),
+    ))
+    .get_result::<bool>(conn)
+    .to_service_result()?;
+    // Terms in use are not deleted, so connection values and model history are not affected
+    if is_used {
+        return validation_error!("linguistic_term_in_use_error");
+    }
+    let project = conn
+        .transaction(|conn| {
+            diesel::delete(
+                linguistic_terms::table.filter(linguistic_terms::id.eq(linguistic_term_id)),
+            )
+            .execute(conn)?;
+            project_services::update_project(conn, project.id, Utc::now())
+        })
+        .to_service_result()?;
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("deleteLinguisticTerm"),
+        LinguisticTermOutDeleteType {
+            id: linguistic_term_id,
+        },
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub async fn change_defuzzification_method(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    project_id: i32,
+    defuzzification_method: DefuzzificationMethodType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<DefuzzificationMethodOutChangeType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    find_linguistic_term_set_by_id(conn, project_id)
+        .to_service_result_find(String::from("linguistic_term_set_not_found_error"))?;
+    let connection_ids = connection_linguistic_terms::table
+        .inner_join(connections::table)
+        .filter(connections::project_id.eq(project_id))
+        .select(connection_linguistic_terms::connection_id)
+        .distinct()
+        .get_results::<i32>(conn)
+        .to_service_result()?;
+    let (linguistic_term_set, connections_out, project) =
+        conn.transaction(|conn| -> ServiceResult<_> {
+            let previous_state = model_history_services::get_model_elements(
+                conn,
+                plugins,
+                &project,
+                &[],
+                &connection_ids,
+            )?;
+            let linguistic_term_set = diesel::update(linguistic_term_sets::table)
+                .filter(linguistic_term_sets::project_id.eq(project_id))
+                .set(linguistic_term_sets::defuzzification_method.eq(defuzzification_method))
+                .get_result::<LinguisticTermSet>(conn)?;
+            let updated_at = Utc::now();
+            let connections_out = recalculate_connection_values(
+                conn,
+                &connection_ids,
+                &linguistic_term_set.defuzzification_method,
+                updated_at,
+            )?;
+            let project = project_services::update_project(conn, project_id, updated_at)?;
+            let next_state = model_history_services::get_model_elements(
+                conn,
+                plugins,
+                &project,
+                &[],
+                &connection_ids,
+            )?;
+            model_history_services::record_model_action(
+                conn,
+                user,
+                &project,
+                "changeDefuzzificationMethod",
+                previous_state,
+                next_state,
+            )?;
+            Ok((linguistic_term_set, connections_out, project))
+        })?;
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("changeDefuzzificationMethod"),
+        DefuzzificationMethodOutChangeType {
+            project_id,
+            defuzzification_method: linguistic_term_set.defuzzification_method,
+            connections: connections_out,
+        },
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub async fn change_connection_linguistic_terms(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    connection_id: i32,
+    connection_linguistic_terms_in: ConnectionLinguisticTermsInChangeType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ConnectionLinguisticTermsOutType>> {
+    let project = model_services::find_project_by_connection_id(conn, connection_id)
+        .to_service_result_find(String::from("connection_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    let linguistic_term_set = find_linguistic_term_set_by_id(conn, project.id)
+        .to_service_result_find(String::from("linguistic_term_set_not_found_error"))?;
+    let project_linguistic_terms =
+        find_project_linguistic_terms(conn, project.id).to_service_result()?;
+    let mut connection_terms = vec![];
+    for linguistic_term_id in &connection_linguistic_terms_in.linguistic_term_ids {
+        match project_linguistic_terms
+            .iter()
+            .find(|linguistic_term| linguistic_term.id == *linguistic_term_id)
+        {
+            Some(linguistic_term) => connection_terms.push(linguistic_term.clone()),
+            None => return validation_error!("linguistic_term_not_found_error"),
+        }
+    }
+    let (connection, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        model_services::check_connection_version(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+            expected_version,
+        )?;
+        let previous_state = model_history_services::get_connection_elements(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+        )?;
+        diesel::delete(
+            connection_linguistic_terms::table
+                .filter(connection_linguistic_terms::connection_id.eq(connection_id)),
+        )
+        .execute(conn)?;
+        let updated_at = Utc::now();
+        let connection = if connection_terms.is_empty() {
+            diesel::update(connections::table)
+                .filter(connections::id.eq(connection_id))
+                .set(connections::updated_at.eq(updated_at))
+                .get_result::<Connection>(conn)?
+        } else {
+            diesel::insert_into(connection_linguistic_terms::table)
+                .values(
+                    &connection_terms
+                        .iter()
+                        .map(|linguistic_term| {
+                            (
+                                connection_linguistic_terms::connection_id.eq(connection_id),
+                                connection_linguistic_terms::linguistic_term_id
+                                    .eq(linguistic_term.id),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+                .execute(conn)?;
+            diesel::update(connections::table)
+                .filter(connections::id.eq(connection_id))
+                .set((
+                    connections::value.eq(get_connection_value(
+                        &connection_terms,
+                        &linguistic_term_set.defuzzification_method,
+                    )),
+                    connections::updated_at.eq(updated_at),
+                ))
+                .get_result::<Connection>(conn)?
+        };
+        let project = project_services::update_project(conn, project.id, updated_at)?;
+        let next_state = model_history_services::get_connection_elements(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+        )?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "changeConnectionLinguisticTerms",
+            previous_state,
+            next_state,
+        )?;
+        Ok((connection, project))
+    })?;
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("changeConnectionLinguisticTerms"),
+        ConnectionLinguisticTermsOutType {
+            connection_id: connection.id,
+            linguistic_term_ids: connection_linguistic_terms_in.linguistic_term_ids,
+            value: connection.value,
+            updated_at: connection.updated_at,
+        },
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub fn find_linguistic_term_set_by_id(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<LinguisticTermSet> {
+    linguistic_term_sets::table
+        .filter(linguistic_term_sets::project_id.eq(project_id))
+        .first::<LinguisticTermSet>(conn)
+}
+
+pub fn find_project_linguistic_terms(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<Vec<LinguisticTerm>> {
+    linguistic_terms::table
+        .filter(linguistic_terms::project_id.eq(project_id))
+        .order(linguistic_terms::id)
+        .get_results::<LinguisticTerm>(conn)
+}
+
+pub fn find_project_connection_linguistic_terms(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<Vec<ConnectionLinguisticTerm>> {
+    connection_linguistic_terms::table
+        .inner_join(connections::table)
+        .filter(connections::project_id.eq(project_id))
+        .select(connection_linguistic_terms::all_columns)
+        .order(connection_linguistic_terms::id)
+        .get_results::<ConnectionLinguisticTerm>(conn)
+}
+
+pub fn find_connection_linguistic_terms(
+    conn: &mut PgConnection,
+    connection_id: i32,
+) -> QueryResult<Vec<LinguisticTerm>> {
+    connection_linguistic_terms::table
+        .inner_join(linguistic_terms::table)
+        .filter(connection_linguistic_terms::connection_id.eq(connection_id))
+        .select(linguistic_terms::all_columns)
+        .order(connection_linguistic_terms::id)
+        .get_results::<LinguisticTerm>(conn)
+}
+
+pub fn find_project_by_linguistic_term_id(
+    conn: &mut PgConnection,
+    linguistic_term_id: i32,
+) -> QueryResult<Project> {
+    linguistic_terms::table
+        .inner_join(projects::table)
+        .filter(linguistic_terms::id.eq(linguistic_term_id))
+        .select(projects::all_columns)
+        .get_result::<Project>(conn)
+}
+
+fn recalculate_connection_values(
+    conn: &mut PgConnection,
+    connection_ids: &[i32],
+    defuzzification_method: &DefuzzificationMethodType,
+    updated_at: DateTime<Utc>,
+) -> QueryResult<Vec<ConnectionLinguisticTermsOutType>> {
+    let mut connection_terms = HashMap::<i32, Vec<LinguisticTerm>>::new();
+    for (connection_id, linguistic_term) in connection_linguistic_terms::table
+        .inner_join(linguistic_terms::table)
+        .filter(connection_linguistic_terms::connection_id.eq_any(connection_ids))
+        .select((
+            connection_linguistic_terms::connection_id,
+            linguistic_terms::all_columns,
+        ))
+        .order(connection_linguistic_terms::id)
+        .get_results::<(i32, LinguisticTerm)>(conn)?
+    {
+        connection_terms
+            .entry(connection_id)
+            .or_insert(vec![])
+            .push(linguistic_term);
+    }
+    let mut connections_out = vec![];
+    for (connection_id, terms) in connection_terms {
+        let connection = diesel::update(connections::table)
+            .filter(connections::id.eq(connection_id))
+            .set((
+                connections::value.eq(get_connection_value(&terms, defuzzification_method)),
+                connections::updated_at.eq(updated_at),
+            ))
+            .get_result::<Connection>(conn)?;
+        connections_out.push(ConnectionLinguisticTermsOutType {
+            connection_id,
+            linguistic_term_ids: terms
+                .iter()
+                .map(|linguistic_term| linguistic_term.id)
+                .collect(),
+            value: connection.value,
+            updated_at: connection.updated_at,
+        });
+    }
+    Ok(connections_out)
+}
+
+fn get_connection_value(
+    linguistic_terms: &[LinguisticTerm],
+    defuzzification_method: &DefuzzificationMethodType,
+) -> f64 {
+    let fuzzy_numbers = linguistic_terms
+        .iter()
+        .map(FuzzyNumber::from)
+        .collect::<Vec<_>>();
+    FuzzyNumber::aggregate(&fuzzy_numbers)
+        .unwrap()
+        .defuzzify(&DefuzzificationMethod::from(defuzzification_method.clone()))
+}
+
+fn get_fuzzy_number(linguistic_term_in: &LinguisticTermInType) -> ServiceResult<FuzzyNumber> {
+    let points = &linguistic_term_in.points;
+    let fuzzy_number = match (&linguistic_term_in.membership_function_type, points.len()) {
+        (MembershipFunctionType::Triangular, 3) => {
+            FuzzyNumber::triangular(points[0], points[1], points[2])
+        }
+        (MembershipFunctionType::Trapezoidal, 4) => {
+            FuzzyNumber::trapezoidal(points[0], points[1], points[2], points[3])
+        }
+        _ => return validation_error!("linguistic_term_points_error"),
+    };
+    if !fuzzy_number.is_valid() || fuzzy_number.a < -1.0 || fuzzy_number.d > 1.0 {
+        return validation_error!("linguistic_term_points_error");
+    }
+    Ok(fuzzy_number)
+}
+
+fn add_connection_linguistic_terms(
+    connection_out: &mut ConnectionOutType,
+    linguistic_term_ids: &[i32],
+) -> () {
+    let plugins_data = match &mut connection_out.plugins_data {
+        Value::Object(plugins_data) => plugins_data,
+        _ => unreachable!(),
+    };
+    plugins_data
+        .entry("linguisticConnections")
+        .or_insert(json!({
+            "linguisticTermIds": linguistic_term_ids
+        }));
+}
+
+impl From<&LinguisticTerm> for FuzzyNumber {
+    fn from(linguistic_term: &LinguisticTerm) -> Self {
+        FuzzyNumber::trapezoidal(
+            linguistic_term.a,
+            linguistic_term.b,
+            linguistic_term.c,
+            linguistic_term.d,
+        )
+    }
+}
+
+impl From<DefuzzificationMethodType> for DefuzzificationMethod {
+    fn from(defuzzification_method_type: DefuzzificationMethodType) -> Self {
+        match defuzzification_method_type {
+            DefuzzificationMethodType::Centroid => Self::Centroid,
+            DefuzzificationMethodType::MeanOfMaxima => Self::MeanOfMaxima,
+        }
+    }
+}
+
+impl From<(LinguisticTerm, &DefuzzificationMethodType)> for LinguisticTermOutType {
+    fn from(
+        (linguistic_term, defuzzification_method): (LinguisticTerm, &DefuzzificationMethodType),
+    ) -> Self {
+        let fuzzy_number = FuzzyNumber::from(&linguistic_term);
+        let points = match linguistic_term.membership_function_type {
+            MembershipFunctionType::Triangular => {
+                vec![linguistic_term.a, linguistic_term.b, linguistic_term.d]
+            }
+            MembershipFunctionType::Trapezoidal => vec![
+                linguistic_term.a,
+                linguistic_term.b,
+                linguistic_term.c,
+                linguistic_term.d,
+            ],
+        };
+        Self {
+            id: linguistic_term.id,
+            name: linguistic_term.name,
+            membership_function_type: linguistic_term.membership_function_type,
+            points,
+            value: fuzzy_number
+                .defuzzify(&DefuzzificationMethod::from(defuzzification_method.clone())),
+        }
+    }
+}
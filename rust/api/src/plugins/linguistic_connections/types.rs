@@ -0,0 +1,96 @@
+use super::models::{DefuzzificationMethodType, MembershipFunctionType};
+use chrono::{DateTime, Utc};
+use rocket::serde::{Deserialize, Serialize};
+use rocket_okapi::JsonSchema;
+
+/// Type of linguistic term to create or change
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LinguisticTermInType {
+    /// Linguistic term name
+    pub name: String,
+    /// Membership function type
+    pub membership_function_type: MembershipFunctionType,
+    /// Membership function points, three for triangular and four for trapezoidal function
+    pub points: Vec<f64>,
+}
+
+/// Type of linguistic term
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LinguisticTermOutType {
+    /// Linguistic term identifier
+    pub id: i32,
+    /// Linguistic term name
+    pub name: String,
+    /// Membership function type
+    pub membership_function_type: MembershipFunctionType,
+    /// Membership function points
+    pub points: Vec<f64>,
+    /// Defuzzified linguistic term value
+    pub value: f64,
+}
+
+/// Type of project linguistic term set
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LinguisticTermSetOutType {
+    /// Project identifier
+    pub project_id: i32,
+    /// Defuzzification method
+    pub defuzzification_method: DefuzzificationMethodType,
+    /// Linguistic terms
+    pub terms: Vec<LinguisticTermOutType>,
+}
+
+/// Type of changed linguistic term
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LinguisticTermOutChangeType {
+    /// Changed linguistic term
+    pub term: LinguisticTermOutType,
+    /// Connections whose values were recalculated
+    pub connections: Vec<ConnectionLinguisticTermsOutType>,
+}
+
+/// Type of deleted linguistic term
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LinguisticTermOutDeleteType {
+    /// Linguistic term identifier
+    pub id: i32,
+}
+
+/// Type of changed defuzzification method
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DefuzzificationMethodOutChangeType {
+    /// Project identifier
+    pub project_id: i32,
+    /// Defuzzification method
+    pub defuzzification_method: DefuzzificationMethodType,
+    /// Connections whose values were recalculated
+    pub connections: Vec<ConnectionLinguisticTermsOutType>,
+}
+
+/// Type of connection linguistic terms to change
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionLinguisticTermsInChangeType {
+    /// Identifiers of linguistic terms given by experts, they are aggregated into one fuzzy weight
+    pub linguistic_term_ids: Vec<i32>,
+}
+
+/// Type of connection linguistic terms
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionLinguisticTermsOutType {
+    /// Connection identifier
+    pub connection_id: i32,
+    /// Identifiers of linguistic terms given by experts
+    pub linguistic_term_ids: Vec<i32>,
+    /// Defuzzified connection value
+    pub value: f64,
+    /// Connection update time
+    pub updated_at: DateTime<Utc>,
+}
@@ -26,6 +26,10 @@ impl Plugin for TargetConceptsPlugin {
         services::delete_project_target_concepts(conn, project.id)?;
         Ok(project)
     }
+    fn sync_model(&self, conn: &mut PgConnection, project: Project) -> ServiceResult<Project> {
+        services::create_project_target_concepts(conn, &project)?;
+        Ok(project)
+    }
 }
 
 #[rocket::async_trait]
@@ -2,6 +2,8 @@ use super::services;
 use super::types::{TargetConceptInChangeType, TargetConceptOutType};
 use crate::db;
 use crate::models::User;
+use crate::plugins::Plugins;
+use crate::request::IfMatch;
 use crate::response::{PathResult, ToPathResult};
 use crate::types::ModelActionType;
 use crate::web_socket::WebSocketModelService;
@@ -18,16 +20,20 @@ use rocket_okapi::openapi;
 pub async fn change_target_concept(
     concept_id: i32,
     target_concept_in: Json<TargetConceptInChangeType>,
+    if_match: IfMatch,
     user: User,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<TargetConceptOutType>> {
     let conn = &mut db::establish_connection();
     services::change_target_concept(
         conn,
+        plugins,
         model_service,
         &user,
         concept_id,
         target_concept_in.into_inner(),
+        if_match.0,
     )
     .await
     .to_path_result()
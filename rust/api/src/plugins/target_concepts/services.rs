@@ -1,17 +1,16 @@
 use super::super::Plugins;
 use super::models::TargetConcept;
 use super::types::{TargetConceptInChangeType, TargetConceptOutType};
-use crate::db;
 use crate::models::{Concept, Project, User};
 use crate::plugins::control_concepts::services as control_concepts_services;
 use crate::plugins::Plugin;
 use crate::response::{ServiceResult, ToServiceResult};
 use crate::schema::{concepts, projects, target_concepts};
-use crate::services::{model_services, permission_services};
+use crate::services::{model_history_services, model_services, permission_services};
 use crate::types::{ConceptOutType, ModelActionType};
 use crate::validation_error;
 use crate::web_socket::WebSocketModelService;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::PgConnection;
 use serde_json::{json, Value};
@@ -25,8 +24,7 @@ pub fn handle_get_model(
         .get_model_emitter
         .lock()
         .unwrap()
-        .on(move |mut model_out, _| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut model_out, _| {
             if !plugin
                 .lock()
                 .unwrap()
@@ -55,8 +53,7 @@ pub fn handle_add_concept(
         .add_concept_emitter
         .lock()
         .unwrap()
-        .on(move |mut concept_out, project| {
-            let conn = &mut db::establish_connection();
+        .on(move |conn, mut concept_out, project| {
             if !plugin.lock().unwrap().is_enabled(conn, project.id)? {
                 return Ok(concept_out);
             }
@@ -86,6 +83,7 @@ pub fn create_project_target_concepts(
                 })
                 .collect::<Vec<_>>(),
         )
+        .on_conflict_do_nothing()
         .get_results::<TargetConcept>(conn)
         .to_service_result()
 }
@@ -127,10 +125,12 @@ pub fn create_target_concept(
 
 pub async fn change_target_concept(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     concept_id: i32,
     target_concept_in: TargetConceptInChangeType,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<TargetConceptOutType>> {
     let project = model_services::find_project_by_concept_id(conn, concept_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
@@ -142,23 +142,40 @@ pub async fn change_target_concept(
     model_services::check_concept_value(&project, Some(target_concept_in.max_value))?;
     let target_concept = find_target_concept_by_id(conn, concept_id)
         .to_service_result_find(String::from("target_concept_not_found_error"))?;
-    let (target_concept, concept, project) = conn
-        .transaction(|conn| {
-            let target_concept = diesel::update(target_concepts::table)
-                .filter(target_concepts::concept_id.eq(target_concept.concept_id))
-                .set((
-                    target_concepts::is_target.eq(target_concept_in.is_target),
-                    target_concepts::min_value.eq(target_concept_in.min_value),
-                    target_concepts::include_min_value.eq(target_concept_in.include_min_value),
-                    target_concepts::max_value.eq(target_concept_in.max_value),
-                    target_concepts::include_max_value.eq(target_concept_in.include_max_value),
-                ))
-                .get_result::<TargetConcept>(conn)?;
-            let (concept, project) =
-                model_services::update_concept(conn, concept_id, project.id, Utc::now())?;
-            Ok((target_concept, concept, project))
-        })
-        .to_service_result()?;
+    let (target_concept, concept, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        model_services::check_concept_version(
+            conn,
+            plugins,
+            &project,
+            concept_id,
+            expected_version,
+        )?;
+        let previous_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        let target_concept = diesel::update(target_concepts::table)
+            .filter(target_concepts::concept_id.eq(target_concept.concept_id))
+            .set((
+                target_concepts::is_target.eq(target_concept_in.is_target),
+                target_concepts::min_value.eq(target_concept_in.min_value),
+                target_concepts::include_min_value.eq(target_concept_in.include_min_value),
+                target_concepts::max_value.eq(target_concept_in.max_value),
+                target_concepts::include_max_value.eq(target_concept_in.include_max_value),
+            ))
+            .get_result::<TargetConcept>(conn)?;
+        let (concept, project) =
+            model_services::update_concept(conn, concept_id, project.id, Utc::now())?;
+        let next_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "changeTargetConcept",
+            previous_state,
+            next_state,
+        )?;
+        Ok((target_concept, concept, project))
+    })?;
     let target_concept_out = TargetConceptOutType::from((target_concept, concept));
     let model_action = ModelActionType::new(
         &project,
@@ -5,6 +5,7 @@ use crate::models::User;
 use crate::plugins::Plugins;
 use crate::web_socket::{WebSocketAdjustmentRunService, WebSocketModelService};
 use chrono::{DateTime, Utc};
+use okapi::openapi3::{Object, Parameter, ParameterValue};
 use rocket::form::{self, FromFormField, ValueField};
 use rocket::http::Status;
 use rocket::request::{FromRequest, Outcome};
@@ -87,6 +88,58 @@ impl<'r> OpenApiFromRequest<'r> for UserAgent {
     }
 }
 
+/// Expected version of the changed element sent in the If-Match header
+pub struct IfMatch(pub Option<DateTime<Utc>>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfMatch {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let if_match = match request.headers().get_one("If-Match") {
+            Some(if_match) => if_match.trim(),
+            None => return Outcome::Success(IfMatch(None)),
+        };
+        if if_match == "*" {
+            return Outcome::Success(IfMatch(None));
+        }
+        let version = if_match
+            .strip_prefix("W/")
+            .unwrap_or(if_match)
+            .trim_matches('"');
+        match DateTime::parse_from_rfc3339(version) {
+            Ok(date_time) => Outcome::Success(IfMatch(Some(date_time.into()))),
+            Err(_) => Outcome::Failure((Status::BadRequest, ())),
+        }
+    }
+}
+
+impl<'r> OpenApiFromRequest<'r> for IfMatch {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> RocketOkapiResult<RequestHeaderInput> {
+        Ok(RequestHeaderInput::Parameter(Parameter {
+            name: "If-Match".to_owned(),
+            location: "header".to_owned(),
+            description: Some("Version the change is based on".to_owned()),
+            required: false,
+            deprecated: false,
+            allow_empty_value: false,
+            value: ParameterValue::Schema {
+                style: None,
+                explode: None,
+                allow_reserved: false,
+                schema: gen.json_schema::<DateTime<Utc>>(),
+                example: None,
+                examples: None,
+            },
+            extensions: Object::default(),
+        }))
+    }
+}
+
 pub struct AcceptLanguage(pub Vec<LanguageIdentifier>);
 
 #[rocket::async_trait]
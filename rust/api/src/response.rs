@@ -1,5 +1,6 @@
 use crate::locale::Locale;
 use crate::request::AcceptLanguage;
+use crate::types::FileOutType;
 use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use okapi::openapi3::Responses;
 use rocket::catcher::BoxFuture;
@@ -42,6 +43,16 @@ macro_rules! not_found_error {
     };
 }
 
+#[macro_export]
+macro_rules! conflict_error {
+    ($key:expr, $current:expr) => {
+        Err(crate::response::AppError::ConflictError(
+            String::from($key),
+            $current,
+        ))
+    };
+}
+
 #[macro_export]
 macro_rules! internal_server_error {
     () => {
@@ -86,6 +97,7 @@ pub enum AppError {
     DieselError(DieselError, Option<String>, Option<String>),
     ForbiddenError(String),
     NotFoundError(String),
+    ConflictError(String, serde_json::Value),
     InternalServerError,
 }
 
@@ -99,6 +111,12 @@ impl ToAppError for DieselError {
     }
 }
 
+impl From<DieselError> for AppError {
+    fn from(diesel_error: DieselError) -> Self {
+        diesel_error.to_app_error(None, None)
+    }
+}
+
 pub trait ToServiceResult<T> {
     fn to_service_result(self) -> ServiceResult<T>;
     fn to_service_result_find(self, not_found_key: String) -> ServiceResult<T>;
@@ -164,6 +182,15 @@ impl<'r> Responder<'r, 'static> for AppError {
                 response.set_status(Status::NotFound);
                 return Ok(response);
             }
+            AppError::ConflictError(conflict_key, current) => {
+                let mut response = Json(serde_json::json!({
+                    "message": t!(&conflict_key, locale = &locale.get_locale()),
+                    "current": current,
+                }))
+                .respond_to(request)?;
+                response.set_status(Status::Conflict);
+                return Ok(response);
+            }
             AppError::InternalServerError => {
                 let mut response = t!("internal_server_error", locale = &locale.get_locale())
                     .respond_to(request)?;
@@ -180,6 +207,45 @@ impl OpenApiResponderInner for AppError {
     }
 }
 
+impl<'r> Responder<'r, 'static> for FileOutType {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = self.content.respond_to(request)?;
+        response.set_header(self.content_type);
+        let ascii_name = self
+            .name
+            .chars()
+            .map(|c| match c {
+                ' '..='~' if c != '"' && c != '\\' => c,
+                _ => '_',
+            })
+            .collect::<String>();
+        let encoded_name = self
+            .name
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect::<String>();
+        response.set_header(Header::new(
+            header::CONTENT_DISPOSITION.as_str(),
+            format!(
+                "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+                ascii_name, encoded_name
+            ),
+        ));
+        Ok(response)
+    }
+}
+
+impl OpenApiResponderInner for FileOutType {
+    fn responses(gen: &mut OpenApiGenerator) -> RocketOkapiResult<Responses> {
+        <Vec<u8>>::responses(gen)
+    }
+}
+
 pub fn handle_bad_request_error<'r>(status: Status, request: &'r Request<'_>) -> BoxFuture<'r> {
     Box::pin(async move { get_response(status, request, "bad_request_error").await })
 }
@@ -3,9 +3,14 @@ mod project_routes;
 mod user_routes;
 use crate::plugins::adjustment::routes as adjustment_routes;
 use crate::plugins::concept_constraints::routes as concept_constraints_routes;
+use crate::plugins::concept_decay_rates::routes as concept_decay_rates_routes;
 use crate::plugins::connection_constraints::routes as connection_constraints_routes;
+use crate::plugins::connection_functions::routes as connection_functions_routes;
+use crate::plugins::connection_lags::routes as connection_lags_routes;
 use crate::plugins::control_concepts::routes as control_concepts_routes;
 use crate::plugins::control_connections::routes as control_connections_routes;
+use crate::plugins::intervention_costs::routes as intervention_costs_routes;
+use crate::plugins::linguistic_connections::routes as linguistic_connections_routes;
 use crate::plugins::target_concepts::routes as target_concepts_routes;
 use okapi::openapi3::{Object, OpenApi, Parameter, ParameterValue, RefOr, SchemaObject};
 use rocket::{Build, Rocket};
@@ -75,6 +80,32 @@ macro_rules! get_routes {
         spec.info.title = String::from("Fuzzy Cognitive Model");
         patch_wrong_content_type(&mut spec, "/user", Operation::Post);
         patch_wrong_content_type(&mut spec, "/me", Operation::Put);
+        patch_wrong_content_type(&mut spec, "/projects/import", Operation::Post);
+        patch_wrong_content_type(
+            &mut spec,
+            "/projects/{project_id}/model/import/csv",
+            Operation::Post,
+        );
+        patch_wrong_content_type(
+            &mut spec,
+            "/projects/{project_id}/model/import/xlsx",
+            Operation::Post,
+        );
+        patch_wrong_content_type(
+            &mut spec,
+            "/projects/{project_id}/model/import/graphml",
+            Operation::Post,
+        );
+        patch_wrong_content_type(
+            &mut spec,
+            "/projects/{project_id}/model/import/mental_modeler",
+            Operation::Post,
+        );
+        patch_wrong_content_type(
+            &mut spec,
+            "/projects/{project_id}/model/import/fcmapper",
+            Operation::Post,
+        );
         add_accept_language_header(&mut spec);
         openapi_routes![$first_route $(,$route)*](Some(spec), &settings)
     }};
@@ -118,6 +149,8 @@ impl MountRoutes for Rocket<Build> {
                 project_routes::leave_project,
                 project_routes::exclude_user,
                 project_routes::delete_project,
+                project_routes::export_project,
+                project_routes::import_project,
                 model_routes::get_model,
                 model_routes::get_model_copy,
                 model_routes::get_active_users,
@@ -128,19 +161,64 @@ impl MountRoutes for Rocket<Build> {
                 model_routes::create_connection,
                 model_routes::change_connection,
                 model_routes::delete_connection,
+                model_routes::export_model_csv,
+                model_routes::export_model_xlsx,
+                model_routes::import_model_csv,
+                model_routes::import_model_xlsx,
+                model_routes::export_model_graph,
+                model_routes::export_model_copy_graph,
+                model_routes::import_model_graphml,
+                model_routes::export_model_mental_modeler,
+                model_routes::export_model_fcmapper,
+                model_routes::import_model_mental_modeler,
+                model_routes::import_model_fcmapper,
+                model_routes::create_model_snapshot,
+                model_routes::get_model_snapshots,
+                model_routes::delete_model_snapshot,
+                model_routes::diff_model_snapshot,
+                model_routes::restore_model_snapshot,
+                model_routes::undo_model_action,
+                model_routes::redo_model_action,
+                model_routes::apply_model_batch,
+                model_routes::export_model_fragment,
+                model_routes::create_model_template,
+                model_routes::get_model_templates,
+                model_routes::delete_model_template,
+                model_routes::paste_model_fragment,
                 control_concepts_routes::change_concept_is_control,
                 target_concepts_routes::change_target_concept,
                 control_connections_routes::change_connection_is_control,
                 concept_constraints_routes::change_concept_constraint,
                 connection_constraints_routes::change_connection_constraint,
+                intervention_costs_routes::change_concept_intervention_cost,
+                intervention_costs_routes::change_connection_intervention_cost,
+                linguistic_connections_routes::get_linguistic_term_set,
+                linguistic_connections_routes::create_linguistic_term,
+                linguistic_connections_routes::change_linguistic_term,
+                linguistic_connections_routes::delete_linguistic_term,
+                linguistic_connections_routes::change_defuzzification_method,
+                linguistic_connections_routes::change_connection_linguistic_terms,
+                connection_lags_routes::change_connection_lag,
+                connection_functions_routes::change_connection_function,
+                concept_decay_rates_routes::change_concept_decay_rate,
                 adjustment_routes::change_dynamic_model_type,
                 adjustment_routes::adjust,
+                adjustment_routes::validate_adjustment,
+                adjustment_routes::simulate_intervals,
+                adjustment_routes::analyze_stability,
+                adjustment_routes::solve_inverse,
+                adjustment_routes::pause_adjustment,
+                adjustment_routes::resume_adjustment,
+                adjustment_routes::cancel_adjustment,
                 adjustment_routes::get_adjustment_run,
+                adjustment_routes::get_adjustment_run_queue_position,
                 adjustment_routes::get_adjustment_runs,
                 adjustment_routes::get_adjustment_generation,
                 adjustment_routes::get_adjustment_generations,
                 adjustment_routes::get_adjustment_individual,
-                adjustment_routes::get_adjustment_individuals
+                adjustment_routes::get_adjustment_individuals,
+                adjustment_routes::explain_adjustment_individual,
+                adjustment_routes::apply_adjustment_individual
             ),
         )
     }
@@ -1,16 +1,32 @@
 use crate::db;
+use crate::locale::Locale;
 use crate::models::User;
 use crate::plugins::Plugins;
-use crate::response::{PathResult, ToPathResult};
-use crate::services::model_services;
+use crate::request::IfMatch;
+use crate::response::{
+    PathAnyResult, PathEmptyResult, PathResult, ToPathEmptyResult, ToPathResult,
+};
+use crate::services::{
+    model_batch_services, model_graph_services, model_history_services, model_interchange_services,
+    model_matrix_services, model_services, model_snapshot_services, model_template_services,
+};
+use crate::storage::Storage;
 use crate::types::{
     ConceptInMoveType, ConceptInType, ConceptOutChangeType, ConceptOutDeleteType,
     ConceptOutMoveType, ConceptOutType, ConnectionInChangeType, ConnectionInCreateType,
-    ConnectionOutChangeType, ConnectionOutDeleteType, ConnectionOutType, ModelActionType,
-    ModelOutType, UserOutType,
+    ConnectionOutChangeType, ConnectionOutDeleteType, ConnectionOutType, FileOutType,
+    GraphFormatType, ModelActionType, ModelBatchInType, ModelBatchOutType, ModelDiffOutType,
+    ModelElementsChangeOutType, ModelElementsType, ModelFragmentInPasteType, ModelFragmentInType,
+    ModelImportReportOutType, ModelInCsvImportType, ModelInFcmapperImportType,
+    ModelInGraphmlImportType, ModelInMentalModelerImportType, ModelInXlsxImportType, ModelOutType,
+    ModelSheetType, ModelSnapshotInType, ModelSnapshotOutType, ModelSnapshotsInType,
+    ModelTemplateInType, ModelTemplateOutType, ModelTemplatesInType, PaginationInType,
+    PaginationOutType, UserOutType,
 };
 use crate::web_socket::WebSocketModelService;
+use rocket::form::Form;
 use rocket::serde::json::Json;
+use rocket::State;
 use rocket_okapi::openapi;
 
 /// Get model
@@ -80,18 +96,22 @@ pub async fn create_concept(
 pub async fn change_concept(
     concept_id: i32,
     concept_in: Json<ConceptInType>,
+    if_match: IfMatch,
     user: User,
     plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ConceptOutChangeType>> {
     let conn = &mut db::establish_connection();
+    let concept_in = concept_in.into_inner();
+    let expected_version = concept_in.expected_version.or(if_match.0);
     model_services::change_concept(
         conn,
         plugins,
         model_service,
         &user,
         concept_id,
-        concept_in.into_inner(),
+        concept_in,
+        expected_version,
     )
     .await
     .to_path_result()
@@ -103,16 +123,22 @@ pub async fn change_concept(
 pub async fn move_concept(
     concept_id: i32,
     concept_in: Json<ConceptInMoveType>,
+    if_match: IfMatch,
     user: User,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ConceptOutMoveType>> {
     let conn = &mut db::establish_connection();
+    let concept_in = concept_in.into_inner();
+    let expected_version = concept_in.expected_version.or(if_match.0);
     model_services::move_concept(
         conn,
+        plugins,
         model_service,
         &user,
         concept_id,
-        concept_in.into_inner(),
+        concept_in,
+        expected_version,
     )
     .await
     .to_path_result()
@@ -123,11 +149,13 @@ pub async fn move_concept(
 #[delete("/concepts/<concept_id>")]
 pub async fn delete_concept(
     concept_id: i32,
+    if_match: IfMatch,
     user: User,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ConceptOutDeleteType>> {
     let conn = &mut db::establish_connection();
-    model_services::delete_concept(conn, model_service, &user, concept_id)
+    model_services::delete_concept(conn, plugins, model_service, &user, concept_id, if_match.0)
         .await
         .to_path_result()
 }
@@ -169,18 +197,22 @@ pub async fn create_connection(
 pub async fn change_connection(
     connection_id: i32,
     connection_in: Json<ConnectionInChangeType>,
+    if_match: IfMatch,
     user: User,
     plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ConnectionOutChangeType>> {
     let conn = &mut db::establish_connection();
+    let connection_in = connection_in.into_inner();
+    let expected_version = connection_in.expected_version.or(if_match.0);
     model_services::change_connection(
         conn,
         plugins,
         model_service,
         &user,
         connection_id,
-        connection_in.into_inner(),
+        connection_in,
+        expected_version,
     )
     .await
     .to_path_result()
@@ -191,11 +223,527 @@ pub async fn change_connection(
 #[delete("/connections/<connection_id>")]
 pub async fn delete_connection(
     connection_id: i32,
+    if_match: IfMatch,
     user: User,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
 ) -> PathResult<ModelActionType<ConnectionOutDeleteType>> {
     let conn = &mut db::establish_connection();
-    model_services::delete_connection(conn, model_service, &user, connection_id)
-        .await
-        .to_path_result()
+    model_services::delete_connection(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        connection_id,
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Export model sheet as CSV
+#[openapi(tag = "model")]
+#[get("/projects/<project_id>/model/export/csv?<sheet>")]
+pub fn export_model_csv(
+    project_id: i32,
+    sheet: Option<ModelSheetType>,
+    user: User,
+    plugins: &Plugins,
+) -> PathAnyResult<FileOutType> {
+    let conn = &mut db::establish_connection();
+    model_matrix_services::export_model_csv(
+        conn,
+        plugins,
+        &user,
+        project_id,
+        sheet.unwrap_or(ModelSheetType::Weights),
+    )
+}
+
+/// Export model as XLSX
+#[openapi(tag = "model")]
+#[get("/projects/<project_id>/model/export/xlsx")]
+pub fn export_model_xlsx(
+    project_id: i32,
+    user: User,
+    plugins: &Plugins,
+) -> PathAnyResult<FileOutType> {
+    let conn = &mut db::establish_connection();
+    model_matrix_services::export_model_xlsx(conn, plugins, &user, project_id)
+}
+
+/// Import model from CSV
+#[openapi(tag = "model")]
+#[post("/projects/<project_id>/model/import/csv", data = "<model_in>")]
+pub async fn import_model_csv(
+    project_id: i32,
+    model_in: Form<ModelInCsvImportType<'_>>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &State<Storage>,
+) -> PathResult<ModelActionType<ModelOutType>> {
+    let conn = &mut db::establish_connection();
+    model_matrix_services::import_model_csv(
+        conn,
+        plugins,
+        model_service,
+        storage,
+        &user,
+        project_id,
+        model_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Import model from XLSX
+#[openapi(tag = "model")]
+#[post("/projects/<project_id>/model/import/xlsx", data = "<model_in>")]
+pub async fn import_model_xlsx(
+    project_id: i32,
+    model_in: Form<ModelInXlsxImportType<'_>>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &State<Storage>,
+) -> PathResult<ModelActionType<ModelOutType>> {
+    let conn = &mut db::establish_connection();
+    model_matrix_services::import_model_xlsx(
+        conn,
+        plugins,
+        model_service,
+        storage,
+        &user,
+        project_id,
+        model_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Export model as graph
+#[openapi(tag = "model")]
+#[get("/projects/<project_id>/model/export/graph?<format>")]
+pub fn export_model_graph(
+    project_id: i32,
+    format: GraphFormatType,
+    user: User,
+    plugins: &Plugins,
+) -> PathAnyResult<FileOutType> {
+    let conn = &mut db::establish_connection();
+    model_graph_services::export_model(conn, plugins, &user, project_id, format)
+}
+
+/// Export model copy as graph
+#[openapi(tag = "model")]
+#[get("/models/<model_copy_id>/export/graph?<format>")]
+pub fn export_model_copy_graph(
+    model_copy_id: i32,
+    format: GraphFormatType,
+    user: User,
+) -> PathAnyResult<FileOutType> {
+    let conn = &mut db::establish_connection();
+    model_graph_services::export_model_copy(conn, &user, model_copy_id, format)
+}
+
+/// Import model from GraphML
+#[openapi(tag = "model")]
+#[post("/projects/<project_id>/model/import/graphml", data = "<model_in>")]
+pub async fn import_model_graphml(
+    project_id: i32,
+    model_in: Form<ModelInGraphmlImportType<'_>>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &State<Storage>,
+) -> PathResult<ModelActionType<ModelOutType>> {
+    let conn = &mut db::establish_connection();
+    model_graph_services::import_model_graphml(
+        conn,
+        plugins,
+        model_service,
+        storage,
+        &user,
+        project_id,
+        model_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Export model as Mental Modeler project
+#[openapi(tag = "model")]
+#[get("/projects/<project_id>/model/export/mental_modeler")]
+pub fn export_model_mental_modeler(
+    project_id: i32,
+    user: User,
+    plugins: &Plugins,
+) -> PathAnyResult<FileOutType> {
+    let conn = &mut db::establish_connection();
+    model_interchange_services::export_model_mental_modeler(conn, plugins, &user, project_id)
+}
+
+/// Export model as FCMapper workbook
+#[openapi(tag = "model")]
+#[get("/projects/<project_id>/model/export/fcmapper")]
+pub fn export_model_fcmapper(
+    project_id: i32,
+    user: User,
+    plugins: &Plugins,
+) -> PathAnyResult<FileOutType> {
+    let conn = &mut db::establish_connection();
+    model_interchange_services::export_model_fcmapper(conn, plugins, &user, project_id)
+}
+
+/// Import model from Mental Modeler project
+#[openapi(tag = "model")]
+#[post(
+    "/projects/<project_id>/model/import/mental_modeler",
+    data = "<model_in>"
+)]
+pub async fn import_model_mental_modeler(
+    project_id: i32,
+    model_in: Form<ModelInMentalModelerImportType<'_>>,
+    if_match: IfMatch,
+    user: User,
+    locale: &Locale,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &State<Storage>,
+) -> PathResult<ModelImportReportOutType> {
+    let conn = &mut db::establish_connection();
+    model_interchange_services::import_model_mental_modeler(
+        conn,
+        plugins,
+        model_service,
+        storage,
+        &user,
+        locale,
+        project_id,
+        model_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Import model from FCMapper workbook
+#[openapi(tag = "model")]
+#[post("/projects/<project_id>/model/import/fcmapper", data = "<model_in>")]
+pub async fn import_model_fcmapper(
+    project_id: i32,
+    model_in: Form<ModelInFcmapperImportType<'_>>,
+    if_match: IfMatch,
+    user: User,
+    locale: &Locale,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &State<Storage>,
+) -> PathResult<ModelImportReportOutType> {
+    let conn = &mut db::establish_connection();
+    model_interchange_services::import_model_fcmapper(
+        conn,
+        plugins,
+        model_service,
+        storage,
+        &user,
+        locale,
+        project_id,
+        model_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Create named model snapshot
+#[openapi(tag = "model")]
+#[post(
+    "/projects/<project_id>/model/snapshots",
+    format = "json",
+    data = "<model_snapshot_in>"
+)]
+pub fn create_model_snapshot(
+    project_id: i32,
+    model_snapshot_in: Json<ModelSnapshotInType>,
+    user: User,
+    plugins: &Plugins,
+) -> PathResult<ModelSnapshotOutType> {
+    let conn = &mut db::establish_connection();
+    model_snapshot_services::create_model_snapshot(
+        conn,
+        plugins,
+        &user,
+        project_id,
+        model_snapshot_in.into_inner(),
+    )
+    .to_path_result()
+}
+
+/// Get model snapshots
+#[openapi(tag = "model")]
+#[get("/projects/<project_id>/model/snapshots?<model_snapshots_in..>")]
+pub fn get_model_snapshots(
+    project_id: i32,
+    model_snapshots_in: ModelSnapshotsInType,
+    user: User,
+) -> PathResult<PaginationOutType<ModelSnapshotOutType>> {
+    let conn = &mut db::establish_connection();
+    let pagination = PaginationInType {
+        page: model_snapshots_in.page.unwrap_or(1),
+        per_page: model_snapshots_in.per_page.unwrap_or(15),
+    };
+    model_snapshot_services::paginate_model_snapshots(
+        conn,
+        &user,
+        project_id,
+        model_snapshots_in.is_automatic,
+        pagination,
+    )
+    .to_path_result()
+}
+
+/// Delete model snapshot
+#[openapi(tag = "model")]
+#[delete("/model_snapshots/<model_snapshot_id>")]
+pub fn delete_model_snapshot(model_snapshot_id: i32, user: User) -> PathEmptyResult {
+    let conn = &mut db::establish_connection();
+    model_snapshot_services::delete_model_snapshot(conn, &user, model_snapshot_id)
+        .to_path_empty_result()
+}
+
+/// Get difference between model snapshot and another snapshot or live model
+#[openapi(tag = "model")]
+#[get("/model_snapshots/<model_snapshot_id>/diff?<target_model_snapshot_id>")]
+pub fn diff_model_snapshot(
+    model_snapshot_id: i32,
+    target_model_snapshot_id: Option<i32>,
+    user: User,
+    plugins: &Plugins,
+) -> PathResult<ModelDiffOutType> {
+    let conn = &mut db::establish_connection();
+    model_snapshot_services::diff_model_snapshot(
+        conn,
+        plugins,
+        &user,
+        model_snapshot_id,
+        target_model_snapshot_id,
+    )
+    .to_path_result()
+}
+
+/// Restore live model from model snapshot
+#[openapi(tag = "model")]
+#[post("/model_snapshots/<model_snapshot_id>/restore")]
+pub async fn restore_model_snapshot(
+    model_snapshot_id: i32,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ModelOutType>> {
+    let conn = &mut db::establish_connection();
+    model_snapshot_services::restore_model_snapshot(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        model_snapshot_id,
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Undo the last model action of the user
+#[openapi(tag = "model")]
+#[post("/projects/<project_id>/model/undo?<force>")]
+pub async fn undo_model_action(
+    project_id: i32,
+    force: Option<bool>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ModelElementsChangeOutType>> {
+    let conn = &mut db::establish_connection();
+    model_history_services::undo_model_action(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        project_id,
+        force.unwrap_or(false),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Redo the last undone model action of the user
+#[openapi(tag = "model")]
+#[post("/projects/<project_id>/model/redo?<force>")]
+pub async fn redo_model_action(
+    project_id: i32,
+    force: Option<bool>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ModelElementsChangeOutType>> {
+    let conn = &mut db::establish_connection();
+    model_history_services::redo_model_action(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        project_id,
+        force.unwrap_or(false),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Apply ordered batch of model operations in one transaction
+#[openapi(tag = "model")]
+#[post(
+    "/projects/<project_id>/model/batch",
+    format = "json",
+    data = "<model_batch_in>"
+)]
+pub async fn apply_model_batch(
+    project_id: i32,
+    model_batch_in: Json<ModelBatchInType>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ModelBatchOutType>> {
+    let conn = &mut db::establish_connection();
+    model_batch_services::apply_model_batch(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        project_id,
+        model_batch_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
+}
+
+/// Export selected concepts with connections between them as model fragment
+#[openapi(tag = "model")]
+#[post(
+    "/projects/<project_id>/model/fragment",
+    format = "json",
+    data = "<model_fragment_in>"
+)]
+pub fn export_model_fragment(
+    project_id: i32,
+    model_fragment_in: Json<ModelFragmentInType>,
+    user: User,
+    plugins: &Plugins,
+) -> PathResult<ModelElementsType> {
+    let conn = &mut db::establish_connection();
+    model_template_services::get_model_fragment(
+        conn,
+        plugins,
+        &user,
+        project_id,
+        &model_fragment_in.concept_ids,
+    )
+    .to_path_result()
+}
+
+/// Save selected concepts with connections between them to user model templates
+#[openapi(tag = "model")]
+#[post(
+    "/projects/<project_id>/model/templates",
+    format = "json",
+    data = "<model_template_in>"
+)]
+pub fn create_model_template(
+    project_id: i32,
+    model_template_in: Json<ModelTemplateInType>,
+    user: User,
+    plugins: &Plugins,
+) -> PathResult<ModelTemplateOutType> {
+    let conn = &mut db::establish_connection();
+    model_template_services::create_model_template(
+        conn,
+        plugins,
+        &user,
+        project_id,
+        model_template_in.into_inner(),
+    )
+    .to_path_result()
+}
+
+/// Get user model templates
+#[openapi(tag = "model")]
+#[get("/model_templates?<model_templates_in..>")]
+pub fn get_model_templates(
+    model_templates_in: ModelTemplatesInType,
+    user: User,
+) -> PathResult<PaginationOutType<ModelTemplateOutType>> {
+    let conn = &mut db::establish_connection();
+    let pagination = PaginationInType {
+        page: model_templates_in.page.unwrap_or(1),
+        per_page: model_templates_in.per_page.unwrap_or(15),
+    };
+    model_template_services::paginate_model_templates(
+        conn,
+        &user,
+        model_templates_in.search,
+        pagination,
+    )
+    .to_path_result()
+}
+
+/// Delete user model template
+#[openapi(tag = "model")]
+#[delete("/model_templates/<model_template_id>")]
+pub fn delete_model_template(model_template_id: i32, user: User) -> PathEmptyResult {
+    let conn = &mut db::establish_connection();
+    model_template_services::delete_model_template(conn, &user, model_template_id)
+        .to_path_empty_result()
+}
+
+/// Paste model fragment or model template into the model with offset of positions
+#[openapi(tag = "model")]
+#[post(
+    "/projects/<project_id>/model/paste",
+    format = "json",
+    data = "<model_fragment_in>"
+)]
+pub async fn paste_model_fragment(
+    project_id: i32,
+    model_fragment_in: Json<ModelFragmentInPasteType>,
+    if_match: IfMatch,
+    user: User,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+) -> PathResult<ModelActionType<ModelBatchOutType>> {
+    let conn = &mut db::establish_connection();
+    model_template_services::paste_model_fragment(
+        conn,
+        plugins,
+        model_service,
+        &user,
+        project_id,
+        model_fragment_in.into_inner(),
+        if_match.0,
+    )
+    .await
+    .to_path_result()
 }
@@ -2,17 +2,21 @@ use crate::db;
 use crate::models::{ProjectUserStatusValue, User};
 use crate::plugins::Plugins;
 use crate::response::{
-    PathEmptyResult, PathResult, ToPathEmptyResult, ToPathResult, ToServiceResult,
+    PathAnyResult, PathEmptyResult, PathResult, ToPathEmptyResult, ToPathResult, ToServiceResult,
 };
 use crate::services::{
-    permission_services, plugin_services, project_services, project_user_services,
+    permission_services, plugin_services, project_bundle_services, project_services,
+    project_user_services,
 };
+use crate::storage::Storage;
 use crate::types::{
-    IntervalInType, PaginationInType, PaginationOutType, PermissionType, PluginType, ProjectInType,
-    ProjectOutType, ProjectUserType, ProjectsInType,
+    FileOutType, IntervalInType, PaginationInType, PaginationOutType, PermissionType, PluginType,
+    ProjectInBundleImportType, ProjectInType, ProjectOutType, ProjectUserType, ProjectsInType,
 };
 use crate::web_socket::{WebSocketAdjustmentRunService, WebSocketModelService};
+use rocket::form::Form;
 use rocket::serde::json::Json;
+use rocket::State;
 use rocket_okapi::openapi;
 
 /// Create new project
@@ -254,3 +258,35 @@ pub async fn delete_project(
     .await?;
     Ok(())
 }
+
+/// Export project bundle
+#[openapi(tag = "projects")]
+#[get("/projects/<project_id>/export?<include_adjustment>")]
+pub fn export_project(
+    project_id: i32,
+    include_adjustment: Option<bool>,
+    user: User,
+) -> PathAnyResult<FileOutType> {
+    let conn = &mut db::establish_connection();
+    project_bundle_services::export_project(
+        conn,
+        &user,
+        project_id,
+        include_adjustment.unwrap_or(false),
+    )
+}
+
+/// Import project bundle
+#[openapi(tag = "projects")]
+#[post("/projects/import", data = "<project_in>")]
+pub async fn import_project(
+    project_in: Form<ProjectInBundleImportType<'_>>,
+    user: User,
+    plugins: &Plugins,
+    storage: &State<Storage>,
+) -> PathResult<ProjectOutType> {
+    let conn = &mut db::establish_connection();
+    project_bundle_services::import_project(conn, plugins, storage, &user, project_in.into_inner())
+        .await
+        .to_path_result()
+}
@@ -1,21 +1,41 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "adjustment_run_state"))]
+    pub struct AdjustmentRunState;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "concept_value_type"))]
     pub struct ConceptValueType;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "connection_function_type"))]
+    pub struct ConnectionFunctionType;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "connection_value_type"))]
     pub struct ConnectionValueType;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "defuzzification_method_type"))]
+    pub struct DefuzzificationMethodType;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "dynamic_model_type"))]
     pub struct DynamicModelType;
 
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "membership_function_type"))]
+    pub struct MembershipFunctionType;
+
     #[derive(diesel::sql_types::SqlType)]
     #[diesel(postgres_type(name = "project_user_status_value"))]
     pub struct ProjectUserStatusValue;
+
+    #[derive(diesel::sql_types::SqlType)]
+    #[diesel(postgres_type(name = "scenario_aggregation_type"))]
+    pub struct ScenarioAggregationType;
 }
 
 diesel::table! {
@@ -27,6 +47,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    adjustment_connection_function_values (id) {
+        id -> Int4,
+        adjustment_individual_id -> Int4,
+        connection_id -> Int4,
+        parameter -> Float8,
+    }
+}
+
 diesel::table! {
     adjustment_connection_values (id) {
         id -> Int4,
@@ -52,12 +81,15 @@ diesel::table! {
         number -> Int4,
         time -> Int4,
         error -> Float8,
+        cost -> Float8,
     }
 }
 
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::DynamicModelType;
+    use super::sql_types::AdjustmentRunState;
+    use super::sql_types::ScenarioAggregationType;
 
     adjustment_runs (id) {
         id -> Int4,
@@ -75,6 +107,24 @@ diesel::table! {
         error -> Float8,
         created_at -> Timestamptz,
         result_individual_id -> Nullable<Int4>,
+        state -> AdjustmentRunState,
+        seed_generation_id -> Nullable<Int4>,
+        control_overrides -> Nullable<Jsonb>,
+        budget -> Nullable<Float8>,
+        minimize_cost -> Bool,
+        scenarios -> Nullable<Jsonb>,
+        scenario_aggregation_type -> ScenarioAggregationType,
+        time_step -> Nullable<Float8>,
+        stability_condition -> Nullable<Jsonb>,
+    }
+}
+
+diesel::table! {
+    adjustment_scenario_errors (id) {
+        id -> Int4,
+        adjustment_individual_id -> Int4,
+        scenario_name -> Varchar,
+        error -> Float8,
     }
 }
 
@@ -89,6 +139,13 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    concept_decay_rates (concept_id) {
+        concept_id -> Int4,
+        decay_rate -> Float8,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::DynamicModelType;
@@ -99,6 +156,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    concept_intervention_costs (concept_id) {
+        concept_id -> Int4,
+        linear_cost -> Float8,
+        fixed_cost -> Float8,
+    }
+}
+
 diesel::table! {
     concepts (id) {
         id -> Int4,
@@ -124,6 +189,42 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::ConnectionFunctionType;
+
+    connection_functions (connection_id) {
+        connection_id -> Int4,
+        function_type -> ConnectionFunctionType,
+        parameter -> Float8,
+        points -> Jsonb,
+        is_control -> Bool,
+    }
+}
+
+diesel::table! {
+    connection_intervention_costs (connection_id) {
+        connection_id -> Int4,
+        linear_cost -> Float8,
+        fixed_cost -> Float8,
+    }
+}
+
+diesel::table! {
+    connection_lags (connection_id) {
+        connection_id -> Int4,
+        lag -> Int4,
+    }
+}
+
+diesel::table! {
+    connection_linguistic_terms (id) {
+        id -> Int4,
+        connection_id -> Int4,
+        linguistic_term_id -> Int4,
+    }
+}
+
 diesel::table! {
     connections (id) {
         id -> Int4,
@@ -162,6 +263,32 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::DefuzzificationMethodType;
+
+    linguistic_term_sets (project_id) {
+        project_id -> Int4,
+        defuzzification_method -> DefuzzificationMethodType,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use super::sql_types::MembershipFunctionType;
+
+    linguistic_terms (id) {
+        id -> Int4,
+        project_id -> Int4,
+        name -> Varchar,
+        membership_function_type -> MembershipFunctionType,
+        a -> Float8,
+        b -> Float8,
+        c -> Float8,
+        d -> Float8,
+    }
+}
+
 diesel::table! {
     model_copies (id) {
         id -> Int4,
@@ -170,6 +297,43 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    model_history_entries (id) {
+        id -> Int4,
+        project_id -> Int4,
+        user_id -> Int4,
+        name -> Varchar,
+        previous_state -> Jsonb,
+        next_state -> Jsonb,
+        is_undone -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    model_snapshots (id) {
+        id -> Int4,
+        project_id -> Int4,
+        model_copy_id -> Int4,
+        creator_id -> Int4,
+        name -> Varchar,
+        description -> Text,
+        is_automatic -> Bool,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    model_templates (id) {
+        id -> Int4,
+        user_id -> Int4,
+        name -> Varchar,
+        description -> Text,
+        fragment -> Jsonb,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     password_resets (id) {
         id -> Int4,
@@ -308,6 +472,8 @@ diesel::table! {
 
 diesel::joinable!(adjustment_concept_values -> adjustment_individuals (adjustment_individual_id));
 diesel::joinable!(adjustment_concept_values -> concepts (concept_id));
+diesel::joinable!(adjustment_connection_function_values -> adjustment_individuals (adjustment_individual_id));
+diesel::joinable!(adjustment_connection_function_values -> connections (connection_id));
 diesel::joinable!(adjustment_connection_values -> adjustment_individuals (adjustment_individual_id));
 diesel::joinable!(adjustment_connection_values -> connections (connection_id));
 diesel::joinable!(adjustment_generations -> adjustment_runs (adjustment_run_id));
@@ -315,15 +481,31 @@ diesel::joinable!(adjustment_individuals -> adjustment_generations (adjustment_g
 diesel::joinable!(adjustment_runs -> adjustment_individuals (result_individual_id));
 diesel::joinable!(adjustment_runs -> model_copies (model_copy_id));
 diesel::joinable!(adjustment_runs -> projects (project_id));
+diesel::joinable!(adjustment_scenario_errors -> adjustment_individuals (adjustment_individual_id));
 diesel::joinable!(concept_constraints -> concepts (concept_id));
+diesel::joinable!(concept_decay_rates -> concepts (concept_id));
 diesel::joinable!(concept_dynamic_models -> concepts (concept_id));
+diesel::joinable!(concept_intervention_costs -> concepts (concept_id));
 diesel::joinable!(concepts -> projects (project_id));
 diesel::joinable!(connection_constraints -> connections (connection_id));
+diesel::joinable!(connection_functions -> connections (connection_id));
+diesel::joinable!(connection_intervention_costs -> connections (connection_id));
+diesel::joinable!(connection_lags -> connections (connection_id));
+diesel::joinable!(connection_linguistic_terms -> connections (connection_id));
+diesel::joinable!(connection_linguistic_terms -> linguistic_terms (linguistic_term_id));
 diesel::joinable!(connections -> projects (project_id));
 diesel::joinable!(control_concepts -> concepts (concept_id));
 diesel::joinable!(control_connections -> connections (connection_id));
 diesel::joinable!(email_confirmations -> users (user_id));
+diesel::joinable!(linguistic_term_sets -> projects (project_id));
+diesel::joinable!(linguistic_terms -> projects (project_id));
 diesel::joinable!(model_copies -> projects (project_id));
+diesel::joinable!(model_history_entries -> projects (project_id));
+diesel::joinable!(model_history_entries -> users (user_id));
+diesel::joinable!(model_snapshots -> model_copies (model_copy_id));
+diesel::joinable!(model_snapshots -> projects (project_id));
+diesel::joinable!(model_snapshots -> users (creator_id));
+diesel::joinable!(model_templates -> users (user_id));
 diesel::joinable!(password_resets -> users (user_id));
 diesel::joinable!(project_plugins -> plugins (plugin_name));
 diesel::joinable!(project_plugins -> projects (project_id));
@@ -336,19 +518,32 @@ diesel::joinable!(target_concepts -> concepts (concept_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
     adjustment_concept_values,
+    adjustment_connection_function_values,
     adjustment_connection_values,
     adjustment_generations,
     adjustment_individuals,
     adjustment_runs,
+    adjustment_scenario_errors,
     concept_constraints,
+    concept_decay_rates,
     concept_dynamic_models,
+    concept_intervention_costs,
     concepts,
     connection_constraints,
+    connection_functions,
+    connection_intervention_costs,
+    connection_lags,
+    connection_linguistic_terms,
     connections,
     control_concepts,
     control_connections,
     email_confirmations,
+    linguistic_term_sets,
+    linguistic_terms,
     model_copies,
+    model_history_entries,
+    model_snapshots,
+    model_templates,
     password_resets,
     permissions,
     plugin_dependencies,
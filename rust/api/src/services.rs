@@ -1,10 +1,20 @@
 pub mod email_confirmation_services;
 pub mod filter_services;
 pub mod mailing_services;
+pub mod model_batch_services;
+pub mod model_graph_services;
+pub mod model_history_services;
+pub mod model_import_services;
+pub mod model_interchange_services;
+pub mod model_matrix_services;
 pub mod model_services;
+pub mod model_snapshot_services;
+pub mod model_template_services;
 pub mod password_services;
 pub mod permission_services;
 pub mod plugin_services;
+pub mod plugins_data_services;
+pub mod project_bundle_services;
 pub mod project_services;
 pub mod project_user_services;
 pub mod session_services;
@@ -0,0 +1,574 @@
+use crate::models::{Concept, Connection, Project, User};
+use crate::plugins::{ChangeConceptValueExtra, ChangeConnectionValueExtra, Plugins};
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{concepts, connections};
+use crate::services::{
+    model_history_services, model_services, model_snapshot_services, permission_services,
+    plugin_services, plugins_data_services, project_services,
+};
+use crate::types::{
+    ConceptInBatchCreateType, ConceptInMoveType, ConceptInType, ConceptOutType,
+    ConnectionInBatchCreateType, ConnectionInChangeType, ConnectionOutType, ModelActionType,
+    ModelBatchIdInType, ModelBatchInType, ModelBatchOperationInType, ModelBatchOutType,
+};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Default)]
+struct ModelBatchState {
+    temporary_concept_ids: HashMap<String, i32>,
+    temporary_connection_ids: HashMap<String, i32>,
+    created_concept_ids: HashSet<i32>,
+    created_connection_ids: HashSet<i32>,
+    concept_ids: HashSet<i32>,
+    connection_ids: HashSet<i32>,
+    deleted_concept_ids: HashSet<i32>,
+    deleted_connection_ids: HashSet<i32>,
+}
+
+pub async fn apply_model_batch(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    project_id: i32,
+    model_batch_in: ModelBatchInType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelBatchOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    if model_batch_in.operations.is_empty() {
+        return validation_error!("model_batch_empty_error");
+    }
+    let (concept_ids, connection_ids) = get_existing_ids(conn, &model_batch_in.operations)?;
+    let (project, state, next_state) = conn.transaction(|conn| -> ServiceResult<_> {
+        model_snapshot_services::save_automatic_model_snapshot(
+            conn,
+            plugins,
+            user,
+            &project,
+            "batchModelEdit",
+        )?;
+        let previous_state = model_history_services::get_model_elements(
+            conn,
+            plugins,
+            &project,
+            &concept_ids,
+            &connection_ids,
+        )?;
+        let (project, state) = apply_operations(conn, plugins, project, model_batch_in.operations)?;
+        let next_state = model_history_services::get_model_elements(
+            conn,
+            plugins,
+            &project,
+            &sort_ids(&state.concept_ids),
+            &sort_ids(&state.connection_ids),
+        )?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "batchModelEdit",
+            previous_state,
+            next_state.clone(),
+        )?;
+        Ok((project, state, next_state))
+    })?;
+    let model_batch_out = ModelBatchOutType {
+        concepts: next_state.concepts,
+        connections: next_state.connections,
+        deleted_concept_ids: sort_ids(&state.deleted_concept_ids),
+        deleted_connection_ids: sort_ids(&state.deleted_connection_ids),
+        temporary_concept_ids: state.temporary_concept_ids,
+        temporary_connection_ids: state.temporary_connection_ids,
+    };
+    let model_action =
+        ModelActionType::new(&project, String::from("batchModelEdit"), model_batch_out);
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+fn apply_operations(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: Project,
+    operations_in: Vec<ModelBatchOperationInType>,
+) -> ServiceResult<(Project, ModelBatchState)> {
+    check_versions(conn, plugins, &project, &operations_in)?;
+    let plugin_names =
+        plugin_services::find_project_plugin_names(conn, project.id).to_service_result()?;
+    let mut state = ModelBatchState::default();
+    for operation_in in operations_in {
+        apply_operation(
+            conn,
+            plugins,
+            &project,
+            &plugin_names,
+            &mut state,
+            operation_in,
+        )?;
+    }
+    let project = project_services::update_project(conn, project.id, Utc::now())?;
+    Ok((project, state))
+}
+
+fn apply_operation(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    plugin_names: &[String],
+    state: &mut ModelBatchState,
+    operation_in: ModelBatchOperationInType,
+) -> ServiceResult<()> {
+    match operation_in {
+        ModelBatchOperationInType::CreateConcept { concept } => {
+            create_concept(conn, plugins, project, plugin_names, state, concept)
+        }
+        ModelBatchOperationInType::ChangeConcept {
+            concept_id,
+            concept: concept_in,
+        } => {
+            let concept_id = state.resolve_concept_id(&concept_id)?;
+            let value = plugins.change_concept_value_emitter.lock().unwrap().emit(
+                conn,
+                concept_in.value,
+                ChangeConceptValueExtra::new(project.clone(), concept_id),
+            )?;
+            model_services::check_concept_value(project, value)?;
+            diesel::update(concepts::table)
+                .filter(concepts::id.eq(concept_id))
+                .filter(concepts::project_id.eq(project.id))
+                .set((
+                    concepts::name.eq(concept_in.name),
+                    concepts::description.eq(concept_in.description),
+                    concepts::value.eq(value),
+                    concepts::x_position.eq(concept_in.x_position),
+                    concepts::y_position.eq(concept_in.y_position),
+                ))
+                .get_result::<Concept>(conn)
+                .to_service_result_find(String::from("concept_not_found_error"))?;
+            state.concept_ids.insert(concept_id);
+            Ok(())
+        }
+        ModelBatchOperationInType::MoveConcept {
+            concept_id,
+            concept: concept_in,
+        } => {
+            let concept_id = state.resolve_concept_id(&concept_id)?;
+            diesel::update(concepts::table)
+                .filter(concepts::id.eq(concept_id))
+                .filter(concepts::project_id.eq(project.id))
+                .set((
+                    concepts::x_position.eq(concept_in.x_position),
+                    concepts::y_position.eq(concept_in.y_position),
+                ))
+                .get_result::<Concept>(conn)
+                .to_service_result_find(String::from("concept_not_found_error"))?;
+            state.concept_ids.insert(concept_id);
+            Ok(())
+        }
+        ModelBatchOperationInType::DeleteConcept { concept_id, .. } => {
+            let concept_id = state.resolve_concept_id(&concept_id)?;
+            for connection_id in
+                model_history_services::find_concept_connection_ids(conn, &[concept_id])?
+            {
+                state.delete_connection(connection_id);
+            }
+            let deleted_number = diesel::delete(
+                concepts::table
+                    .filter(concepts::id.eq(concept_id))
+                    .filter(concepts::project_id.eq(project.id)),
+            )
+            .execute(conn)?;
+            if deleted_number == 0 {
+                return validation_error!("concept_not_found_error");
+            }
+            state.concept_ids.remove(&concept_id);
+            if !state.created_concept_ids.contains(&concept_id) {
+                state.deleted_concept_ids.insert(concept_id);
+            }
+            Ok(())
+        }
+        ModelBatchOperationInType::CreateConnection { connection } => {
+            create_connection(conn, plugins, project, plugin_names, state, connection)
+        }
+        ModelBatchOperationInType::ChangeConnection {
+            connection_id,
+            connection: connection_in,
+        } => {
+            let connection_id = state.resolve_connection_id(&connection_id)?;
+            let value = plugins
+                .change_connection_value_emitter
+                .lock()
+                .unwrap()
+                .emit(
+                    conn,
+                    connection_in.value,
+                    ChangeConnectionValueExtra::new(project.clone(), connection_id),
+                )?;
+            model_services::check_connection_value(project, value)?;
+            diesel::update(connections::table)
+                .filter(connections::id.eq(connection_id))
+                .filter(connections::project_id.eq(project.id))
+                .set((
+                    connections::description.eq(connection_in.description),
+                    connections::value.eq(value),
+                ))
+                .get_result::<Connection>(conn)
+                .to_service_result_find(String::from("connection_not_found_error"))?;
+            state.connection_ids.insert(connection_id);
+            Ok(())
+        }
+        ModelBatchOperationInType::DeleteConnection { connection_id, .. } => {
+            let connection_id = state.resolve_connection_id(&connection_id)?;
+            let deleted_number = diesel::delete(
+                connections::table
+                    .filter(connections::id.eq(connection_id))
+                    .filter(connections::project_id.eq(project.id)),
+            )
+            .execute(conn)?;
+            if deleted_number == 0 {
+                return validation_error!("connection_not_found_error");
+            }
+            state.delete_connection(connection_id);
+            Ok(())
+        }
+    }
+}
+
+fn create_concept(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    plugin_names: &[String],
+    state: &mut ModelBatchState,
+    concept_in: ConceptInBatchCreateType,
+) -> ServiceResult<()> {
+    model_services::check_concept_value(project, concept_in.value)?;
+    if let Some(plugins_data) = &concept_in.plugins_data {
+        plugins_data_services::check_concept_plugins_data(
+            project,
+            plugin_names,
+            concept_in.value,
+            plugins_data,
+        )?;
+    }
+    let concept = diesel::insert_into(concepts::table)
+        .values((
+            concepts::project_id.eq(project.id),
+            concepts::name.eq(concept_in.name),
+            concepts::description.eq(concept_in.description),
+            concepts::value.eq(concept_in.value),
+            concepts::x_position.eq(concept_in.x_position),
+            concepts::y_position.eq(concept_in.y_position),
+        ))
+        .get_result::<Concept>(conn)?;
+    let concept_id = concept.id;
+    plugins.add_concept_emitter.lock().unwrap().emit(
+        conn,
+        ConceptOutType::from(concept),
+        project.clone(),
+    )?;
+    if let Some(plugins_data) = &concept_in.plugins_data {
+        model_snapshot_services::write_concept_plugins_data(conn, concept_id, plugins_data)?;
+    }
+    if let Some(temporary_id) = concept_in.temporary_id {
+        if state.temporary_concept_ids.contains_key(&temporary_id) {
+            return validation_error!(
+                "model_batch_temporary_id_duplication_error",
+                temporary_id = &temporary_id
+            );
+        }
+        state.temporary_concept_ids.insert(temporary_id, concept_id);
+    }
+    state.created_concept_ids.insert(concept_id);
+    state.concept_ids.insert(concept_id);
+    Ok(())
+}
+
+fn create_connection(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    plugin_names: &[String],
+    state: &mut ModelBatchState,
+    connection_in: ConnectionInBatchCreateType,
+) -> ServiceResult<()> {
+    let source_id = state.resolve_concept_id(&connection_in.source_id)?;
+    let target_id = state.resolve_concept_id(&connection_in.target_id)?;
+    find_project_concept(conn, project.id, source_id)
+        .to_service_result_find(String::from("connection_source_concept_not_found_error"))?;
+    find_project_concept(conn, project.id, target_id)
+        .to_service_result_find(String::from("connection_target_concept_not_found_error"))?;
+    model_services::check_connection_value(project, connection_in.value)?;
+    if let Some(plugins_data) = &connection_in.plugins_data {
+        plugins_data_services::check_connection_plugins_data(
+            project,
+            plugin_names,
+            connection_in.value,
+            plugins_data,
+        )?;
+    }
+    let connection = diesel::insert_into(connections::table)
+        .values((
+            connections::project_id.eq(project.id),
+            connections::description.eq(connection_in.description),
+            connections::value.eq(connection_in.value),
+            connections::source_id.eq(source_id),
+            connections::target_id.eq(target_id),
+        ))
+        .get_result::<Connection>(conn)
+        .to_service_result_unique(String::from("connection_duplication_error"))?;
+    let connection_id = connection.id;
+    plugins.add_connection_emitter.lock().unwrap().emit(
+        conn,
+        ConnectionOutType::from(connection),
+        project.clone(),
+    )?;
+    if let Some(plugins_data) = &connection_in.plugins_data {
+        model_snapshot_services::write_connection_plugins_data(
+            conn,
+            project,
+            plugin_names,
+            connection_id,
+            plugins_data,
+        )?;
+    }
+    if let Some(temporary_id) = connection_in.temporary_id {
+        if state.temporary_connection_ids.contains_key(&temporary_id) {
+            return validation_error!(
+                "model_batch_temporary_id_duplication_error",
+                temporary_id = &temporary_id
+            );
+        }
+        state
+            .temporary_connection_ids
+            .insert(temporary_id, connection_id);
+    }
+    state.created_connection_ids.insert(connection_id);
+    state.connection_ids.insert(connection_id);
+    Ok(())
+}
+
+fn get_existing_ids(
+    conn: &mut PgConnection,
+    operations_in: &[ModelBatchOperationInType],
+) -> ServiceResult<(Vec<i32>, Vec<i32>)> {
+    let mut concept_ids = HashSet::new();
+    let mut deleted_concept_ids = vec![];
+    let mut connection_ids = HashSet::new();
+    for operation_in in operations_in {
+        match operation_in {
+            ModelBatchOperationInType::ChangeConcept {
+                concept_id: ModelBatchIdInType::Id(concept_id),
+                ..
+            }
+            | ModelBatchOperationInType::MoveConcept {
+                concept_id: ModelBatchIdInType::Id(concept_id),
+                ..
+            } => {
+                concept_ids.insert(*concept_id);
+            }
+            ModelBatchOperationInType::DeleteConcept {
+                concept_id: ModelBatchIdInType::Id(concept_id),
+                ..
+            } => {
+                concept_ids.insert(*concept_id);
+                deleted_concept_ids.push(*concept_id);
+            }
+            ModelBatchOperationInType::ChangeConnection {
+                connection_id: ModelBatchIdInType::Id(connection_id),
+                ..
+            }
+            | ModelBatchOperationInType::DeleteConnection {
+                connection_id: ModelBatchIdInType::Id(connection_id),
+                ..
+            } => {
+                connection_ids.insert(*connection_id);
+            }
+            _ => {}
+        }
+    }
+    // Connections are deleted together with their concepts
+    connection_ids.extend(model_history_services::find_concept_connection_ids(
+        conn,
+        &deleted_concept_ids,
+    )?);
+    Ok((sort_ids(&concept_ids), sort_ids(&connection_ids)))
+}
+
+// Versions are checked before any operation changes update times of the elements
+fn check_versions(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    operations_in: &[ModelBatchOperationInType],
+) -> ServiceResult<()> {
+    for operation_in in operations_in {
+        match operation_in {
+            ModelBatchOperationInType::ChangeConcept {
+                concept_id: ModelBatchIdInType::Id(concept_id),
+                concept: ConceptInType {
+                    expected_version, ..
+                },
+            }
+            | ModelBatchOperationInType::MoveConcept {
+                concept_id: ModelBatchIdInType::Id(concept_id),
+                concept:
+                    ConceptInMoveType {
+                        expected_version, ..
+                    },
+            }
+            | ModelBatchOperationInType::DeleteConcept {
+                concept_id: ModelBatchIdInType::Id(concept_id),
+                expected_version,
+            } => model_services::check_concept_version(
+                conn,
+                plugins,
+                project,
+                *concept_id,
+                *expected_version,
+            )?,
+            ModelBatchOperationInType::ChangeConnection {
+                connection_id: ModelBatchIdInType::Id(connection_id),
+                connection:
+                    ConnectionInChangeType {
+                        expected_version, ..
+                    },
+            }
+            | ModelBatchOperationInType::DeleteConnection {
+                connection_id: ModelBatchIdInType::Id(connection_id),
+                expected_version,
+            } => model_services::check_connection_version(
+                conn,
+                plugins,
+                project,
+                *connection_id,
+                *expected_version,
+            )?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn find_project_concept(
+    conn: &mut PgConnection,
+    project_id: i32,
+    concept_id: i32,
+) -> QueryResult<Concept> {
+    concepts::table
+        .filter(concepts::id.eq(concept_id))
+        .filter(concepts::project_id.eq(project_id))
+        .get_result::<Concept>(conn)
+}
+
+fn sort_ids(ids: &HashSet<i32>) -> Vec<i32> {
+    let mut ids = ids.iter().copied().collect::<Vec<_>>();
+    ids.sort();
+    ids
+}
+
+impl ModelBatchState {
+    fn resolve_concept_id(&self, concept_id: &ModelBatchIdInType) -> ServiceResult<i32> {
+        match concept_id {
+            ModelBatchIdInType::Id(concept_id) => Ok(*concept_id),
+            ModelBatchIdInType::TemporaryId(temporary_id) => {
+                match self.temporary_concept_ids.get(temporary_id) {
+                    Some(concept_id) => Ok(*concept_id),
+                    None => {
+                        let temporary_id = temporary_id.clone();
+                        validation_error!(
+                            "model_batch_temporary_id_not_found_error",
+                            temporary_id = &temporary_id
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    fn resolve_connection_id(&self, connection_id: &ModelBatchIdInType) -> ServiceResult<i32> {
+        match connection_id {
+            ModelBatchIdInType::Id(connection_id) => Ok(*connection_id),
+            ModelBatchIdInType::TemporaryId(temporary_id) => {
+                match self.temporary_connection_ids.get(temporary_id) {
+                    Some(connection_id) => Ok(*connection_id),
+                    None => {
+                        let temporary_id = temporary_id.clone();
+                        validation_error!(
+                            "model_batch_temporary_id_not_found_error",
+                            temporary_id = &temporary_id
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    fn delete_connection(&mut self, connection_id: i32) {
+        self.connection_ids.remove(&connection_id);
+        if !self.created_connection_ids.contains(&connection_id) {
+            self.deleted_connection_ids.insert(connection_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_state() -> ModelBatchState {
+        let mut state = ModelBatchState::default();
+        state.temporary_concept_ids.insert(String::from("a"), 10);
+        state.temporary_connection_ids.insert(String::from("a"), 20);
+        state.created_connection_ids.insert(20);
+        state.connection_ids.extend([1, 20]);
+        state
+    }
+
+    #[test]
+    fn resolve_ids() {
+        let state = get_state();
+        let temporary_id = |id: &str| ModelBatchIdInType::TemporaryId(id.to_owned());
+        assert_eq!(
+            state.resolve_concept_id(&ModelBatchIdInType::Id(1)).ok(),
+            Some(1)
+        );
+        assert_eq!(state.resolve_concept_id(&temporary_id("a")).ok(), Some(10));
+        assert!(state.resolve_concept_id(&temporary_id("b")).is_err());
+        assert_eq!(
+            state.resolve_connection_id(&ModelBatchIdInType::Id(1)).ok(),
+            Some(1)
+        );
+        assert_eq!(
+            state.resolve_connection_id(&temporary_id("a")).ok(),
+            Some(20)
+        );
+        assert!(state.resolve_connection_id(&temporary_id("b")).is_err());
+    }
+
+    #[test]
+    fn temporary_ids_are_separate_for_concepts_and_connections() {
+        let mut state = ModelBatchState::default();
+        state.temporary_concept_ids.insert(String::from("a"), 10);
+        let temporary_id = ModelBatchIdInType::TemporaryId(String::from("a"));
+        assert!(state.resolve_connection_id(&temporary_id).is_err());
+    }
+
+    #[test]
+    fn deleted_created_connections_are_not_reported() {
+        let mut state = get_state();
+        state.delete_connection(1);
+        state.delete_connection(20);
+        assert!(state.connection_ids.is_empty());
+        assert_eq!(sort_ids(&state.deleted_connection_ids), vec![1]);
+    }
+}
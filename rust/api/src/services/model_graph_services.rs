@@ -0,0 +1,794 @@
+use crate::models::User;
+use crate::plugins::adjustment::models::DynamicModelType;
+use crate::plugins::Plugins;
+use crate::response::ServiceResult;
+use crate::services::{model_import_services, model_services};
+use crate::storage::Storage;
+use crate::types::{
+    ConceptInImportType, ConnectionInImportType, ConstraintInImportType, FileOutType,
+    GraphFormatType, ModelActionType, ModelInGraphmlImportType, ModelInImportType, ModelOutType,
+};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use rocket::http::ContentType;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq)]
+enum AttributeType {
+    Boolean,
+    Double,
+    String,
+}
+
+struct Attribute {
+    name: String,
+    attribute_type: AttributeType,
+}
+
+struct Element {
+    id: String,
+    source_id: Option<String>,
+    target_id: Option<String>,
+    values: HashMap<String, String>,
+}
+
+pub fn export_model(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    format: GraphFormatType,
+) -> ServiceResult<FileOutType> {
+    let model = model_services::get_model(conn, plugins, user, project_id)?;
+    Ok(write_graph(&model, &model.project.name, format))
+}
+
+pub fn export_model_copy(
+    conn: &mut PgConnection,
+    user: &User,
+    model_copy_id: i32,
+    format: GraphFormatType,
+) -> ServiceResult<FileOutType> {
+    let model = model_services::get_model_copy(conn, user, model_copy_id)?;
+    let name = format!("{}_{}", model.project.name, model_copy_id);
+    Ok(write_graph(&model, &name, format))
+}
+
+pub async fn import_model_graphml(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &Storage,
+    user: &User,
+    project_id: i32,
+    model_in: ModelInGraphmlImportType<'_>,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelOutType>> {
+    let content = storage.read_file(model_in.file).await?;
+    let model_in = read_graphml(&content)?;
+    model_import_services::import_model(
+        conn,
+        plugins,
+        model_service,
+        user,
+        project_id,
+        model_in,
+        expected_version,
+    )
+    .await
+}
+
+fn write_graph(model: &ModelOutType, name: &str, format: GraphFormatType) -> FileOutType {
+    let node_attributes = get_attributes(
+        &[
+            ("description", AttributeType::String),
+            ("value", AttributeType::Double),
+            ("x", AttributeType::Double),
+            ("y", AttributeType::Double),
+        ],
+        model.concepts.iter().map(|concept| &concept.plugins_data),
+    );
+    let edge_attributes = get_attributes(
+        &[
+            ("description", AttributeType::String),
+            ("weight", AttributeType::Double),
+        ],
+        model
+            .connections
+            .iter()
+            .map(|connection| &connection.plugins_data),
+    );
+    let nodes = get_nodes(model);
+    let edges = get_edges(model);
+    let (content, extension, content_type) = match format {
+        GraphFormatType::Graphml => (
+            write_graphml(&node_attributes, &edge_attributes, &nodes, &edges),
+            "graphml",
+            ContentType::new("application", "graphml+xml"),
+        ),
+        GraphFormatType::Gexf => (
+            write_gexf(name, &node_attributes, &edge_attributes, &nodes, &edges),
+            "gexf",
+            ContentType::new("application", "gexf+xml"),
+        ),
+        GraphFormatType::Dot => (
+            write_dot(name, &node_attributes, &edge_attributes, &nodes, &edges),
+            "dot",
+            ContentType::new("text", "vnd.graphviz"),
+        ),
+    };
+    FileOutType {
+        name: format!("{}.{}", name, extension),
+        content_type,
+        content: content.into_bytes(),
+    }
+}
+
+fn get_attributes<'a>(
+    base_attributes: &[(&str, AttributeType)],
+    plugins_data: impl Iterator<Item = &'a Value>,
+) -> Vec<Attribute> {
+    let mut attributes = base_attributes
+        .iter()
+        .map(|(name, attribute_type)| Attribute {
+            name: String::from(*name),
+            attribute_type: *attribute_type,
+        })
+        .collect::<Vec<_>>();
+    for plugin_data in plugins_data {
+        for (name, value) in flatten_plugins_data(plugin_data) {
+            let attribute_type = match value {
+                Value::Bool(_) => AttributeType::Boolean,
+                Value::Number(_) => AttributeType::Double,
+                _ => AttributeType::String,
+            };
+            match attributes.iter_mut().find(|a| a.name == name) {
+                Some(attribute) if attribute.attribute_type != attribute_type => {
+                    attribute.attribute_type = AttributeType::String
+                }
+                Some(_) => {}
+                None => attributes.push(Attribute {
+                    name,
+                    attribute_type,
+                }),
+            }
+        }
+    }
+    attributes
+}
+
+fn flatten_plugins_data(plugins_data: &Value) -> Vec<(String, &Value)> {
+    let mut values = vec![];
+    if let Value::Object(plugins_data) = plugins_data {
+        for (plugin, data) in plugins_data {
+            if let Value::Object(data) = data {
+                for (field, value) in data {
+                    if !value.is_null() {
+                        values.push((format!("{}.{}", plugin, field), value));
+                    }
+                }
+            }
+        }
+    }
+    values
+}
+
+fn get_nodes(model: &ModelOutType) -> Vec<Element> {
+    model
+        .concepts
+        .iter()
+        .map(|concept| {
+            let mut values = get_plugin_values(&concept.plugins_data);
+            values.insert(String::from("label"), concept.name.clone());
+            values.insert(String::from("description"), concept.description.clone());
+            if let Some(value) = concept.value {
+                values.insert(String::from("value"), value.to_string());
+            }
+            values.insert(String::from("x"), concept.x_position.to_string());
+            values.insert(String::from("y"), concept.y_position.to_string());
+            Element {
+                id: get_node_id(concept.id),
+                source_id: None,
+                target_id: None,
+                values,
+            }
+        })
+        .collect()
+}
+
+fn get_edges(model: &ModelOutType) -> Vec<Element> {
+    model
+        .connections
+        .iter()
+        .map(|connection| {
+            let mut values = get_plugin_values(&connection.plugins_data);
+            values.insert(String::from("description"), connection.description.clone());
+            values.insert(String::from("weight"), connection.value.to_string());
+            Element {
+                id: format!("e{}", connection.id),
+                source_id: Some(get_node_id(connection.source_id)),
+                target_id: Some(get_node_id(connection.target_id)),
+                values,
+            }
+        })
+        .collect()
+}
+
+fn get_node_id(concept_id: i32) -> String {
+    format!("n{}", concept_id)
+}
+
+fn get_plugin_values(plugins_data: &Value) -> HashMap<String, String> {
+    flatten_plugins_data(plugins_data)
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            (name, value)
+        })
+        .collect()
+}
+
+fn write_graphml(
+    node_attributes: &[Attribute],
+    edge_attributes: &[Attribute],
+    nodes: &[Element],
+    edges: &[Element],
+) -> String {
+    let mut graphml = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\" ",
+        "xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ",
+        "xsi:schemaLocation=\"http://graphml.graphdrawing.org/xmlns ",
+        "http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd\">\n",
+        "  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n",
+    ));
+    for (prefix, domain, attributes) in [
+        ("d", "node", node_attributes),
+        ("e", "edge", edge_attributes),
+    ] {
+        for (index, attribute) in attributes.iter().enumerate() {
+            graphml.push_str(&format!(
+                "  <key id=\"{}{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"{}\"/>\n",
+                prefix,
+                index,
+                domain,
+                escape(&attribute.name),
+                get_xml_type(attribute.attribute_type)
+            ));
+        }
+    }
+    graphml.push_str("  <graph id=\"G\" edgedefault=\"directed\">\n");
+    for node in nodes {
+        graphml.push_str(&format!("    <node id=\"{}\">\n", node.id));
+        graphml.push_str(&format!(
+            "      <data key=\"label\">{}</data>\n",
+            escape(&node.values["label"])
+        ));
+        write_graphml_data(&mut graphml, "d", node_attributes, node);
+        graphml.push_str("    </node>\n");
+    }
+    for edge in edges {
+        graphml.push_str(&format!(
+            "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n",
+            edge.id,
+            edge.source_id.as_ref().unwrap(),
+            edge.target_id.as_ref().unwrap()
+        ));
+        write_graphml_data(&mut graphml, "e", edge_attributes, edge);
+        graphml.push_str("    </edge>\n");
+    }
+    graphml.push_str("  </graph>\n</graphml>\n");
+    graphml
+}
+
+fn write_graphml_data(
+    graphml: &mut String,
+    prefix: &str,
+    attributes: &[Attribute],
+    element: &Element,
+) -> () {
+    for (index, attribute) in attributes.iter().enumerate() {
+        if let Some(value) = element.values.get(&attribute.name) {
+            graphml.push_str(&format!(
+                "      <data key=\"{}{}\">{}</data>\n",
+                prefix,
+                index,
+                escape(value)
+            ));
+        }
+    }
+}
+
+fn write_gexf(
+    name: &str,
+    node_attributes: &[Attribute],
+    edge_attributes: &[Attribute],
+    nodes: &[Element],
+    edges: &[Element],
+) -> String {
+    let mut gexf = String::from(concat!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+        "<gexf xmlns=\"http://gexf.net/1.2\" xmlns:viz=\"http://gexf.net/1.2/viz\" ",
+        "version=\"1.2\">\n",
+    ));
+    gexf.push_str(&format!(
+        "  <meta>\n    <description>{}</description>\n  </meta>\n",
+        escape(name)
+    ));
+    gexf.push_str("  <graph defaultedgetype=\"directed\" mode=\"static\">\n");
+    for (class, attributes) in [("node", node_attributes), ("edge", edge_attributes)] {
+        gexf.push_str(&format!("    <attributes class=\"{}\">\n", class));
+        for (index, attribute) in attributes.iter().enumerate() {
+            gexf.push_str(&format!(
+                "      <attribute id=\"{}\" title=\"{}\" type=\"{}\"/>\n",
+                index,
+                escape(&attribute.name),
+                get_xml_type(attribute.attribute_type)
+            ));
+        }
+        gexf.push_str("    </attributes>\n");
+    }
+    gexf.push_str("    <nodes>\n");
+    for node in nodes {
+        gexf.push_str(&format!(
+            "      <node id=\"{}\" label=\"{}\">\n",
+            node.id,
+            escape(&node.values["label"])
+        ));
+        write_gexf_attvalues(&mut gexf, node_attributes, node);
+        gexf.push_str(&format!(
+            "        <viz:position x=\"{}\" y=\"{}\" z=\"0.0\"/>\n",
+            node.values["x"], node.values["y"]
+        ));
+        gexf.push_str("      </node>\n");
+    }
+    gexf.push_str("    </nodes>\n    <edges>\n");
+    for edge in edges {
+        gexf.push_str(&format!(
+            "      <edge id=\"{}\" source=\"{}\" target=\"{}\" weight=\"{}\">\n",
+            edge.id,
+            edge.source_id.as_ref().unwrap(),
+            edge.target_id.as_ref().unwrap(),
+            edge.values["weight"]
+        ));
+        write_gexf_attvalues(&mut gexf, edge_attributes, edge);
+        gexf.push_str("      </edge>\n");
+    }
+    gexf.push_str("    </edges>\n  </graph>\n</gexf>\n");
+    gexf
+}
+
+fn write_gexf_attvalues(gexf: &mut String, attributes: &[Attribute], element: &Element) -> () {
+    gexf.push_str("        <attvalues>\n");
+    for (index, attribute) in attributes.iter().enumerate() {
+        if let Some(value) = element.values.get(&attribute.name) {
+            gexf.push_str(&format!(
+                "          <attvalue for=\"{}\" value=\"{}\"/>\n",
+                index,
+                escape(value)
+            ));
+        }
+    }
+    gexf.push_str("        </attvalues>\n");
+}
+
+fn write_dot(
+    name: &str,
+    node_attributes: &[Attribute],
+    edge_attributes: &[Attribute],
+    nodes: &[Element],
+    edges: &[Element],
+) -> String {
+    let mut dot = format!("digraph {} {{\n", escape_dot(name));
+    for node in nodes {
+        let mut values = vec![
+            (String::from("label"), node.values["label"].clone()),
+            (
+                String::from("pos"),
+                format!(
+                    "{},{}!",
+                    node.values["x"],
+                    -node.values["y"].parse::<f64>().unwrap()
+                ),
+            ),
+        ];
+        values.extend(get_dot_values(node_attributes, node));
+        dot.push_str(&format!(
+            "  {} [{}];\n",
+            escape_dot(&node.id),
+            join_dot_values(values)
+        ));
+    }
+    for edge in edges {
+        let mut values = vec![(String::from("label"), edge.values["weight"].clone())];
+        values.extend(get_dot_values(edge_attributes, edge));
+        dot.push_str(&format!(
+            "  {} -> {} [{}];\n",
+            escape_dot(edge.source_id.as_ref().unwrap()),
+            escape_dot(edge.target_id.as_ref().unwrap()),
+            join_dot_values(values)
+        ));
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+fn get_dot_values(attributes: &[Attribute], element: &Element) -> Vec<(String, String)> {
+    attributes
+        .iter()
+        .filter(|attribute| !["x", "y"].contains(&attribute.name.as_str()))
+        .filter_map(|attribute| {
+            let value = element.values.get(&attribute.name)?;
+            // Graphviz treats `weight` as an integer layout hint, so the connection value is stored
+            // in `value` instead
+            let name = match attribute.name.as_str() {
+                "weight" => String::from("value"),
+                name => String::from(name),
+            };
+            Some((name, value.clone()))
+        })
+        .collect()
+}
+
+fn join_dot_values(values: Vec<(String, String)>) -> String {
+    values
+        .iter()
+        .map(|(name, value)| format!("{}={}", escape_dot(name), escape_dot(value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn escape_dot(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+fn get_xml_type(attribute_type: AttributeType) -> &'static str {
+    match attribute_type {
+        AttributeType::Boolean => "boolean",
+        AttributeType::Double => "double",
+        AttributeType::String => "string",
+    }
+}
+
+fn read_graphml(content: &[u8]) -> ServiceResult<ModelInImportType> {
+    let content = match std::str::from_utf8(content) {
+        Ok(content) => content,
+        Err(_) => return validation_error!("import_file_error", sheet = "graphml"),
+    };
+    match parse_graphml(content) {
+        Ok((nodes, edges)) => get_model_in(nodes, edges),
+        Err(_) => validation_error!("import_file_error", sheet = "graphml"),
+    }
+}
+
+fn parse_graphml(content: &str) -> quick_xml::Result<(Vec<Element>, Vec<Element>)> {
+    let mut reader = Reader::from_str(content);
+    let mut keys = HashMap::new();
+    let mut nodes = vec![];
+    let mut edges = vec![];
+    let mut element: Option<Element> = None;
+    let mut data: Option<(String, String)> = None;
+    loop {
+        match reader.read_event()? {
+            Event::Start(event) | Event::Empty(event) if event.local_name().as_ref() == b"key" => {
+                let attributes = get_xml_attributes(&event)?;
+                if let (Some(id), Some(name)) = (attributes.get("id"), attributes.get("attr.name"))
+                {
+                    keys.insert(id.clone(), name.clone());
+                }
+            }
+            Event::Start(event) if ["node", "edge"].contains(&get_local_name(&event).as_str()) => {
+                let attributes = get_xml_attributes(&event)?;
+                element = Some(Element {
+                    id: attributes.get("id").cloned().unwrap_or_default(),
+                    source_id: attributes.get("source").cloned(),
+                    target_id: attributes.get("target").cloned(),
+                    values: HashMap::new(),
+                });
+            }
+            Event::Empty(event) if ["node", "edge"].contains(&get_local_name(&event).as_str()) => {
+                let attributes = get_xml_attributes(&event)?;
+                let element = Element {
+                    id: attributes.get("id").cloned().unwrap_or_default(),
+                    source_id: attributes.get("source").cloned(),
+                    target_id: attributes.get("target").cloned(),
+                    values: HashMap::new(),
+                };
+                match element.source_id {
+                    Some(_) => edges.push(element),
+                    None => nodes.push(element),
+                }
+            }
+            Event::Start(event) if event.local_name().as_ref() == b"data" => {
+                let attributes = get_xml_attributes(&event)?;
+                data = attributes
+                    .get("key")
+                    .and_then(|key| keys.get(key))
+                    .map(|name| (name.clone(), String::new()));
+            }
+            Event::Text(event) => {
+                if let Some((_, value)) = &mut data {
+                    value.push_str(&event.unescape()?);
+                }
+            }
+            Event::CData(event) => {
+                if let Some((_, value)) = &mut data {
+                    value.push_str(&String::from_utf8_lossy(&event.into_inner()));
+                }
+            }
+            Event::End(event) if event.local_name().as_ref() == b"data" => {
+                if let (Some((name, value)), Some(element)) = (data.take(), &mut element) {
+                    element.values.insert(name, value.trim().to_owned());
+                }
+            }
+            Event::End(event) if event.local_name().as_ref() == b"node" => {
+                nodes.extend(element.take());
+            }
+            Event::End(event) if event.local_name().as_ref() == b"edge" => {
+                edges.extend(element.take());
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok((nodes, edges))
+}
+
+fn get_local_name(event: &BytesStart) -> String {
+    String::from_utf8_lossy(event.local_name().as_ref()).into_owned()
+}
+
+fn get_xml_attributes(event: &BytesStart) -> quick_xml::Result<HashMap<String, String>> {
+    let mut attributes = HashMap::new();
+    for attribute in event.attributes() {
+        let attribute = attribute?;
+        attributes.insert(
+            String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+            attribute.unescape_value()?.into_owned(),
+        );
+    }
+    Ok(attributes)
+}
+
+fn get_model_in(nodes: Vec<Element>, edges: Vec<Element>) -> ServiceResult<ModelInImportType> {
+    let mut model_in = ModelInImportType::default();
+    let mut names = HashMap::new();
+    for node in nodes {
+        let concept_in = get_concept_in(node.values.get("label").unwrap_or(&node.id), &node)?;
+        names.insert(node.id, concept_in.name.clone());
+        model_in.concepts.push(concept_in);
+    }
+    for edge in edges {
+        let get_name = |id: &Option<String>| {
+            let id = id.clone().unwrap_or_default();
+            match names.get(&id) {
+                Some(name) => Ok(name.clone()),
+                None => validation_error!("import_graph_node_not_found_error", node = &id),
+            }
+        };
+        let source_name = get_name(&edge.source_id)?;
+        let target_name = get_name(&edge.target_id)?;
+        let is_control = get_bool(&edge, "controlConnections.isControl")?;
+        let constraint = match is_control {
+            Some(true) => get_constraint(&edge, "connectionConstraints", true)?,
+            _ => None,
+        };
+        model_in.connections.push(ConnectionInImportType {
+            source_name,
+            target_name,
+            description: edge.values.get("description").cloned(),
+            value: get_number(&edge, "weight")?.unwrap_or(0.0),
+            is_control,
+            constraint,
+        });
+    }
+    Ok(model_in)
+}
+
+fn get_concept_in(name: &str, node: &Element) -> ServiceResult<ConceptInImportType> {
+    let is_control = get_bool(node, "controlConcepts.isControl")?;
+    let is_target = get_bool(node, "targetConcepts.isTarget")?;
+    let constraint = match (is_control, is_target) {
+        (Some(true), _) => get_constraint(node, "conceptConstraints", true)?,
+        (_, Some(true)) => get_constraint(node, "targetConcepts", false)?,
+        _ => None,
+    };
+    let dynamic_model_type = match node.values.get("adjustment.dynamicModelType") {
+        Some(value) if value.is_empty() => Some(None),
+        Some(value) => {
+            match serde_json::from_value::<DynamicModelType>(Value::String(value.clone())) {
+                Ok(dynamic_model_type) => Some(Some(dynamic_model_type)),
+                Err(_) => {
+                    let value = value.clone();
+                    return validation_error!(
+                        "import_graph_attribute_error",
+                        name = "adjustment.dynamicModelType",
+                        value = &value
+                    );
+                }
+            }
+        }
+        None => None,
+    };
+    Ok(ConceptInImportType {
+        name: name.to_owned(),
+        description: node.values.get("description").cloned(),
+        value: match node.values.get("value") {
+            Some(_) => Some(get_number(node, "value")?),
+            None => None,
+        },
+        x_position: get_number(node, "x")?,
+        y_position: get_number(node, "y")?,
+        is_control,
+        is_target,
+        constraint,
+        dynamic_model_type,
+    })
+}
+
+fn get_constraint(
+    element: &Element,
+    plugin: &str,
+    has_constraint: bool,
+) -> ServiceResult<Option<Option<ConstraintInImportType>>> {
+    if has_constraint {
+        match get_bool(element, &format!("{}.hasConstraint", plugin))? {
+            Some(true) => {}
+            Some(false) => return Ok(Some(None)),
+            None => return Ok(None),
+        }
+    }
+    let get_field = |field: &str| format!("{}.{}", plugin, field);
+    let constraint = match (
+        get_number(element, &get_field("minValue"))?,
+        get_bool(element, &get_field("includeMinValue"))?,
+        get_number(element, &get_field("maxValue"))?,
+        get_bool(element, &get_field("includeMaxValue"))?,
+    ) {
+        (Some(min_value), Some(include_min_value), Some(max_value), Some(include_max_value)) => {
+            Some(Some(ConstraintInImportType {
+                min_value,
+                include_min_value,
+                max_value,
+                include_max_value,
+            }))
+        }
+        _ => None,
+    };
+    Ok(constraint)
+}
+
+fn get_number(element: &Element, name: &str) -> ServiceResult<Option<f64>> {
+    match element.values.get(name) {
+        Some(value) if value.is_empty() => Ok(None),
+        Some(value) => match value.parse::<f64>() {
+            Ok(value) => Ok(Some(value)),
+            Err(_) => {
+                let (name, value) = (name.to_owned(), value.clone());
+                validation_error!("import_graph_attribute_error", name = &name, value = &value)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+fn get_bool(element: &Element, name: &str) -> ServiceResult<Option<bool>> {
+    match element.values.get(name).map(|value| value.as_str()) {
+        Some("") | None => Ok(None),
+        Some("true") | Some("1") => Ok(Some(true)),
+        Some("false") | Some("0") => Ok(Some(false)),
+        Some(value) => {
+            let (name, value) = (name.to_owned(), value.to_owned());
+            validation_error!("import_graph_attribute_error", name = &name, value = &value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAPHML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="d0" for="node" attr.name="label" attr.type="string"/>
+  <key id="d1" for="node" attr.name="value" attr.type="double"/>
+  <key id="d2" for="node" attr.name="controlConcepts.isControl" attr.type="boolean"/>
+  <key id="d3" for="node" attr.name="targetConcepts.isTarget" attr.type="boolean"/>
+  <key id="d4" for="node" attr.name="targetConcepts.minValue" attr.type="double"/>
+  <key id="d5" for="node" attr.name="targetConcepts.includeMinValue" attr.type="boolean"/>
+  <key id="d6" for="node" attr.name="targetConcepts.maxValue" attr.type="double"/>
+  <key id="d7" for="node" attr.name="targetConcepts.includeMaxValue" attr.type="boolean"/>
+  <key id="d8" for="edge" attr.name="weight" attr.type="double"/>
+  <key id="d9" for="edge" attr.name="description" attr.type="string"/>
+  <graph edgedefault="directed">
+    <node id="n1">
+      <data key="d0">Control &amp; input</data>
+      <data key="d1">0.5</data>
+      <data key="d2">true</data>
+    </node>
+    <node id="n2">
+      <data key="d0"><![CDATA[Target]]></data>
+      <data key="d3">1</data>
+      <data key="d4">0.6</data>
+      <data key="d5">true</data>
+      <data key="d6">0.9</data>
+      <data key="d7">false</data>
+    </node>
+    <node id="n3"/>
+    <edge source="n1" target="n2">
+      <data key="d8">-0.5</data>
+      <data key="d9">Influence</data>
+    </edge>
+    <edge source="n2" target="n3"/>
+  </graph>
+</graphml>
+"#;
+
+    #[test]
+    fn parse_graphml_model() {
+        let model_in = read_graphml(GRAPHML.as_bytes()).ok().unwrap();
+        assert_eq!(
+            model_in
+                .concepts
+                .iter()
+                .map(|concept_in| concept_in.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Control & input", "Target", "n3"]
+        );
+        assert_eq!(model_in.concepts[0].value, Some(Some(0.5)));
+        assert_eq!(model_in.concepts[0].is_control, Some(true));
+        assert_eq!(model_in.concepts[1].is_target, Some(true));
+        assert!(
+            model_in.concepts[1].constraint
+                == Some(Some(ConstraintInImportType {
+                    min_value: 0.6,
+                    include_min_value: true,
+                    max_value: 0.9,
+                    include_max_value: false,
+                }))
+        );
+        assert_eq!(model_in.concepts[2].value, None);
+        assert_eq!(model_in.connections.len(), 2);
+        assert_eq!(model_in.connections[0].source_name, "Control & input");
+        assert_eq!(model_in.connections[0].target_name, "Target");
+        assert_eq!(model_in.connections[0].value, -0.5);
+        assert_eq!(
+            model_in.connections[0].description.as_deref(),
+            Some("Influence")
+        );
+        assert_eq!(model_in.connections[1].value, 0.0);
+    }
+
+    #[test]
+    fn reject_malformed_graphml() {
+        // Invalid UTF-8
+        assert!(read_graphml(b"<graphml>\xFF</graphml>").is_err());
+        // Mismatched closing tag
+        assert!(read_graphml(b"<graphml><graph><node id=\"n1\"></graph></graphml>").is_err());
+        // Edge to a missing node
+        assert!(read_graphml(
+            br#"<graphml><graph><node id="n1"/><edge source="n1" target="n2"/></graph></graphml>"#
+        )
+        .is_err());
+        // Invalid attribute values
+        let graphml = GRAPHML.replace(">0.5<", ">high<");
+        assert!(read_graphml(graphml.as_bytes()).is_err());
+        let graphml = GRAPHML.replace(">1<", ">yes<");
+        assert!(read_graphml(graphml.as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,468 @@
+use crate::models::{Concept, Connection, ModelHistoryEntry, Project, User};
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{concepts, connections, model_history_entries};
+use crate::services::{
+    model_services, model_snapshot_services, permission_services, plugin_services, project_services,
+};
+use crate::types::{
+    ConceptOutType, ConnectionOutType, ModelActionType, ModelElementsChangeOutType,
+    ModelElementsType, ModelOutType, ProjectOutType,
+};
+use crate::web_socket::WebSocketModelService;
+use crate::{conflict_error, validation_error};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use std::collections::HashSet;
+
+pub async fn undo_model_action(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    project_id: i32,
+    force: bool,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelElementsChangeOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    let model_history_entry = model_history_entries::table
+        .filter(model_history_entries::project_id.eq(project_id))
+        .filter(model_history_entries::user_id.eq(user.id))
+        .filter(model_history_entries::is_undone.eq(false))
+        .order(model_history_entries::id.desc())
+        .first::<ModelHistoryEntry>(conn)
+        .optional()
+        .to_service_result()?;
+    let model_history_entry = match model_history_entry {
+        Some(model_history_entry) => model_history_entry,
+        None => return validation_error!("model_history_undo_empty_error"),
+    };
+    let (project, elements_change_out) =
+        apply_model_history_entry(conn, plugins, project, model_history_entry, true, force)?;
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("undoModelAction"),
+        elements_change_out,
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub async fn redo_model_action(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    project_id: i32,
+    force: bool,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelElementsChangeOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    let model_history_entry = model_history_entries::table
+        .filter(model_history_entries::project_id.eq(project_id))
+        .filter(model_history_entries::user_id.eq(user.id))
+        .filter(model_history_entries::is_undone.eq(true))
+        .order(model_history_entries::id.asc())
+        .first::<ModelHistoryEntry>(conn)
+        .optional()
+        .to_service_result()?;
+    let model_history_entry = match model_history_entry {
+        Some(model_history_entry) => model_history_entry,
+        None => return validation_error!("model_history_redo_empty_error"),
+    };
+    let (project, elements_change_out) =
+        apply_model_history_entry(conn, plugins, project, model_history_entry, false, force)?;
+    let model_action = ModelActionType::new(
+        &project,
+        String::from("redoModelAction"),
+        elements_change_out,
+    );
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub fn record_model_action(
+    conn: &mut PgConnection,
+    user: &User,
+    project: &Project,
+    name: &str,
+    previous_state: ModelElementsType,
+    next_state: ModelElementsType,
+) -> ServiceResult<()> {
+    conn.transaction(|conn| {
+        // A new action makes the undone actions of the user impossible to redo
+        diesel::delete(
+            model_history_entries::table
+                .filter(model_history_entries::project_id.eq(project.id))
+                .filter(model_history_entries::user_id.eq(user.id))
+                .filter(model_history_entries::is_undone.eq(true)),
+        )
+        .execute(conn)?;
+        diesel::insert_into(model_history_entries::table)
+            .values((
+                model_history_entries::project_id.eq(project.id),
+                model_history_entries::user_id.eq(user.id),
+                model_history_entries::name.eq(name),
+                model_history_entries::previous_state
+                    .eq(serde_json::to_value(previous_state).unwrap()),
+                model_history_entries::next_state.eq(serde_json::to_value(next_state).unwrap()),
+            ))
+            .execute(conn)?;
+        Ok(())
+    })
+}
+
+pub fn get_concept_elements(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    concept_id: i32,
+) -> ServiceResult<ModelElementsType> {
+    get_model_elements(conn, plugins, project, &[concept_id], &[])
+}
+
+pub fn get_concept_with_connections_elements(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    concept_id: i32,
+) -> ServiceResult<ModelElementsType> {
+    let connection_ids = find_concept_connection_ids(conn, &[concept_id])?;
+    get_model_elements(conn, plugins, project, &[concept_id], &connection_ids)
+}
+
+pub fn get_connection_elements(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    connection_id: i32,
+) -> ServiceResult<ModelElementsType> {
+    get_model_elements(conn, plugins, project, &[], &[connection_id])
+}
+
+pub fn get_model_elements(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    concept_ids: &[i32],
+    connection_ids: &[i32],
+) -> ServiceResult<ModelElementsType> {
+    let concepts = concepts::table
+        .filter(concepts::project_id.eq(project.id))
+        .filter(concepts::id.eq_any(concept_ids.to_vec()))
+        .order(concepts::id)
+        .get_results::<Concept>(conn)?
+        .into_iter()
+        .map(ConceptOutType::from)
+        .collect();
+    let connections = connections::table
+        .filter(connections::project_id.eq(project.id))
+        .filter(connections::id.eq_any(connection_ids.to_vec()))
+        .order(connections::id)
+        .get_results::<Connection>(conn)?
+        .into_iter()
+        .map(ConnectionOutType::from)
+        .collect();
+    let model_out = ModelOutType {
+        project: ProjectOutType::from_project(conn, project.clone())?,
+        concepts,
+        connections,
+    };
+    let model_out = plugins
+        .get_model_emitter
+        .lock()
+        .unwrap()
+        .emit(conn, model_out, ())?;
+    Ok(ModelElementsType {
+        concepts: model_out.concepts,
+        connections: model_out.connections,
+    })
+}
+
+fn apply_model_history_entry(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: Project,
+    model_history_entry: ModelHistoryEntry,
+    is_undone: bool,
+    force: bool,
+) -> ServiceResult<(Project, ModelElementsChangeOutType)> {
+    let previous_state =
+        serde_json::from_value::<ModelElementsType>(model_history_entry.previous_state).unwrap();
+    let next_state =
+        serde_json::from_value::<ModelElementsType>(model_history_entry.next_state).unwrap();
+    let (from_state, to_state) = if is_undone {
+        (next_state, previous_state)
+    } else {
+        (previous_state, next_state)
+    };
+    for concept_out in &to_state.concepts {
+        model_services::check_concept_value(&project, concept_out.value)?;
+    }
+    for connection_out in &to_state.connections {
+        model_services::check_connection_value(&project, connection_out.value)?;
+    }
+    let to_concept_ids = get_concept_ids(&to_state.concepts);
+    let to_connection_ids = get_connection_ids(&to_state.connections);
+    let mut deleted_concept_ids = get_concept_ids(&from_state.concepts)
+        .difference(&to_concept_ids)
+        .copied()
+        .collect::<Vec<_>>();
+    deleted_concept_ids.sort();
+    let mut deleted_connection_ids = get_connection_ids(&from_state.connections)
+        .difference(&to_connection_ids)
+        .copied()
+        .collect::<HashSet<_>>();
+    let mut concept_ids = deleted_concept_ids.clone();
+    concept_ids.extend(&to_concept_ids);
+    let mut connection_ids = deleted_connection_ids.iter().copied().collect::<Vec<_>>();
+    connection_ids.extend(&to_connection_ids);
+    // Conflicts are checked in the same transaction as the writes
+    let (project, deleted_connection_ids) = conn.transaction(|conn| -> ServiceResult<_> {
+        let current_state =
+            get_model_elements(conn, plugins, &project, &concept_ids, &connection_ids)?;
+        // Connections removed together with the concepts
+        let cascade_connection_ids = find_concept_connection_ids(conn, &deleted_concept_ids)?;
+        let is_conflict = !force
+            && (has_changes(&from_state, &current_state)
+                || cascade_connection_ids
+                    .iter()
+                    .any(|connection_id| !deleted_connection_ids.contains(connection_id)));
+        if is_conflict
+            || has_created_elements(&from_state, &to_state, &current_state)
+            || !has_connection_concepts(conn, &project, &to_state, &deleted_concept_ids)?
+        {
+            return conflict_error!(
+                "model_history_conflict_error",
+                serde_json::to_value(current_state).unwrap()
+            );
+        }
+        deleted_connection_ids.extend(cascade_connection_ids);
+        let mut deleted_connection_ids = deleted_connection_ids.into_iter().collect::<Vec<_>>();
+        deleted_connection_ids.sort();
+        let plugin_names =
+            plugin_services::find_project_plugin_names(conn, project.id).to_service_result()?;
+        let updated_at = Utc::now();
+        diesel::delete(
+            connections::table
+                .filter(connections::project_id.eq(project.id))
+                .filter(connections::id.eq_any(&deleted_connection_ids)),
+        )
+        .execute(conn)?;
+        diesel::delete(
+            concepts::table
+                .filter(concepts::project_id.eq(project.id))
+                .filter(concepts::id.eq_any(&deleted_concept_ids)),
+        )
+        .execute(conn)?;
+        for concept_out in &to_state.concepts {
+            let updated_number = diesel::update(concepts::table)
+                .filter(concepts::id.eq(concept_out.id))
+                .set((
+                    concepts::name.eq(&concept_out.name),
+                    concepts::description.eq(&concept_out.description),
+                    concepts::value.eq(concept_out.value),
+                    concepts::x_position.eq(concept_out.x_position),
+                    concepts::y_position.eq(concept_out.y_position),
+                    concepts::updated_at.eq(updated_at),
+                ))
+                .execute(conn)?;
+            if updated_number == 0 {
+                // Deleted concepts are restored with their original identifiers
+                diesel::insert_into(concepts::table)
+                    .values((
+                        concepts::id.eq(concept_out.id),
+                        concepts::project_id.eq(project.id),
+                        concepts::name.eq(&concept_out.name),
+                        concepts::description.eq(&concept_out.description),
+                        concepts::value.eq(concept_out.value),
+                        concepts::x_position.eq(concept_out.x_position),
+                        concepts::y_position.eq(concept_out.y_position),
+                        concepts::created_at.eq(concept_out.created_at),
+                        concepts::updated_at.eq(updated_at),
+                    ))
+                    .execute(conn)?;
+            }
+        }
+        for connection_out in &to_state.connections {
+            let updated_number = diesel::update(connections::table)
+                .filter(connections::id.eq(connection_out.id))
+                .set((
+                    connections::description.eq(&connection_out.description),
+                    connections::value.eq(connection_out.value),
+                    connections::updated_at.eq(updated_at),
+                ))
+                .execute(conn)?;
+            if updated_number == 0 {
+                diesel::insert_into(connections::table)
+                    .values((
+                        connections::id.eq(connection_out.id),
+                        connections::project_id.eq(project.id),
+                        connections::description.eq(&connection_out.description),
+                        connections::value.eq(connection_out.value),
+                        connections::source_id.eq(connection_out.source_id),
+                        connections::target_id.eq(connection_out.target_id),
+                        connections::created_at.eq(connection_out.created_at),
+                        connections::updated_at.eq(updated_at),
+                    ))
+                    .execute(conn)
+                    .to_service_result_unique(String::from("connection_duplication_error"))?;
+            }
+        }
+        let mut project = project;
+        for plugin_name in &plugin_names {
+            project = plugins.plugins[plugin_name]
+                .lock()
+                .unwrap()
+                .sync_model(conn, project)?;
+        }
+        for concept_out in &to_state.concepts {
+            model_snapshot_services::write_concept_plugins_data(
+                conn,
+                concept_out.id,
+                &concept_out.plugins_data,
+            )?;
+        }
+        for connection_out in &to_state.connections {
+            model_snapshot_services::write_connection_plugins_data(
+                conn,
+                &project,
+                &plugin_names,
+                connection_out.id,
+                &connection_out.plugins_data,
+            )?;
+        }
+        diesel::update(model_history_entries::table)
+            .filter(model_history_entries::id.eq(model_history_entry.id))
+            .set(model_history_entries::is_undone.eq(is_undone))
+            .execute(conn)?;
+        let project = project_services::update_project(conn, project.id, updated_at)?;
+        Ok((project, deleted_connection_ids))
+    })?;
+    let to_concept_ids = to_concept_ids.into_iter().collect::<Vec<_>>();
+    let to_connection_ids = to_connection_ids.into_iter().collect::<Vec<_>>();
+    let elements =
+        get_model_elements(conn, plugins, &project, &to_concept_ids, &to_connection_ids)?;
+    Ok((
+        project,
+        ModelElementsChangeOutType {
+            concepts: elements.concepts,
+            connections: elements.connections,
+            deleted_concept_ids,
+            deleted_connection_ids,
+        },
+    ))
+}
+
+fn has_changes(expected_state: &ModelElementsType, current_state: &ModelElementsType) -> bool {
+    let has_concept_changes =
+        expected_state.concepts.iter().any(|concept_out| {
+            match current_state
+                .concepts
+                .iter()
+                .find(|current_concept_out| current_concept_out.id == concept_out.id)
+            {
+                Some(current_concept_out) => {
+                    !model_snapshot_services::diff_fields(concept_out, current_concept_out)
+                        .is_empty()
+                }
+                None => true,
+            }
+        });
+    let has_connection_changes = expected_state.connections.iter().any(|connection_out| {
+        match current_state
+            .connections
+            .iter()
+            .find(|current_connection_out| current_connection_out.id == connection_out.id)
+        {
+            Some(current_connection_out) => {
+                !model_snapshot_services::diff_fields(connection_out, current_connection_out)
+                    .is_empty()
+            }
+            None => true,
+        }
+    });
+    has_concept_changes || has_connection_changes
+}
+
+fn has_created_elements(
+    from_state: &ModelElementsType,
+    to_state: &ModelElementsType,
+    current_state: &ModelElementsType,
+) -> bool {
+    let from_concept_ids = get_concept_ids(&from_state.concepts);
+    let from_connection_ids = get_connection_ids(&from_state.connections);
+    let current_concept_ids = get_concept_ids(&current_state.concepts);
+    let current_connection_ids = get_connection_ids(&current_state.connections);
+    to_state.concepts.iter().any(|concept_out| {
+        !from_concept_ids.contains(&concept_out.id) && current_concept_ids.contains(&concept_out.id)
+    }) || to_state.connections.iter().any(|connection_out| {
+        !from_connection_ids.contains(&connection_out.id)
+            && current_connection_ids.contains(&connection_out.id)
+    })
+}
+
+fn has_connection_concepts(
+    conn: &mut PgConnection,
+    project: &Project,
+    to_state: &ModelElementsType,
+    deleted_concept_ids: &[i32],
+) -> ServiceResult<bool> {
+    let to_concept_ids = get_concept_ids(&to_state.concepts);
+    let concept_ids = to_state
+        .connections
+        .iter()
+        .flat_map(|connection_out| [connection_out.source_id, connection_out.target_id])
+        .filter(|concept_id| !to_concept_ids.contains(concept_id))
+        .collect::<HashSet<_>>();
+    if concept_ids
+        .iter()
+        .any(|concept_id| deleted_concept_ids.contains(concept_id))
+    {
+        return Ok(false);
+    }
+    let concepts_number = concepts::table
+        .filter(concepts::project_id.eq(project.id))
+        .filter(concepts::id.eq_any(concept_ids.iter().copied().collect::<Vec<_>>()))
+        .count()
+        .get_result::<i64>(conn)?;
+    Ok(concepts_number as usize == concept_ids.len())
+}
+
+pub fn find_concept_connection_ids(
+    conn: &mut PgConnection,
+    concept_ids: &[i32],
+) -> ServiceResult<Vec<i32>> {
+    connections::table
+        .select(connections::id)
+        .filter(
+            connections::source_id
+                .eq_any(concept_ids.to_vec())
+                .or(connections::target_id.eq_any(concept_ids.to_vec())),
+        )
+        .order(connections::id)
+        .get_results::<i32>(conn)
+        .to_service_result()
+}
+
+fn get_concept_ids(concepts_out: &[ConceptOutType]) -> HashSet<i32> {
+    concepts_out
+        .iter()
+        .map(|concept_out| concept_out.id)
+        .collect()
+}
+
+fn get_connection_ids(connections_out: &[ConnectionOutType]) -> HashSet<i32> {
+    connections_out
+        .iter()
+        .map(|connection_out| connection_out.id)
+        .collect()
+}
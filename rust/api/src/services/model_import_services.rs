@@ -0,0 +1,455 @@
+use crate::models::{Concept, ConceptValueType, Connection, Project, User};
+use crate::plugins::control_concepts::services as control_concepts_services;
+use crate::plugins::control_connections::services as control_connections_services;
+use crate::plugins::target_concepts::services as target_concepts_services;
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{
+    concept_constraints, concept_dynamic_models, concepts, connection_constraints, connections,
+    control_concepts, control_connections, target_concepts,
+};
+use crate::services::{
+    model_services, model_snapshot_services, permission_services, plugin_services, project_services,
+};
+use crate::types::{
+    ConceptInImportType, ConnectionInImportType, ConstraintInImportType, ModelActionType,
+    ModelInImportType, ModelOutType,
+};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+
+const CONTROL_CONCEPTS_PLUGIN: &str = "Control Concepts";
+const TARGET_CONCEPTS_PLUGIN: &str = "Target Concepts";
+const CONCEPT_CONSTRAINTS_PLUGIN: &str = "Concept Constraints";
+const CONTROL_CONNECTIONS_PLUGIN: &str = "Control Connections";
+const CONNECTION_CONSTRAINTS_PLUGIN: &str = "Connection Constraints";
+const ADJUSTMENT_PLUGIN: &str = "Adjustment With Genetic Algorithms";
+const CONCEPT_SPACING: f64 = 120.0;
+
+pub async fn import_model(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    project_id: i32,
+    model_in: ModelInImportType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    let plugin_names =
+        plugin_services::find_project_plugin_names(conn, project_id).to_service_result()?;
+    check_model(&project, &plugin_names, &model_in)?;
+    model_snapshot_services::save_automatic_model_snapshot(
+        conn,
+        plugins,
+        user,
+        &project,
+        "importModel",
+    )?;
+    let project =
+        conn.transaction(|conn| write_model(conn, plugins, project, &plugin_names, &model_in))?;
+    let model_out = model_services::get_model(conn, plugins, user, project.id)?;
+    let model_action = ModelActionType::new(&project, String::from("importModel"), model_out);
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+fn check_model(
+    project: &Project,
+    plugin_names: &[String],
+    model_in: &ModelInImportType,
+) -> ServiceResult<()> {
+    let mut names = HashSet::new();
+    for concept_in in &model_in.concepts {
+        if !names.insert(concept_in.name.as_str()) {
+            let name = concept_in.name.clone();
+            return validation_error!("import_concept_duplication_error", name = &name);
+        }
+        if let Some(value) = concept_in.value {
+            model_services::check_concept_value(project, value)?;
+        }
+        if concept_in.is_control == Some(true) {
+            check_plugin_is_enabled(plugin_names, CONTROL_CONCEPTS_PLUGIN)?;
+        }
+        if concept_in.is_target == Some(true) {
+            check_plugin_is_enabled(plugin_names, TARGET_CONCEPTS_PLUGIN)?;
+        }
+        if concept_in.is_control == Some(true) && concept_in.is_target == Some(true) {
+            return validation_error!("concept_is_target_error");
+        }
+        if let Some(Some(constraint)) = &concept_in.constraint {
+            check_constraint(&concept_in.name, constraint, 0.0)?;
+        }
+        if concept_in.dynamic_model_type.is_some() {
+            check_plugin_is_enabled(plugin_names, ADJUSTMENT_PLUGIN)?;
+        }
+    }
+    let mut pairs = HashSet::new();
+    for connection_in in &model_in.connections {
+        for name in [&connection_in.source_name, &connection_in.target_name] {
+            if !names.contains(name.as_str()) {
+                let name = name.clone();
+                return validation_error!("import_concept_not_found_error", name = &name);
+            }
+        }
+        if !pairs.insert((
+            connection_in.source_name.as_str(),
+            connection_in.target_name.as_str(),
+        )) {
+            return validation_error!("connection_duplication_error");
+        }
+        model_services::check_connection_value(project, connection_in.value)?;
+        if connection_in.is_control == Some(true) {
+            check_plugin_is_enabled(plugin_names, CONTROL_CONNECTIONS_PLUGIN)?;
+        }
+        if let Some(Some(constraint)) = &connection_in.constraint {
+            check_constraint(&get_connection_name(connection_in), constraint, -1.0)?;
+        }
+    }
+    Ok(())
+}
+
+fn check_constraint(
+    name: &str,
+    constraint: &ConstraintInImportType,
+    min_value: f64,
+) -> ServiceResult<()> {
+    if constraint.min_value < min_value
+        || constraint.max_value > 1.0
+        || constraint.min_value > constraint.max_value
+    {
+        let name = name.to_owned();
+        let range = format!("[{}; {}]", constraint.min_value, constraint.max_value);
+        let allowed_range = format!("[{}; 1]", min_value);
+        return validation_error!(
+            "import_constraint_range_error",
+            name = &name,
+            range = &range,
+            allowed_range = &allowed_range
+        );
+    }
+    Ok(())
+}
+
+fn check_plugin_is_enabled(plugin_names: &[String], plugin_name: &str) -> ServiceResult<()> {
+    if !plugin_names.iter().any(|name| name == plugin_name) {
+        let plugin_name = plugin_name.to_owned();
+        return validation_error!(
+            "import_plugin_is_not_enabled_error",
+            plugin_name = &plugin_name
+        );
+    }
+    Ok(())
+}
+
+fn write_model(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    mut project: Project,
+    plugin_names: &[String],
+    model_in: &ModelInImportType,
+) -> ServiceResult<Project> {
+    let updated_at = Utc::now();
+    let concept_ids = write_concepts(conn, &project, &model_in.concepts, updated_at)?;
+    let connection_ids = write_connections(conn, &project, &concept_ids, model_in, updated_at)?;
+    for plugin_name in plugin_names {
+        project = plugins.plugins[plugin_name]
+            .lock()
+            .unwrap()
+            .sync_model(conn, project)?;
+    }
+    for concept_in in &model_in.concepts {
+        write_concept_plugins_data(
+            conn,
+            plugin_names,
+            concept_ids[&concept_in.name],
+            concept_in,
+        )?;
+    }
+    for (connection_in, connection_id) in model_in.connections.iter().zip(connection_ids) {
+        write_connection_plugins_data(conn, plugin_names, connection_id, connection_in)?;
+    }
+    project_services::update_project(conn, project.id, updated_at).to_service_result()
+}
+
+fn write_concepts(
+    conn: &mut PgConnection,
+    project: &Project,
+    concepts_in: &[ConceptInImportType],
+    updated_at: DateTime<Utc>,
+) -> ServiceResult<HashMap<String, i32>> {
+    let mut current_concepts = HashMap::new();
+    let mut stale_concept_ids = vec![];
+    for concept in model_services::find_project_concepts(conn, project.id)? {
+        if current_concepts.contains_key(&concept.name)
+            || !concepts_in.iter().any(|c| c.name == concept.name)
+        {
+            stale_concept_ids.push(concept.id);
+        } else {
+            current_concepts.insert(concept.name.clone(), concept);
+        }
+    }
+    diesel::delete(concepts::table.filter(concepts::id.eq_any(stale_concept_ids))).execute(conn)?;
+    let mut concept_ids = HashMap::new();
+    for (index, concept_in) in concepts_in.iter().enumerate() {
+        let concept = match current_concepts.get(&concept_in.name) {
+            Some(concept) => diesel::update(concepts::table)
+                .filter(concepts::id.eq(concept.id))
+                .set((
+                    concepts::description.eq(concept_in
+                        .description
+                        .as_ref()
+                        .unwrap_or(&concept.description)),
+                    concepts::value.eq(concept_in.value.unwrap_or(concept.value)),
+                    concepts::x_position.eq(concept_in.x_position.unwrap_or(concept.x_position)),
+                    concepts::y_position.eq(concept_in.y_position.unwrap_or(concept.y_position)),
+                    concepts::updated_at.eq(updated_at),
+                ))
+                .get_result::<Concept>(conn)?,
+            None => {
+                let (x_position, y_position) = get_default_position(index, concepts_in.len());
+                let value = match project.concept_value_type {
+                    ConceptValueType::None => None,
+                    ConceptValueType::FromZeroToOne => Some(0.0),
+                };
+                diesel::insert_into(concepts::table)
+                    .values((
+                        concepts::project_id.eq(project.id),
+                        concepts::name.eq(&concept_in.name),
+                        concepts::description
+                            .eq(concept_in.description.clone().unwrap_or_default()),
+                        concepts::value.eq(concept_in.value.unwrap_or(value)),
+                        concepts::x_position.eq(concept_in.x_position.unwrap_or(x_position)),
+                        concepts::y_position.eq(concept_in.y_position.unwrap_or(y_position)),
+                    ))
+                    .get_result::<Concept>(conn)?
+            }
+        };
+        concept_ids.insert(concept.name, concept.id);
+    }
+    Ok(concept_ids)
+}
+
+fn write_connections(
+    conn: &mut PgConnection,
+    project: &Project,
+    concept_ids: &HashMap<String, i32>,
+    model_in: &ModelInImportType,
+    updated_at: DateTime<Utc>,
+) -> ServiceResult<Vec<i32>> {
+    let connections_in = model_in
+        .connections
+        .iter()
+        .map(|connection_in| {
+            (
+                (
+                    concept_ids[&connection_in.source_name],
+                    concept_ids[&connection_in.target_name],
+                ),
+                connection_in,
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let mut current_connections = HashMap::new();
+    let mut stale_connection_ids = vec![];
+    for connection in model_services::find_project_connections(conn, project.id)? {
+        let key = (connection.source_id, connection.target_id);
+        if connections_in.contains_key(&key) {
+            current_connections.insert(key, connection);
+        } else {
+            stale_connection_ids.push(connection.id);
+        }
+    }
+    diesel::delete(connections::table.filter(connections::id.eq_any(stale_connection_ids)))
+        .execute(conn)?;
+    let mut connection_ids = vec![];
+    for connection_in in &model_in.connections {
+        let key = (
+            concept_ids[&connection_in.source_name],
+            concept_ids[&connection_in.target_name],
+        );
+        let connection = match current_connections.get(&key) {
+            Some(connection) => diesel::update(connections::table)
+                .filter(connections::id.eq(connection.id))
+                .set((
+                    connections::description.eq(connection_in
+                        .description
+                        .as_ref()
+                        .unwrap_or(&connection.description)),
+                    connections::value.eq(connection_in.value),
+                    connections::updated_at.eq(updated_at),
+                ))
+                .get_result::<Connection>(conn)?,
+            None => diesel::insert_into(connections::table)
+                .values((
+                    connections::project_id.eq(project.id),
+                    connections::description
+                        .eq(connection_in.description.clone().unwrap_or_default()),
+                    connections::value.eq(connection_in.value),
+                    connections::source_id.eq(key.0),
+                    connections::target_id.eq(key.1),
+                ))
+                .get_result::<Connection>(conn)?,
+        };
+        connection_ids.push(connection.id);
+    }
+    Ok(connection_ids)
+}
+
+fn write_concept_plugins_data(
+    conn: &mut PgConnection,
+    plugin_names: &[String],
+    concept_id: i32,
+    concept_in: &ConceptInImportType,
+) -> ServiceResult<()> {
+    let is_enabled = |plugin_name: &str| plugin_names.iter().any(|name| name == plugin_name);
+    if let (Some(dynamic_model_type), true) = (
+        concept_in.dynamic_model_type.clone(),
+        is_enabled(ADJUSTMENT_PLUGIN),
+    ) {
+        diesel::update(concept_dynamic_models::table)
+            .filter(concept_dynamic_models::concept_id.eq(concept_id))
+            .set(concept_dynamic_models::dynamic_model_type.eq(dynamic_model_type))
+            .execute(conn)?;
+    }
+    if let (Some(is_control), true) = (concept_in.is_control, is_enabled(CONTROL_CONCEPTS_PLUGIN)) {
+        diesel::update(control_concepts::table)
+            .filter(control_concepts::concept_id.eq(concept_id))
+            .set(control_concepts::is_control.eq(is_control))
+            .execute(conn)?;
+    }
+    if let (Some(is_target), true) = (concept_in.is_target, is_enabled(TARGET_CONCEPTS_PLUGIN)) {
+        diesel::update(target_concepts::table)
+            .filter(target_concepts::concept_id.eq(concept_id))
+            .set(target_concepts::is_target.eq(is_target))
+            .execute(conn)?;
+    }
+    let is_control = control_concepts_services::is_control(conn, concept_id)?;
+    let is_target = target_concepts_services::is_target(conn, concept_id)?;
+    if is_control && is_target {
+        return validation_error!("concept_is_target_error");
+    }
+    let constraint = concept_in.constraint.unwrap_or(None);
+    if (!is_control || concept_in.constraint == Some(None))
+        && is_enabled(CONCEPT_CONSTRAINTS_PLUGIN)
+    {
+        diesel::update(concept_constraints::table)
+            .filter(concept_constraints::concept_id.eq(concept_id))
+            .set(concept_constraints::has_constraint.eq(false))
+            .execute(conn)?;
+    }
+    if concept_in.constraint.is_none() {
+        return Ok(());
+    }
+    if is_control {
+        if let Some(constraint) = constraint {
+            check_plugin_is_enabled(plugin_names, CONCEPT_CONSTRAINTS_PLUGIN)?;
+            diesel::update(concept_constraints::table)
+                .filter(concept_constraints::concept_id.eq(concept_id))
+                .set((
+                    concept_constraints::has_constraint.eq(true),
+                    concept_constraints::min_value.eq(constraint.min_value),
+                    concept_constraints::include_min_value.eq(constraint.include_min_value),
+                    concept_constraints::max_value.eq(constraint.max_value),
+                    concept_constraints::include_max_value.eq(constraint.include_max_value),
+                ))
+                .execute(conn)?;
+        }
+    } else if is_target {
+        let constraint = constraint.unwrap_or_else(get_default_constraint);
+        diesel::update(target_concepts::table)
+            .filter(target_concepts::concept_id.eq(concept_id))
+            .set((
+                target_concepts::min_value.eq(constraint.min_value),
+                target_concepts::include_min_value.eq(constraint.include_min_value),
+                target_concepts::max_value.eq(constraint.max_value),
+                target_concepts::include_max_value.eq(constraint.include_max_value),
+            ))
+            .execute(conn)?;
+    } else if constraint.is_some() {
+        let name = concept_in.name.clone();
+        return validation_error!("import_constraint_concept_error", name = &name);
+    }
+    Ok(())
+}
+
+fn write_connection_plugins_data(
+    conn: &mut PgConnection,
+    plugin_names: &[String],
+    connection_id: i32,
+    connection_in: &ConnectionInImportType,
+) -> ServiceResult<()> {
+    let is_enabled = |plugin_name: &str| plugin_names.iter().any(|name| name == plugin_name);
+    if let (Some(is_control), true) = (
+        connection_in.is_control,
+        is_enabled(CONTROL_CONNECTIONS_PLUGIN),
+    ) {
+        diesel::update(control_connections::table)
+            .filter(control_connections::connection_id.eq(connection_id))
+            .set(control_connections::is_control.eq(is_control))
+            .execute(conn)?;
+    }
+    let is_control =
+        control_connections_services::find_control_connection_by_id(conn, connection_id)
+            .optional()?
+            .map_or(false, |control_connection| control_connection.is_control);
+    if (!is_control || connection_in.constraint == Some(None))
+        && is_enabled(CONNECTION_CONSTRAINTS_PLUGIN)
+    {
+        diesel::update(connection_constraints::table)
+            .filter(connection_constraints::connection_id.eq(connection_id))
+            .set(connection_constraints::has_constraint.eq(false))
+            .execute(conn)?;
+    }
+    let constraint = match connection_in.constraint {
+        Some(Some(constraint)) => constraint,
+        _ => return Ok(()),
+    };
+    if !is_control {
+        let name = get_connection_name(connection_in);
+        return validation_error!("import_constraint_connection_error", name = &name);
+    }
+    check_plugin_is_enabled(plugin_names, CONNECTION_CONSTRAINTS_PLUGIN)?;
+    diesel::update(connection_constraints::table)
+        .filter(connection_constraints::connection_id.eq(connection_id))
+        .set((
+            connection_constraints::has_constraint.eq(true),
+            connection_constraints::min_value.eq(constraint.min_value),
+            connection_constraints::include_min_value.eq(constraint.include_min_value),
+            connection_constraints::max_value.eq(constraint.max_value),
+            connection_constraints::include_max_value.eq(constraint.include_max_value),
+        ))
+        .execute(conn)?;
+    Ok(())
+}
+
+fn get_connection_name(connection_in: &ConnectionInImportType) -> String {
+    format!(
+        "{} -> {}",
+        connection_in.source_name, connection_in.target_name
+    )
+}
+
+fn get_default_constraint() -> ConstraintInImportType {
+    ConstraintInImportType {
+        min_value: 0.0,
+        include_min_value: true,
+        max_value: 1.0,
+        include_max_value: true,
+    }
+}
+
+fn get_default_position(index: usize, count: usize) -> (f64, f64) {
+    let radius = CONCEPT_SPACING * count as f64 / (2.0 * PI);
+    let angle = 2.0 * PI * index as f64 / count as f64;
+    (radius * angle.cos(), radius * angle.sin())
+}
@@ -0,0 +1,814 @@
+use crate::locale::Locale;
+use crate::models::{Project, User};
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::services::{
+    model_import_services, model_services, permission_services, project_services,
+};
+use crate::storage::Storage;
+use crate::types::{
+    ConceptInImportType, ConnectionInImportType, FileOutType, ModelImportReportOutType,
+    ModelInFcmapperImportType, ModelInImportType, ModelInMentalModelerImportType, ModelOutType,
+    UnmappedItemOutType, UnmappedItemType,
+};
+use crate::web_socket::WebSocketModelService;
+use crate::{internal_server_error, validation_error};
+use calamine::{open_workbook_from_rs, DataType, Reader, Xlsx};
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use rocket::http::ContentType;
+use rust_xlsxwriter::{Workbook, XlsxError};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::io::Cursor;
+
+const FCMAPPER_SHEET_NAME: &str = "Matrix";
+
+struct UnmappedItem {
+    unmapped_item_type: UnmappedItemType,
+    item: String,
+    attribute: String,
+    value: String,
+}
+
+pub fn export_model_mental_modeler(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+) -> ServiceResult<FileOutType> {
+    let model = model_services::get_model(conn, plugins, user, project_id)?;
+    let content = serde_json::to_vec_pretty(&get_mental_modeler_model(&model)).unwrap();
+    Ok(FileOutType {
+        name: format!("{}.mmp", model.project.name),
+        content_type: ContentType::JSON,
+        content,
+    })
+}
+
+pub fn export_model_fcmapper(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+) -> ServiceResult<FileOutType> {
+    let model = model_services::get_model(conn, plugins, user, project_id)?;
+    let content = match write_fcmapper_workbook(&model) {
+        Ok(content) => content,
+        Err(_) => return internal_server_error!(),
+    };
+    Ok(FileOutType {
+        name: format!("{}_fcmapper.xlsx", model.project.name),
+        content_type: ContentType::new(
+            "application",
+            "vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        ),
+        content,
+    })
+}
+
+pub async fn import_model_mental_modeler(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &Storage,
+    user: &User,
+    locale: &Locale,
+    project_id: i32,
+    model_in: ModelInMentalModelerImportType<'_>,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelImportReportOutType> {
+    let project = find_project(conn, user, project_id)?;
+    let content = storage.read_file(model_in.file).await?;
+    let source = match serde_json::from_slice::<Value>(&content) {
+        Ok(source) => source,
+        Err(_) => return validation_error!("import_file_error", sheet = "mmp"),
+    };
+    let (model_in, unmapped_items) = parse_mental_modeler_model(&project, &source);
+    import_model(
+        conn,
+        plugins,
+        model_service,
+        user,
+        locale,
+        project_id,
+        model_in,
+        unmapped_items,
+        expected_version,
+    )
+    .await
+}
+
+pub async fn import_model_fcmapper(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &Storage,
+    user: &User,
+    locale: &Locale,
+    project_id: i32,
+    model_in: ModelInFcmapperImportType<'_>,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelImportReportOutType> {
+    let project = find_project(conn, user, project_id)?;
+    let content = storage.read_file(model_in.file).await?;
+    let sheet = read_fcmapper_workbook(&content)?;
+    let (model_in, unmapped_items) = parse_fcmapper_matrix(&project, &sheet)?;
+    import_model(
+        conn,
+        plugins,
+        model_service,
+        user,
+        locale,
+        project_id,
+        model_in,
+        unmapped_items,
+        expected_version,
+    )
+    .await
+}
+
+fn find_project(conn: &mut PgConnection, user: &User, project_id: i32) -> ServiceResult<Project> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    Ok(project)
+}
+
+async fn import_model(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    locale: &Locale,
+    project_id: i32,
+    model_in: ModelInImportType,
+    unmapped_items: Vec<UnmappedItem>,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelImportReportOutType> {
+    let model_action = model_import_services::import_model(
+        conn,
+        plugins,
+        model_service,
+        user,
+        project_id,
+        model_in,
+        expected_version,
+    )
+    .await?;
+    let locale = locale.get_locale();
+    Ok(ModelImportReportOutType {
+        model_action,
+        unmapped_items: unmapped_items
+            .into_iter()
+            .map(|unmapped_item| unmapped_item.into_out_type(&locale))
+            .collect(),
+    })
+}
+
+fn get_mental_modeler_model(model: &ModelOutType) -> Value {
+    json!({
+        "info": {
+            "name": model.project.name,
+            "description": model.project.description,
+            "author": model.project.creator.username,
+        },
+        "concepts": model
+            .concepts
+            .iter()
+            .map(|concept| json!({
+                "id": concept.id.to_string(),
+                "text": concept.name,
+                "notes": concept.description,
+                "units": "",
+                "group": "-1",
+                "preferredState": 0,
+                "x": concept.x_position.to_string(),
+                "y": concept.y_position.to_string(),
+            }))
+            .collect::<Vec<_>>(),
+        "relationships": model
+            .connections
+            .iter()
+            .map(|connection| json!({
+                "id": connection.id.to_string(),
+                "sourceID": connection.source_id.to_string(),
+                "targetID": connection.target_id.to_string(),
+                "influence": connection.value.to_string(),
+                "confidence": 0,
+                "notes": connection.description,
+                "group": "-1",
+            }))
+            .collect::<Vec<_>>(),
+        "groups": [],
+    })
+}
+
+fn parse_mental_modeler_model(
+    project: &Project,
+    source: &Value,
+) -> (ModelInImportType, Vec<UnmappedItem>) {
+    let mut model_in = ModelInImportType::default();
+    let mut unmapped_items = vec![];
+    if get_array(source, "groups")
+        .iter()
+        .any(|group| !group.is_null())
+    {
+        unmapped_items.push(UnmappedItem::new(
+            UnmappedItemType::UnsupportedAttribute,
+            String::from("model"),
+            "groups",
+            source.get("groups"),
+        ));
+    }
+    let mut names = HashMap::new();
+    for concept in get_array(source, "concepts") {
+        let id = get_string(concept.get("id")).unwrap_or_default();
+        let name = match get_string(concept.get("text")) {
+            Some(name) if !name.trim().is_empty() => name.trim().to_owned(),
+            _ => format!("Concept {}", id),
+        };
+        if names.values().any(|n| n == &name) {
+            unmapped_items.push(UnmappedItem::new(
+                UnmappedItemType::DuplicateItem,
+                name,
+                "text",
+                concept.get("text"),
+            ));
+            continue;
+        }
+        for attribute in ["units", "preferredState"] {
+            if !is_default_value(concept.get(attribute)) {
+                unmapped_items.push(UnmappedItem::new(
+                    UnmappedItemType::UnsupportedAttribute,
+                    name.clone(),
+                    attribute,
+                    concept.get(attribute),
+                ));
+            }
+        }
+        let mut get_position = |attribute: &str| match concept.get(attribute) {
+            Some(value) if !value.is_null() => {
+                let position = get_number(Some(value));
+                if position.is_none() {
+                    unmapped_items.push(UnmappedItem::new(
+                        UnmappedItemType::InvalidValue,
+                        name.clone(),
+                        attribute,
+                        Some(value),
+                    ));
+                }
+                position
+            }
+            _ => None,
+        };
+        let (x_position, y_position) = (get_position("x"), get_position("y"));
+        model_in.concepts.push(ConceptInImportType {
+            name: name.clone(),
+            description: get_string(concept.get("notes")),
+            x_position,
+            y_position,
+            ..Default::default()
+        });
+        names.insert(id, name);
+    }
+    let mut pairs = HashSet::new();
+    for relationship in get_array(source, "relationships") {
+        let source_id = get_string(relationship.get("sourceID")).unwrap_or_default();
+        let target_id = get_string(relationship.get("targetID")).unwrap_or_default();
+        let item = format!("{} -> {}", source_id, target_id);
+        let (source_name, target_name) = match (names.get(&source_id), names.get(&target_id)) {
+            (Some(source_name), Some(target_name)) => (source_name.clone(), target_name.clone()),
+            (None, _) => {
+                unmapped_items.push(UnmappedItem::new(
+                    UnmappedItemType::ConceptNotFound,
+                    item,
+                    "sourceID",
+                    relationship.get("sourceID"),
+                ));
+                continue;
+            }
+            (_, None) => {
+                unmapped_items.push(UnmappedItem::new(
+                    UnmappedItemType::ConceptNotFound,
+                    item,
+                    "targetID",
+                    relationship.get("targetID"),
+                ));
+                continue;
+            }
+        };
+        let item = format!("{} -> {}", source_name, target_name);
+        let value = match get_influence(relationship.get("influence")) {
+            Some(value) => value,
+            None => {
+                unmapped_items.push(UnmappedItem::new(
+                    UnmappedItemType::InvalidValue,
+                    item,
+                    "influence",
+                    relationship.get("influence"),
+                ));
+                continue;
+            }
+        };
+        if model_services::check_connection_value(project, value).is_err() {
+            unmapped_items.push(UnmappedItem::new(
+                UnmappedItemType::UnsupportedValue,
+                item,
+                "influence",
+                relationship.get("influence"),
+            ));
+            continue;
+        }
+        if !pairs.insert((source_name.clone(), target_name.clone())) {
+            unmapped_items.push(UnmappedItem::new(
+                UnmappedItemType::DuplicateItem,
+                item,
+                "influence",
+                relationship.get("influence"),
+            ));
+            continue;
+        }
+        if !is_default_value(relationship.get("confidence")) {
+            unmapped_items.push(UnmappedItem::new(
+                UnmappedItemType::UnsupportedAttribute,
+                item.clone(),
+                "confidence",
+                relationship.get("confidence"),
+            ));
+        }
+        model_in.connections.push(ConnectionInImportType {
+            source_name,
+            target_name,
+            description: get_string(relationship.get("notes")),
+            value,
+            ..Default::default()
+        });
+    }
+    (model_in, unmapped_items)
+}
+
+fn get_array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    match value.get(key) {
+        Some(Value::Array(values)) => values,
+        _ => &[],
+    }
+}
+
+fn get_string(value: Option<&Value>) -> Option<String> {
+    match value {
+        Some(Value::String(value)) => Some(value.clone()),
+        Some(Value::Number(value)) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+fn get_number(value: Option<&Value>) -> Option<f64> {
+    match value {
+        Some(Value::Number(value)) => value.as_f64(),
+        Some(Value::String(value)) => value.trim().replace(',', ".").parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+fn get_influence(value: Option<&Value>) -> Option<f64> {
+    // Mental Modeler stores qualitative influences as high, medium and low with a sign
+    let qualitative_value = match get_string(value)?.trim().to_uppercase().as_str() {
+        "H+" => Some(0.75),
+        "M+" => Some(0.5),
+        "L+" => Some(0.25),
+        "L-" => Some(-0.25),
+        "M-" => Some(-0.5),
+        "H-" => Some(-0.75),
+        _ => None,
+    };
+    qualitative_value.or_else(|| get_number(value))
+}
+
+fn is_default_value(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => true,
+        Some(Value::String(value)) => value.trim().is_empty() || value.trim() == "0",
+        Some(Value::Number(value)) => value.as_f64() == Some(0.0),
+        _ => false,
+    }
+}
+
+fn write_fcmapper_workbook(model: &ModelOutType) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    worksheet.set_name(FCMAPPER_SHEET_NAME)?;
+    for (index, concept) in model.concepts.iter().enumerate() {
+        let index = index as u32 + 1;
+        worksheet.write_string(0, index as u16, &concept.name)?;
+        worksheet.write_string(index, 0, &concept.name)?;
+    }
+    for (row_index, source) in model.concepts.iter().enumerate() {
+        for (column_index, target) in model.concepts.iter().enumerate() {
+            let value = model
+                .connections
+                .iter()
+                .find(|c| c.source_id == source.id && c.target_id == target.id)
+                .map_or(0.0, |connection| connection.value);
+            worksheet.write_number(row_index as u32 + 1, column_index as u16 + 1, value)?;
+        }
+    }
+    workbook.save_to_buffer()
+}
+
+fn read_fcmapper_workbook(content: &[u8]) -> ServiceResult<Vec<Vec<DataType>>> {
+    let mut workbook = match open_workbook_from_rs::<Xlsx<_>, _>(Cursor::new(content)) {
+        Ok(workbook) => workbook,
+        Err(_) => return validation_error!("import_file_error", sheet = "xlsx"),
+    };
+    let sheet_names = workbook.sheet_names().to_owned();
+    let sheet_name = match sheet_names
+        .iter()
+        .find(|name| name.trim().to_lowercase() == FCMAPPER_SHEET_NAME.to_lowercase())
+        .or_else(|| sheet_names.first())
+    {
+        Some(sheet_name) => sheet_name,
+        None => return validation_error!("import_sheet_not_found_error", sheet = "Matrix"),
+    };
+    match workbook.worksheet_range(sheet_name) {
+        Some(Ok(range)) => Ok(range.rows().map(|row| row.to_vec()).collect()),
+        _ => validation_error!("import_file_error", sheet = "xlsx"),
+    }
+}
+
+fn parse_fcmapper_matrix(
+    project: &Project,
+    sheet: &[Vec<DataType>],
+) -> ServiceResult<(ModelInImportType, Vec<UnmappedItem>)> {
+    let get_name = |cell: Option<&DataType>| match cell {
+        Some(DataType::Empty) | None => String::new(),
+        Some(cell) => cell.to_string().trim().to_owned(),
+    };
+    let mut names = match sheet.first() {
+        Some(header) => header
+            .iter()
+            .skip(1)
+            .map(|cell| get_name(Some(cell)))
+            .collect::<Vec<_>>(),
+        None => return validation_error!("import_sheet_not_found_error", sheet = "Matrix"),
+    };
+    while names.last().map_or(false, |name| name.is_empty()) {
+        names.pop();
+    }
+    let rows = sheet
+        .iter()
+        .skip(1)
+        .filter(|row| !get_name(row.first()).is_empty())
+        .collect::<Vec<_>>();
+    if rows.len() != names.len()
+        || rows
+            .iter()
+            .zip(names.iter())
+            .any(|(row, name)| &get_name(row.first()) != name || name.is_empty())
+    {
+        return validation_error!("import_matrix_headers_error");
+    }
+    let mut model_in = ModelInImportType::default();
+    let mut unmapped_items = vec![];
+    for name in &names {
+        model_in.concepts.push(ConceptInImportType {
+            name: name.to_owned(),
+            ..Default::default()
+        });
+    }
+    for (row, source_name) in rows.iter().zip(names.iter()) {
+        for (index, target_name) in names.iter().enumerate() {
+            let item = format!("{} -> {}", source_name, target_name);
+            let value = match row.get(index + 1) {
+                Some(DataType::Float(value)) => *value,
+                Some(DataType::Int(value)) => *value as f64,
+                Some(DataType::String(value)) if value.trim().is_empty() => continue,
+                Some(DataType::String(value)) => {
+                    match value.trim().replace(',', ".").parse::<f64>() {
+                        Ok(value) => value,
+                        Err(_) => {
+                            unmapped_items.push(UnmappedItem::new(
+                                UnmappedItemType::InvalidValue,
+                                item,
+                                "weight",
+                                Some(&Value::String(value.clone())),
+                            ));
+                            continue;
+                        }
+                    }
+                }
+                Some(DataType::Empty) | None => continue,
+                Some(cell) => {
+                    unmapped_items.push(UnmappedItem::new(
+                        UnmappedItemType::InvalidValue,
+                        item,
+                        "weight",
+                        Some(&Value::String(cell.to_string())),
+                    ));
+                    continue;
+                }
+            };
+            // FCMapper uses zero weights for absent connections
+            if value == 0.0 {
+                continue;
+            }
+            if model_services::check_connection_value(project, value).is_err() {
+                unmapped_items.push(UnmappedItem::new(
+                    UnmappedItemType::UnsupportedValue,
+                    item,
+                    "weight",
+                    Some(&json!(value)),
+                ));
+                continue;
+            }
+            model_in.connections.push(ConnectionInImportType {
+                source_name: source_name.to_owned(),
+                target_name: target_name.to_owned(),
+                value,
+                ..Default::default()
+            });
+        }
+    }
+    Ok((model_in, unmapped_items))
+}
+
+impl UnmappedItem {
+    fn new(
+        unmapped_item_type: UnmappedItemType,
+        item: String,
+        attribute: &str,
+        value: Option<&Value>,
+    ) -> Self {
+        let value = match value {
+            Some(Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        };
+        Self {
+            unmapped_item_type,
+            item,
+            attribute: attribute.to_owned(),
+            value,
+        }
+    }
+    fn into_out_type(self, locale: &str) -> UnmappedItemOutType {
+        let (item, attribute, value) = (&self.item, &self.attribute, &self.value);
+        let message = match self.unmapped_item_type {
+            UnmappedItemType::UnsupportedAttribute => t!(
+                "unsupported_attribute_unmapped",
+                locale = locale,
+                item = item,
+                attribute = attribute
+            ),
+            UnmappedItemType::UnsupportedValue => t!(
+                "unsupported_value_unmapped",
+                locale = locale,
+                item = item,
+                value = value
+            ),
+            UnmappedItemType::InvalidValue => t!(
+                "invalid_value_unmapped",
+                locale = locale,
+                item = item,
+                attribute = attribute,
+                value = value
+            ),
+            UnmappedItemType::ConceptNotFound => t!(
+                "concept_not_found_unmapped",
+                locale = locale,
+                item = item,
+                value = value
+            ),
+            UnmappedItemType::DuplicateItem => {
+                t!("duplicate_item_unmapped", locale = locale, item = item)
+            }
+        };
+        UnmappedItemOutType {
+            unmapped_item_type: self.unmapped_item_type,
+            message,
+            item: self.item,
+            attribute: self.attribute,
+            value: self.value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ConceptValueType, ConnectionValueType};
+
+    fn get_project() -> Project {
+        Project {
+            id: 1,
+            name: String::from("Project"),
+            description: String::new(),
+            is_public: false,
+            is_archived: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            concept_value_type: ConceptValueType::FromZeroToOne,
+            connection_value_type: ConnectionValueType::FromMinusOneToOne,
+        }
+    }
+
+    fn get_names(model_in: &ModelInImportType) -> Vec<&str> {
+        model_in
+            .concepts
+            .iter()
+            .map(|concept_in| concept_in.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn parse_mental_modeler() {
+        let source = json!({
+            "concepts": [
+                {"id": "1", "text": " A ", "notes": "Note", "x": "10", "y": 20},
+                {"id": "2", "text": "B", "units": "kg", "x": "left"},
+                {"id": "3", "text": ""},
+                {"id": "4", "text": "A"}
+            ],
+            "relationships": [
+                {"sourceID": "1", "targetID": "2", "influence": "H+", "confidence": 1},
+                {"sourceID": "2", "targetID": "3", "influence": "-0,3"},
+                {"sourceID": "1", "targetID": "2", "influence": "0.5"},
+                {"sourceID": "3", "targetID": "1", "influence": "high"},
+                {"sourceID": "3", "targetID": "2", "influence": 2},
+                {"sourceID": "1", "targetID": "4", "influence": "L-"}
+            ],
+            "groups": [null]
+        });
+        let (model_in, unmapped_items) = parse_mental_modeler_model(&get_project(), &source);
+        assert_eq!(get_names(&model_in), vec!["A", "B", "Concept 3"]);
+        assert_eq!(model_in.concepts[0].description.as_deref(), Some("Note"));
+        assert_eq!(model_in.concepts[0].x_position, Some(10.0));
+        assert_eq!(model_in.concepts[0].y_position, Some(20.0));
+        assert_eq!(model_in.concepts[1].x_position, None);
+        assert_eq!(model_in.connections.len(), 2);
+        assert_eq!(model_in.connections[0].target_name, "B");
+        assert_eq!(model_in.connections[0].value, 0.75);
+        assert_eq!(model_in.connections[1].source_name, "B");
+        assert_eq!(model_in.connections[1].value, -0.3);
+        let unmapped_items = unmapped_items
+            .iter()
+            .map(|unmapped_item| {
+                (
+                    unmapped_item.item.as_str(),
+                    unmapped_item.attribute.as_str(),
+                    unmapped_item.unmapped_item_type.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(unmapped_items.len(), 8);
+        assert!(matches!(
+            unmapped_items[0],
+            ("B", "units", UnmappedItemType::UnsupportedAttribute)
+        ));
+        assert!(matches!(
+            unmapped_items[1],
+            ("B", "x", UnmappedItemType::InvalidValue)
+        ));
+        assert!(matches!(
+            unmapped_items[2],
+            ("A", "text", UnmappedItemType::DuplicateItem)
+        ));
+        assert!(matches!(
+            unmapped_items[3],
+            (
+                "A -> B",
+                "confidence",
+                UnmappedItemType::UnsupportedAttribute
+            )
+        ));
+        assert!(matches!(
+            unmapped_items[4],
+            ("A -> B", "influence", UnmappedItemType::DuplicateItem)
+        ));
+        assert!(matches!(
+            unmapped_items[5],
+            (
+                "Concept 3 -> A",
+                "influence",
+                UnmappedItemType::InvalidValue
+            )
+        ));
+        assert!(matches!(
+            unmapped_items[6],
+            (
+                "Concept 3 -> B",
+                "influence",
+                UnmappedItemType::UnsupportedValue
+            )
+        ));
+        assert!(matches!(
+            unmapped_items[7],
+            ("1 -> 4", "targetID", UnmappedItemType::ConceptNotFound)
+        ));
+    }
+
+    #[test]
+    fn parse_malformed_mental_modeler() {
+        for source in [
+            json!([1, 2, 3]),
+            json!({"concepts": "A", "relationships": {}}),
+            json!({"concepts": [null, 1], "relationships": [null]}),
+        ] {
+            let (model_in, _) = parse_mental_modeler_model(&get_project(), &source);
+            assert!(model_in.connections.is_empty());
+            assert!(model_in
+                .concepts
+                .iter()
+                .all(|concept_in| concept_in.name.starts_with("Concept")));
+        }
+    }
+
+    #[test]
+    fn parse_fcmapper() {
+        let string = |value: &str| DataType::String(value.to_owned());
+        let sheet = vec![
+            vec![DataType::Empty, string("A"), string("B"), string("C")],
+            vec![
+                string("A"),
+                DataType::Float(0.0),
+                DataType::Float(0.5),
+                string("x"),
+            ],
+            vec![
+                string("B"),
+                DataType::Int(-1),
+                DataType::Empty,
+                DataType::Float(1.5),
+            ],
+            vec![
+                string("C"),
+                string("0,25"),
+                DataType::Bool(true),
+                string(" "),
+            ],
+            vec![DataType::Empty, DataType::Empty],
+        ];
+        let (model_in, unmapped_items) =
+            parse_fcmapper_matrix(&get_project(), &sheet).ok().unwrap();
+        assert_eq!(get_names(&model_in), vec!["A", "B", "C"]);
+        let connections = model_in
+            .connections
+            .iter()
+            .map(|connection_in| {
+                (
+                    connection_in.source_name.as_str(),
+                    connection_in.target_name.as_str(),
+                    connection_in.value,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            connections,
+            vec![("A", "B", 0.5), ("B", "A", -1.0), ("C", "A", 0.25)]
+        );
+        let unmapped_items = unmapped_items
+            .iter()
+            .map(|unmapped_item| {
+                (
+                    unmapped_item.item.as_str(),
+                    unmapped_item.unmapped_item_type.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(unmapped_items.len(), 3);
+        assert!(matches!(
+            unmapped_items[0],
+            ("A -> C", UnmappedItemType::InvalidValue)
+        ));
+        assert!(matches!(
+            unmapped_items[1],
+            ("B -> C", UnmappedItemType::UnsupportedValue)
+        ));
+        assert!(matches!(
+            unmapped_items[2],
+            ("C -> B", UnmappedItemType::InvalidValue)
+        ));
+    }
+
+    #[test]
+    fn reject_malformed_fcmapper() {
+        let string = |value: &str| DataType::String(value.to_owned());
+        // Empty sheet
+        assert!(parse_fcmapper_matrix(&get_project(), &[]).is_err());
+        // Row headers do not match column headers
+        let sheet = vec![
+            vec![DataType::Empty, string("A"), string("B")],
+            vec![string("B"), DataType::Float(0.5), DataType::Empty],
+            vec![string("A"), DataType::Empty, DataType::Empty],
+        ];
+        assert!(parse_fcmapper_matrix(&get_project(), &sheet).is_err());
+        // Missing row
+        let sheet = vec![
+            vec![DataType::Empty, string("A"), string("B")],
+            vec![string("A"), DataType::Float(0.5), DataType::Empty],
+        ];
+        assert!(parse_fcmapper_matrix(&get_project(), &sheet).is_err());
+        // Not a workbook
+        assert!(read_fcmapper_workbook(b"A,B\n0,1\n").is_err());
+    }
+}
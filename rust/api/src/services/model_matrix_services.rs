@@ -0,0 +1,652 @@
+use crate::models::User;
+use crate::plugins::Plugins;
+use crate::response::ServiceResult;
+use crate::services::{model_import_services, model_services};
+use crate::storage::Storage;
+use crate::types::{
+    ConceptInImportType, ConceptOutType, ConnectionInImportType, ConstraintInImportType,
+    FileOutType, ModelActionType, ModelInCsvImportType, ModelInImportType, ModelInXlsxImportType,
+    ModelOutType, ModelSheetType,
+};
+use crate::web_socket::WebSocketModelService;
+use crate::{internal_server_error, validation_error};
+use calamine::{open_workbook_from_rs, DataType, Reader, Xlsx};
+use chrono::{DateTime, Utc};
+use diesel::PgConnection;
+use rocket::http::ContentType;
+use rust_xlsxwriter::{Workbook, XlsxError};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Cursor;
+
+type Sheet = Vec<Vec<String>>;
+
+const SHEET_TYPES: [ModelSheetType; 4] = [
+    ModelSheetType::Weights,
+    ModelSheetType::Values,
+    ModelSheetType::Flags,
+    ModelSheetType::Constraints,
+];
+
+pub fn export_model_csv(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    sheet_type: ModelSheetType,
+) -> ServiceResult<FileOutType> {
+    let model = model_services::get_model(conn, plugins, user, project_id)?;
+    let sheet = get_sheet(&model, sheet_type);
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for row in sheet {
+        if writer.write_record(row).is_err() {
+            return internal_server_error!();
+        }
+    }
+    let content = match writer.into_inner() {
+        Ok(content) => content,
+        Err(_) => return internal_server_error!(),
+    };
+    Ok(FileOutType {
+        name: format!("{}_{}.csv", model.project.name, get_sheet_name(sheet_type)),
+        content_type: ContentType::CSV,
+        content,
+    })
+}
+
+pub fn export_model_xlsx(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+) -> ServiceResult<FileOutType> {
+    let model = model_services::get_model(conn, plugins, user, project_id)?;
+    let content = match write_workbook(&model) {
+        Ok(content) => content,
+        Err(_) => return internal_server_error!(),
+    };
+    Ok(FileOutType {
+        name: format!("{}.xlsx", model.project.name),
+        content_type: ContentType::new(
+            "application",
+            "vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        ),
+        content,
+    })
+}
+
+pub async fn import_model_csv(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &Storage,
+    user: &User,
+    project_id: i32,
+    model_in: ModelInCsvImportType<'_>,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelOutType>> {
+    let files = [
+        (ModelSheetType::Weights, Some(model_in.weights)),
+        (ModelSheetType::Values, model_in.values),
+        (ModelSheetType::Flags, model_in.flags),
+        (ModelSheetType::Constraints, model_in.constraints),
+    ];
+    let mut sheets = HashMap::new();
+    for (sheet_type, file) in files {
+        if let Some(file) = file {
+            let content = storage.read_file(file).await?;
+            sheets.insert(sheet_type, read_csv(sheet_type, &content)?);
+        }
+    }
+    let model_in = parse_sheets(sheets)?;
+    model_import_services::import_model(
+        conn,
+        plugins,
+        model_service,
+        user,
+        project_id,
+        model_in,
+        expected_version,
+    )
+    .await
+}
+
+pub async fn import_model_xlsx(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    storage: &Storage,
+    user: &User,
+    project_id: i32,
+    model_in: ModelInXlsxImportType<'_>,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelOutType>> {
+    let content = storage.read_file(model_in.file).await?;
+    let sheets = read_workbook(&content)?;
+    let model_in = parse_sheets(sheets)?;
+    model_import_services::import_model(
+        conn,
+        plugins,
+        model_service,
+        user,
+        project_id,
+        model_in,
+        expected_version,
+    )
+    .await
+}
+
+fn get_sheet_name(sheet_type: ModelSheetType) -> &'static str {
+    match sheet_type {
+        ModelSheetType::Weights => "weights",
+        ModelSheetType::Values => "values",
+        ModelSheetType::Flags => "flags",
+        ModelSheetType::Constraints => "constraints",
+    }
+}
+
+fn get_sheet_columns(sheet_type: ModelSheetType) -> &'static [&'static str] {
+    match sheet_type {
+        ModelSheetType::Weights => &[],
+        ModelSheetType::Values => &["concept", "value"],
+        ModelSheetType::Flags => &["concept", "is_control", "is_target"],
+        ModelSheetType::Constraints => &[
+            "concept",
+            "min_value",
+            "include_min_value",
+            "max_value",
+            "include_max_value",
+        ],
+    }
+}
+
+fn get_sheet(model: &ModelOutType, sheet_type: ModelSheetType) -> Sheet {
+    let mut sheet = vec![];
+    if sheet_type == ModelSheetType::Weights {
+        let mut header = vec![String::new()];
+        header.extend(model.concepts.iter().map(|concept| concept.name.clone()));
+        sheet.push(header);
+        for source in &model.concepts {
+            let mut row = vec![source.name.clone()];
+            row.extend(model.concepts.iter().map(|target| {
+                model
+                    .connections
+                    .iter()
+                    .find(|c| c.source_id == source.id && c.target_id == target.id)
+                    .map(|connection| connection.value.to_string())
+                    .unwrap_or_default()
+            }));
+            sheet.push(row);
+        }
+        return sheet;
+    }
+    sheet.push(
+        get_sheet_columns(sheet_type)
+            .iter()
+            .map(|column| String::from(*column))
+            .collect(),
+    );
+    for concept in &model.concepts {
+        let row = match sheet_type {
+            ModelSheetType::Weights => unreachable!(),
+            ModelSheetType::Values => Some(vec![
+                concept.name.clone(),
+                concept.value.map(|v| v.to_string()).unwrap_or_default(),
+            ]),
+            ModelSheetType::Flags => Some(vec![
+                concept.name.clone(),
+                get_plugin_field(concept, "controlConcepts", "isControl"),
+                get_plugin_field(concept, "targetConcepts", "isTarget"),
+            ]),
+            ModelSheetType::Constraints => get_constraint_plugin(concept).map(|plugin| {
+                let mut row = vec![concept.name.clone()];
+                row.extend(
+                    get_sheet_columns(sheet_type)[1..]
+                        .iter()
+                        .map(|column| get_plugin_field(concept, plugin, &to_camel_case(column))),
+                );
+                row
+            }),
+        };
+        if let Some(row) = row {
+            sheet.push(row);
+        }
+    }
+    sheet
+}
+
+fn get_constraint_plugin(concept: &ConceptOutType) -> Option<&'static str> {
+    if get_plugin_field(concept, "conceptConstraints", "hasConstraint") == "true" {
+        return Some("conceptConstraints");
+    }
+    if get_plugin_field(concept, "targetConcepts", "isTarget") == "true" {
+        return Some("targetConcepts");
+    }
+    None
+}
+
+fn get_plugin_field(concept: &ConceptOutType, plugin: &str, field: &str) -> String {
+    match concept
+        .plugins_data
+        .get(plugin)
+        .and_then(|data| data.get(field))
+    {
+        Some(Value::Bool(value)) => value.to_string(),
+        Some(Value::Number(value)) => value.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn to_camel_case(column: &str) -> String {
+    column
+        .split('_')
+        .enumerate()
+        .map(|(index, word)| match index {
+            0 => word.to_owned(),
+            _ => word[..1].to_uppercase() + &word[1..],
+        })
+        .collect()
+}
+
+fn get_sheet_types(model: &ModelOutType) -> Vec<ModelSheetType> {
+    let has_plugin = |name: &str| model.project.plugins.iter().any(|plugin| plugin == name);
+    SHEET_TYPES
+        .into_iter()
+        .filter(|sheet_type| match sheet_type {
+            ModelSheetType::Weights | ModelSheetType::Values => true,
+            ModelSheetType::Flags => {
+                has_plugin("Control Concepts") || has_plugin("Target Concepts")
+            }
+            ModelSheetType::Constraints => {
+                has_plugin("Concept Constraints") || has_plugin("Target Concepts")
+            }
+        })
+        .collect()
+}
+
+fn write_workbook(model: &ModelOutType) -> Result<Vec<u8>, XlsxError> {
+    let mut workbook = Workbook::new();
+    for sheet_type in get_sheet_types(model) {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(get_sheet_name(sheet_type))?;
+        for (row_index, row) in get_sheet(model, sheet_type).iter().enumerate() {
+            for (column_index, cell) in row.iter().enumerate() {
+                let (row_index, column_index) = (row_index as u32, column_index as u16);
+                if row_index == 0 || column_index == 0 || cell.is_empty() {
+                    worksheet.write_string(row_index, column_index, cell)?;
+                } else if let Ok(value) = cell.parse::<f64>() {
+                    worksheet.write_number(row_index, column_index, value)?;
+                } else if let Ok(value) = cell.parse::<bool>() {
+                    worksheet.write_boolean(row_index, column_index, value)?;
+                } else {
+                    worksheet.write_string(row_index, column_index, cell)?;
+                }
+            }
+        }
+    }
+    workbook.save_to_buffer()
+}
+
+fn read_csv(sheet_type: ModelSheetType, content: &[u8]) -> ServiceResult<Sheet> {
+    let content = content.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(content);
+    let first_line = content.split(|byte| *byte == b'\n').next().unwrap_or(&[]);
+    let delimiter = if first_line.contains(&b';') {
+        b';'
+    } else {
+        b','
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(delimiter)
+        .from_reader(content);
+    let mut sheet = vec![];
+    for record in reader.records() {
+        match record {
+            Ok(record) => sheet.push(record.iter().map(|cell| cell.trim().to_owned()).collect()),
+            Err(_) => {
+                let sheet_name = get_sheet_name(sheet_type);
+                return validation_error!("import_file_error", sheet = sheet_name);
+            }
+        }
+    }
+    Ok(sheet)
+}
+
+fn read_workbook(content: &[u8]) -> ServiceResult<HashMap<ModelSheetType, Sheet>> {
+    let mut workbook = match open_workbook_from_rs::<Xlsx<_>, _>(Cursor::new(content)) {
+        Ok(workbook) => workbook,
+        Err(_) => return validation_error!("import_file_error", sheet = "xlsx"),
+    };
+    let sheet_names = workbook.sheet_names().to_owned();
+    let mut sheets = HashMap::new();
+    for sheet_type in SHEET_TYPES {
+        let sheet_name = sheet_names
+            .iter()
+            .find(|name| name.trim().to_lowercase() == get_sheet_name(sheet_type))
+            .or_else(|| match sheet_type {
+                ModelSheetType::Weights => sheet_names.first(),
+                _ => None,
+            });
+        let sheet_name = match sheet_name {
+            Some(sheet_name) => sheet_name,
+            None => continue,
+        };
+        let range = match workbook.worksheet_range(sheet_name) {
+            Some(Ok(range)) => range,
+            _ => {
+                let sheet_name = get_sheet_name(sheet_type);
+                return validation_error!("import_file_error", sheet = sheet_name);
+            }
+        };
+        let sheet = range
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        DataType::Empty => String::new(),
+                        cell => cell.to_string().trim().to_owned(),
+                    })
+                    .collect()
+            })
+            .collect();
+        sheets.insert(sheet_type, sheet);
+    }
+    Ok(sheets)
+}
+
+fn parse_sheets(mut sheets: HashMap<ModelSheetType, Sheet>) -> ServiceResult<ModelInImportType> {
+    let weights = match sheets.remove(&ModelSheetType::Weights) {
+        Some(weights) => trim_sheet(weights),
+        None => return validation_error!("import_sheet_not_found_error", sheet = "weights"),
+    };
+    let mut model_in = parse_weights(&weights)?;
+    for sheet_type in &SHEET_TYPES[1..] {
+        let sheet = match sheets.remove(sheet_type) {
+            Some(sheet) => trim_sheet(sheet),
+            None => continue,
+        };
+        let rows = get_sheet_rows(*sheet_type, &sheet)?;
+        if *sheet_type == ModelSheetType::Constraints {
+            for concept_in in model_in.concepts.iter_mut() {
+                concept_in.constraint = Some(None);
+            }
+        }
+        for row in rows {
+            let concept_in = match model_in.concepts.iter_mut().find(|c| c.name == row[0]) {
+                Some(concept_in) => concept_in,
+                None => {
+                    let name = row[0].to_owned();
+                    return validation_error!("import_concept_not_found_error", name = &name);
+                }
+            };
+            parse_row(*sheet_type, concept_in, &row)?;
+        }
+    }
+    Ok(model_in)
+}
+
+fn trim_sheet(mut sheet: Sheet) -> Sheet {
+    while let Some(row) = sheet.last() {
+        if row.iter().all(|cell| cell.is_empty()) {
+            sheet.pop();
+        } else {
+            break;
+        }
+    }
+    sheet
+}
+
+fn parse_weights(sheet: &Sheet) -> ServiceResult<ModelInImportType> {
+    let mut names = match sheet.first() {
+        Some(header) => header.iter().skip(1).cloned().collect::<Vec<_>>(),
+        None => return validation_error!("import_sheet_not_found_error", sheet = "weights"),
+    };
+    while names.last().map_or(false, |name| name.is_empty()) {
+        names.pop();
+    }
+    let rows = &sheet[1..];
+    if rows.len() != names.len()
+        || rows
+            .iter()
+            .zip(names.iter())
+            .any(|(row, name)| row.first() != Some(name) || name.is_empty())
+    {
+        return validation_error!("import_matrix_headers_error");
+    }
+    let mut model_in = ModelInImportType::default();
+    for name in &names {
+        model_in.concepts.push(ConceptInImportType {
+            name: name.to_owned(),
+            ..Default::default()
+        });
+    }
+    for (row, source_name) in rows.iter().zip(names.iter()) {
+        for (cell, target_name) in row.iter().skip(1).zip(names.iter()) {
+            if let Some(value) = parse_number(ModelSheetType::Weights, cell)? {
+                model_in.connections.push(ConnectionInImportType {
+                    source_name: source_name.to_owned(),
+                    target_name: target_name.to_owned(),
+                    value,
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    Ok(model_in)
+}
+
+fn get_sheet_rows(sheet_type: ModelSheetType, sheet: &Sheet) -> ServiceResult<Vec<Vec<&str>>> {
+    let header = sheet.first().cloned().unwrap_or_default();
+    let mut indices = vec![];
+    for column in get_sheet_columns(sheet_type) {
+        match header
+            .iter()
+            .position(|cell| cell.to_lowercase() == *column)
+        {
+            Some(index) => indices.push(index),
+            None => {
+                let sheet_name = get_sheet_name(sheet_type);
+                let column = *column;
+                return validation_error!(
+                    "import_sheet_column_error",
+                    sheet = sheet_name,
+                    column = column
+                );
+            }
+        }
+    }
+    Ok(sheet
+        .iter()
+        .skip(1)
+        .filter(|row| row.iter().any(|cell| !cell.is_empty()))
+        .map(|row| {
+            indices
+                .iter()
+                .map(|index| row.get(*index).map(|cell| cell.as_str()).unwrap_or(""))
+                .collect()
+        })
+        .collect())
+}
+
+fn parse_row(
+    sheet_type: ModelSheetType,
+    concept_in: &mut ConceptInImportType,
+    row: &[&str],
+) -> ServiceResult<()> {
+    match sheet_type {
+        ModelSheetType::Weights => unreachable!(),
+        ModelSheetType::Values => {
+            concept_in.value = Some(parse_number(sheet_type, row[1])?);
+        }
+        ModelSheetType::Flags => {
+            concept_in.is_control = parse_bool(sheet_type, row[1])?;
+            concept_in.is_target = parse_bool(sheet_type, row[2])?;
+        }
+        ModelSheetType::Constraints => {
+            let (min_value, max_value) = match (
+                parse_number(sheet_type, row[1])?,
+                parse_number(sheet_type, row[3])?,
+            ) {
+                (Some(min_value), Some(max_value)) => (min_value, max_value),
+                _ => {
+                    let sheet_name = get_sheet_name(sheet_type);
+                    return validation_error!(
+                        "import_number_error",
+                        sheet = sheet_name,
+                        value = ""
+                    );
+                }
+            };
+            concept_in.constraint = Some(Some(ConstraintInImportType {
+                min_value,
+                include_min_value: parse_bool(sheet_type, row[2])?.unwrap_or(true),
+                max_value,
+                include_max_value: parse_bool(sheet_type, row[4])?.unwrap_or(true),
+            }));
+        }
+    }
+    Ok(())
+}
+
+fn parse_number(sheet_type: ModelSheetType, cell: &str) -> ServiceResult<Option<f64>> {
+    if cell.is_empty() {
+        return Ok(None);
+    }
+    match cell.replace(',', ".").parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(Some(value)),
+        _ => {
+            let sheet_name = get_sheet_name(sheet_type);
+            let value = cell.to_owned();
+            validation_error!("import_number_error", sheet = sheet_name, value = &value)
+        }
+    }
+}
+
+fn parse_bool(sheet_type: ModelSheetType, cell: &str) -> ServiceResult<Option<bool>> {
+    match cell.to_lowercase().as_str() {
+        "" => Ok(None),
+        "true" | "1" => Ok(Some(true)),
+        "false" | "0" => Ok(Some(false)),
+        _ => {
+            let sheet_name = get_sheet_name(sheet_type);
+            let value = cell.to_owned();
+            validation_error!("import_bool_error", sheet = sheet_name, value = &value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_csv(sheets: &[(ModelSheetType, &str)]) -> Option<ModelInImportType> {
+        let sheets = sheets
+            .iter()
+            .map(|(sheet_type, content)| {
+                Some((*sheet_type, read_csv(*sheet_type, content.as_bytes()).ok()?))
+            })
+            .collect::<Option<HashMap<_, _>>>()?;
+        parse_sheets(sheets).ok()
+    }
+
+    #[test]
+    fn parse_weights_csv() {
+        let model_in = parse_csv(&[(
+            ModelSheetType::Weights,
+            "\u{FEFF}, a, b\na, , 0.5\nb, -1, \n,,\n",
+        )])
+        .unwrap();
+        assert_eq!(
+            model_in
+                .concepts
+                .iter()
+                .map(|concept_in| concept_in.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(model_in.connections.len(), 2);
+        assert_eq!(model_in.connections[0].source_name, "a");
+        assert_eq!(model_in.connections[0].target_name, "b");
+        assert_eq!(model_in.connections[0].value, 0.5);
+        assert_eq!(model_in.connections[1].source_name, "b");
+        assert_eq!(model_in.connections[1].target_name, "a");
+        assert_eq!(model_in.connections[1].value, -1.0);
+    }
+
+    #[test]
+    fn parse_semicolon_csv_with_decimal_commas() {
+        let model_in = parse_csv(&[
+            (ModelSheetType::Weights, ";a;b\na;;0,5\nb;;\n"),
+            (ModelSheetType::Values, "Concept;Value\nb;0,25\n"),
+        ])
+        .unwrap();
+        assert_eq!(model_in.connections.len(), 1);
+        assert_eq!(model_in.connections[0].value, 0.5);
+        assert_eq!(model_in.concepts[0].value, None);
+        assert_eq!(model_in.concepts[1].value, Some(Some(0.25)));
+    }
+
+    #[test]
+    fn parse_flags_and_constraints_csv() {
+        let model_in = parse_csv(&[
+            (ModelSheetType::Weights, ",a,b\na,,1\nb,,\n"),
+            (
+                ModelSheetType::Flags,
+                "concept,is_control,is_target\na,true,false\nb,0,1\n",
+            ),
+            (
+                ModelSheetType::Constraints,
+                "concept,min_value,include_min_value,max_value,include_max_value\na,0.2,,0.8,false\n",
+            ),
+        ])
+        .unwrap();
+        assert_eq!(model_in.concepts[0].is_control, Some(true));
+        assert_eq!(model_in.concepts[0].is_target, Some(false));
+        assert_eq!(model_in.concepts[1].is_control, Some(false));
+        assert_eq!(model_in.concepts[1].is_target, Some(true));
+        assert!(
+            model_in.concepts[0].constraint
+                == Some(Some(ConstraintInImportType {
+                    min_value: 0.2,
+                    include_min_value: true,
+                    max_value: 0.8,
+                    include_max_value: false,
+                }))
+        );
+        assert!(model_in.concepts[1].constraint == Some(None));
+    }
+
+    #[test]
+    fn reject_malformed_csv() {
+        // Row headers do not match column headers
+        assert!(parse_csv(&[(ModelSheetType::Weights, ",a,b\nb,,1\na,,\n")]).is_none());
+        // Missing row
+        assert!(parse_csv(&[(ModelSheetType::Weights, ",a,b\na,,1\n")]).is_none());
+        // Invalid weight
+        assert!(parse_csv(&[(ModelSheetType::Weights, ",a,b\na,,x\nb,,\n")]).is_none());
+        // Unknown concept in values
+        assert!(parse_csv(&[
+            (ModelSheetType::Weights, ",a\na,\n"),
+            (ModelSheetType::Values, "concept,value\nc,0.5\n"),
+        ])
+        .is_none());
+        // Missing column in flags
+        assert!(parse_csv(&[
+            (ModelSheetType::Weights, ",a\na,\n"),
+            (ModelSheetType::Flags, "concept,is_control\na,true\n"),
+        ])
+        .is_none());
+        // Invalid flag
+        assert!(parse_csv(&[
+            (ModelSheetType::Weights, ",a\na,\n"),
+            (
+                ModelSheetType::Flags,
+                "concept,is_control,is_target\na,yes,\n"
+            ),
+        ])
+        .is_none());
+        // Missing weights
+        assert!(parse_csv(&[(ModelSheetType::Values, "concept,value\na,0.5\n")]).is_none());
+    }
+}
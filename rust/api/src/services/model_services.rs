@@ -4,15 +4,15 @@ use crate::models::{
 use crate::plugins::{ChangeConceptValueExtra, ChangeConnectionValueExtra, Plugins};
 use crate::response::{ServiceResult, ToServiceResult};
 use crate::schema::{concepts, connections, model_copies, projects};
-use crate::services::{permission_services, project_services};
+use crate::services::{model_history_services, permission_services, project_services};
 use crate::types::{
     ConceptInMoveType, ConceptInType, ConceptOutChangeType, ConceptOutDeleteType,
     ConceptOutMoveType, ConceptOutType, ConnectionInChangeType, ConnectionInCreateType,
     ConnectionOutChangeType, ConnectionOutDeleteType, ConnectionOutType, ModelActionType,
-    ModelOutType, ProjectOutType,
+    ModelElementsType, ModelOutType, ProjectOutType,
 };
-use crate::validation_error;
 use crate::web_socket::WebSocketModelService;
+use crate::{conflict_error, validation_error};
 use chrono::DateTime;
 use chrono::Utc;
 use diesel::prelude::*;
@@ -51,7 +51,7 @@ pub fn get_model(
         .get_model_emitter
         .lock()
         .unwrap()
-        .emit(model_out, ())?;
+        .emit(conn, model_out, ())?;
     Ok(model_out)
 }
 
@@ -165,27 +165,35 @@ pub async fn create_concept(
         .to_service_result_find(String::from("project_not_found_error"))?;
     permission_services::can_change_model(conn, &project, user.id)?;
     check_concept_value(&project, concept_in.value.clone())?;
-    let (concept, project) = conn
-        .transaction(|conn| {
-            let concept = diesel::insert_into(concepts::table)
-                .values((
-                    concepts::project_id.eq(project_id),
-                    concepts::name.eq(concept_in.name),
-                    concepts::description.eq(concept_in.description),
-                    concepts::value.eq(concept_in.value),
-                    concepts::x_position.eq(concept_in.x_position),
-                    concepts::y_position.eq(concept_in.y_position),
-                ))
-                .get_result::<Concept>(conn)?;
-            let project = project_services::update_project(conn, project_id, concept.created_at)?;
-            Ok((concept, project))
-        })
-        .to_service_result()?;
-    let concept_out = plugins
-        .add_concept_emitter
-        .lock()
-        .unwrap()
-        .emit(ConceptOutType::from(concept), project.clone())?;
+    let (concept_out, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        let concept = diesel::insert_into(concepts::table)
+            .values((
+                concepts::project_id.eq(project_id),
+                concepts::name.eq(concept_in.name),
+                concepts::description.eq(concept_in.description),
+                concepts::value.eq(concept_in.value),
+                concepts::x_position.eq(concept_in.x_position),
+                concepts::y_position.eq(concept_in.y_position),
+            ))
+            .get_result::<Concept>(conn)?;
+        let project = project_services::update_project(conn, project_id, concept.created_at)?;
+        let concept_out = plugins.add_concept_emitter.lock().unwrap().emit(
+            conn,
+            ConceptOutType::from(concept),
+            project.clone(),
+        )?;
+        let next_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_out.id)?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "createConcept",
+            ModelElementsType::default(),
+            next_state,
+        )?;
+        Ok((concept_out, project))
+    })?;
     let model_action = ModelActionType::new(&project, String::from("createConcept"), concept_out);
     model_service.notify(model_action.clone()).await;
     Ok(model_action)
@@ -230,31 +238,45 @@ pub async fn change_concept(
     user: &User,
     concept_id: i32,
     concept_in: ConceptInType,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ConceptOutChangeType>> {
     let project = find_project_by_concept_id(conn, concept_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
     permission_services::can_change_model(conn, &project, user.id)?;
     let value = plugins.change_concept_value_emitter.lock().unwrap().emit(
+        conn,
         concept_in.value,
         ChangeConceptValueExtra::new(project.clone(), concept_id),
     )?;
     check_concept_value(&project, value.clone())?;
-    let (concept, project) = conn
-        .transaction(|conn| {
-            let concept = diesel::update(concepts::table)
-                .filter(concepts::id.eq(concept_id))
-                .set((
-                    concepts::name.eq(concept_in.name),
-                    concepts::description.eq(concept_in.description),
-                    concepts::value.eq(value),
-                    concepts::x_position.eq(concept_in.x_position),
-                    concepts::y_position.eq(concept_in.y_position),
-                ))
-                .get_result::<Concept>(conn)?;
-            let project = project_services::update_project(conn, project.id, concept.updated_at)?;
-            Ok((concept, project))
-        })
-        .to_service_result_find(String::from("concept_not_found_error"))?;
+    let (concept, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        check_concept_version(conn, plugins, &project, concept_id, expected_version)?;
+        let previous_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        let concept = diesel::update(concepts::table)
+            .filter(concepts::id.eq(concept_id))
+            .set((
+                concepts::name.eq(concept_in.name),
+                concepts::description.eq(concept_in.description),
+                concepts::value.eq(value),
+                concepts::x_position.eq(concept_in.x_position),
+                concepts::y_position.eq(concept_in.y_position),
+            ))
+            .get_result::<Concept>(conn)
+            .to_service_result_find(String::from("concept_not_found_error"))?;
+        let project = project_services::update_project(conn, project.id, concept.updated_at)?;
+        let next_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "changeConcept",
+            previous_state,
+            next_state,
+        )?;
+        Ok((concept, project))
+    })?;
     let concept_out = ConceptOutChangeType::from(concept);
     let model_action = ModelActionType::new(&project, String::from("changeConcept"), concept_out);
     model_service.notify(model_action.clone()).await;
@@ -263,27 +285,41 @@ pub async fn change_concept(
 
 pub async fn move_concept(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     concept_id: i32,
     concept_in: ConceptInMoveType,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ConceptOutMoveType>> {
     let project = find_project_by_concept_id(conn, concept_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
     permission_services::can_change_model(conn, &project, user.id)?;
-    let (concept, project) = conn
-        .transaction(|conn| {
-            let concept = diesel::update(concepts::table)
-                .filter(concepts::id.eq(concept_id))
-                .set((
-                    concepts::x_position.eq(concept_in.x_position),
-                    concepts::y_position.eq(concept_in.y_position),
-                ))
-                .get_result::<Concept>(conn)?;
-            let project = project_services::update_project(conn, project.id, concept.updated_at)?;
-            Ok((concept, project))
-        })
-        .to_service_result_find(String::from("concept_not_found_error"))?;
+    let (concept, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        check_concept_version(conn, plugins, &project, concept_id, expected_version)?;
+        let previous_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        let concept = diesel::update(concepts::table)
+            .filter(concepts::id.eq(concept_id))
+            .set((
+                concepts::x_position.eq(concept_in.x_position),
+                concepts::y_position.eq(concept_in.y_position),
+            ))
+            .get_result::<Concept>(conn)
+            .to_service_result_find(String::from("concept_not_found_error"))?;
+        let project = project_services::update_project(conn, project.id, concept.updated_at)?;
+        let next_state =
+            model_history_services::get_concept_elements(conn, plugins, &project, concept_id)?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "moveConcept",
+            previous_state,
+            next_state,
+        )?;
+        Ok((concept, project))
+    })?;
     let concept_out = ConceptOutMoveType::from(concept);
     let model_action = ModelActionType::new(&project, String::from("moveConcept"), concept_out);
     model_service.notify(model_action.clone()).await;
@@ -292,29 +328,36 @@ pub async fn move_concept(
 
 pub async fn delete_concept(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     concept_id: i32,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ConceptOutDeleteType>> {
     let project = find_project_by_concept_id(conn, concept_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
     permission_services::can_change_model(conn, &project, user.id)?;
-    let (deleted_number, project) = conn
-        .transaction(|conn| {
-            let deleted_number =
-                diesel::delete(concepts::table.filter(concepts::id.eq(concept_id)))
-                    .execute(conn)?;
-            let project = if deleted_number == 0 {
-                project
-            } else {
-                project_services::update_project(conn, project.id, Utc::now())?
-            };
-            Ok((deleted_number, project))
-        })
-        .to_service_result()?;
-    if deleted_number == 0 {
-        return validation_error!("concept_not_found_error");
-    }
+    let project = conn.transaction(|conn| -> ServiceResult<_> {
+        check_concept_version(conn, plugins, &project, concept_id, expected_version)?;
+        let previous_state = model_history_services::get_concept_with_connections_elements(
+            conn, plugins, &project, concept_id,
+        )?;
+        let deleted_number =
+            diesel::delete(concepts::table.filter(concepts::id.eq(concept_id))).execute(conn)?;
+        if deleted_number == 0 {
+            return validation_error!("concept_not_found_error");
+        }
+        let project = project_services::update_project(conn, project.id, Utc::now())?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "deleteConcept",
+            previous_state,
+            ModelElementsType::default(),
+        )?;
+        Ok(project)
+    })?;
     let model_action = ModelActionType::new(
         &project,
         String::from("deleteConcept"),
@@ -343,27 +386,39 @@ pub async fn create_connection(
     find_concept_by_id(conn, connection_in.target_id)
         .to_service_result_find(String::from("connection_target_concept_not_found_error"))?;
     check_connection_value(&project, connection_in.value)?;
-    let (connection, project) = conn
-        .transaction(|conn| {
-            let connection = diesel::insert_into(connections::table)
-                .values((
-                    connections::project_id.eq(project_id),
-                    connections::description.eq(connection_in.description),
-                    connections::value.eq(connection_in.value),
-                    connections::source_id.eq(connection_in.source_id),
-                    connections::target_id.eq(connection_in.target_id),
-                ))
-                .get_result::<Connection>(conn)?;
-            let project =
-                project_services::update_project(conn, project_id, connection.created_at)?;
-            Ok((connection, project))
-        })
-        .to_service_result_unique(String::from("connection_duplication_error"))?;
-    let connection_out = plugins
-        .add_connection_emitter
-        .lock()
-        .unwrap()
-        .emit(ConnectionOutType::from(connection), project.clone())?;
+    let (connection_out, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        let connection = diesel::insert_into(connections::table)
+            .values((
+                connections::project_id.eq(project_id),
+                connections::description.eq(connection_in.description),
+                connections::value.eq(connection_in.value),
+                connections::source_id.eq(connection_in.source_id),
+                connections::target_id.eq(connection_in.target_id),
+            ))
+            .get_result::<Connection>(conn)
+            .to_service_result_unique(String::from("connection_duplication_error"))?;
+        let project = project_services::update_project(conn, project_id, connection.created_at)?;
+        let connection_out = plugins.add_connection_emitter.lock().unwrap().emit(
+            conn,
+            ConnectionOutType::from(connection),
+            project.clone(),
+        )?;
+        let next_state = model_history_services::get_connection_elements(
+            conn,
+            plugins,
+            &project,
+            connection_out.id,
+        )?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "createConnection",
+            ModelElementsType::default(),
+            next_state,
+        )?;
+        Ok((connection_out, project))
+    })?;
     let model_action =
         ModelActionType::new(&project, String::from("createConnection"), connection_out);
     model_service.notify(model_action.clone()).await;
@@ -377,6 +432,7 @@ pub async fn change_connection(
     user: &User,
     connection_id: i32,
     connection_in: ConnectionInChangeType,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ConnectionOutChangeType>> {
     let project = find_project_by_connection_id(conn, connection_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
@@ -386,24 +442,44 @@ pub async fn change_connection(
         .lock()
         .unwrap()
         .emit(
+            conn,
             connection_in.value,
             ChangeConnectionValueExtra::new(project.clone(), connection_id),
         )?;
     check_connection_value(&project, value)?;
-    let (connection, project) = conn
-        .transaction(|conn| {
-            let connection = diesel::update(connections::table)
-                .filter(connections::id.eq(connection_id))
-                .set((
-                    connections::description.eq(connection_in.description),
-                    connections::value.eq(value),
-                ))
-                .get_result::<Connection>(conn)?;
-            let project =
-                project_services::update_project(conn, project.id, connection.updated_at)?;
-            Ok((connection, project))
-        })
-        .to_service_result_find(String::from("connection_not_found_error"))?;
+    let (connection, project) = conn.transaction(|conn| -> ServiceResult<_> {
+        check_connection_version(conn, plugins, &project, connection_id, expected_version)?;
+        let previous_state = model_history_services::get_connection_elements(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+        )?;
+        let connection = diesel::update(connections::table)
+            .filter(connections::id.eq(connection_id))
+            .set((
+                connections::description.eq(connection_in.description),
+                connections::value.eq(value),
+            ))
+            .get_result::<Connection>(conn)
+            .to_service_result_find(String::from("connection_not_found_error"))?;
+        let project = project_services::update_project(conn, project.id, connection.updated_at)?;
+        let next_state = model_history_services::get_connection_elements(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+        )?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "changeConnection",
+            previous_state,
+            next_state,
+        )?;
+        Ok((connection, project))
+    })?;
     let connection_out = ConnectionOutChangeType::from(connection);
     let model_action =
         ModelActionType::new(&project, String::from("changeConnection"), connection_out);
@@ -413,29 +489,40 @@ pub async fn change_connection(
 
 pub async fn delete_connection(
     conn: &mut PgConnection,
+    plugins: &Plugins,
     model_service: WebSocketModelService,
     user: &User,
     connection_id: i32,
+    expected_version: Option<DateTime<Utc>>,
 ) -> ServiceResult<ModelActionType<ConnectionOutDeleteType>> {
     let project = find_project_by_connection_id(conn, connection_id)
         .to_service_result_find(String::from("project_not_found_error"))?;
     permission_services::can_change_model(conn, &project, user.id)?;
-    let (deleted_number, project) = conn
-        .transaction(|conn| {
-            let deleted_number =
-                diesel::delete(connections::table.filter(connections::id.eq(connection_id)))
-                    .execute(conn)?;
-            let project = if deleted_number == 0 {
-                project
-            } else {
-                project_services::update_project(conn, project.id, Utc::now())?
-            };
-            Ok((deleted_number, project))
-        })
-        .to_service_result()?;
-    if deleted_number == 0 {
-        return validation_error!("connection_not_found_error");
-    }
+    let project = conn.transaction(|conn| -> ServiceResult<_> {
+        check_connection_version(conn, plugins, &project, connection_id, expected_version)?;
+        let previous_state = model_history_services::get_connection_elements(
+            conn,
+            plugins,
+            &project,
+            connection_id,
+        )?;
+        let deleted_number =
+            diesel::delete(connections::table.filter(connections::id.eq(connection_id)))
+                .execute(conn)?;
+        if deleted_number == 0 {
+            return validation_error!("connection_not_found_error");
+        }
+        let project = project_services::update_project(conn, project.id, Utc::now())?;
+        model_history_services::record_model_action(
+            conn,
+            user,
+            &project,
+            "deleteConnection",
+            previous_state,
+            ModelElementsType::default(),
+        )?;
+        Ok(project)
+    })?;
     let model_action = ModelActionType::new(
         &project,
         String::from("deleteConnection"),
@@ -448,6 +535,68 @@ pub async fn delete_connection(
     Ok(model_action)
 }
 
+/// Must be called inside the write transaction, the concept row stays locked until it ends.
+pub fn check_concept_version(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    concept_id: i32,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<()> {
+    let expected_version = match expected_version {
+        Some(expected_version) => expected_version,
+        None => return Ok(()),
+    };
+    let concept = concepts::table
+        .filter(concepts::id.eq(concept_id))
+        .for_update()
+        .get_result::<Concept>(conn)
+        .to_service_result_find(String::from("concept_not_found_error"))?;
+    if concept.project_id != project.id {
+        return validation_error!("concept_not_found_error");
+    }
+    if concept.updated_at == expected_version {
+        return Ok(());
+    }
+    let current_state =
+        model_history_services::get_concept_elements(conn, plugins, project, concept_id)?;
+    conflict_error!(
+        "concept_version_conflict_error",
+        serde_json::to_value(&current_state.concepts[0]).unwrap()
+    )
+}
+
+/// Must be called inside the write transaction, the connection row stays locked until it ends.
+pub fn check_connection_version(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    connection_id: i32,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<()> {
+    let expected_version = match expected_version {
+        Some(expected_version) => expected_version,
+        None => return Ok(()),
+    };
+    let connection = connections::table
+        .filter(connections::id.eq(connection_id))
+        .for_update()
+        .get_result::<Connection>(conn)
+        .to_service_result_find(String::from("connection_not_found_error"))?;
+    if connection.project_id != project.id {
+        return validation_error!("connection_not_found_error");
+    }
+    if connection.updated_at == expected_version {
+        return Ok(());
+    }
+    let current_state =
+        model_history_services::get_connection_elements(conn, plugins, project, connection_id)?;
+    conflict_error!(
+        "connection_version_conflict_error",
+        serde_json::to_value(&current_state.connections[0]).unwrap()
+    )
+}
+
 pub fn check_concept_value(project: &Project, value: Option<f64>) -> ServiceResult<()> {
     match value {
         Some(value) => match project.concept_value_type {
@@ -483,7 +632,7 @@ pub fn check_concept_value(project: &Project, value: Option<f64>) -> ServiceResu
     }
 }
 
-fn check_connection_value(project: &Project, value: f64) -> ServiceResult<()> {
+pub fn check_connection_value(project: &Project, value: f64) -> ServiceResult<()> {
     match project.connection_value_type {
         ConnectionValueType::Symbolic => {
             if value == 0.0 || value == 1.0 {
@@ -492,7 +641,7 @@ fn check_connection_value(project: &Project, value: f64) -> ServiceResult<()> {
                 validation_error!("invalid_connection_value_symbolic_error", got = value)
             }
         }
-        ConnectionValueType::FromMinusOneToOne => {
+        ConnectionValueType::FromMinusOneToOne | ConnectionValueType::Linguistic => {
             if value >= -1.0 && value <= 1.0 {
                 Ok(())
             } else {
@@ -0,0 +1,926 @@
+use crate::models::{Concept, Connection, ModelSnapshot, Project, User};
+use crate::pagination::Paginate;
+use crate::plugins::linguistic_connections::services as linguistic_connections_services;
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{
+    adjustment_runs, concept_constraints, concept_decay_rates, concept_dynamic_models,
+    concept_intervention_costs, concepts, connection_constraints, connection_functions,
+    connection_intervention_costs, connection_lags, connection_linguistic_terms, connections,
+    control_concepts, control_connections, model_copies, model_snapshots, target_concepts,
+};
+use crate::services::plugins_data_services::{
+    get_plugin_data, ConstraintData, DecayRateData, DynamicModelData, FunctionData,
+    InterventionCostData, IsControlData, LagData, TargetData,
+};
+use crate::services::{
+    model_services, permission_services, plugin_services, project_services, user_services,
+};
+use crate::types::{
+    ConceptDiffOutType, ConceptOutType, ConnectionDiffOutType, ConnectionOutType, FieldDiffOutType,
+    ModelActionType, ModelDiffOutType, ModelOutType, ModelSnapshotInType, ModelSnapshotOutType,
+    PaginationInType, PaginationOutType, UserOutType,
+};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use rocket::serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+
+const LINGUISTIC_CONNECTIONS_PLUGIN: &str = "Linguistic Connections";
+const SKIPPED_FIELDS: [&str; 7] = [
+    "id",
+    "projectId",
+    "sourceId",
+    "targetId",
+    "pluginsData",
+    "createdAt",
+    "updatedAt",
+];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LinguisticData {
+    linguistic_term_ids: Vec<i32>,
+}
+
+pub fn create_model_snapshot(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    model_snapshot_in: ModelSnapshotInType,
+) -> ServiceResult<ModelSnapshotOutType> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    let model_snapshot = save_model_snapshot(
+        conn,
+        plugins,
+        user,
+        &project,
+        model_snapshot_in.name,
+        model_snapshot_in.description,
+        false,
+    )?;
+    Ok(ModelSnapshotOutType::from((model_snapshot, user.clone())))
+}
+
+pub fn save_automatic_model_snapshot(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project: &Project,
+    action_name: &str,
+) -> ServiceResult<ModelSnapshot> {
+    save_model_snapshot(
+        conn,
+        plugins,
+        user,
+        project,
+        action_name.to_owned(),
+        String::new(),
+        true,
+    )
+}
+
+pub fn paginate_model_snapshots(
+    conn: &mut PgConnection,
+    user: &User,
+    project_id: i32,
+    is_automatic: Option<bool>,
+    pagination_in: PaginationInType,
+) -> ServiceResult<PaginationOutType<ModelSnapshotOutType>> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_view_project(conn, &project, user)?;
+    let mut query = model_snapshots::table
+        .filter(model_snapshots::project_id.eq(project_id))
+        .order(model_snapshots::created_at.desc())
+        .into_boxed();
+    if let Some(is_automatic) = is_automatic {
+        query = query.filter(model_snapshots::is_automatic.eq(is_automatic));
+    }
+    let (model_snapshots, total_count, total_pages) = query
+        .paginate(pagination_in.page as i64)
+        .per_page(pagination_in.per_page as i64)
+        .load_and_count_pages::<ModelSnapshot>(conn)
+        .to_service_result()?;
+    Ok(PaginationOutType {
+        data: ModelSnapshotOutType::from_model_snapshots(conn, model_snapshots)?,
+        total_count: total_count as i32,
+        total_pages: total_pages as i32,
+    })
+}
+
+pub fn delete_model_snapshot(
+    conn: &mut PgConnection,
+    user: &User,
+    model_snapshot_id: i32,
+) -> ServiceResult<()> {
+    let model_snapshot = find_model_snapshot_by_id(conn, model_snapshot_id)
+        .to_service_result_find(String::from("model_snapshot_not_found_error"))?;
+    let project = project_services::find_project_by_id(conn, model_snapshot.project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    conn.transaction(|conn| {
+        diesel::delete(model_snapshots::table.filter(model_snapshots::id.eq(model_snapshot_id)))
+            .execute(conn)?;
+        let model_copy_id = model_snapshot.model_copy_id;
+        let adjustment_run_count = adjustment_runs::table
+            .filter(adjustment_runs::model_copy_id.eq(model_copy_id))
+            .count()
+            .get_result::<i64>(conn)?;
+        let model_snapshot_count = model_snapshots::table
+            .filter(model_snapshots::model_copy_id.eq(model_copy_id))
+            .count()
+            .get_result::<i64>(conn)?;
+        if adjustment_run_count == 0 && model_snapshot_count == 0 {
+            diesel::delete(model_copies::table.filter(model_copies::id.eq(model_copy_id)))
+                .execute(conn)?;
+        }
+        Ok(())
+    })
+    .to_service_result()
+}
+
+pub fn diff_model_snapshot(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    model_snapshot_id: i32,
+    target_model_snapshot_id: Option<i32>,
+) -> ServiceResult<ModelDiffOutType> {
+    let model_snapshot = find_model_snapshot_by_id(conn, model_snapshot_id)
+        .to_service_result_find(String::from("model_snapshot_not_found_error"))?;
+    let source_model = model_services::get_model_copy(conn, user, model_snapshot.model_copy_id)?;
+    let target_model = match target_model_snapshot_id {
+        Some(target_model_snapshot_id) => {
+            let target_model_snapshot =
+                find_model_snapshot_by_id(conn, target_model_snapshot_id)
+                    .to_service_result_find(String::from("model_snapshot_not_found_error"))?;
+            if target_model_snapshot.project_id != model_snapshot.project_id {
+                return validation_error!("model_snapshot_project_error");
+            }
+            model_services::get_model_copy(conn, user, target_model_snapshot.model_copy_id)?
+        }
+        None => model_services::get_model(conn, plugins, user, model_snapshot.project_id)?,
+    };
+    Ok(diff_models(&source_model, &target_model))
+}
+
+pub async fn restore_model_snapshot(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    model_snapshot_id: i32,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelOutType>> {
+    let model_snapshot = find_model_snapshot_by_id(conn, model_snapshot_id)
+        .to_service_result_find(String::from("model_snapshot_not_found_error"))?;
+    let project = project_services::find_project_by_id(conn, model_snapshot.project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_change_model(conn, &project, user.id)?;
+    project_services::check_project_version(conn, &project, expected_version)?;
+    let model = model_services::get_model_copy(conn, user, model_snapshot.model_copy_id)?;
+    for concept_out in &model.concepts {
+        model_services::check_concept_value(&project, concept_out.value)?;
+    }
+    for connection_out in &model.connections {
+        model_services::check_connection_value(&project, connection_out.value)?;
+    }
+    let plugin_names =
+        plugin_services::find_project_plugin_names(conn, project.id).to_service_result()?;
+    save_automatic_model_snapshot(conn, plugins, user, &project, "restoreModel")?;
+    let project =
+        conn.transaction(|conn| write_model(conn, plugins, project, &plugin_names, &model))?;
+    let model_out = model_services::get_model(conn, plugins, user, project.id)?;
+    let model_action = ModelActionType::new(&project, String::from("restoreModel"), model_out);
+    model_service.notify(model_action.clone()).await;
+    Ok(model_action)
+}
+
+pub fn diff_models(source_model: &ModelOutType, target_model: &ModelOutType) -> ModelDiffOutType {
+    let concept_ids = match_concepts(
+        source_model
+            .concepts
+            .iter()
+            .map(|concept_out| (concept_out.id, concept_out.name.as_str())),
+        target_model
+            .concepts
+            .iter()
+            .map(|concept_out| (concept_out.id, concept_out.name.as_str())),
+    );
+    let target_concepts = target_model
+        .concepts
+        .iter()
+        .map(|concept_out| (concept_out.id, concept_out))
+        .collect::<HashMap<_, _>>();
+    let mut matched_concept_ids = HashSet::new();
+    let mut removed_concepts = vec![];
+    let mut changed_concepts = vec![];
+    for source_concept in &source_model.concepts {
+        let target_concept = match concept_ids.get(&source_concept.id) {
+            Some(concept_id) => target_concepts[concept_id],
+            None => {
+                removed_concepts.push(source_concept.clone());
+                continue;
+            }
+        };
+        matched_concept_ids.insert(target_concept.id);
+        let fields = diff_fields(source_concept, target_concept);
+        if !fields.is_empty() {
+            changed_concepts.push(ConceptDiffOutType {
+                source: source_concept.clone(),
+                target: target_concept.clone(),
+                fields,
+            });
+        }
+    }
+    let added_concepts = target_model
+        .concepts
+        .iter()
+        .filter(|concept_out| !matched_concept_ids.contains(&concept_out.id))
+        .cloned()
+        .collect();
+    let target_connections = target_model
+        .connections
+        .iter()
+        .map(|connection_out| {
+            (
+                (connection_out.source_id, connection_out.target_id),
+                connection_out,
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let mut matched_connection_ids = HashSet::new();
+    let mut removed_connections = vec![];
+    let mut changed_connections = vec![];
+    for source_connection in &source_model.connections {
+        let target_connection = match (
+            concept_ids.get(&source_connection.source_id),
+            concept_ids.get(&source_connection.target_id),
+        ) {
+            (Some(source_id), Some(target_id)) => target_connections.get(&(*source_id, *target_id)),
+            _ => None,
+        };
+        let target_connection = match target_connection {
+            Some(target_connection) => *target_connection,
+            None => {
+                removed_connections.push(source_connection.clone());
+                continue;
+            }
+        };
+        matched_connection_ids.insert(target_connection.id);
+        let fields = diff_fields(source_connection, target_connection);
+        if !fields.is_empty() {
+            changed_connections.push(ConnectionDiffOutType {
+                source: source_connection.clone(),
+                target: target_connection.clone(),
+                fields,
+            });
+        }
+    }
+    let added_connections = target_model
+        .connections
+        .iter()
+        .filter(|connection_out| !matched_connection_ids.contains(&connection_out.id))
+        .cloned()
+        .collect();
+    ModelDiffOutType {
+        added_concepts,
+        removed_concepts,
+        changed_concepts,
+        added_connections,
+        removed_connections,
+        changed_connections,
+    }
+}
+
+pub fn find_model_snapshot_by_id(
+    conn: &mut PgConnection,
+    model_snapshot_id: i32,
+) -> QueryResult<ModelSnapshot> {
+    model_snapshots::table
+        .filter(model_snapshots::id.eq(model_snapshot_id))
+        .get_result::<ModelSnapshot>(conn)
+}
+
+fn save_model_snapshot(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project: &Project,
+    name: String,
+    description: String,
+    is_automatic: bool,
+) -> ServiceResult<ModelSnapshot> {
+    let model_copy = model_services::save_model_copy(conn, plugins, user, project.id)?;
+    diesel::insert_into(model_snapshots::table)
+        .values((
+            model_snapshots::project_id.eq(project.id),
+            model_snapshots::model_copy_id.eq(model_copy.id),
+            model_snapshots::creator_id.eq(user.id),
+            model_snapshots::name.eq(name),
+            model_snapshots::description.eq(description),
+            model_snapshots::is_automatic.eq(is_automatic),
+        ))
+        .get_result::<ModelSnapshot>(conn)
+        .to_service_result()
+}
+
+// Concepts keep their identifiers across edits and restores, so they are matched by
+// identifier first and concepts recreated after deletion are matched by name
+fn match_concepts<'a>(
+    source_concepts: impl Iterator<Item = (i32, &'a str)>,
+    target_concepts: impl Iterator<Item = (i32, &'a str)>,
+) -> HashMap<i32, i32> {
+    let target_concepts = target_concepts.collect::<Vec<_>>();
+    let target_ids = target_concepts
+        .iter()
+        .map(|(id, _)| *id)
+        .collect::<HashSet<_>>();
+    let mut concept_ids = HashMap::new();
+    let mut unmatched_concepts = vec![];
+    for (id, name) in source_concepts {
+        if target_ids.contains(&id) {
+            concept_ids.insert(id, id);
+        } else {
+            unmatched_concepts.push((id, name));
+        }
+    }
+    let mut matched_ids = concept_ids.values().copied().collect::<HashSet<_>>();
+    for (id, name) in unmatched_concepts {
+        if let Some((target_id, _)) = target_concepts.iter().find(|(target_id, target_name)| {
+            *target_name == name && !matched_ids.contains(target_id)
+        }) {
+            concept_ids.insert(id, *target_id);
+            matched_ids.insert(*target_id);
+        }
+    }
+    concept_ids
+}
+
+pub fn diff_fields<T: Serialize>(source: &T, target: &T) -> Vec<FieldDiffOutType> {
+    let source_fields = get_fields(source);
+    let mut target_fields = get_fields(target);
+    let mut fields = vec![];
+    for (name, source_value) in source_fields {
+        let target_value = target_fields.remove(&name).unwrap_or(Value::Null);
+        if source_value != target_value {
+            fields.push(FieldDiffOutType {
+                name,
+                source_value,
+                target_value,
+            });
+        }
+    }
+    for (name, target_value) in target_fields {
+        fields.push(FieldDiffOutType {
+            name,
+            source_value: Value::Null,
+            target_value,
+        });
+    }
+    fields
+}
+
+fn get_fields<T: Serialize>(element: &T) -> Map<String, Value> {
+    let mut element = match serde_json::to_value(element).unwrap() {
+        Value::Object(element) => element,
+        _ => unreachable!(),
+    };
+    let plugins_data = element.remove("pluginsData");
+    for field in SKIPPED_FIELDS {
+        element.remove(field);
+    }
+    if let Some(Value::Object(plugins_data)) = plugins_data {
+        for (plugin_key, plugin_data) in plugins_data {
+            if let Value::Object(plugin_data) = plugin_data {
+                for (name, value) in plugin_data {
+                    element.insert(format!("{}.{}", plugin_key, name), value);
+                }
+            }
+        }
+    }
+    element
+}
+
+fn write_model(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    mut project: Project,
+    plugin_names: &[String],
+    model: &ModelOutType,
+) -> ServiceResult<Project> {
+    let updated_at = Utc::now();
+    let concept_ids = write_concepts(conn, &project, &model.concepts, updated_at)?;
+    let connection_ids =
+        write_connections(conn, &project, &concept_ids, &model.connections, updated_at)?;
+    for plugin_name in plugin_names {
+        project = plugins.plugins[plugin_name]
+            .lock()
+            .unwrap()
+            .sync_model(conn, project)?;
+    }
+    for concept_out in &model.concepts {
+        write_concept_plugins_data(
+            conn,
+            concept_ids[&concept_out.id],
+            &concept_out.plugins_data,
+        )?;
+    }
+    for connection_out in &model.connections {
+        write_connection_plugins_data(
+            conn,
+            &project,
+            plugin_names,
+            connection_ids[&connection_out.id],
+            &connection_out.plugins_data,
+        )?;
+    }
+    project_services::update_project(conn, project.id, updated_at).to_service_result()
+}
+
+fn write_concepts(
+    conn: &mut PgConnection,
+    project: &Project,
+    concepts_out: &[ConceptOutType],
+    updated_at: DateTime<Utc>,
+) -> ServiceResult<HashMap<i32, i32>> {
+    let current_concepts = model_services::find_project_concepts(conn, project.id)?;
+    let mut concept_ids = match_concepts(
+        concepts_out
+            .iter()
+            .map(|concept_out| (concept_out.id, concept_out.name.as_str())),
+        current_concepts
+            .iter()
+            .map(|concept| (concept.id, concept.name.as_str())),
+    );
+    let matched_ids = concept_ids.values().copied().collect::<HashSet<_>>();
+    let stale_concept_ids = current_concepts
+        .iter()
+        .map(|concept| concept.id)
+        .filter(|concept_id| !matched_ids.contains(concept_id))
+        .collect::<Vec<_>>();
+    diesel::delete(concepts::table.filter(concepts::id.eq_any(stale_concept_ids))).execute(conn)?;
+    for concept_out in concepts_out {
+        match concept_ids.get(&concept_out.id) {
+            Some(concept_id) => {
+                diesel::update(concepts::table)
+                    .filter(concepts::id.eq(concept_id))
+                    .set((
+                        concepts::name.eq(&concept_out.name),
+                        concepts::description.eq(&concept_out.description),
+                        concepts::value.eq(concept_out.value),
+                        concepts::x_position.eq(concept_out.x_position),
+                        concepts::y_position.eq(concept_out.y_position),
+                        concepts::updated_at.eq(updated_at),
+                    ))
+                    .execute(conn)?;
+            }
+            None => {
+                let concept = diesel::insert_into(concepts::table)
+                    .values((
+                        concepts::project_id.eq(project.id),
+                        concepts::name.eq(&concept_out.name),
+                        concepts::description.eq(&concept_out.description),
+                        concepts::value.eq(concept_out.value),
+                        concepts::x_position.eq(concept_out.x_position),
+                        concepts::y_position.eq(concept_out.y_position),
+                    ))
+                    .get_result::<Concept>(conn)?;
+                concept_ids.insert(concept_out.id, concept.id);
+            }
+        }
+    }
+    Ok(concept_ids)
+}
+
+fn write_connections(
+    conn: &mut PgConnection,
+    project: &Project,
+    concept_ids: &HashMap<i32, i32>,
+    connections_out: &[ConnectionOutType],
+    updated_at: DateTime<Utc>,
+) -> ServiceResult<HashMap<i32, i32>> {
+    let current_connections = model_services::find_project_connections(conn, project.id)?
+        .into_iter()
+        .map(|connection| ((connection.source_id, connection.target_id), connection.id))
+        .collect::<HashMap<_, _>>();
+    let keys = connections_out
+        .iter()
+        .map(|connection_out| {
+            (
+                concept_ids[&connection_out.source_id],
+                concept_ids[&connection_out.target_id],
+            )
+        })
+        .collect::<Vec<_>>();
+    let stale_connection_ids = current_connections
+        .iter()
+        .filter(|(key, _)| !keys.contains(key))
+        .map(|(_, connection_id)| *connection_id)
+        .collect::<Vec<_>>();
+    diesel::delete(connections::table.filter(connections::id.eq_any(stale_connection_ids)))
+        .execute(conn)?;
+    let mut connection_ids = HashMap::new();
+    for (connection_out, key) in connections_out.iter().zip(keys) {
+        let connection_id = match current_connections.get(&key) {
+            Some(connection_id) => {
+                diesel::update(connections::table)
+                    .filter(connections::id.eq(connection_id))
+                    .set((
+                        connections::description.eq(&connection_out.description),
+                        connections::value.eq(connection_out.value),
+                        connections::updated_at.eq(updated_at),
+                    ))
+                    .execute(conn)?;
+                *connection_id
+            }
+            None => {
+                diesel::insert_into(connections::table)
+                    .values((
+                        connections::project_id.eq(project.id),
+                        connections::description.eq(&connection_out.description),
+                        connections::value.eq(connection_out.value),
+                        connections::source_id.eq(key.0),
+                        connections::target_id.eq(key.1),
+                    ))
+                    .get_result::<Connection>(conn)?
+                    .id
+            }
+        };
+        connection_ids.insert(connection_out.id, connection_id);
+    }
+    Ok(connection_ids)
+}
+
+pub fn write_concept_plugins_data(
+    conn: &mut PgConnection,
+    concept_id: i32,
+    plugins_data: &Value,
+) -> ServiceResult<()> {
+    if let Some(data) = get_plugin_data::<IsControlData>(plugins_data, "controlConcepts") {
+        diesel::update(control_concepts::table)
+            .filter(control_concepts::concept_id.eq(concept_id))
+            .set(control_concepts::is_control.eq(data.is_control))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<TargetData>(plugins_data, "targetConcepts") {
+        diesel::update(target_concepts::table)
+            .filter(target_concepts::concept_id.eq(concept_id))
+            .set((
+                target_concepts::is_target.eq(data.is_target),
+                target_concepts::min_value.eq(data.min_value),
+                target_concepts::include_min_value.eq(data.include_min_value),
+                target_concepts::max_value.eq(data.max_value),
+                target_concepts::include_max_value.eq(data.include_max_value),
+            ))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<ConstraintData>(plugins_data, "conceptConstraints") {
+        diesel::update(concept_constraints::table)
+            .filter(concept_constraints::concept_id.eq(concept_id))
+            .set((
+                concept_constraints::has_constraint.eq(data.has_constraint),
+                concept_constraints::min_value.eq(data.min_value),
+                concept_constraints::include_min_value.eq(data.include_min_value),
+                concept_constraints::max_value.eq(data.max_value),
+                concept_constraints::include_max_value.eq(data.include_max_value),
+            ))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<DecayRateData>(plugins_data, "conceptDecayRates") {
+        diesel::update(concept_decay_rates::table)
+            .filter(concept_decay_rates::concept_id.eq(concept_id))
+            .set(concept_decay_rates::decay_rate.eq(data.decay_rate))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<DynamicModelData>(plugins_data, "adjustment") {
+        diesel::update(concept_dynamic_models::table)
+            .filter(concept_dynamic_models::concept_id.eq(concept_id))
+            .set(concept_dynamic_models::dynamic_model_type.eq(data.dynamic_model_type))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<InterventionCostData>(plugins_data, "interventionCosts") {
+        diesel::update(concept_intervention_costs::table)
+            .filter(concept_intervention_costs::concept_id.eq(concept_id))
+            .set((
+                concept_intervention_costs::linear_cost.eq(data.linear_cost),
+                concept_intervention_costs::fixed_cost.eq(data.fixed_cost),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+pub fn write_connection_plugins_data(
+    conn: &mut PgConnection,
+    project: &Project,
+    plugin_names: &[String],
+    connection_id: i32,
+    plugins_data: &Value,
+) -> ServiceResult<()> {
+    if let Some(data) = get_plugin_data::<IsControlData>(plugins_data, "controlConnections") {
+        diesel::update(control_connections::table)
+            .filter(control_connections::connection_id.eq(connection_id))
+            .set(control_connections::is_control.eq(data.is_control))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<ConstraintData>(plugins_data, "connectionConstraints") {
+        diesel::update(connection_constraints::table)
+            .filter(connection_constraints::connection_id.eq(connection_id))
+            .set((
+                connection_constraints::has_constraint.eq(data.has_constraint),
+                connection_constraints::min_value.eq(data.min_value),
+                connection_constraints::include_min_value.eq(data.include_min_value),
+                connection_constraints::max_value.eq(data.max_value),
+                connection_constraints::include_max_value.eq(data.include_max_value),
+            ))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<FunctionData>(plugins_data, "connectionFunctions") {
+        diesel::update(connection_functions::table)
+            .filter(connection_functions::connection_id.eq(connection_id))
+            .set((
+                connection_functions::function_type.eq(data.function_type),
+                connection_functions::parameter.eq(data.parameter),
+                connection_functions::points.eq(data.points),
+                connection_functions::is_control.eq(data.is_control),
+            ))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<LagData>(plugins_data, "connectionLags") {
+        diesel::update(connection_lags::table)
+            .filter(connection_lags::connection_id.eq(connection_id))
+            .set(connection_lags::lag.eq(data.lag))
+            .execute(conn)?;
+    }
+    if let Some(data) = get_plugin_data::<InterventionCostData>(plugins_data, "interventionCosts") {
+        diesel::update(connection_intervention_costs::table)
+            .filter(connection_intervention_costs::connection_id.eq(connection_id))
+            .set((
+                connection_intervention_costs::linear_cost.eq(data.linear_cost),
+                connection_intervention_costs::fixed_cost.eq(data.fixed_cost),
+            ))
+            .execute(conn)?;
+    }
+    if let (Some(data), true) = (
+        get_plugin_data::<LinguisticData>(plugins_data, "linguisticConnections"),
+        plugin_names
+            .iter()
+            .any(|name| name == LINGUISTIC_CONNECTIONS_PLUGIN),
+    ) {
+        // Terms deleted after the snapshot was taken are skipped
+        let linguistic_term_ids =
+            linguistic_connections_services::find_project_linguistic_terms(conn, project.id)?
+                .into_iter()
+                .map(|linguistic_term| linguistic_term.id)
+                .filter(|linguistic_term_id| data.linguistic_term_ids.contains(linguistic_term_id))
+                .collect::<Vec<_>>();
+        diesel::delete(
+            connection_linguistic_terms::table
+                .filter(connection_linguistic_terms::connection_id.eq(connection_id)),
+        )
+        .execute(conn)?;
+        let insert_rows = data
+            .linguistic_term_ids
+            .iter()
+            .filter(|linguistic_term_id| linguistic_term_ids.contains(linguistic_term_id))
+            .map(|linguistic_term_id| {
+                (
+                    connection_linguistic_terms::connection_id.eq(connection_id),
+                    connection_linguistic_terms::linguistic_term_id.eq(*linguistic_term_id),
+                )
+            })
+            .collect::<Vec<_>>();
+        if !insert_rows.is_empty() {
+            diesel::insert_into(connection_linguistic_terms::table)
+                .values(&insert_rows)
+                .execute(conn)?;
+        }
+    }
+    Ok(())
+}
+
+impl ModelSnapshotOutType {
+    pub fn from_model_snapshots(
+        conn: &mut PgConnection,
+        model_snapshots: Vec<ModelSnapshot>,
+    ) -> ServiceResult<Vec<Self>> {
+        let creator_ids = model_snapshots
+            .iter()
+            .map(|model_snapshot| model_snapshot.creator_id)
+            .collect::<HashSet<_>>();
+        let creators = user_services::find_users_by_id(conn, creator_ids.into_iter())
+            .to_service_result()?
+            .into_iter()
+            .map(|creator| (creator.id, creator))
+            .collect::<HashMap<_, _>>();
+        Ok(model_snapshots
+            .into_iter()
+            .map(|model_snapshot| {
+                let creator = creators[&model_snapshot.creator_id].clone();
+                Self::from((model_snapshot, creator))
+            })
+            .collect())
+    }
+}
+
+impl From<(ModelSnapshot, User)> for ModelSnapshotOutType {
+    fn from((model_snapshot, creator): (ModelSnapshot, User)) -> Self {
+        Self {
+            id: model_snapshot.id,
+            project_id: model_snapshot.project_id,
+            model_copy_id: model_snapshot.model_copy_id,
+            creator: UserOutType::from(creator),
+            name: model_snapshot.name,
+            description: model_snapshot.description,
+            is_automatic: model_snapshot.is_automatic,
+            created_at: model_snapshot.created_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_concept(id: i32, name: &str, value: f64) -> Value {
+        json!({
+            "id": id,
+            "name": name,
+            "description": "",
+            "value": value,
+            "projectId": 1,
+            "xPosition": 0.0,
+            "yPosition": 0.0,
+            "pluginsData": {},
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z"
+        })
+    }
+
+    fn get_connection(id: i32, source_id: i32, target_id: i32, value: f64) -> Value {
+        json!({
+            "id": id,
+            "description": "",
+            "value": value,
+            "sourceId": source_id,
+            "targetId": target_id,
+            "projectId": 1,
+            "pluginsData": {},
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-01T00:00:00Z"
+        })
+    }
+
+    fn get_model(concepts: Vec<Value>, connections: Vec<Value>) -> ModelOutType {
+        serde_json::from_value(json!({
+            "project": {
+                "id": 1,
+                "name": "Project",
+                "description": "",
+                "creator": {
+                    "id": 1,
+                    "username": "user",
+                    "email": "user@example.com",
+                    "isEmailConfirmed": true,
+                    "firstName": "First",
+                    "secondName": null,
+                    "lastName": "Last",
+                    "avatar": null,
+                    "locale": null,
+                    "createdAt": "2024-01-01T00:00:00Z",
+                    "updatedAt": "2024-01-01T00:00:00Z"
+                },
+                "isPublic": false,
+                "isArchived": false,
+                "createdAt": "2024-01-01T00:00:00Z",
+                "updatedAt": "2024-01-01T00:00:00Z",
+                "conceptValueType": "from_zero_to_one",
+                "connectionValueType": "from_minus_one_to_one",
+                "plugins": []
+            },
+            "concepts": concepts,
+            "connections": connections
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn match_concepts_by_id_then_by_name() {
+        let concept_ids = match_concepts(
+            vec![(1, "a"), (2, "b"), (3, "c")].into_iter(),
+            vec![(1, "renamed"), (4, "b"), (5, "d")].into_iter(),
+        );
+        assert_eq!(concept_ids, HashMap::from([(1, 1), (2, 4)]));
+    }
+
+    #[test]
+    fn match_concepts_uses_each_target_once() {
+        let concept_ids = match_concepts(
+            vec![(1, "a"), (2, "a")].into_iter(),
+            vec![(3, "a")].into_iter(),
+        );
+        assert_eq!(concept_ids, HashMap::from([(1, 3)]));
+    }
+
+    #[test]
+    fn diff_identical_models() {
+        let model = get_model(
+            vec![get_concept(1, "a", 0.5), get_concept(2, "b", 0.5)],
+            vec![get_connection(1, 1, 2, 0.5)],
+        );
+        let model_diff = diff_models(&model, &model);
+        assert!(model_diff.added_concepts.is_empty());
+        assert!(model_diff.removed_concepts.is_empty());
+        assert!(model_diff.changed_concepts.is_empty());
+        assert!(model_diff.added_connections.is_empty());
+        assert!(model_diff.removed_connections.is_empty());
+        assert!(model_diff.changed_connections.is_empty());
+    }
+
+    #[test]
+    fn diff_changed_models() {
+        let source_model = get_model(
+            vec![
+                get_concept(1, "a", 0.5),
+                get_concept(2, "b", 0.5),
+                get_concept(3, "c", 0.5),
+            ],
+            vec![get_connection(1, 1, 2, 0.5), get_connection(2, 2, 3, 0.5)],
+        );
+        // The concept `b` is recreated with a new identifier, and `c` is deleted
+        let target_model = get_model(
+            vec![
+                get_concept(1, "a", 0.7),
+                get_concept(4, "b", 0.5),
+                get_concept(5, "d", 0.5),
+            ],
+            vec![get_connection(3, 1, 4, -0.5), get_connection(4, 4, 5, 0.5)],
+        );
+        let model_diff = diff_models(&source_model, &target_model);
+        assert_eq!(model_diff.changed_concepts.len(), 1);
+        let concept_diff = &model_diff.changed_concepts[0];
+        assert_eq!(concept_diff.source.id, 1);
+        assert_eq!(concept_diff.fields.len(), 1);
+        assert_eq!(concept_diff.fields[0].name, "value");
+        assert_eq!(concept_diff.fields[0].source_value, json!(0.5));
+        assert_eq!(concept_diff.fields[0].target_value, json!(0.7));
+        assert_eq!(
+            model_diff
+                .removed_concepts
+                .iter()
+                .map(|concept_out| concept_out.id)
+                .collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(
+            model_diff
+                .added_concepts
+                .iter()
+                .map(|concept_out| concept_out.id)
+                .collect::<Vec<_>>(),
+            vec![5]
+        );
+        assert_eq!(model_diff.changed_connections.len(), 1);
+        assert_eq!(model_diff.changed_connections[0].target.id, 3);
+        assert_eq!(
+            model_diff
+                .removed_connections
+                .iter()
+                .map(|connection_out| connection_out.id)
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(
+            model_diff
+                .added_connections
+                .iter()
+                .map(|connection_out| connection_out.id)
+                .collect::<Vec<_>>(),
+            vec![4]
+        );
+    }
+
+    #[test]
+    fn diff_fields_flattens_plugins_data() {
+        let mut source_concept = get_concept(1, "a", 0.5);
+        let mut target_concept = get_concept(1, "a", 0.5);
+        source_concept["pluginsData"] = json!({"controlConcepts": {"isControl": false}});
+        target_concept["pluginsData"] = json!({"controlConcepts": {"isControl": true}});
+        let fields = diff_fields(&source_concept, &target_concept);
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields[0].name, "controlConcepts.isControl");
+        assert_eq!(fields[0].source_value, json!(false));
+        assert_eq!(fields[0].target_value, json!(true));
+    }
+}
@@ -0,0 +1,362 @@
+use crate::models::{ModelTemplate, Project, User};
+use crate::pagination::Paginate;
+use crate::plugins::Plugins;
+use crate::response::{AppError, ServiceResult, ToServiceResult};
+use crate::schema::{connections, model_templates};
+use crate::services::{
+    model_batch_services, model_history_services, permission_services, plugin_services,
+    plugins_data_services, project_services,
+};
+use crate::types::{
+    ConceptInBatchCreateType, ConnectionInBatchCreateType, ModelActionType, ModelBatchIdInType,
+    ModelBatchInType, ModelBatchOperationInType, ModelBatchOutType, ModelElementsType,
+    ModelFragmentInPasteType, ModelTemplateInType, ModelTemplateOutType, PaginationInType,
+    PaginationOutType,
+};
+use crate::validation_error;
+use crate::web_socket::WebSocketModelService;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::PgConnection;
+
+pub fn get_model_fragment(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    concept_ids: &[i32],
+) -> ServiceResult<ModelElementsType> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_view_project(conn, &project, user)?;
+    read_model_fragment(conn, plugins, &project, concept_ids)
+}
+
+pub fn create_model_template(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    project_id: i32,
+    model_template_in: ModelTemplateInType,
+) -> ServiceResult<ModelTemplateOutType> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_view_project(conn, &project, user)?;
+    let fragment = read_model_fragment(conn, plugins, &project, &model_template_in.concept_ids)?;
+    let model_template = diesel::insert_into(model_templates::table)
+        .values((
+            model_templates::user_id.eq(user.id),
+            model_templates::name.eq(model_template_in.name),
+            model_templates::description.eq(model_template_in.description),
+            model_templates::fragment.eq(serde_json::to_value(fragment).unwrap()),
+        ))
+        .get_result::<ModelTemplate>(conn)
+        .to_service_result()?;
+    ModelTemplateOutType::try_from(model_template)
+}
+
+pub fn paginate_model_templates(
+    conn: &mut PgConnection,
+    user: &User,
+    search: Option<String>,
+    pagination_in: PaginationInType,
+) -> ServiceResult<PaginationOutType<ModelTemplateOutType>> {
+    let mut query = model_templates::table
+        .filter(model_templates::user_id.eq(user.id))
+        .order(model_templates::created_at.desc())
+        .into_boxed();
+    if let Some(search) = search {
+        let like_pattern = format!("{}%", search);
+        query = query.filter(
+            model_templates::name
+                .ilike(like_pattern.to_owned())
+                .or(model_templates::description.ilike(like_pattern)),
+        );
+    }
+    let (model_templates, total_count, total_pages) = query
+        .paginate(pagination_in.page as i64)
+        .per_page(pagination_in.per_page as i64)
+        .load_and_count_pages::<ModelTemplate>(conn)
+        .to_service_result()?;
+    Ok(PaginationOutType {
+        data: model_templates
+            .into_iter()
+            .map(ModelTemplateOutType::try_from)
+            .collect::<ServiceResult<Vec<_>>>()?,
+        total_count: total_count as i32,
+        total_pages: total_pages as i32,
+    })
+}
+
+pub fn delete_model_template(
+    conn: &mut PgConnection,
+    user: &User,
+    model_template_id: i32,
+) -> ServiceResult<()> {
+    let deleted_number = diesel::delete(
+        model_templates::table
+            .filter(model_templates::id.eq(model_template_id))
+            .filter(model_templates::user_id.eq(user.id)),
+    )
+    .execute(conn)
+    .to_service_result()?;
+    if deleted_number == 0 {
+        return validation_error!("model_template_not_found_error");
+    }
+    Ok(())
+}
+
+pub async fn paste_model_fragment(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    model_service: WebSocketModelService,
+    user: &User,
+    project_id: i32,
+    model_fragment_in: ModelFragmentInPasteType,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<ModelActionType<ModelBatchOutType>> {
+    let fragment = match (
+        model_fragment_in.model_template_id,
+        model_fragment_in.fragment,
+    ) {
+        (Some(model_template_id), None) => {
+            let model_template = find_user_model_template(conn, user, model_template_id)
+                .to_service_result_find(String::from("model_template_not_found_error"))?;
+            ModelTemplateOutType::try_from(model_template)?.fragment
+        }
+        (None, Some(fragment)) => fragment,
+        _ => return validation_error!("model_fragment_source_error"),
+    };
+    let plugin_names =
+        plugin_services::find_project_plugin_names(conn, project_id).to_service_result()?;
+    let operations = get_paste_operations(
+        fragment,
+        &plugin_names,
+        model_fragment_in.x_offset,
+        model_fragment_in.y_offset,
+    );
+    // Pasting is a batch of creations, so permissions and value checks of the model apply
+    model_batch_services::apply_model_batch(
+        conn,
+        plugins,
+        model_service,
+        user,
+        project_id,
+        ModelBatchInType { operations },
+        expected_version,
+    )
+    .await
+}
+
+pub fn find_user_model_template(
+    conn: &mut PgConnection,
+    user: &User,
+    model_template_id: i32,
+) -> QueryResult<ModelTemplate> {
+    model_templates::table
+        .filter(model_templates::id.eq(model_template_id))
+        .filter(model_templates::user_id.eq(user.id))
+        .get_result::<ModelTemplate>(conn)
+}
+
+fn read_model_fragment(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    project: &Project,
+    concept_ids: &[i32],
+) -> ServiceResult<ModelElementsType> {
+    let connection_ids = connections::table
+        .select(connections::id)
+        .filter(connections::project_id.eq(project.id))
+        .filter(connections::source_id.eq_any(concept_ids.to_vec()))
+        .filter(connections::target_id.eq_any(concept_ids.to_vec()))
+        .order(connections::id)
+        .get_results::<i32>(conn)
+        .to_service_result()?;
+    let fragment = model_history_services::get_model_elements(
+        conn,
+        plugins,
+        project,
+        concept_ids,
+        &connection_ids,
+    )?;
+    if fragment.concepts.is_empty() {
+        return validation_error!("model_fragment_empty_error");
+    }
+    Ok(fragment)
+}
+
+// Fragment identifiers become temporary identifiers of the created elements,
+// plugins data is validated by the batch against the target project
+fn get_paste_operations(
+    fragment: ModelElementsType,
+    plugin_names: &[String],
+    x_offset: f64,
+    y_offset: f64,
+) -> Vec<ModelBatchOperationInType> {
+    let concept_operations = fragment.concepts.into_iter().map(|mut concept_out| {
+        plugins_data_services::retain_enabled_plugins_data(
+            &mut concept_out.plugins_data,
+            plugin_names,
+        );
+        ModelBatchOperationInType::CreateConcept {
+            concept: ConceptInBatchCreateType {
+                temporary_id: Some(concept_out.id.to_string()),
+                name: concept_out.name,
+                description: concept_out.description,
+                value: concept_out.value,
+                x_position: concept_out.x_position + x_offset,
+                y_position: concept_out.y_position + y_offset,
+                plugins_data: Some(concept_out.plugins_data),
+            },
+        }
+    });
+    let connection_operations = fragment.connections.into_iter().map(|mut connection_out| {
+        plugins_data_services::retain_enabled_plugins_data(
+            &mut connection_out.plugins_data,
+            plugin_names,
+        );
+        ModelBatchOperationInType::CreateConnection {
+            connection: ConnectionInBatchCreateType {
+                temporary_id: Some(connection_out.id.to_string()),
+                description: connection_out.description,
+                value: connection_out.value,
+                source_id: ModelBatchIdInType::TemporaryId(connection_out.source_id.to_string()),
+                target_id: ModelBatchIdInType::TemporaryId(connection_out.target_id.to_string()),
+                plugins_data: Some(connection_out.plugins_data),
+            },
+        }
+    });
+    concept_operations.chain(connection_operations).collect()
+}
+
+impl TryFrom<ModelTemplate> for ModelTemplateOutType {
+    type Error = AppError;
+
+    fn try_from(model_template: ModelTemplate) -> ServiceResult<Self> {
+        let fragment = match serde_json::from_value::<ModelElementsType>(model_template.fragment) {
+            Ok(fragment) => fragment,
+            Err(_) => return validation_error!("model_template_fragment_error"),
+        };
+        Ok(Self {
+            id: model_template.id,
+            name: model_template.name,
+            description: model_template.description,
+            fragment,
+            created_at: model_template.created_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn get_fragment() -> ModelElementsType {
+        serde_json::from_value(json!({
+            "concepts": [
+                {
+                    "id": 10,
+                    "name": "A",
+                    "description": "",
+                    "value": 0.5,
+                    "projectId": 1,
+                    "xPosition": 100.0,
+                    "yPosition": 200.0,
+                    "pluginsData": {
+                        "controlConcepts": {"isControl": true},
+                        "conceptDecayRates": {"decayRate": 0.1}
+                    },
+                    "createdAt": "2024-01-01T00:00:00Z",
+                    "updatedAt": "2024-01-01T00:00:00Z"
+                },
+                {
+                    "id": 11,
+                    "name": "B",
+                    "description": "",
+                    "value": null,
+                    "projectId": 1,
+                    "xPosition": 0.0,
+                    "yPosition": 0.0,
+                    "pluginsData": {},
+                    "createdAt": "2024-01-01T00:00:00Z",
+                    "updatedAt": "2024-01-01T00:00:00Z"
+                }
+            ],
+            "connections": [
+                {
+                    "id": 20,
+                    "description": "Influence",
+                    "value": -0.5,
+                    "sourceId": 10,
+                    "targetId": 11,
+                    "projectId": 1,
+                    "pluginsData": {"connectionLags": {"lag": 2}},
+                    "createdAt": "2024-01-01T00:00:00Z",
+                    "updatedAt": "2024-01-01T00:00:00Z"
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn paste_operations_create_concepts_before_connections() {
+        let plugin_names = vec![String::from("Control Concepts")];
+        let operations = get_paste_operations(get_fragment(), &plugin_names, 10.0, -20.0);
+        assert_eq!(operations.len(), 3);
+        match &operations[0] {
+            ModelBatchOperationInType::CreateConcept { concept } => {
+                assert_eq!(concept.temporary_id.as_deref(), Some("10"));
+                assert_eq!(concept.name, "A");
+                assert_eq!(concept.value, Some(0.5));
+                assert_eq!(concept.x_position, 110.0);
+                assert_eq!(concept.y_position, 180.0);
+                // Data of plugins which are not enabled in the target project is dropped
+                assert_eq!(
+                    concept.plugins_data,
+                    Some(json!({"controlConcepts": {"isControl": true}}))
+                );
+            }
+            _ => panic!("expected concept creation"),
+        }
+        match &operations[1] {
+            ModelBatchOperationInType::CreateConcept { concept } => {
+                assert_eq!(concept.temporary_id.as_deref(), Some("11"));
+                assert_eq!(concept.value, None);
+            }
+            _ => panic!("expected concept creation"),
+        }
+        match &operations[2] {
+            ModelBatchOperationInType::CreateConnection { connection } => {
+                assert_eq!(connection.temporary_id.as_deref(), Some("20"));
+                assert_eq!(connection.description, "Influence");
+                assert_eq!(connection.value, -0.5);
+                assert!(matches!(
+                    &connection.source_id,
+                    ModelBatchIdInType::TemporaryId(source_id) if source_id == "10"
+                ));
+                assert!(matches!(
+                    &connection.target_id,
+                    ModelBatchIdInType::TemporaryId(target_id) if target_id == "11"
+                ));
+                assert_eq!(connection.plugins_data, Some(json!({})));
+            }
+            _ => panic!("expected connection creation"),
+        }
+    }
+
+    #[test]
+    fn malformed_model_template_is_rejected() {
+        let model_template = ModelTemplate {
+            id: 1,
+            user_id: 1,
+            name: String::from("Template"),
+            description: String::new(),
+            fragment: json!({"concepts": "A"}),
+            created_at: Utc::now(),
+        };
+        assert!(ModelTemplateOutType::try_from(model_template).is_err());
+    }
+}
@@ -72,7 +72,7 @@ pub fn find_project_plugin_names(
         .collect())
 }
 
-fn check_plugins(
+pub fn check_plugins(
     conn: &mut PgConnection,
     project: &Project,
     plugins: &[String],
@@ -0,0 +1,441 @@
+use crate::models::Project;
+use crate::plugins::adjustment::models::DynamicModelType;
+use crate::plugins::connection_functions::models::ConnectionFunctionType;
+use crate::plugins::connection_functions::services as connection_functions_services;
+use crate::plugins::connection_functions::types::FunctionPointType;
+use crate::response::ServiceResult;
+use crate::services::model_services;
+use crate::validation_error;
+use fuzzy_cognitive_model_common::adjustment::MAX_CONNECTION_LAG;
+use rocket::serde::de::DeserializeOwned;
+use rocket::serde::Deserialize;
+use serde_json::Value;
+
+const PLUGIN_KEYS: [(&str, &str); 11] = [
+    ("controlConcepts", "Control Concepts"),
+    ("targetConcepts", "Target Concepts"),
+    ("conceptConstraints", "Concept Constraints"),
+    ("conceptDecayRates", "Concept Decay Rates"),
+    ("adjustment", "Adjustment With Genetic Algorithms"),
+    ("interventionCosts", "Intervention Costs"),
+    ("controlConnections", "Control Connections"),
+    ("connectionConstraints", "Connection Constraints"),
+    ("connectionFunctions", "Connection Functions"),
+    ("connectionLags", "Connection Lags"),
+    ("linguisticConnections", "Linguistic Connections"),
+];
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IsControlData {
+    pub is_control: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetData {
+    pub is_target: bool,
+    pub min_value: f64,
+    pub include_min_value: bool,
+    pub max_value: f64,
+    pub include_max_value: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstraintData {
+    pub has_constraint: bool,
+    pub min_value: f64,
+    pub include_min_value: bool,
+    pub max_value: f64,
+    pub include_max_value: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecayRateData {
+    pub decay_rate: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DynamicModelData {
+    pub dynamic_model_type: Option<DynamicModelType>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterventionCostData {
+    pub linear_cost: f64,
+    pub fixed_cost: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionData {
+    pub function_type: ConnectionFunctionType,
+    pub parameter: f64,
+    pub points: Value,
+    pub is_control: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LagData {
+    pub lag: i32,
+}
+
+pub fn get_plugin_data<T: DeserializeOwned>(plugins_data: &Value, plugin_key: &str) -> Option<T> {
+    serde_json::from_value(plugins_data.get(plugin_key)?.clone()).ok()
+}
+
+// Data of plugins which are not enabled in the project is dropped
+pub fn retain_enabled_plugins_data(plugins_data: &mut Value, plugin_names: &[String]) {
+    if let Value::Object(plugins_data) = plugins_data {
+        plugins_data.retain(|plugin_key, _| match get_plugin_name(plugin_key) {
+            Some(plugin_name) => plugin_names.iter().any(|name| name == plugin_name),
+            None => true,
+        });
+    }
+}
+
+pub fn check_concept_plugins_data(
+    project: &Project,
+    plugin_names: &[String],
+    value: Option<f64>,
+    plugins_data: &Value,
+) -> ServiceResult<()> {
+    check_plugins_are_enabled(plugin_names, plugins_data)?;
+    let is_control = get_plugin_data::<IsControlData>(plugins_data, "controlConcepts")
+        .map_or(false, |data| data.is_control);
+    if let Some(data) = get_plugin_data::<TargetData>(plugins_data, "targetConcepts") {
+        if data.is_target {
+            if is_control {
+                return validation_error!("concept_is_target_error");
+            }
+            model_services::check_concept_value(project, Some(data.min_value))?;
+            model_services::check_concept_value(project, Some(data.max_value))?;
+        }
+    }
+    if let Some(data) = get_plugin_data::<ConstraintData>(plugins_data, "conceptConstraints") {
+        if data.has_constraint {
+            model_services::check_concept_value(project, Some(data.min_value))?;
+            model_services::check_concept_value(project, Some(data.max_value))?;
+            if let Some(value) = value.filter(|value| !data.contains(*value)) {
+                let range = data.format_range();
+                return validation_error!(
+                    "concept_constraint_range_error",
+                    range = range,
+                    value = value
+                );
+            }
+        }
+    }
+    if let Some(data) = get_plugin_data::<DecayRateData>(plugins_data, "conceptDecayRates") {
+        if data.decay_rate < 0.0 {
+            return validation_error!("concept_decay_rate_negative_error");
+        }
+    }
+    if let Some(data) = get_plugin_data::<InterventionCostData>(plugins_data, "interventionCosts") {
+        data.check()?;
+    }
+    Ok(())
+}
+
+pub fn check_connection_plugins_data(
+    project: &Project,
+    plugin_names: &[String],
+    value: f64,
+    plugins_data: &Value,
+) -> ServiceResult<()> {
+    check_plugins_are_enabled(plugin_names, plugins_data)?;
+    if let Some(data) = get_plugin_data::<ConstraintData>(plugins_data, "connectionConstraints") {
+        if data.has_constraint {
+            model_services::check_connection_value(project, data.min_value)?;
+            model_services::check_connection_value(project, data.max_value)?;
+            if !data.contains(value) {
+                let range = data.format_range();
+                return validation_error!(
+                    "connection_constraint_range_error",
+                    range = range,
+                    value = value
+                );
+            }
+        }
+    }
+    if let Some(data) = get_plugin_data::<FunctionData>(plugins_data, "connectionFunctions") {
+        let points = match serde_json::from_value::<Vec<FunctionPointType>>(data.points) {
+            Ok(points) => points,
+            Err(_) => return validation_error!("connection_function_parameters_error"),
+        };
+        let function = connection_functions_services::get_function(
+            &data.function_type,
+            data.parameter,
+            &points,
+        );
+        if !function.is_valid() {
+            return validation_error!("connection_function_parameters_error");
+        }
+        if data.is_control && function.get_parameter().is_none() {
+            return validation_error!("connection_function_not_adjustable_error");
+        }
+    }
+    if let Some(data) = get_plugin_data::<LagData>(plugins_data, "connectionLags") {
+        if data.lag < 0 {
+            return validation_error!("connection_lag_negative_error");
+        }
+        if data.lag > MAX_CONNECTION_LAG {
+            let max_lag = MAX_CONNECTION_LAG.to_string();
+            return validation_error!("connection_lag_max_error", max_lag = &max_lag);
+        }
+    }
+    if let Some(data) = get_plugin_data::<InterventionCostData>(plugins_data, "interventionCosts") {
+        data.check()?;
+    }
+    Ok(())
+}
+
+fn check_plugins_are_enabled(plugin_names: &[String], plugins_data: &Value) -> ServiceResult<()> {
+    if let Value::Object(plugins_data) = plugins_data {
+        for plugin_key in plugins_data.keys() {
+            if let Some(plugin_name) = get_plugin_name(plugin_key) {
+                if !plugin_names.iter().any(|name| name == plugin_name) {
+                    let plugin_name = plugin_name.to_owned();
+                    return validation_error!(
+                        "plugin_is_not_enabled_error",
+                        plugin_name = &plugin_name
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn get_plugin_name(plugin_key: &str) -> Option<&'static str> {
+    PLUGIN_KEYS
+        .iter()
+        .find(|(key, _)| *key == plugin_key)
+        .map(|(_, plugin_name)| *plugin_name)
+}
+
+impl ConstraintData {
+    fn contains(&self, value: f64) -> bool {
+        (if self.include_min_value {
+            value >= self.min_value
+        } else {
+            value > self.min_value
+        }) && (if self.include_max_value {
+            value <= self.max_value
+        } else {
+            value < self.max_value
+        })
+    }
+
+    fn format_range(&self) -> String {
+        format!(
+            "{}{}; {}{}",
+            if self.include_min_value { "[" } else { "(" },
+            self.min_value,
+            self.max_value,
+            if self.include_max_value { "]" } else { ")" },
+        )
+    }
+}
+
+impl InterventionCostData {
+    fn check(&self) -> ServiceResult<()> {
+        if self.linear_cost < 0.0 || self.fixed_cost < 0.0 {
+            return validation_error!("intervention_cost_negative_error");
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ConceptValueType, ConnectionValueType};
+    use chrono::Utc;
+    use serde_json::json;
+
+    fn get_project() -> Project {
+        Project {
+            id: 1,
+            name: String::from("Project"),
+            description: String::new(),
+            is_public: false,
+            is_archived: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            concept_value_type: ConceptValueType::FromZeroToOne,
+            connection_value_type: ConnectionValueType::FromMinusOneToOne,
+        }
+    }
+
+    fn get_plugin_names() -> Vec<String> {
+        PLUGIN_KEYS
+            .iter()
+            .map(|(_, plugin_name)| plugin_name.to_string())
+            .collect()
+    }
+
+    fn check_concept(value: Option<f64>, plugins_data: Value) -> bool {
+        check_concept_plugins_data(&get_project(), &get_plugin_names(), value, &plugins_data)
+            .is_ok()
+    }
+
+    fn check_connection(value: f64, plugins_data: Value) -> bool {
+        check_connection_plugins_data(&get_project(), &get_plugin_names(), value, &plugins_data)
+            .is_ok()
+    }
+
+    fn get_range(has_range: bool, min_value: f64, max_value: f64) -> Value {
+        json!({
+            "isTarget": has_range,
+            "hasConstraint": has_range,
+            "minValue": min_value,
+            "includeMinValue": true,
+            "maxValue": max_value,
+            "includeMaxValue": false
+        })
+    }
+
+    #[test]
+    fn check_concept_data() {
+        assert!(check_concept(Some(0.5), json!({})));
+        assert!(check_concept(
+            Some(0.5),
+            json!({
+                "controlConcepts": {"isControl": true},
+                "targetConcepts": get_range(false, 0.0, 1.0),
+                "conceptConstraints": get_range(true, 0.2, 0.8),
+                "conceptDecayRates": {"decayRate": 0.1},
+                "interventionCosts": {"linearCost": 1.0, "fixedCost": 0.0}
+            })
+        ));
+        assert!(!check_concept(
+            Some(0.5),
+            json!({
+                "controlConcepts": {"isControl": true},
+                "targetConcepts": get_range(true, 0.0, 1.0)
+            })
+        ));
+        assert!(!check_concept(
+            Some(0.5),
+            json!({"targetConcepts": get_range(true, 0.5, 1.5)})
+        ));
+        assert!(!check_concept(
+            Some(0.5),
+            json!({"conceptConstraints": get_range(true, -0.5, 0.8)})
+        ));
+        // The value must lie in the constraint range, which excludes its maximum
+        assert!(!check_concept(
+            Some(0.8),
+            json!({"conceptConstraints": get_range(true, 0.2, 0.8)})
+        ));
+        assert!(!check_concept(
+            Some(0.5),
+            json!({"conceptDecayRates": {"decayRate": -0.1}})
+        ));
+        assert!(!check_concept(
+            Some(0.5),
+            json!({"interventionCosts": {"linearCost": 1.0, "fixedCost": -1.0}})
+        ));
+    }
+
+    #[test]
+    fn check_connection_data() {
+        let get_function = |function_type: &str, parameter: f64, points: Value| {
+            json!({
+                "connectionFunctions": {
+                    "functionType": function_type,
+                    "parameter": parameter,
+                    "points": points,
+                    "isControl": false
+                }
+            })
+        };
+        assert!(check_connection(0.5, json!({})));
+        assert!(check_connection(
+            -0.5,
+            json!({
+                "connectionConstraints": get_range(true, -1.0, 0.0),
+                "connectionLags": {"lag": MAX_CONNECTION_LAG},
+                "interventionCosts": {"linearCost": 0.0, "fixedCost": 2.0}
+            })
+        ));
+        assert!(!check_connection(
+            0.5,
+            json!({"connectionConstraints": get_range(true, -2.0, 1.0)})
+        ));
+        assert!(!check_connection(
+            0.5,
+            json!({"connectionConstraints": get_range(true, -1.0, 0.0)})
+        ));
+        assert!(check_connection(
+            0.5,
+            get_function(
+                "piecewise_linear",
+                0.0,
+                json!([{"x": -1.0, "y": -1.0}, {"x": 1.0, "y": 1.0}])
+            )
+        ));
+        assert!(!check_connection(
+            0.5,
+            get_function("threshold", 1.5, json!([]))
+        ));
+        assert!(!check_connection(
+            0.5,
+            get_function(
+                "piecewise_linear",
+                0.0,
+                json!([{"x": 1.0, "y": 1.0}, {"x": -1.0, "y": -1.0}])
+            )
+        ));
+        assert!(!check_connection(
+            0.5,
+            get_function("piecewise_linear", 0.0, json!({"x": 1.0}))
+        ));
+        assert!(!check_connection(
+            0.5,
+            json!({"connectionLags": {"lag": -1}})
+        ));
+        assert!(!check_connection(
+            0.5,
+            json!({"connectionLags": {"lag": MAX_CONNECTION_LAG + 1}})
+        ));
+        assert!(!check_connection(
+            0.5,
+            json!({"interventionCosts": {"linearCost": -1.0, "fixedCost": 0.0}})
+        ));
+    }
+
+    #[test]
+    fn check_enabled_plugins() {
+        let plugin_names = vec![String::from("Connection Lags")];
+        let plugins_data = json!({
+            "connectionLags": {"lag": 1},
+            "unknownPlugin": {}
+        });
+        assert!(
+            check_connection_plugins_data(&get_project(), &plugin_names, 0.5, &plugins_data)
+                .is_ok()
+        );
+        let plugins_data = json!({"controlConnections": {"isControl": true}});
+        assert!(
+            check_connection_plugins_data(&get_project(), &plugin_names, 0.5, &plugins_data)
+                .is_err()
+        );
+        let mut plugins_data = json!({
+            "controlConnections": {"isControl": true},
+            "connectionLags": {"lag": 1},
+            "unknownPlugin": {}
+        });
+        retain_enabled_plugins_data(&mut plugins_data, &plugin_names);
+        assert_eq!(
+            plugins_data,
+            json!({"connectionLags": {"lag": 1}, "unknownPlugin": {}})
+        );
+    }
+}
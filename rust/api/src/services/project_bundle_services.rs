@@ -0,0 +1,997 @@
+use crate::models::{Concept, Connection, ModelCopy, Project, User};
+use crate::plugins::adjustment::models::{
+    AdjustmentConceptValue, AdjustmentConnectionFunctionValue, AdjustmentConnectionValue,
+    AdjustmentGeneration, AdjustmentIndividual, AdjustmentRun, AdjustmentRunState,
+    AdjustmentScenarioError, ConceptDynamicModel,
+};
+use crate::plugins::adjustment::services::concept_dynamic_model_services;
+use crate::plugins::adjustment::types::{ControlOverridesType, ScenarioType};
+use crate::plugins::concept_constraints::models::ConceptConstraint;
+use crate::plugins::concept_constraints::services as concept_constraints_services;
+use crate::plugins::concept_decay_rates::models::ConceptDecayRate;
+use crate::plugins::concept_decay_rates::services as concept_decay_rates_services;
+use crate::plugins::connection_constraints::models::ConnectionConstraint;
+use crate::plugins::connection_constraints::services as connection_constraints_services;
+use crate::plugins::connection_functions::models::ConnectionFunction;
+use crate::plugins::connection_functions::services as connection_functions_services;
+use crate::plugins::connection_lags::models::ConnectionLag;
+use crate::plugins::connection_lags::services as connection_lags_services;
+use crate::plugins::control_concepts::models::ControlConcept;
+use crate::plugins::control_concepts::services as control_concepts_services;
+use crate::plugins::control_connections::models::ControlConnection;
+use crate::plugins::control_connections::services as control_connections_services;
+use crate::plugins::intervention_costs::models::{
+    ConceptInterventionCost, ConnectionInterventionCost,
+};
+use crate::plugins::intervention_costs::services as intervention_costs_services;
+use crate::plugins::linguistic_connections::models::{
+    ConnectionLinguisticTerm, LinguisticTerm, LinguisticTermSet,
+};
+use crate::plugins::linguistic_connections::services as linguistic_connections_services;
+use crate::plugins::target_concepts::models::TargetConcept;
+use crate::plugins::target_concepts::services as target_concepts_services;
+use crate::plugins::Plugins;
+use crate::response::{ServiceResult, ToServiceResult};
+use crate::schema::{
+    adjustment_concept_values, adjustment_connection_function_values, adjustment_connection_values,
+    adjustment_generations, adjustment_individuals, adjustment_runs, adjustment_scenario_errors,
+    concept_constraints, concept_decay_rates, concept_dynamic_models, concept_intervention_costs,
+    concepts, connection_constraints, connection_functions, connection_intervention_costs,
+    connection_lags, connection_linguistic_terms, connections, control_concepts,
+    control_connections, linguistic_term_sets, linguistic_terms, model_copies, project_plugins,
+    target_concepts,
+};
+use crate::services::{model_services, permission_services, plugin_services, project_services};
+use crate::storage::Storage;
+use crate::types::{
+    FileOutType, ModelOutType, ProjectInBundleImportType, ProjectInType, ProjectOutType,
+};
+use crate::{internal_server_error, validation_error};
+use diesel::prelude::*;
+use diesel::Connection as DieselConnection;
+use diesel::PgConnection;
+use rocket::http::ContentType;
+use rocket::serde::de::DeserializeOwned;
+use rocket::serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::{Cursor, Read, Write};
+use zip::result::{ZipError, ZipResult};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+const BUNDLE_FORMAT: &str = "fuzzy_cognitive_model_project";
+const BUNDLE_VERSION: i32 = 1;
+const MANIFEST_FILE: &str = "manifest.json";
+const PROJECT_FILE: &str = "project.json";
+const MODEL_FILE: &str = "model.json";
+const PLUGINS_FILE: &str = "plugins.json";
+const MODEL_COPIES_FILE: &str = "model_copies.json";
+const ADJUSTMENT_FILE: &str = "adjustment.json";
+// Uncompressed size limit of a bundle file, a small archive may unpack into a huge file
+const BUNDLE_FILE_MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Serialize, Deserialize)]
+struct BundleManifest {
+    format: String,
+    version: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundleProject {
+    project: Project,
+    plugins: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BundleModel {
+    concepts: Vec<Concept>,
+    connections: Vec<Connection>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct BundlePlugins {
+    control_concepts: Vec<ControlConcept>,
+    target_concepts: Vec<TargetConcept>,
+    concept_constraints: Vec<ConceptConstraint>,
+    concept_decay_rates: Vec<ConceptDecayRate>,
+    concept_dynamic_models: Vec<ConceptDynamicModel>,
+    concept_intervention_costs: Vec<ConceptInterventionCost>,
+    control_connections: Vec<ControlConnection>,
+    connection_constraints: Vec<ConnectionConstraint>,
+    connection_functions: Vec<ConnectionFunction>,
+    connection_lags: Vec<ConnectionLag>,
+    connection_intervention_costs: Vec<ConnectionInterventionCost>,
+    linguistic_term_set: Option<LinguisticTermSet>,
+    linguistic_terms: Vec<LinguisticTerm>,
+    connection_linguistic_terms: Vec<ConnectionLinguisticTerm>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct BundleAdjustment {
+    adjustment_runs: Vec<AdjustmentRun>,
+    adjustment_generations: Vec<AdjustmentGeneration>,
+    adjustment_individuals: Vec<AdjustmentIndividual>,
+    adjustment_concept_values: Vec<AdjustmentConceptValue>,
+    adjustment_connection_values: Vec<AdjustmentConnectionValue>,
+    adjustment_connection_function_values: Vec<AdjustmentConnectionFunctionValue>,
+    adjustment_scenario_errors: Vec<AdjustmentScenarioError>,
+}
+
+struct Bundle {
+    project: BundleProject,
+    model: BundleModel,
+    plugins: BundlePlugins,
+    model_copies: Vec<ModelCopy>,
+    adjustment: Option<BundleAdjustment>,
+}
+
+#[derive(Default)]
+struct BundleIds {
+    concepts: HashMap<i32, i32>,
+    connections: HashMap<i32, i32>,
+    linguistic_terms: HashMap<i32, i32>,
+    model_copies: HashMap<i32, i32>,
+    adjustment_generations: HashMap<i32, i32>,
+    adjustment_individuals: HashMap<i32, i32>,
+}
+
+pub fn export_project(
+    conn: &mut PgConnection,
+    user: &User,
+    project_id: i32,
+    include_adjustment: bool,
+) -> ServiceResult<FileOutType> {
+    let project = project_services::find_project_by_id(conn, project_id)
+        .to_service_result_find(String::from("project_not_found_error"))?;
+    permission_services::can_view_project(conn, &project, user)?;
+    let manifest = BundleManifest {
+        format: String::from(BUNDLE_FORMAT),
+        version: BUNDLE_VERSION,
+    };
+    let model = BundleModel {
+        concepts: model_services::find_project_concepts(conn, project_id).to_service_result()?,
+        connections: model_services::find_project_connections(conn, project_id)
+            .to_service_result()?,
+    };
+    let bundle_plugins = read_bundle_plugins(conn, project_id).to_service_result()?;
+    let model_copies = model_copies::table
+        .filter(model_copies::project_id.eq(project_id))
+        .order(model_copies::id)
+        .get_results::<ModelCopy>(conn)
+        .to_service_result()?;
+    let mut files = vec![
+        (MANIFEST_FILE, serde_json::to_vec(&manifest).unwrap()),
+        (MODEL_FILE, serde_json::to_vec(&model).unwrap()),
+        (PLUGINS_FILE, serde_json::to_vec(&bundle_plugins).unwrap()),
+        (
+            MODEL_COPIES_FILE,
+            serde_json::to_vec(&model_copies).unwrap(),
+        ),
+    ];
+    if include_adjustment {
+        let adjustment = read_bundle_adjustment(conn, project_id).to_service_result()?;
+        files.push((ADJUSTMENT_FILE, serde_json::to_vec(&adjustment).unwrap()));
+    }
+    let name = format!("{}.zip", project.name);
+    let bundle_project = BundleProject {
+        project,
+        plugins: plugin_services::find_project_plugin_names(conn, project_id)
+            .to_service_result()?,
+    };
+    files.push((PROJECT_FILE, serde_json::to_vec(&bundle_project).unwrap()));
+    let content = match write_zip(&files) {
+        Ok(content) => content,
+        Err(_) => return internal_server_error!(),
+    };
+    Ok(FileOutType {
+        name,
+        content_type: ContentType::ZIP,
+        content,
+    })
+}
+
+pub async fn import_project(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    storage: &Storage,
+    user: &User,
+    project_in: ProjectInBundleImportType<'_>,
+) -> ServiceResult<ProjectOutType> {
+    let content = storage.read_file(project_in.file).await?;
+    let bundle = read_bundle(&content)?;
+    let project = conn.transaction(|conn| write_bundle(conn, plugins, user, bundle))?;
+    ProjectOutType::from_project(conn, project)
+}
+
+fn read_bundle_plugins(conn: &mut PgConnection, project_id: i32) -> QueryResult<BundlePlugins> {
+    Ok(BundlePlugins {
+        control_concepts: control_concepts_services::find_project_control_concepts(
+            conn, project_id,
+        )?,
+        target_concepts: target_concepts_services::find_project_target_concepts(conn, project_id)?,
+        concept_constraints: concept_constraints_services::find_project_concept_constraints(
+            conn, project_id,
+        )?,
+        concept_decay_rates: concept_decay_rates_services::find_project_concept_decay_rates(
+            conn, project_id,
+        )?,
+        concept_dynamic_models:
+            concept_dynamic_model_services::find_project_concept_dynamic_models(conn, project_id)?,
+        concept_intervention_costs:
+            intervention_costs_services::find_project_concept_intervention_costs(conn, project_id)?,
+        control_connections: control_connections_services::find_project_control_connections(
+            conn, project_id,
+        )?,
+        connection_constraints:
+            connection_constraints_services::find_project_connection_constraints(conn, project_id)?,
+        connection_functions: connection_functions_services::find_project_connection_functions(
+            conn, project_id,
+        )?,
+        connection_lags: connection_lags_services::find_project_connection_lags(conn, project_id)?,
+        connection_intervention_costs:
+            intervention_costs_services::find_project_connection_intervention_costs(
+                conn, project_id,
+            )?,
+        linguistic_term_set: linguistic_connections_services::find_linguistic_term_set_by_id(
+            conn, project_id,
+        )
+        .optional()?,
+        linguistic_terms: linguistic_connections_services::find_project_linguistic_terms(
+            conn, project_id,
+        )?,
+        connection_linguistic_terms:
+            linguistic_connections_services::find_project_connection_linguistic_terms(
+                conn, project_id,
+            )?,
+    })
+}
+
+fn read_bundle_adjustment(
+    conn: &mut PgConnection,
+    project_id: i32,
+) -> QueryResult<BundleAdjustment> {
+    let adjustment_runs = adjustment_runs::table
+        .filter(adjustment_runs::project_id.eq(project_id))
+        .order(adjustment_runs::id)
+        .get_results::<AdjustmentRun>(conn)?;
+    let run_ids = adjustment_runs.iter().map(|run| run.id).collect::<Vec<_>>();
+    let adjustment_generations = adjustment_generations::table
+        .filter(adjustment_generations::adjustment_run_id.eq_any(&run_ids))
+        .order(adjustment_generations::id)
+        .get_results::<AdjustmentGeneration>(conn)?;
+    let generation_ids = adjustment_generations
+        .iter()
+        .map(|generation| generation.id)
+        .collect::<Vec<_>>();
+    let adjustment_individuals = adjustment_individuals::table
+        .filter(adjustment_individuals::adjustment_generation_id.eq_any(&generation_ids))
+        .order(adjustment_individuals::id)
+        .get_results::<AdjustmentIndividual>(conn)?;
+    let individual_ids = adjustment_individuals
+        .iter()
+        .map(|individual| individual.id)
+        .collect::<Vec<_>>();
+    Ok(BundleAdjustment {
+        adjustment_runs,
+        adjustment_generations,
+        adjustment_individuals,
+        adjustment_concept_values: adjustment_concept_values::table
+            .filter(adjustment_concept_values::adjustment_individual_id.eq_any(&individual_ids))
+            .order(adjustment_concept_values::id)
+            .get_results::<AdjustmentConceptValue>(conn)?,
+        adjustment_connection_values: adjustment_connection_values::table
+            .filter(adjustment_connection_values::adjustment_individual_id.eq_any(&individual_ids))
+            .order(adjustment_connection_values::id)
+            .get_results::<AdjustmentConnectionValue>(conn)?,
+        adjustment_connection_function_values: adjustment_connection_function_values::table
+            .filter(
+                adjustment_connection_function_values::adjustment_individual_id
+                    .eq_any(&individual_ids),
+            )
+            .order(adjustment_connection_function_values::id)
+            .get_results::<AdjustmentConnectionFunctionValue>(conn)?,
+        adjustment_scenario_errors: adjustment_scenario_errors::table
+            .filter(adjustment_scenario_errors::adjustment_individual_id.eq_any(&individual_ids))
+            .order(adjustment_scenario_errors::id)
+            .get_results::<AdjustmentScenarioError>(conn)?,
+    })
+}
+
+fn write_zip(files: &[(&str, Vec<u8>)]) -> ZipResult<Vec<u8>> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    for (name, content) in files {
+        writer.start_file(*name, options)?;
+        writer.write_all(content)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+fn read_bundle(content: &[u8]) -> ServiceResult<Bundle> {
+    let mut archive = match ZipArchive::new(Cursor::new(content)) {
+        Ok(archive) => archive,
+        Err(_) => return validation_error!("import_file_error", sheet = "zip"),
+    };
+    let manifest = read_required_file::<BundleManifest>(&mut archive, MANIFEST_FILE)?;
+    if manifest.format != BUNDLE_FORMAT {
+        return validation_error!("import_file_error", sheet = "zip");
+    }
+    if manifest.version > BUNDLE_VERSION {
+        let version = manifest.version.to_string();
+        let supported_version = BUNDLE_VERSION.to_string();
+        return validation_error!(
+            "import_bundle_version_error",
+            version = &version,
+            supported_version = &supported_version
+        );
+    }
+    Ok(Bundle {
+        project: read_required_file(&mut archive, PROJECT_FILE)?,
+        model: read_required_file(&mut archive, MODEL_FILE)?,
+        plugins: read_file(&mut archive, PLUGINS_FILE)?.unwrap_or_default(),
+        model_copies: read_file(&mut archive, MODEL_COPIES_FILE)?.unwrap_or_default(),
+        adjustment: read_file(&mut archive, ADJUSTMENT_FILE)?,
+    })
+}
+
+fn read_required_file<T: DeserializeOwned>(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &'static str,
+) -> ServiceResult<T> {
+    match read_file(archive, name)? {
+        Some(value) => Ok(value),
+        None => validation_error!("import_bundle_file_error", file = name),
+    }
+}
+
+fn read_file<T: DeserializeOwned>(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    name: &'static str,
+) -> ServiceResult<Option<T>> {
+    let mut content = vec![];
+    match archive.by_name(name) {
+        Ok(file) => {
+            if file.size() > BUNDLE_FILE_MAX_SIZE {
+                return bundle_file_size_error(name);
+            }
+            if file
+                .take(BUNDLE_FILE_MAX_SIZE + 1)
+                .read_to_end(&mut content)
+                .is_err()
+            {
+                return validation_error!("import_bundle_file_error", file = name);
+            }
+            if content.len() as u64 > BUNDLE_FILE_MAX_SIZE {
+                return bundle_file_size_error(name);
+            }
+        }
+        Err(ZipError::FileNotFound) => return Ok(None),
+        Err(_) => return validation_error!("import_bundle_file_error", file = name),
+    }
+    match serde_json::from_slice::<T>(&content) {
+        Ok(value) => Ok(Some(value)),
+        Err(_) => validation_error!("import_bundle_file_error", file = name),
+    }
+}
+
+fn bundle_file_size_error<T>(name: &'static str) -> ServiceResult<T> {
+    let max_size = (BUNDLE_FILE_MAX_SIZE / 1024 / 1024).to_string();
+    validation_error!(
+        "import_bundle_file_size_error",
+        file = name,
+        max_size = &max_size
+    )
+}
+
+fn write_bundle(
+    conn: &mut PgConnection,
+    plugins: &Plugins,
+    user: &User,
+    bundle: Bundle,
+) -> ServiceResult<Project> {
+    let bundle_project = bundle.project.project;
+    let mut project = project_services::create_project(
+        conn,
+        user,
+        ProjectInType {
+            name: bundle_project.name,
+            description: bundle_project.description,
+            is_public: bundle_project.is_public,
+            is_archived: bundle_project.is_archived,
+            concept_value_type: bundle_project.concept_value_type,
+            connection_value_type: bundle_project.connection_value_type,
+        },
+    )?;
+    let plugin_names = bundle.project.plugins;
+    plugin_services::check_plugins(conn, &project, &plugin_names)?;
+    let insert_rows = plugin_names
+        .iter()
+        .map(|plugin_name| {
+            (
+                project_plugins::project_id.eq(project.id),
+                project_plugins::plugin_name.eq(plugin_name),
+            )
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(project_plugins::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let mut ids = BundleIds::default();
+    write_model(conn, &project, &bundle.model, &mut ids)?;
+    write_plugins_data(conn, &project, &bundle.plugins, &mut ids)?;
+    for plugin_name in &plugin_names {
+        project = plugins.plugins[plugin_name]
+            .lock()
+            .unwrap()
+            .sync_model(conn, project)?;
+    }
+    let project_out = ProjectOutType::from_project(conn, project.clone())?;
+    write_model_copies(conn, &project_out, bundle.model_copies, &mut ids)?;
+    if let Some(adjustment) = bundle.adjustment {
+        write_adjustment(conn, &project, adjustment, &mut ids)?;
+    }
+    Ok(project)
+}
+
+fn write_model(
+    conn: &mut PgConnection,
+    project: &Project,
+    model: &BundleModel,
+    ids: &mut BundleIds,
+) -> ServiceResult<()> {
+    for concept in &model.concepts {
+        let new_concept = diesel::insert_into(concepts::table)
+            .values((
+                concepts::name.eq(&concept.name),
+                concepts::description.eq(&concept.description),
+                concepts::value.eq(concept.value),
+                concepts::project_id.eq(project.id),
+                concepts::x_position.eq(concept.x_position),
+                concepts::y_position.eq(concept.y_position),
+                concepts::created_at.eq(concept.created_at),
+                concepts::updated_at.eq(concept.updated_at),
+            ))
+            .get_result::<Concept>(conn)?;
+        ids.concepts.insert(concept.id, new_concept.id);
+    }
+    for connection in &model.connections {
+        let (source_id, target_id) = match (
+            ids.concepts.get(&connection.source_id),
+            ids.concepts.get(&connection.target_id),
+        ) {
+            (Some(source_id), Some(target_id)) => (*source_id, *target_id),
+            _ => return validation_error!("import_bundle_file_error", file = MODEL_FILE),
+        };
+        let new_connection = diesel::insert_into(connections::table)
+            .values((
+                connections::description.eq(&connection.description),
+                connections::value.eq(connection.value),
+                connections::source_id.eq(source_id),
+                connections::target_id.eq(target_id),
+                connections::project_id.eq(project.id),
+                connections::created_at.eq(connection.created_at),
+                connections::updated_at.eq(connection.updated_at),
+            ))
+            .get_result::<Connection>(conn)?;
+        ids.connections.insert(connection.id, new_connection.id);
+    }
+    Ok(())
+}
+
+fn write_plugins_data(
+    conn: &mut PgConnection,
+    project: &Project,
+    bundle_plugins: &BundlePlugins,
+    ids: &mut BundleIds,
+) -> ServiceResult<()> {
+    let concept_ids = &ids.concepts;
+    let connection_ids = &ids.connections;
+    let insert_rows = bundle_plugins
+        .control_concepts
+        .iter()
+        .filter_map(|row| {
+            Some((
+                control_concepts::concept_id.eq(*concept_ids.get(&row.concept_id)?),
+                control_concepts::is_control.eq(row.is_control),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(control_concepts::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .target_concepts
+        .iter()
+        .filter_map(|row| {
+            Some((
+                target_concepts::concept_id.eq(*concept_ids.get(&row.concept_id)?),
+                target_concepts::is_target.eq(row.is_target),
+                target_concepts::min_value.eq(row.min_value),
+                target_concepts::include_min_value.eq(row.include_min_value),
+                target_concepts::max_value.eq(row.max_value),
+                target_concepts::include_max_value.eq(row.include_max_value),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(target_concepts::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .concept_constraints
+        .iter()
+        .filter_map(|row| {
+            Some((
+                concept_constraints::concept_id.eq(*concept_ids.get(&row.concept_id)?),
+                concept_constraints::has_constraint.eq(row.has_constraint),
+                concept_constraints::min_value.eq(row.min_value),
+                concept_constraints::include_min_value.eq(row.include_min_value),
+                concept_constraints::max_value.eq(row.max_value),
+                concept_constraints::include_max_value.eq(row.include_max_value),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(concept_constraints::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .concept_decay_rates
+        .iter()
+        .filter_map(|row| {
+            Some((
+                concept_decay_rates::concept_id.eq(*concept_ids.get(&row.concept_id)?),
+                concept_decay_rates::decay_rate.eq(row.decay_rate),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(concept_decay_rates::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .concept_dynamic_models
+        .iter()
+        .filter_map(|row| {
+            Some((
+                concept_dynamic_models::concept_id.eq(*concept_ids.get(&row.concept_id)?),
+                concept_dynamic_models::dynamic_model_type.eq(row.dynamic_model_type.clone()),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(concept_dynamic_models::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .concept_intervention_costs
+        .iter()
+        .filter_map(|row| {
+            Some((
+                concept_intervention_costs::concept_id.eq(*concept_ids.get(&row.concept_id)?),
+                concept_intervention_costs::linear_cost.eq(row.linear_cost),
+                concept_intervention_costs::fixed_cost.eq(row.fixed_cost),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(concept_intervention_costs::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .control_connections
+        .iter()
+        .filter_map(|row| {
+            Some((
+                control_connections::connection_id.eq(*connection_ids.get(&row.connection_id)?),
+                control_connections::is_control.eq(row.is_control),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(control_connections::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .connection_constraints
+        .iter()
+        .filter_map(|row| {
+            Some((
+                connection_constraints::connection_id.eq(*connection_ids.get(&row.connection_id)?),
+                connection_constraints::has_constraint.eq(row.has_constraint),
+                connection_constraints::min_value.eq(row.min_value),
+                connection_constraints::include_min_value.eq(row.include_min_value),
+                connection_constraints::max_value.eq(row.max_value),
+                connection_constraints::include_max_value.eq(row.include_max_value),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(connection_constraints::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .connection_functions
+        .iter()
+        .filter_map(|row| {
+            Some((
+                connection_functions::connection_id.eq(*connection_ids.get(&row.connection_id)?),
+                connection_functions::function_type.eq(row.function_type.clone()),
+                connection_functions::parameter.eq(row.parameter),
+                connection_functions::points.eq(row.points.clone()),
+                connection_functions::is_control.eq(row.is_control),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(connection_functions::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .connection_lags
+        .iter()
+        .filter_map(|row| {
+            Some((
+                connection_lags::connection_id.eq(*connection_ids.get(&row.connection_id)?),
+                connection_lags::lag.eq(row.lag),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(connection_lags::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = bundle_plugins
+        .connection_intervention_costs
+        .iter()
+        .filter_map(|row| {
+            Some((
+                connection_intervention_costs::connection_id
+                    .eq(*connection_ids.get(&row.connection_id)?),
+                connection_intervention_costs::linear_cost.eq(row.linear_cost),
+                connection_intervention_costs::fixed_cost.eq(row.fixed_cost),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(connection_intervention_costs::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    if let Some(linguistic_term_set) = &bundle_plugins.linguistic_term_set {
+        diesel::insert_into(linguistic_term_sets::table)
+            .values((
+                linguistic_term_sets::project_id.eq(project.id),
+                linguistic_term_sets::defuzzification_method
+                    .eq(linguistic_term_set.defuzzification_method.clone()),
+            ))
+            .execute(conn)?;
+    }
+    for linguistic_term in &bundle_plugins.linguistic_terms {
+        let new_linguistic_term = diesel::insert_into(linguistic_terms::table)
+            .values((
+                linguistic_terms::project_id.eq(project.id),
+                linguistic_terms::name.eq(&linguistic_term.name),
+                linguistic_terms::membership_function_type
+                    .eq(linguistic_term.membership_function_type.clone()),
+                linguistic_terms::a.eq(linguistic_term.a),
+                linguistic_terms::b.eq(linguistic_term.b),
+                linguistic_terms::c.eq(linguistic_term.c),
+                linguistic_terms::d.eq(linguistic_term.d),
+            ))
+            .get_result::<LinguisticTerm>(conn)?;
+        ids.linguistic_terms
+            .insert(linguistic_term.id, new_linguistic_term.id);
+    }
+    let insert_rows = bundle_plugins
+        .connection_linguistic_terms
+        .iter()
+        .filter_map(|row| {
+            Some((
+                connection_linguistic_terms::connection_id
+                    .eq(*ids.connections.get(&row.connection_id)?),
+                connection_linguistic_terms::linguistic_term_id
+                    .eq(*ids.linguistic_terms.get(&row.linguistic_term_id)?),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(connection_linguistic_terms::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+fn write_model_copies(
+    conn: &mut PgConnection,
+    project_out: &ProjectOutType,
+    model_copies: Vec<ModelCopy>,
+    ids: &mut BundleIds,
+) -> ServiceResult<()> {
+    for model_copy in model_copies {
+        let model = remap_model_copy(project_out, model_copy.model, ids)?;
+        let new_model_copy = diesel::insert_into(model_copies::table)
+            .values((
+                model_copies::project_id.eq(project_out.id),
+                model_copies::model.eq(model),
+            ))
+            .get_result::<ModelCopy>(conn)?;
+        ids.model_copies.insert(model_copy.id, new_model_copy.id);
+    }
+    Ok(())
+}
+
+fn remap_model_copy(
+    project_out: &ProjectOutType,
+    model: Value,
+    ids: &BundleIds,
+) -> ServiceResult<Value> {
+    let mut model_out = match serde_json::from_value::<ModelOutType>(model) {
+        Ok(model_out) => model_out,
+        Err(_) => return validation_error!("import_bundle_file_error", file = MODEL_COPIES_FILE),
+    };
+    model_out.project.id = project_out.id;
+    model_out.project.creator = project_out.creator.clone();
+    for concept_out in model_out.concepts.iter_mut() {
+        concept_out.id = remap_id(&ids.concepts, concept_out.id);
+        concept_out.project_id = project_out.id;
+    }
+    for connection_out in model_out.connections.iter_mut() {
+        connection_out.id = remap_id(&ids.connections, connection_out.id);
+        connection_out.source_id = remap_id(&ids.concepts, connection_out.source_id);
+        connection_out.target_id = remap_id(&ids.concepts, connection_out.target_id);
+        connection_out.project_id = project_out.id;
+        if let Some(Value::Array(linguistic_term_ids)) = connection_out
+            .plugins_data
+            .pointer_mut("/linguisticConnections/linguisticTermIds")
+        {
+            for linguistic_term_id in linguistic_term_ids.iter_mut() {
+                if let Some(id) = linguistic_term_id.as_i64() {
+                    *linguistic_term_id = remap_id(&ids.linguistic_terms, id as i32).into();
+                }
+            }
+        }
+    }
+    Ok(serde_json::to_value(model_out).unwrap())
+}
+
+fn write_adjustment(
+    conn: &mut PgConnection,
+    project: &Project,
+    adjustment: BundleAdjustment,
+    ids: &mut BundleIds,
+) -> ServiceResult<()> {
+    let mut run_ids = HashMap::new();
+    for adjustment_run in &adjustment.adjustment_runs {
+        let model_copy_id = match ids.model_copies.get(&adjustment_run.model_copy_id) {
+            Some(model_copy_id) => *model_copy_id,
+            None => return validation_error!("import_bundle_file_error", file = ADJUSTMENT_FILE),
+        };
+        // Workers of unfinished runs stay on the exporting server
+        let state = match adjustment_run.state {
+            AdjustmentRunState::Queued
+            | AdjustmentRunState::Running
+            | AdjustmentRunState::Paused => AdjustmentRunState::Cancelled,
+            ref state => state.clone(),
+        };
+        let new_adjustment_run_id = diesel::insert_into(adjustment_runs::table)
+            .values((
+                adjustment_runs::project_id.eq(project.id),
+                adjustment_runs::model_copy_id.eq(model_copy_id),
+                adjustment_runs::name.eq(&adjustment_run.name),
+                adjustment_runs::description.eq(&adjustment_run.description),
+                adjustment_runs::min_model_time.eq(adjustment_run.min_model_time),
+                adjustment_runs::max_model_time.eq(adjustment_run.max_model_time),
+                adjustment_runs::dynamic_model_type.eq(adjustment_run.dynamic_model_type.clone()),
+                adjustment_runs::generation_size.eq(adjustment_run.generation_size),
+                adjustment_runs::generation_save_interval
+                    .eq(adjustment_run.generation_save_interval),
+                adjustment_runs::max_generations.eq(adjustment_run.max_generations),
+                adjustment_runs::max_without_improvements
+                    .eq(adjustment_run.max_without_improvements),
+                adjustment_runs::error.eq(adjustment_run.error),
+                adjustment_runs::created_at.eq(adjustment_run.created_at),
+                adjustment_runs::state.eq(state),
+                adjustment_runs::control_overrides.eq(remap_control_overrides(
+                    &adjustment_run.control_overrides,
+                    ids,
+                )),
+                adjustment_runs::budget.eq(adjustment_run.budget),
+                adjustment_runs::minimize_cost.eq(adjustment_run.minimize_cost),
+                adjustment_runs::scenarios.eq(remap_scenarios(&adjustment_run.scenarios, ids)),
+                adjustment_runs::scenario_aggregation_type
+                    .eq(adjustment_run.scenario_aggregation_type.clone()),
+                adjustment_runs::time_step.eq(adjustment_run.time_step),
+                adjustment_runs::stability_condition.eq(adjustment_run.stability_condition.clone()),
+            ))
+            .returning(adjustment_runs::id)
+            .get_result::<i32>(conn)?;
+        run_ids.insert(adjustment_run.id, new_adjustment_run_id);
+    }
+    for generation in &adjustment.adjustment_generations {
+        let adjustment_run_id = match run_ids.get(&generation.adjustment_run_id) {
+            Some(adjustment_run_id) => *adjustment_run_id,
+            None => continue,
+        };
+        let new_generation_id = diesel::insert_into(adjustment_generations::table)
+            .values((
+                adjustment_generations::adjustment_run_id.eq(adjustment_run_id),
+                adjustment_generations::number.eq(generation.number),
+                adjustment_generations::error.eq(generation.error),
+            ))
+            .returning(adjustment_generations::id)
+            .get_result::<i32>(conn)?;
+        ids.adjustment_generations
+            .insert(generation.id, new_generation_id);
+    }
+    for individual in &adjustment.adjustment_individuals {
+        let generation_id = match ids
+            .adjustment_generations
+            .get(&individual.adjustment_generation_id)
+        {
+            Some(generation_id) => *generation_id,
+            None => continue,
+        };
+        let new_individual_id = diesel::insert_into(adjustment_individuals::table)
+            .values((
+                adjustment_individuals::adjustment_generation_id.eq(generation_id),
+                adjustment_individuals::number.eq(individual.number),
+                adjustment_individuals::time.eq(individual.time),
+                adjustment_individuals::error.eq(individual.error),
+                adjustment_individuals::cost.eq(individual.cost),
+            ))
+            .returning(adjustment_individuals::id)
+            .get_result::<i32>(conn)?;
+        ids.adjustment_individuals
+            .insert(individual.id, new_individual_id);
+    }
+    write_adjustment_values(conn, &adjustment, ids)?;
+    for adjustment_run in &adjustment.adjustment_runs {
+        diesel::update(adjustment_runs::table.find(run_ids[&adjustment_run.id]))
+            .set((
+                adjustment_runs::result_individual_id.eq(adjustment_run
+                    .result_individual_id
+                    .and_then(|id| ids.adjustment_individuals.get(&id).copied())),
+                adjustment_runs::seed_generation_id.eq(adjustment_run
+                    .seed_generation_id
+                    .and_then(|id| ids.adjustment_generations.get(&id).copied())),
+            ))
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+fn write_adjustment_values(
+    conn: &mut PgConnection,
+    adjustment: &BundleAdjustment,
+    ids: &BundleIds,
+) -> ServiceResult<()> {
+    let individual_ids = &ids.adjustment_individuals;
+    let insert_rows = adjustment
+        .adjustment_concept_values
+        .iter()
+        .filter_map(|row| {
+            Some((
+                adjustment_concept_values::adjustment_individual_id
+                    .eq(*individual_ids.get(&row.adjustment_individual_id)?),
+                adjustment_concept_values::concept_id.eq(*ids.concepts.get(&row.concept_id)?),
+                adjustment_concept_values::value.eq(row.value),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(adjustment_concept_values::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = adjustment
+        .adjustment_connection_values
+        .iter()
+        .filter_map(|row| {
+            Some((
+                adjustment_connection_values::adjustment_individual_id
+                    .eq(*individual_ids.get(&row.adjustment_individual_id)?),
+                adjustment_connection_values::connection_id
+                    .eq(*ids.connections.get(&row.connection_id)?),
+                adjustment_connection_values::value.eq(row.value),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(adjustment_connection_values::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = adjustment
+        .adjustment_connection_function_values
+        .iter()
+        .filter_map(|row| {
+            Some((
+                adjustment_connection_function_values::adjustment_individual_id
+                    .eq(*individual_ids.get(&row.adjustment_individual_id)?),
+                adjustment_connection_function_values::connection_id
+                    .eq(*ids.connections.get(&row.connection_id)?),
+                adjustment_connection_function_values::parameter.eq(row.parameter),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(adjustment_connection_function_values::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    let insert_rows = adjustment
+        .adjustment_scenario_errors
+        .iter()
+        .filter_map(|row| {
+            Some((
+                adjustment_scenario_errors::adjustment_individual_id
+                    .eq(*individual_ids.get(&row.adjustment_individual_id)?),
+                adjustment_scenario_errors::scenario_name.eq(&row.scenario_name),
+                adjustment_scenario_errors::error.eq(row.error),
+            ))
+        })
+        .collect::<Vec<_>>();
+    if !insert_rows.is_empty() {
+        diesel::insert_into(adjustment_scenario_errors::table)
+            .values(&insert_rows)
+            .execute(conn)?;
+    }
+    Ok(())
+}
+
+fn remap_control_overrides(control_overrides: &Option<Value>, ids: &BundleIds) -> Option<Value> {
+    let control_overrides = control_overrides.clone()?;
+    let mut control_overrides_in =
+        match serde_json::from_value::<ControlOverridesType>(control_overrides.clone()) {
+            Ok(control_overrides_in) => control_overrides_in,
+            Err(_) => return Some(control_overrides),
+        };
+    for concept_override in control_overrides_in.concepts.iter_mut() {
+        concept_override.id = remap_id(&ids.concepts, concept_override.id);
+    }
+    for connection_override in control_overrides_in.connections.iter_mut() {
+        connection_override.id = remap_id(&ids.connections, connection_override.id);
+    }
+    Some(serde_json::to_value(control_overrides_in).unwrap())
+}
+
+fn remap_scenarios(scenarios: &Option<Value>, ids: &BundleIds) -> Option<Value> {
+    let scenarios = scenarios.clone()?;
+    let mut scenarios_in = match serde_json::from_value::<Vec<ScenarioType>>(scenarios.clone()) {
+        Ok(scenarios_in) => scenarios_in,
+        Err(_) => return Some(scenarios),
+    };
+    for scenario in scenarios_in.iter_mut() {
+        for concept_value in scenario.concept_values.iter_mut() {
+            concept_value.concept_id = remap_id(&ids.concepts, concept_value.concept_id);
+        }
+    }
+    Some(serde_json::to_value(scenarios_in).unwrap())
+}
+
+// Elements deleted before the export are still referenced by model copies and run settings,
+// negated identifiers keep them apart without colliding with the imported ones
+fn remap_id(ids: &HashMap<i32, i32>, id: i32) -> i32 {
+    match ids.get(&id) {
+        Some(new_id) => *new_id,
+        None => -id,
+    }
+}
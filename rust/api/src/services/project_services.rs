@@ -11,7 +11,7 @@ use crate::types::{
     ProjectOutType, UserOutType,
 };
 use crate::web_socket::{WebSocketAdjustmentRunService, WebSocketModelService};
-use crate::{filter_date_time, validation_error};
+use crate::{conflict_error, filter_date_time, validation_error};
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use diesel::PgConnection;
@@ -198,6 +198,23 @@ pub fn is_not_archived(project: &Project) -> ServiceResult<()> {
     Ok(())
 }
 
+pub fn check_project_version(
+    conn: &mut PgConnection,
+    project: &Project,
+    expected_version: Option<DateTime<Utc>>,
+) -> ServiceResult<()> {
+    match expected_version {
+        Some(expected_version) if expected_version != project.updated_at => {
+            let project_out = ProjectOutType::from_project(conn, project.clone())?;
+            conflict_error!(
+                "project_version_conflict_error",
+                serde_json::to_value(project_out).unwrap()
+            )
+        }
+        _ => Ok(()),
+    }
+}
+
 fn check_project_plugins(
     conn: &mut PgConnection,
     project_id: i32,
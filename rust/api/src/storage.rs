@@ -28,6 +28,18 @@ impl Storage {
     pub async fn get_user_avatar(&self, avatar_path: PathBuf) -> ServiceResult<NamedFile> {
         self.user_avatars_storage.get_file(avatar_path).await
     }
+    pub async fn read_file(&self, mut file: TempFile<'_>) -> ServiceResult<Vec<u8>> {
+        let full_path = self.path.join(format!("{}.tmp", Uuid::new_v4()));
+        if let Err(_) = file.persist_to(&full_path).await {
+            return internal_server_error!();
+        }
+        let content = fs::read(&full_path);
+        let _ = fs::remove_file(&full_path);
+        match content {
+            Ok(content) => Ok(content),
+            Err(_) => internal_server_error!(),
+        }
+    }
     pub fn new() -> Storage {
         let storage_path = env::current_dir().unwrap().join("storage");
         if !storage_path.exists() {
@@ -1,17 +1,20 @@
 use crate::models::{ConceptValueType, ConnectionValueType, ProjectUserStatusValue};
+use crate::plugins::adjustment::models::DynamicModelType;
 use crate::request::DateTimeWrapper;
 use chrono::{DateTime, Utc};
 use rocket::form::FromForm;
 use rocket::fs::TempFile;
+use rocket::http::ContentType;
 use rocket::serde::{Deserialize, Serialize};
 use rocket_okapi::JsonSchema;
 use schemars::gen::SchemaGenerator;
 use schemars::schema::{InstanceType, Metadata, ObjectValidation, Schema, SchemaObject};
 use schemars::{Map, Set};
 use serde_json::Value;
+use std::collections::HashMap;
 
 /// Type of user (expert or researcher)
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UserOutType {
     /// User identifier
@@ -177,7 +180,7 @@ pub struct ResetPasswordType {
 }
 
 /// Type of project
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectOutType {
     /// Project identifier
@@ -222,6 +225,13 @@ pub struct ProjectInType {
     pub connection_value_type: ConnectionValueType,
 }
 
+/// Type of project bundle to import
+#[derive(FromForm)]
+pub struct ProjectInBundleImportType<'r> {
+    /// Project bundle archive
+    pub file: TempFile<'r>,
+}
+
 #[derive(JsonSchema, FromFormField)]
 /// Type of project group filter
 pub enum ProjectGroupFilterType {
@@ -389,6 +399,8 @@ pub struct ConceptInType {
     pub x_position: f64,
     /// Concept position in y coordinate
     pub y_position: f64,
+    /// Expected concept update time when changing concept
+    pub expected_version: Option<DateTime<Utc>>,
 }
 
 /// Type of result of changing concept
@@ -433,6 +445,8 @@ pub struct ConceptInMoveType {
     pub x_position: f64,
     /// Concept position in y coordinate
     pub y_position: f64,
+    /// Expected concept update time
+    pub expected_version: Option<DateTime<Utc>>,
 }
 
 /// Type of result of deleting concept
@@ -490,6 +504,8 @@ pub struct ConnectionInChangeType {
     pub description: String,
     /// Connection value
     pub value: f64,
+    /// Expected connection update time
+    pub expected_version: Option<DateTime<Utc>>,
 }
 
 /// Type of result of changing connection
@@ -516,7 +532,7 @@ pub struct ConnectionOutDeleteType {
 }
 
 /// Type of model
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ModelOutType {
     /// Model project
@@ -527,6 +543,496 @@ pub struct ModelOutType {
     pub connections: Vec<ConnectionOutType>,
 }
 
+/// Type of model sheet
+#[derive(Clone, Copy, PartialEq, Eq, Hash, JsonSchema, FromFormField)]
+pub enum ModelSheetType {
+    /// Adjacency matrix of connection weights
+    Weights,
+    /// Initial concept values
+    Values,
+    /// Control and target concept flags
+    Flags,
+    /// Concept constraints and target ranges
+    Constraints,
+}
+
+/// Type of model CSV files to import
+#[derive(FromForm)]
+pub struct ModelInCsvImportType<'r> {
+    /// Adjacency matrix of connection weights
+    pub weights: TempFile<'r>,
+    /// Initial concept values
+    pub values: Option<TempFile<'r>>,
+    /// Control and target concept flags
+    pub flags: Option<TempFile<'r>>,
+    /// Concept constraints and target ranges
+    pub constraints: Option<TempFile<'r>>,
+}
+
+/// Type of model XLSX file to import
+#[derive(FromForm)]
+pub struct ModelInXlsxImportType<'r> {
+    /// Workbook with model sheets
+    pub file: TempFile<'r>,
+}
+
+/// Type of model GraphML file to import
+#[derive(FromForm)]
+pub struct ModelInGraphmlImportType<'r> {
+    /// GraphML file with model graph
+    pub file: TempFile<'r>,
+}
+
+/// Type of Mental Modeler file to import
+#[derive(FromForm)]
+pub struct ModelInMentalModelerImportType<'r> {
+    /// Mental Modeler project file
+    pub file: TempFile<'r>,
+}
+
+/// Type of FCMapper file to import
+#[derive(FromForm)]
+pub struct ModelInFcmapperImportType<'r> {
+    /// FCMapper workbook with adjacency matrix
+    pub file: TempFile<'r>,
+}
+
+/// Type of source item that could not be mapped to model
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UnmappedItemType {
+    UnsupportedAttribute,
+    UnsupportedValue,
+    InvalidValue,
+    ConceptNotFound,
+    DuplicateItem,
+}
+
+/// Source item that could not be mapped to model
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnmappedItemOutType {
+    /// Unmapped item type
+    pub unmapped_item_type: UnmappedItemType,
+    /// Localised message
+    pub message: String,
+    /// Source concept name or connection in the form `source -> target`
+    pub item: String,
+    /// Source attribute
+    pub attribute: String,
+    /// Source attribute value
+    pub value: String,
+}
+
+/// Result of model import from other tool
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelImportReportOutType {
+    /// Model import action
+    pub model_action: ModelActionType<ModelOutType>,
+    /// Source items that could not be mapped to model
+    pub unmapped_items: Vec<UnmappedItemOutType>,
+}
+
+/// Type of model graph format
+#[derive(Clone, Copy, PartialEq, JsonSchema, FromFormField)]
+pub enum GraphFormatType {
+    /// GraphML format
+    Graphml,
+    /// GEXF format
+    Gexf,
+    /// Graphviz DOT format
+    Dot,
+}
+
+/// Type of model snapshot to create
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSnapshotInType {
+    /// Model snapshot name
+    pub name: String,
+    /// Model snapshot description
+    pub description: String,
+}
+
+/// Input type for getting model snapshots
+#[derive(FromForm, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSnapshotsInType {
+    /// Whether to get only automatic or only named snapshots
+    #[field(name = "isAutomatic")]
+    pub is_automatic: Option<bool>,
+    /// Page number
+    pub page: Option<u16>,
+    /// Number of records per page
+    #[field(name = "perPage")]
+    pub per_page: Option<u16>,
+}
+
+/// Type of model snapshot
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelSnapshotOutType {
+    /// Model snapshot identifier
+    pub id: i32,
+    /// Model snapshot project identifier
+    pub project_id: i32,
+    /// Model copy identifier
+    pub model_copy_id: i32,
+    /// Model snapshot creator
+    pub creator: UserOutType,
+    /// Model snapshot name
+    pub name: String,
+    /// Model snapshot description
+    pub description: String,
+    /// Whether snapshot was taken automatically before bulk operation
+    pub is_automatic: bool,
+    /// Model snapshot creation time
+    pub created_at: DateTime<Utc>,
+}
+
+/// Type of model difference
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDiffOutType {
+    /// Concepts missing in the source model
+    pub added_concepts: Vec<ConceptOutType>,
+    /// Concepts missing in the target model
+    pub removed_concepts: Vec<ConceptOutType>,
+    /// Concepts with changed fields
+    pub changed_concepts: Vec<ConceptDiffOutType>,
+    /// Connections missing in the source model
+    pub added_connections: Vec<ConnectionOutType>,
+    /// Connections missing in the target model
+    pub removed_connections: Vec<ConnectionOutType>,
+    /// Connections with changed fields
+    pub changed_connections: Vec<ConnectionDiffOutType>,
+}
+
+/// Type of concept difference
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConceptDiffOutType {
+    /// Concept in the source model
+    pub source: ConceptOutType,
+    /// Concept in the target model
+    pub target: ConceptOutType,
+    /// Changed concept fields
+    pub fields: Vec<FieldDiffOutType>,
+}
+
+/// Type of connection difference
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionDiffOutType {
+    /// Connection in the source model
+    pub source: ConnectionOutType,
+    /// Connection in the target model
+    pub target: ConnectionOutType,
+    /// Changed connection fields
+    pub fields: Vec<FieldDiffOutType>,
+}
+
+/// Type of field difference
+#[derive(Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldDiffOutType {
+    /// Field name, plugin fields are prefixed with plugin key
+    pub name: String,
+    /// Field value in the source model
+    pub source_value: Value,
+    /// Field value in the target model
+    pub target_value: Value,
+}
+
+/// Type of model elements state
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelElementsType {
+    /// Concepts
+    pub concepts: Vec<ConceptOutType>,
+    /// Connections
+    pub connections: Vec<ConnectionOutType>,
+}
+
+/// Identifier of model element in batch operation
+#[derive(Clone, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ModelBatchIdInType {
+    /// Identifier of existing element
+    Id(i32),
+    /// Temporary identifier of element created earlier in the batch
+    TemporaryId(String),
+}
+
+/// Type of concept to create in batch
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConceptInBatchCreateType {
+    /// Temporary concept identifier to reference it in the next operations
+    pub temporary_id: Option<String>,
+    /// Concept name
+    pub name: String,
+    /// Concept description
+    pub description: String,
+    /// Concept value
+    pub value: Option<f64>,
+    /// Concept position in x coordinate
+    pub x_position: f64,
+    /// Concept position in y coordinate
+    pub y_position: f64,
+    /// Concept plugins data to write after creating concept
+    pub plugins_data: Option<Value>,
+}
+
+/// Type of connection to create in batch
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionInBatchCreateType {
+    /// Temporary connection identifier to reference it in the next operations
+    pub temporary_id: Option<String>,
+    /// Connection description
+    pub description: String,
+    /// Connection value
+    pub value: f64,
+    /// Connection source concept identifier
+    pub source_id: ModelBatchIdInType,
+    /// Connection target concept identifier
+    pub target_id: ModelBatchIdInType,
+    /// Connection plugins data to write after creating connection
+    pub plugins_data: Option<Value>,
+}
+
+/// Type of model batch operation
+#[derive(Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ModelBatchOperationInType {
+    /// Create concept
+    CreateConcept {
+        /// Concept to create
+        concept: ConceptInBatchCreateType,
+    },
+    /// Change concept
+    #[serde(rename_all = "camelCase")]
+    ChangeConcept {
+        /// Concept identifier
+        concept_id: ModelBatchIdInType,
+        /// Concept data
+        concept: ConceptInType,
+    },
+    /// Move concept
+    #[serde(rename_all = "camelCase")]
+    MoveConcept {
+        /// Concept identifier
+        concept_id: ModelBatchIdInType,
+        /// Concept position
+        concept: ConceptInMoveType,
+    },
+    /// Delete concept with its connections
+    #[serde(rename_all = "camelCase")]
+    DeleteConcept {
+        /// Concept identifier
+        concept_id: ModelBatchIdInType,
+        /// Expected concept update time
+        expected_version: Option<DateTime<Utc>>,
+    },
+    /// Create connection
+    CreateConnection {
+        /// Connection to create
+        connection: ConnectionInBatchCreateType,
+    },
+    /// Change connection
+    #[serde(rename_all = "camelCase")]
+    ChangeConnection {
+        /// Connection identifier
+        connection_id: ModelBatchIdInType,
+        /// Connection data
+        connection: ConnectionInChangeType,
+    },
+    /// Delete connection
+    #[serde(rename_all = "camelCase")]
+    DeleteConnection {
+        /// Connection identifier
+        connection_id: ModelBatchIdInType,
+        /// Expected connection update time
+        expected_version: Option<DateTime<Utc>>,
+    },
+}
+
+/// Type of model batch
+#[derive(Deserialize, JsonSchema)]
+pub struct ModelBatchInType {
+    /// Operations applied in order
+    pub operations: Vec<ModelBatchOperationInType>,
+}
+
+/// Type of result of model batch
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelBatchOutType {
+    /// Created or changed concepts
+    pub concepts: Vec<ConceptOutType>,
+    /// Created or changed connections
+    pub connections: Vec<ConnectionOutType>,
+    /// Deleted concept identifiers
+    pub deleted_concept_ids: Vec<i32>,
+    /// Deleted connection identifiers
+    pub deleted_connection_ids: Vec<i32>,
+    /// Identifiers of created concepts by their temporary identifiers
+    pub temporary_concept_ids: HashMap<String, i32>,
+    /// Identifiers of created connections by their temporary identifiers
+    pub temporary_connection_ids: HashMap<String, i32>,
+}
+
+/// Type of model fragment to export
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelFragmentInType {
+    /// Identifiers of selected concepts, connections between them are exported too
+    pub concept_ids: Vec<i32>,
+}
+
+/// Type of model fragment to paste
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelFragmentInPasteType {
+    /// Model template identifier, required if fragment is not given
+    pub model_template_id: Option<i32>,
+    /// Exported model fragment, required if model template is not given
+    pub fragment: Option<ModelElementsType>,
+    /// Offset of concept positions in x coordinate
+    pub x_offset: f64,
+    /// Offset of concept positions in y coordinate
+    pub y_offset: f64,
+}
+
+/// Type of model template to create
+#[derive(Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelTemplateInType {
+    /// Model template name
+    pub name: String,
+    /// Model template description
+    pub description: String,
+    /// Identifiers of selected concepts, connections between them are saved too
+    pub concept_ids: Vec<i32>,
+}
+
+/// Input type for getting model templates
+#[derive(FromForm, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelTemplatesInType {
+    /// Search string
+    pub search: Option<String>,
+    /// Page number
+    pub page: Option<u16>,
+    /// Number of records per page
+    #[field(name = "perPage")]
+    pub per_page: Option<u16>,
+}
+
+/// Type of model template
+#[derive(Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelTemplateOutType {
+    /// Model template identifier
+    pub id: i32,
+    /// Model template name
+    pub name: String,
+    /// Model template description
+    pub description: String,
+    /// Model fragment
+    pub fragment: ModelElementsType,
+    /// Model template creation time
+    pub created_at: DateTime<Utc>,
+}
+
+/// Type of model elements change
+#[derive(Clone, Default, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelElementsChangeOutType {
+    /// Created or changed concepts
+    pub concepts: Vec<ConceptOutType>,
+    /// Created or changed connections
+    pub connections: Vec<ConnectionOutType>,
+    /// Deleted concept identifiers
+    pub deleted_concept_ids: Vec<i32>,
+    /// Deleted connection identifiers
+    pub deleted_connection_ids: Vec<i32>,
+}
+
+/// Type of model to import
+#[derive(Default)]
+pub struct ModelInImportType {
+    /// Model concepts
+    pub concepts: Vec<ConceptInImportType>,
+    /// Model connections
+    pub connections: Vec<ConnectionInImportType>,
+}
+
+/// Type of concept to import
+#[derive(Default)]
+pub struct ConceptInImportType {
+    /// Concept name
+    pub name: String,
+    /// Concept description, `None` keeps the current description
+    pub description: Option<String>,
+    /// Concept value, `None` keeps the current value
+    pub value: Option<Option<f64>>,
+    /// Concept position in x coordinate, `None` keeps the current position
+    pub x_position: Option<f64>,
+    /// Concept position in y coordinate, `None` keeps the current position
+    pub y_position: Option<f64>,
+    /// Is concept control
+    pub is_control: Option<bool>,
+    /// Is concept target
+    pub is_target: Option<bool>,
+    /// Control concept constraint or target concept range, `Some(None)` removes it
+    pub constraint: Option<Option<ConstraintInImportType>>,
+    /// Concept dynamic model type, `Some(None)` resets it to the default one
+    pub dynamic_model_type: Option<Option<DynamicModelType>>,
+}
+
+/// Type of connection to import
+#[derive(Default)]
+pub struct ConnectionInImportType {
+    /// Connection source concept name
+    pub source_name: String,
+    /// Connection target concept name
+    pub target_name: String,
+    /// Connection description, `None` keeps the current description
+    pub description: Option<String>,
+    /// Connection value
+    pub value: f64,
+    /// Is connection control
+    pub is_control: Option<bool>,
+    /// Control connection constraint, `Some(None)` removes it
+    pub constraint: Option<Option<ConstraintInImportType>>,
+}
+
+/// Type of constraint to import
+#[derive(Clone, Copy, PartialEq)]
+pub struct ConstraintInImportType {
+    /// Minimum value
+    pub min_value: f64,
+    /// Whether to include minimum value
+    pub include_min_value: bool,
+    /// Maximum value
+    pub max_value: f64,
+    /// Whether to include maximum value
+    pub include_max_value: bool,
+}
+
+/// Type of exported file
+pub struct FileOutType {
+    /// File name
+    pub name: String,
+    /// File content type
+    pub content_type: ContentType,
+    /// File content
+    pub content: Vec<u8>,
+}
+
 /// Interval input type
 #[derive(Deserialize, FromForm, JsonSchema)]
 pub struct IntervalInType<T: JsonSchema> {
@@ -719,3 +1225,134 @@ impl<'r> JsonSchema for UserInChangeType<'r> {
         )
     }
 }
+
+impl<'r> JsonSchema for ModelInCsvImportType<'r> {
+    fn schema_name() -> String {
+        String::from("ModelInCsvImportType")
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        user_json_schema!(
+            [
+                (
+                    "weights",
+                    InstanceType::String,
+                    "Adjacency matrix of connection weights",
+                    true,
+                    Some(String::from("binary"))
+                ),
+                (
+                    "values",
+                    InstanceType::String,
+                    "Initial concept values",
+                    false,
+                    Some(String::from("binary"))
+                ),
+                (
+                    "flags",
+                    InstanceType::String,
+                    "Control and target concept flags",
+                    false,
+                    Some(String::from("binary"))
+                ),
+                (
+                    "constraints",
+                    InstanceType::String,
+                    "Concept constraints and target ranges",
+                    false,
+                    Some(String::from("binary"))
+                )
+            ],
+            "Type of model CSV files to import"
+        )
+    }
+}
+
+impl<'r> JsonSchema for ModelInXlsxImportType<'r> {
+    fn schema_name() -> String {
+        String::from("ModelInXlsxImportType")
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        user_json_schema!(
+            [(
+                "file",
+                InstanceType::String,
+                "Workbook with model sheets",
+                true,
+                Some(String::from("binary"))
+            )],
+            "Type of model XLSX file to import"
+        )
+    }
+}
+
+impl<'r> JsonSchema for ModelInGraphmlImportType<'r> {
+    fn schema_name() -> String {
+        String::from("ModelInGraphmlImportType")
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        user_json_schema!(
+            [(
+                "file",
+                InstanceType::String,
+                "GraphML file with model graph",
+                true,
+                Some(String::from("binary"))
+            )],
+            "Type of model GraphML file to import"
+        )
+    }
+}
+
+impl<'r> JsonSchema for ModelInMentalModelerImportType<'r> {
+    fn schema_name() -> String {
+        String::from("ModelInMentalModelerImportType")
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        user_json_schema!(
+            [(
+                "file",
+                InstanceType::String,
+                "Mental Modeler project file",
+                true,
+                Some(String::from("binary"))
+            )],
+            "Type of Mental Modeler file to import"
+        )
+    }
+}
+
+impl<'r> JsonSchema for ModelInFcmapperImportType<'r> {
+    fn schema_name() -> String {
+        String::from("ModelInFcmapperImportType")
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        user_json_schema!(
+            [(
+                "file",
+                InstanceType::String,
+                "FCMapper workbook with adjacency matrix",
+                true,
+                Some(String::from("binary"))
+            )],
+            "Type of FCMapper file to import"
+        )
+    }
+}
+
+impl<'r> JsonSchema for ProjectInBundleImportType<'r> {
+    fn schema_name() -> String {
+        String::from("ProjectInBundleImportType")
+    }
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        user_json_schema!(
+            [(
+                "file",
+                InstanceType::String,
+                "Project bundle archive",
+                true,
+                Some(String::from("binary"))
+            )],
+            "Type of project bundle to import"
+        )
+    }
+}
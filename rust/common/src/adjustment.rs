@@ -1,9 +1,11 @@
+use crate::connection_function::ConnectionFunction;
+use crate::stability::{Activation, WeightMatrix};
 use async_trait::async_trait;
 use ordered_float::OrderedFloat;
 use rand::rngs::ThreadRng;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 #[async_trait]
@@ -19,6 +21,7 @@ pub enum DynamicModel {
     DeltaValue,
     ValueDelta,
     ValueValue,
+    Continuous,
 }
 
 #[derive(Deserialize)]
@@ -40,6 +43,34 @@ pub struct AdjustmentInput {
     pub generation_size: i32,
     pub generation_save_interval: i32,
     pub stop_condition: StopCondition,
+    #[serde(default)]
+    pub budget: Option<f64>,
+    #[serde(default)]
+    pub minimize_cost: bool,
+    #[serde(default)]
+    pub scenarios: Vec<Scenario>,
+    #[serde(default)]
+    pub scenario_aggregation: ScenarioAggregation,
+    #[serde(default)]
+    pub time_step: Option<f64>,
+    #[serde(default)]
+    pub stability_activation: Option<Activation>,
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scenario {
+    pub name: String,
+    pub initial_values: HashMap<i32, f64>,
+    pub clamped_values: HashMap<i32, f64>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioAggregation {
+    #[default]
+    WorstCase,
+    Mean,
 }
 
 #[derive(Deserialize)]
@@ -52,6 +83,10 @@ pub struct AdjustmentModel {
     pub regular_concepts: Vec<Arc<Concept>>,
     pub connections_map: HashMap<i32, Arc<Connection>>,
     pub control_connections: Vec<Arc<Connection>>,
+    #[serde(default)]
+    pub seed_individuals: Vec<Individual>,
+    #[serde(skip)]
+    function_control_connections: Vec<Arc<Connection>>,
     without_improvements: i32,
     current_generation: Option<Generation>,
     generation_number: i32,
@@ -68,6 +103,10 @@ pub struct Concept {
     pub target_value: Option<TargetValue>,
     pub constraint: Option<Constraint>,
     pub dynamic_model: Option<DynamicModel>,
+    #[serde(default)]
+    pub cost: Option<InterventionCost>,
+    #[serde(default)]
+    pub decay_rate: Option<f64>,
 }
 
 #[derive(Deserialize)]
@@ -79,6 +118,14 @@ pub struct Connection {
     pub target_id: i32,
     pub is_control: bool,
     pub constraint: Option<Constraint>,
+    #[serde(default)]
+    pub cost: Option<InterventionCost>,
+    #[serde(default)]
+    pub lag: i32,
+    #[serde(default)]
+    pub function: ConnectionFunction,
+    #[serde(default)]
+    pub is_function_control: bool,
 }
 
 #[derive(Deserialize)]
@@ -99,10 +146,31 @@ pub struct Constraint {
     pub include_max_value: bool,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterventionCost {
+    pub linear_cost: f64,
+    pub fixed_cost: f64,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScenarioError {
+    pub scenario_name: String,
+    pub error: f64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Fitness {
     pub time: i32,
     pub error: f64,
+    #[serde(default)]
+    pub cost: f64,
+    #[serde(default)]
+    pub scenario_errors: Vec<ScenarioError>,
+    #[serde(default)]
+    pub is_unstable: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -111,6 +179,8 @@ pub struct Individual {
     pub id: Option<i32>,
     pub concepts: HashMap<i32, f64>,
     pub connections: HashMap<i32, f64>,
+    #[serde(default)]
+    pub function_parameters: HashMap<i32, f64>,
     pub fitness: Option<Fitness>,
 }
 
@@ -142,6 +212,8 @@ impl AdjustmentModel {
             regular_concepts,
             connections_map,
             control_connections,
+            seed_individuals: vec![],
+            function_control_connections: vec![],
             without_improvements: 0,
             current_generation: None,
             generation_number: 0,
@@ -149,6 +221,14 @@ impl AdjustmentModel {
         }
     }
     pub fn start(&mut self) -> () {
+        self.function_control_connections = self
+            .connections_map
+            .values()
+            .filter(|connection| {
+                connection.is_function_control && connection.function.get_parameter().is_some()
+            })
+            .cloned()
+            .collect();
         self.without_improvements = 0;
         self.current_generation = Some(self.create_first_generation());
         self.generation_number = 0;
@@ -176,12 +256,14 @@ impl AdjustmentModel {
                 .await?;
             self.is_generation_saved = true;
         }
-        let best_individual_error = self.current_generation.as_ref().unwrap().individuals[0]
+        let best_individual_fitness = self.current_generation.as_ref().unwrap().individuals[0]
             .fitness
             .as_ref()
-            .unwrap()
-            .error;
-        if best_individual_error < self.adjustment_input.stop_condition.error {
+            .unwrap();
+        if !self.adjustment_input.minimize_cost
+            && !self.is_over_budget(best_individual_fitness)
+            && best_individual_fitness.error < self.adjustment_input.stop_condition.error
+        {
             return Ok(false);
         }
         let next_generation = self.create_next_generation();
@@ -224,9 +306,21 @@ impl AdjustmentModel {
         &self,
         concepts: &HashMap<i32, f64>,
         connections: &HashMap<i32, f64>,
+        function_parameters: &HashMap<i32, f64>,
     ) -> Fitness {
+        let cost = self.get_intervention_cost(concepts, connections);
+        let is_unstable = self.is_unstable(connections, function_parameters);
+        if !self.adjustment_input.scenarios.is_empty() {
+            return self.get_scenarios_fitness(
+                concepts,
+                connections,
+                function_parameters,
+                cost,
+                is_unstable,
+            );
+        }
         let concepts = self.get_initial_state(concepts);
-        let time_simulation = TimeSimulation::new(
+        let mut time_simulation = TimeSimulation::new(
             self.adjustment_input.max_model_time,
             self.concepts_map.clone(),
             self.connections_map.clone(),
@@ -235,9 +329,16 @@ impl AdjustmentModel {
             concepts,
             connections.clone(),
         );
+        time_simulation.set_function_parameters(function_parameters.clone());
+        if let Some(time_step) = self.adjustment_input.time_step {
+            time_simulation.set_time_step(time_step);
+        }
         let mut fitness = Fitness {
             error: f64::MAX,
             time: self.adjustment_input.min_model_time,
+            cost,
+            scenario_errors: vec![],
+            is_unstable,
         };
         for data in time_simulation {
             if data.time >= self.adjustment_input.min_model_time && data.error < fitness.error {
@@ -247,6 +348,149 @@ impl AdjustmentModel {
         }
         fitness
     }
+    fn get_scenarios_fitness(
+        &self,
+        concepts: &HashMap<i32, f64>,
+        connections: &HashMap<i32, f64>,
+        function_parameters: &HashMap<i32, f64>,
+        cost: f64,
+        is_unstable: bool,
+    ) -> Fitness {
+        let scenario_errors = self
+            .adjustment_input
+            .scenarios
+            .iter()
+            .map(|scenario| {
+                let mut state = self.get_initial_state(concepts);
+                for (concept_id, value) in scenario
+                    .initial_values
+                    .iter()
+                    .chain(scenario.clamped_values.iter())
+                {
+                    if !concepts.contains_key(concept_id) {
+                        state.insert(*concept_id, *value);
+                    }
+                }
+                let mut time_simulation = TimeSimulation::new(
+                    self.adjustment_input.max_model_time,
+                    self.concepts_map.clone(),
+                    self.connections_map.clone(),
+                    self.target_concepts.clone(),
+                    self.adjustment_input.dynamic_model.clone(),
+                    state,
+                    connections.clone(),
+                );
+                time_simulation.set_function_parameters(function_parameters.clone());
+                if let Some(time_step) = self.adjustment_input.time_step {
+                    time_simulation.set_time_step(time_step);
+                }
+                time_simulation.clamp(scenario.clamped_values.clone());
+                time_simulation.map(|data| data.error).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut fitness = Fitness {
+            error: f64::MAX,
+            time: self.adjustment_input.min_model_time,
+            cost,
+            scenario_errors: vec![],
+            is_unstable,
+        };
+        for time in
+            self.adjustment_input.min_model_time.max(1)..=self.adjustment_input.max_model_time
+        {
+            let errors = scenario_errors
+                .iter()
+                .map(|errors| errors[time as usize - 1])
+                .collect::<Vec<_>>();
+            let error = match self.adjustment_input.scenario_aggregation {
+                ScenarioAggregation::WorstCase => errors.iter().cloned().fold(0.0, f64::max),
+                ScenarioAggregation::Mean => errors.iter().sum::<f64>() / errors.len() as f64,
+            };
+            if error < fitness.error {
+                fitness.error = error;
+                fitness.time = time;
+                fitness.scenario_errors = self
+                    .adjustment_input
+                    .scenarios
+                    .iter()
+                    .zip(errors)
+                    .map(|(scenario, error)| ScenarioError {
+                        scenario_name: scenario.name.clone(),
+                        error,
+                    })
+                    .collect();
+            }
+        }
+        fitness
+    }
+    fn get_intervention_cost(
+        &self,
+        concepts: &HashMap<i32, f64>,
+        connections: &HashMap<i32, f64>,
+    ) -> f64 {
+        let concepts_cost = concepts
+            .iter()
+            .map(|(id, value)| {
+                let concept = &self.concepts_map[id];
+                match &concept.cost {
+                    Some(cost) => cost.get_cost(value - concept.value),
+                    None => 0.0,
+                }
+            })
+            .sum::<f64>();
+        let connections_cost = connections
+            .iter()
+            .map(|(id, value)| {
+                let connection = &self.connections_map[id];
+                match &connection.cost {
+                    Some(cost) => cost.get_cost(value - connection.value),
+                    None => 0.0,
+                }
+            })
+            .sum::<f64>();
+        concepts_cost + connections_cost
+    }
+    fn is_unstable(
+        &self,
+        connections: &HashMap<i32, f64>,
+        function_parameters: &HashMap<i32, f64>,
+    ) -> bool {
+        match &self.adjustment_input.stability_activation {
+            Some(activation) => !WeightMatrix::new(
+                &self.concepts_map,
+                &self.connections_map,
+                connections,
+                function_parameters,
+                &self.adjustment_input.dynamic_model,
+                self.adjustment_input.time_step.unwrap_or(DEFAULT_TIME_STEP),
+            )
+            .is_contraction(activation),
+            None => false,
+        }
+    }
+    fn is_over_budget(&self, fitness: &Fitness) -> bool {
+        match self.adjustment_input.budget {
+            Some(budget) => fitness.cost > budget,
+            None => false,
+        }
+    }
+    fn get_fitness_key(&self, fitness: &Fitness) -> (bool, OrderedFloat<f64>, OrderedFloat<f64>) {
+        if self.adjustment_input.minimize_cost
+            && fitness.error < self.adjustment_input.stop_condition.error
+        {
+            (
+                self.is_over_budget(fitness) || fitness.is_unstable,
+                OrderedFloat(0.0),
+                OrderedFloat(fitness.cost),
+            )
+        } else {
+            (
+                self.is_over_budget(fitness) || fitness.is_unstable,
+                OrderedFloat(fitness.error),
+                OrderedFloat(fitness.cost),
+            )
+        }
+    }
     fn get_generation_error(individuals: &[Individual]) -> f64 {
         individuals
             .iter()
@@ -262,8 +506,8 @@ impl AdjustmentModel {
                 &generation.individuals[rng.gen_range(0..generation.individuals.len())];
             let candidate2 =
                 &generation.individuals[rng.gen_range(0..generation.individuals.len())];
-            if candidate1.fitness.as_ref().unwrap().error
-                <= candidate2.fitness.as_ref().unwrap().error
+            if self.get_fitness_key(candidate1.fitness.as_ref().unwrap())
+                <= self.get_fitness_key(candidate2.fitness.as_ref().unwrap())
             {
                 parents.push(candidate1);
             } else {
@@ -302,17 +546,34 @@ impl AdjustmentModel {
             *individual.connections.get_mut(&connection.id).unwrap() =
                 connection.generate_value(rng);
         }
-        let fitness = self.get_individual_fitness(&individual.concepts, &individual.connections);
+        if !individual.function_parameters.is_empty() && rng.gen::<f64>() < connection_probability {
+            let connection = &self.function_control_connections
+                [rng.gen_range(0..self.function_control_connections.len())];
+            *individual
+                .function_parameters
+                .get_mut(&connection.id)
+                .unwrap() = connection.generate_function_parameter(rng);
+        }
+        let fitness = self.get_individual_fitness(
+            &individual.concepts,
+            &individual.connections,
+            &individual.function_parameters,
+        );
         individual.fitness = Some(fitness);
         individual
     }
     fn create_first_generation(&self) -> Generation {
         let mut rng = rand::thread_rng();
-        let mut individuals = Vec::new();
-        for _ in 0..self.adjustment_input.generation_size {
+        let mut individuals = self
+            .seed_individuals
+            .iter()
+            .take(self.adjustment_input.generation_size as usize)
+            .map(|seed_individual| self.create_seed_individual(seed_individual, &mut rng))
+            .collect::<Vec<_>>();
+        while individuals.len() < self.adjustment_input.generation_size as usize {
             individuals.push(Self::create_random_individual(self, &mut rng));
         }
-        Self::sort_by_fitness(&mut individuals);
+        self.sort_by_fitness(&mut individuals);
         let error = Self::get_generation_error(&individuals);
         Generation { individuals, error }
     }
@@ -335,7 +596,7 @@ impl AdjustmentModel {
         {
             individuals.push(individual.clone());
         }
-        Self::sort_by_fitness(&mut individuals);
+        self.sort_by_fitness(&mut individuals);
         let error = Self::get_generation_error(&individuals);
         Generation { individuals, error }
     }
@@ -348,11 +609,54 @@ impl AdjustmentModel {
         for connection in &self.control_connections {
             connections.insert(connection.id, connection.generate_value(rng));
         }
-        let fitness = self.get_individual_fitness(&concepts, &connections);
+        let mut function_parameters = HashMap::new();
+        for connection in &self.function_control_connections {
+            function_parameters.insert(connection.id, connection.generate_function_parameter(rng));
+        }
+        let fitness = self.get_individual_fitness(&concepts, &connections, &function_parameters);
+        Individual {
+            id: None,
+            concepts,
+            connections,
+            function_parameters,
+            fitness: Some(fitness),
+        }
+    }
+    fn create_seed_individual(
+        &self,
+        seed_individual: &Individual,
+        rng: &mut ThreadRng,
+    ) -> Individual {
+        let mut concepts = HashMap::new();
+        for concept in &self.control_concepts {
+            let value = match seed_individual.concepts.get(&concept.id) {
+                Some(value) if concept.is_valid_value(*value) => *value,
+                _ => concept.generate_value(rng),
+            };
+            concepts.insert(concept.id, value);
+        }
+        let mut connections = HashMap::new();
+        for connection in &self.control_connections {
+            let value = match seed_individual.connections.get(&connection.id) {
+                Some(value) if connection.is_valid_value(*value) => *value,
+                _ => connection.generate_value(rng),
+            };
+            connections.insert(connection.id, value);
+        }
+        let mut function_parameters = HashMap::new();
+        for connection in &self.function_control_connections {
+            let value = match seed_individual.function_parameters.get(&connection.id) {
+                Some(value) if *value >= 0.0 && *value <= 1.0 => *value,
+                _ => connection.generate_function_parameter(rng),
+            };
+            function_parameters.insert(connection.id, value);
+        }
+        let fitness = self.get_individual_fitness(&concepts, &connections, &function_parameters);
         Individual {
             id: None,
             concepts,
             connections,
+            function_parameters,
             fitness: Some(fitness),
         }
     }
@@ -400,10 +704,22 @@ impl AdjustmentModel {
             }
             connections.insert(*id, rng.gen_range(min..=max));
         }
+        let mut function_parameters = HashMap::new();
+        for (id, p1) in &parent1.function_parameters {
+            let mut p1 = *p1;
+            let mut p2 = parent2.function_parameters[id];
+            if p1 > p2 {
+                (p1, p2) = (p2, p1);
+            }
+            let min = f64::max(p1 - ALPHA * (p2 - p1), 0.0);
+            let max = f64::min(p2 + ALPHA * (p2 - p1), 1.0);
+            function_parameters.insert(*id, rng.gen_range(min..=max));
+        }
         Individual {
             id: None,
             concepts,
             connections,
+            function_parameters,
             fitness: None,
         }
     }
@@ -417,9 +733,9 @@ impl AdjustmentModel {
         }
         state
     }
-    fn sort_by_fitness(individuals: &mut Vec<Individual>) -> () {
+    fn sort_by_fitness(&self, individuals: &mut Vec<Individual>) -> () {
         individuals
-            .sort_by_key(|individual| OrderedFloat(individual.fitness.as_ref().unwrap().error))
+            .sort_by_key(|individual| self.get_fitness_key(individual.fitness.as_ref().unwrap()))
     }
 }
 
@@ -433,7 +749,13 @@ pub struct TimeSimulation {
     dynamic_model: DynamicModel,
     previous_state: HashMap<i32, f64>,
     delta_state: HashMap<i32, f64>,
+    state_history: VecDeque<State>,
+    delta_history: VecDeque<State>,
+    max_lag: usize,
     connections: HashMap<i32, f64>,
+    function_parameters: HashMap<i32, f64>,
+    time_step: f64,
+    clamped_values: HashMap<i32, f64>,
 }
 
 #[derive(Serialize)]
@@ -443,6 +765,26 @@ pub struct TimeSimulationData {
     pub state: HashMap<i32, f64>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contribution {
+    pub concept_id: i32,
+    pub connection_id: Option<i32>,
+    pub time: i32,
+    pub value: f64,
+    pub input: f64,
+    pub contribution: Option<f64>,
+    pub is_control: bool,
+    pub children: Vec<Contribution>,
+}
+
+struct ExplanationContext<'a> {
+    states: &'a [State],
+    deltas: &'a [State],
+    min_contribution: f64,
+    remaining_nodes: usize,
+}
+
 impl TimeSimulation {
     pub fn new(
         max_model_time: i32,
@@ -455,6 +797,7 @@ impl TimeSimulation {
     ) -> Self {
         let previous_state = concepts;
         let delta_state = previous_state.clone();
+        let max_lag = get_max_lag(&connections_map);
         Self {
             max_model_time,
             current_time: 0,
@@ -465,9 +808,29 @@ impl TimeSimulation {
             dynamic_model,
             previous_state,
             delta_state,
+            state_history: VecDeque::new(),
+            delta_history: VecDeque::new(),
+            max_lag,
             connections,
+            function_parameters: HashMap::new(),
+            time_step: DEFAULT_TIME_STEP,
+            clamped_values: HashMap::new(),
         }
     }
+    pub fn clamp(&mut self, clamped_values: HashMap<i32, f64>) {
+        for (concept_id, value) in &clamped_values {
+            self.previous_state.insert(*concept_id, *value);
+            self.delta_state.insert(*concept_id, *value);
+        }
+        self.error = Self::calculate_error(&self.previous_state, &self.target_concepts);
+        self.clamped_values = clamped_values;
+    }
+    pub fn set_function_parameters(&mut self, function_parameters: HashMap<i32, f64>) {
+        self.function_parameters = function_parameters;
+    }
+    pub fn set_time_step(&mut self, time_step: f64) {
+        self.time_step = time_step;
+    }
     pub fn get_max_model_time(&self) -> i32 {
         self.max_model_time
     }
@@ -480,26 +843,148 @@ impl TimeSimulation {
     pub fn get_state(&self) -> HashMap<i32, f64> {
         self.previous_state.clone()
     }
+    pub fn explain(
+        mut self,
+        time: i32,
+        max_depth: usize,
+        min_contribution: f64,
+    ) -> Vec<Contribution> {
+        let mut states = vec![self.previous_state.clone()];
+        while self.current_time < time {
+            match self.next() {
+                Some(data) => states.push(data.state),
+                None => break,
+            }
+        }
+        let deltas = (0..states.len())
+            .map(|time| match time {
+                0 => states[0].clone(),
+                _ => State::from_iter(
+                    states[time]
+                        .iter()
+                        .map(|(k, v)| (*k, v - states[time - 1][k])),
+                ),
+            })
+            .collect::<Vec<_>>();
+        let mut target_concepts = self.target_concepts.clone();
+        target_concepts.sort_by_key(|concept| concept.id);
+        let mut context = ExplanationContext {
+            states: &states,
+            deltas: &deltas,
+            min_contribution,
+            remaining_nodes: MAX_EXPLANATION_NODES.saturating_sub(target_concepts.len()),
+        };
+        target_concepts
+            .iter()
+            .map(|concept| {
+                self.explain_concept(&mut context, concept.id, None, states.len() - 1, max_depth)
+            })
+            .collect()
+    }
+    // Sources are traced from the largest contribution while the node budget of the tree lasts,
+    // so the tree does not grow exponentially with the depth
+    fn explain_concept(
+        &self,
+        context: &mut ExplanationContext,
+        concept_id: i32,
+        contribution: Option<(i32, f64)>,
+        time: usize,
+        depth: usize,
+    ) -> Contribution {
+        let concept = &self.concepts_map[&concept_id];
+        let dynamic_model = concept
+            .dynamic_model
+            .as_ref()
+            .unwrap_or(&self.dynamic_model);
+        let source_states = match dynamic_model {
+            DynamicModel::DeltaDelta | DynamicModel::ValueDelta => context.deltas,
+            DynamicModel::DeltaValue | DynamicModel::ValueValue | DynamicModel::Continuous => {
+                context.states
+            }
+        };
+        let mut input = 0.0;
+        let mut sources = vec![];
+        if time > 0 {
+            for (connection, value) in self.get_to_connections(concept_id) {
+                let source_time = time.saturating_sub(1 + connection.get_lag());
+                let source_value = source_states[source_time][&connection.source_id];
+                let contribution = value * self.apply_function(connection, source_value);
+                input += contribution;
+                if depth == 0 || concept.is_control || contribution.abs() < context.min_contribution
+                {
+                    continue;
+                }
+                sources.push((connection, contribution, source_time));
+            }
+        }
+        sources.sort_by(|a, b| b.1.abs().total_cmp(&a.1.abs()));
+        let mut children = vec![];
+        for (connection, contribution, source_time) in sources {
+            if context.remaining_nodes == 0 {
+                break;
+            }
+            context.remaining_nodes -= 1;
+            children.push(self.explain_concept(
+                context,
+                connection.source_id,
+                Some((connection.id, contribution)),
+                source_time,
+                depth - 1,
+            ));
+        }
+        Contribution {
+            concept_id,
+            connection_id: contribution.map(|(connection_id, _)| connection_id),
+            time: time as i32,
+            value: context.states[time][&concept_id],
+            input,
+            contribution: contribution.map(|(_, contribution)| contribution),
+            is_control: concept.is_control,
+            children,
+        }
+    }
     fn execute_next_value(
         &self,
         current_state: &mut HashMap<i32, f64>,
         concept_id: i32,
         dynamic_model: &DynamicModel,
-        to_connections: &[(i32, f64)],
+        to_connections: &[(&Connection, f64)],
     ) -> () {
         let current_value = current_state.get_mut(&concept_id).unwrap();
+        let delta_value = |connection: &Connection| {
+            self.apply_function(
+                connection,
+                get_lagged_value(
+                    &self.delta_state,
+                    &self.delta_history,
+                    connection.source_id,
+                    connection.get_lag(),
+                ),
+            )
+        };
+        let state_value = |connection: &Connection| {
+            self.apply_function(
+                connection,
+                get_lagged_value(
+                    &self.previous_state,
+                    &self.state_history,
+                    connection.source_id,
+                    connection.get_lag(),
+                ),
+            )
+        };
         match dynamic_model {
             DynamicModel::DeltaDelta => {
                 *current_value += to_connections
                     .iter()
-                    .map(|(source_id, value)| value * self.delta_state[&source_id])
+                    .map(|(connection, value)| value * delta_value(connection))
                     .sum::<f64>();
                 *current_value = Self::normalize_value(*current_value)
             }
             DynamicModel::DeltaValue => {
                 *current_value += to_connections
                     .iter()
-                    .map(|(source_id, value)| value * self.previous_state[&source_id])
+                    .map(|(connection, value)| value * state_value(connection))
                     .sum::<f64>();
                 *current_value = Self::normalize_value(*current_value)
             }
@@ -507,7 +992,7 @@ impl TimeSimulation {
                 *current_value = Self::normalize_value(
                     to_connections
                         .iter()
-                        .map(|(source_id, value)| value * self.delta_state[&source_id])
+                        .map(|(connection, value)| value * delta_value(connection))
                         .sum::<f64>(),
                 );
             }
@@ -515,12 +1000,90 @@ impl TimeSimulation {
                 *current_value = Self::normalize_value(
                     to_connections
                         .iter()
-                        .map(|(source_id, value)| value * self.previous_state[&source_id])
+                        .map(|(connection, value)| value * state_value(connection))
                         .sum::<f64>(),
                 );
             }
+            DynamicModel::Continuous => unreachable!(),
         };
     }
+    fn execute_continuous_step(&self, current_state: &mut State, concept_ids: &[i32]) {
+        let time_step = self.time_step;
+        let k1 = self.calculate_derivative(&self.previous_state, concept_ids);
+        let k2 = self.calculate_derivative(
+            &Self::add_scaled(&self.previous_state, &k1, time_step / 2.0),
+            concept_ids,
+        );
+        let k3 = self.calculate_derivative(
+            &Self::add_scaled(&self.previous_state, &k2, time_step / 2.0),
+            concept_ids,
+        );
+        let k4 = self.calculate_derivative(
+            &Self::add_scaled(&self.previous_state, &k3, time_step),
+            concept_ids,
+        );
+        for concept_id in concept_ids {
+            let value = self.previous_state[concept_id]
+                + time_step / 6.0
+                    * (k1[concept_id]
+                        + 2.0 * k2[concept_id]
+                        + 2.0 * k3[concept_id]
+                        + k4[concept_id]);
+            current_state.insert(*concept_id, Self::normalize_value(value));
+        }
+    }
+    fn calculate_derivative(&self, state: &State, concept_ids: &[i32]) -> State {
+        concept_ids
+            .iter()
+            .map(|concept_id| {
+                let concept = &self.concepts_map[concept_id];
+                let sum = self
+                    .get_to_connections(*concept_id)
+                    .iter()
+                    .map(|(connection, value)| {
+                        let source_value = match connection.get_lag() {
+                            0 => state[&connection.source_id],
+                            lag => get_lagged_value(
+                                &self.previous_state,
+                                &self.state_history,
+                                connection.source_id,
+                                lag,
+                            ),
+                        };
+                        value * self.apply_function(connection, source_value)
+                    })
+                    .sum::<f64>();
+                let derivative =
+                    -concept.get_decay_rate() * state[concept_id] + Self::normalize_value(sum);
+                (*concept_id, derivative)
+            })
+            .collect()
+    }
+    fn add_scaled(state: &State, derivative: &State, factor: f64) -> State {
+        let mut state = state.clone();
+        for (concept_id, value) in derivative {
+            *state.get_mut(concept_id).unwrap() += factor * value;
+        }
+        state
+    }
+    fn get_to_connections(&self, concept_id: i32) -> Vec<(&Connection, f64)> {
+        self.connections_map
+            .values()
+            .filter(|connection| connection.target_id == concept_id)
+            .map(|connection| {
+                let value = match self.connections.get(&connection.id) {
+                    Some(value) => *value,
+                    None => connection.value,
+                };
+                (connection.as_ref(), value)
+            })
+            .collect()
+    }
+    fn apply_function(&self, connection: &Connection, value: f64) -> f64 {
+        connection
+            .function
+            .apply(value, self.function_parameters.get(&connection.id).cloned())
+    }
     fn calculate_delta_state(&self, current_state: &State) -> State {
         State::from_iter(
             current_state
@@ -567,21 +1130,19 @@ impl Iterator for TimeSimulation {
             panic!("The current time must be less or equal to the max model time");
         }
         let mut current_state = self.previous_state.clone();
+        let mut continuous_concepts = vec![];
         for concept in self.concepts_map.values() {
             let dynamic_model = concept
                 .dynamic_model
                 .as_ref()
                 .unwrap_or(&self.dynamic_model);
-            let to_connections = self
-                .connections_map
-                .values()
-                .filter(|connection| connection.target_id == concept.id)
-                .map(|connection| match self.connections.get(&connection.id) {
-                    Some(value) => (connection.source_id, *value),
-                    None => (connection.source_id, connection.value),
-                })
-                .collect::<Vec<_>>();
-            if to_connections.len() == 0 {
+            let to_connections = self.get_to_connections(concept.id);
+            // Source concepts have no input, so they keep their value in every model
+            if to_connections.is_empty() {
+                continue;
+            }
+            if let DynamicModel::Continuous = dynamic_model {
+                continuous_concepts.push(concept.id);
                 continue;
             }
             self.execute_next_value(
@@ -591,7 +1152,18 @@ impl Iterator for TimeSimulation {
                 &to_connections,
             )
         }
-        self.delta_state = self.calculate_delta_state(&current_state);
+        if !continuous_concepts.is_empty() {
+            self.execute_continuous_step(&mut current_state, &continuous_concepts);
+        }
+        for (concept_id, value) in &self.clamped_values {
+            current_state.insert(*concept_id, *value);
+        }
+        let delta_state = self.calculate_delta_state(&current_state);
+        if self.max_lag > 0 {
+            push_history(&mut self.state_history, &self.previous_state, self.max_lag);
+            push_history(&mut self.delta_history, &self.delta_state, self.max_lag);
+        }
+        self.delta_state = delta_state;
         self.previous_state = current_state;
         self.current_time += 1;
         self.error = Self::calculate_error(&self.previous_state, &self.target_concepts);
@@ -614,6 +1186,15 @@ impl Concept {
             None => rng.gen_range(0.0..=1.0),
         }
     }
+    fn is_valid_value(&self, value: f64) -> bool {
+        match &self.constraint {
+            Some(constraint) => constraint.contains(value),
+            None => (0.0..=1.0).contains(&value),
+        }
+    }
+    pub fn get_decay_rate(&self) -> f64 {
+        self.decay_rate.unwrap_or(DEFAULT_DECAY_RATE)
+    }
 }
 
 impl Connection {
@@ -629,9 +1210,51 @@ impl Connection {
             }
         }
     }
+    fn is_valid_value(&self, value: f64) -> bool {
+        match &self.constraint {
+            Some(constraint) => constraint.contains(value),
+            None => (-1.0..=1.0).contains(&value),
+        }
+    }
+    fn generate_function_parameter(&self, rng: &mut ThreadRng) -> f64 {
+        rng.gen_range(0.0..=1.0)
+    }
+    pub fn get_lag(&self) -> usize {
+        self.lag.clamp(0, MAX_CONNECTION_LAG) as usize
+    }
 }
 
 const SIGNIFICANT_DIFF: f64 = 0.0000001;
+pub const DEFAULT_TIME_STEP: f64 = 1.0;
+pub const DEFAULT_DECAY_RATE: f64 = 1.0;
+pub const MAX_CONNECTION_LAG: i32 = 100;
+pub const MAX_EXPLANATION_NODES: usize = 1000;
+
+impl InterventionCost {
+    fn get_cost(&self, delta: f64) -> f64 {
+        if delta.abs() < SIGNIFICANT_DIFF {
+            return 0.0;
+        }
+        self.fixed_cost + self.linear_cost * delta.abs()
+    }
+}
+
+impl TargetValue {
+    pub fn get_min(&self) -> f64 {
+        if self.include_min_value {
+            self.min_value
+        } else {
+            self.min_value + SIGNIFICANT_DIFF
+        }
+    }
+    pub fn get_max(&self) -> f64 {
+        if self.include_max_value {
+            self.max_value
+        } else {
+            self.max_value - SIGNIFICANT_DIFF
+        }
+    }
+}
 
 impl Constraint {
     fn generate_value(&self, rng: &mut ThreadRng) -> f64 {
@@ -648,7 +1271,11 @@ impl Constraint {
         }
         number
     }
-    fn get_min(&self, min: f64) -> f64 {
+    fn contains(&self, value: f64) -> bool {
+        (self.include_min_value && value >= self.min_value || value > self.min_value)
+            && (self.include_max_value && value <= self.max_value || value < self.max_value)
+    }
+    pub fn get_min(&self, min: f64) -> f64 {
         f64::max(
             min,
             if self.include_min_value {
@@ -658,7 +1285,7 @@ impl Constraint {
             },
         )
     }
-    fn get_max(&self, max: f64) -> f64 {
+    pub fn get_max(&self, max: f64) -> f64 {
         f64::min(
             max,
             if self.include_max_value {
@@ -671,3 +1298,132 @@ impl Constraint {
 }
 
 type State = HashMap<i32, f64>;
+
+pub fn get_max_lag(connections_map: &HashMap<i32, Arc<Connection>>) -> usize {
+    connections_map
+        .values()
+        .map(|connection| connection.get_lag())
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn get_lagged_value<T: Copy>(
+    current_state: &HashMap<i32, T>,
+    history: &VecDeque<HashMap<i32, T>>,
+    concept_id: i32,
+    lag: usize,
+) -> T {
+    if lag == 0 || history.is_empty() {
+        return current_state[&concept_id];
+    }
+    let index = history.len().saturating_sub(lag);
+    history[index][&concept_id]
+}
+
+pub fn push_history<T: Clone>(
+    history: &mut VecDeque<HashMap<i32, T>>,
+    state: &HashMap<i32, T>,
+    max_lag: usize,
+) {
+    if history.len() == max_lag {
+        history.pop_front();
+    }
+    history.push_back(state.clone());
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn get_concept(id: i32, value: f64, decay_rate: Option<f64>) -> Arc<Concept> {
+        Arc::new(Concept {
+            id,
+            value,
+            is_control: false,
+            is_target: false,
+            target_value: None,
+            constraint: None,
+            dynamic_model: None,
+            cost: None,
+            decay_rate,
+        })
+    }
+
+    pub(crate) fn get_connection(
+        id: i32,
+        value: f64,
+        source_id: i32,
+        target_id: i32,
+    ) -> Arc<Connection> {
+        Arc::new(Connection {
+            id,
+            value,
+            source_id,
+            target_id,
+            is_control: false,
+            constraint: None,
+            cost: None,
+            lag: 0,
+            function: ConnectionFunction::Linear,
+            is_function_control: false,
+        })
+    }
+
+    fn get_time_simulation(
+        concepts: Vec<Arc<Concept>>,
+        connections: Vec<Arc<Connection>>,
+        dynamic_model: DynamicModel,
+        max_model_time: i32,
+    ) -> TimeSimulation {
+        let state = HashMap::from_iter(concepts.iter().map(|concept| (concept.id, concept.value)));
+        TimeSimulation::new(
+            max_model_time,
+            HashMap::from_iter(concepts.into_iter().map(|concept| (concept.id, concept))),
+            HashMap::from_iter(
+                connections
+                    .into_iter()
+                    .map(|connection| (connection.id, connection)),
+            ),
+            vec![],
+            dynamic_model,
+            state,
+            HashMap::new(),
+        )
+    }
+
+    #[test]
+    fn continuous_step_matches_exponential_decay() {
+        let decay_rate = 0.5;
+        let time_step = 0.1;
+        // The zero weight connection leaves only the decay term in the derivative
+        let mut time_simulation = get_time_simulation(
+            vec![
+                get_concept(1, 0.0, None),
+                get_concept(2, 1.0, Some(decay_rate)),
+            ],
+            vec![get_connection(1, 0.0, 1, 2)],
+            DynamicModel::Continuous,
+            50,
+        );
+        time_simulation.set_time_step(time_step);
+        for data in time_simulation {
+            let expected = (-decay_rate * time_step * data.time as f64).exp();
+            assert!((data.state[&2] - expected).abs() < 1e-7);
+            assert_eq!(data.state[&1], 0.0);
+        }
+    }
+
+    #[test]
+    fn source_concepts_keep_their_value() {
+        let time_simulation = get_time_simulation(
+            vec![get_concept(1, 0.7, None), get_concept(2, 0.0, None)],
+            vec![get_connection(1, 0.5, 1, 2)],
+            DynamicModel::ValueValue,
+            5,
+        );
+        for data in time_simulation {
+            assert_eq!(data.state[&1], 0.7);
+            assert!((data.state[&2] - 0.35).abs() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FunctionPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearPiece {
+    pub slope: f64,
+    pub intercept: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConnectionFunction {
+    #[default]
+    Linear,
+    Threshold {
+        threshold: f64,
+    },
+    Saturation {
+        saturation: f64,
+    },
+    PiecewiseLinear {
+        points: Vec<FunctionPoint>,
+    },
+}
+
+impl ConnectionFunction {
+    pub fn get_parameter(&self) -> Option<f64> {
+        match self {
+            Self::Threshold { threshold } => Some(*threshold),
+            Self::Saturation { saturation } => Some(*saturation),
+            Self::Linear | Self::PiecewiseLinear { .. } => None,
+        }
+    }
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Linear => true,
+            Self::Threshold { threshold } => *threshold >= 0.0 && *threshold <= 1.0,
+            Self::Saturation { saturation } => *saturation >= 0.0 && *saturation <= 1.0,
+            Self::PiecewiseLinear { points } => {
+                points.len() >= 2
+                    && points.iter().all(|point| {
+                        point.x >= -1.0 && point.x <= 1.0 && point.y >= -1.0 && point.y <= 1.0
+                    })
+                    && points.windows(2).all(|pair| pair[0].x < pair[1].x)
+            }
+        }
+    }
+    pub fn apply(&self, value: f64, parameter: Option<f64>) -> f64 {
+        match self {
+            Self::Linear => value,
+            // Weak influence of either sign is cut off, strong influence keeps its sign
+            Self::Threshold { threshold } => {
+                if value.abs() >= parameter.unwrap_or(*threshold) {
+                    value
+                } else {
+                    0.0
+                }
+            }
+            Self::Saturation { saturation } => {
+                let saturation = parameter.unwrap_or(*saturation);
+                value.clamp(-saturation, saturation)
+            }
+            Self::PiecewiseLinear { points } => Self::interpolate(points, value),
+        }
+    }
+    pub fn get_lipschitz_constant(&self, parameter: Option<f64>) -> Option<f64> {
+        match self {
+            Self::Linear | Self::Saturation { .. } => Some(1.0),
+            Self::Threshold { threshold } => {
+                if parameter.unwrap_or(*threshold) > 0.0 {
+                    None
+                } else {
+                    Some(1.0)
+                }
+            }
+            Self::PiecewiseLinear { points } => Some(
+                points
+                    .windows(2)
+                    .map(|pair| ((pair[1].y - pair[0].y) / (pair[1].x - pair[0].x)).abs())
+                    .fold(0.0, f64::max),
+            ),
+        }
+    }
+    pub fn get_linear_piece(&self, value: f64, parameter: Option<f64>) -> LinearPiece {
+        match self {
+            Self::Linear => LinearPiece::new(1.0, 0.0, f64::NEG_INFINITY, f64::INFINITY),
+            Self::Threshold { threshold } => {
+                let threshold = parameter.unwrap_or(*threshold);
+                if value >= threshold {
+                    LinearPiece::new(1.0, 0.0, threshold, f64::INFINITY)
+                } else if value <= -threshold {
+                    LinearPiece::new(1.0, 0.0, f64::NEG_INFINITY, -threshold)
+                } else {
+                    LinearPiece::new(0.0, 0.0, -threshold, threshold)
+                }
+            }
+            Self::Saturation { saturation } => {
+                let saturation = parameter.unwrap_or(*saturation);
+                if value < -saturation {
+                    LinearPiece::new(0.0, -saturation, f64::NEG_INFINITY, -saturation)
+                } else if value > saturation {
+                    LinearPiece::new(0.0, saturation, saturation, f64::INFINITY)
+                } else {
+                    LinearPiece::new(1.0, 0.0, -saturation, saturation)
+                }
+            }
+            Self::PiecewiseLinear { points } => {
+                let (first, last) = match (points.first(), points.last()) {
+                    (Some(first), Some(last)) => (first, last),
+                    _ => return LinearPiece::new(1.0, 0.0, f64::NEG_INFINITY, f64::INFINITY),
+                };
+                if value <= first.x {
+                    return LinearPiece::new(0.0, first.y, f64::NEG_INFINITY, first.x);
+                }
+                if value >= last.x {
+                    return LinearPiece::new(0.0, last.y, last.x, f64::INFINITY);
+                }
+                for pair in points.windows(2) {
+                    let (start, end) = (pair[0], pair[1]);
+                    if value <= end.x {
+                        let slope = (end.y - start.y) / (end.x - start.x);
+                        return LinearPiece::new(slope, start.y - slope * start.x, start.x, end.x);
+                    }
+                }
+                LinearPiece::new(0.0, last.y, last.x, f64::INFINITY)
+            }
+        }
+    }
+    pub fn get_breakpoints(&self) -> Vec<FunctionPoint> {
+        match self {
+            Self::PiecewiseLinear { points } => points.clone(),
+            _ => vec![],
+        }
+    }
+    fn interpolate(points: &[FunctionPoint], value: f64) -> f64 {
+        let (first, last) = match (points.first(), points.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return value,
+        };
+        if value <= first.x {
+            return first.y;
+        }
+        if value >= last.x {
+            return last.y;
+        }
+        for pair in points.windows(2) {
+            let (start, end) = (pair[0], pair[1]);
+            if value <= end.x {
+                return start.y + (value - start.x) * (end.y - start.y) / (end.x - start.x);
+            }
+        }
+        last.y
+    }
+}
+
+impl LinearPiece {
+    pub fn new(slope: f64, intercept: f64, lower: f64, upper: f64) -> Self {
+        Self {
+            slope,
+            intercept,
+            lower,
+            upper,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_piecewise_linear() -> ConnectionFunction {
+        ConnectionFunction::PiecewiseLinear {
+            points: vec![
+                FunctionPoint { x: -0.5, y: -1.0 },
+                FunctionPoint { x: 0.0, y: 0.0 },
+                FunctionPoint { x: 0.5, y: 0.25 },
+            ],
+        }
+    }
+
+    #[test]
+    fn piecewise_linear_interpolates_edge_points() {
+        let function = get_piecewise_linear();
+        assert_eq!(function.apply(-1.0, None), -1.0);
+        assert_eq!(function.apply(-0.5, None), -1.0);
+        assert_eq!(function.apply(0.0, None), 0.0);
+        assert_eq!(function.apply(0.25, None), 0.125);
+        assert_eq!(function.apply(0.5, None), 0.25);
+        assert_eq!(function.apply(1.0, None), 0.25);
+    }
+
+    #[test]
+    fn piecewise_linear_piece_matches_interpolation() {
+        let function = get_piecewise_linear();
+        for value in [-1.0, -0.5, -0.25, 0.0, 0.25, 0.5, 1.0] {
+            let piece = function.get_linear_piece(value, None);
+            assert!(piece.lower <= value && value <= piece.upper);
+            assert_eq!(
+                piece.slope * value + piece.intercept,
+                function.apply(value, None)
+            );
+        }
+    }
+
+    #[test]
+    fn threshold_keeps_sign_of_strong_influence() {
+        let function = ConnectionFunction::Threshold { threshold: 0.3 };
+        assert_eq!(function.apply(0.5, None), 0.5);
+        assert_eq!(function.apply(-0.5, None), -0.5);
+        assert_eq!(function.apply(0.2, None), 0.0);
+        assert_eq!(function.apply(-0.2, None), 0.0);
+        assert_eq!(function.apply(-0.2, Some(0.1)), -0.2);
+        for value in [-0.5, -0.3, -0.2, 0.0, 0.2, 0.3, 0.5] {
+            let piece = function.get_linear_piece(value, None);
+            assert_eq!(
+                piece.slope * value + piece.intercept,
+                function.apply(value, None)
+            );
+        }
+    }
+}
@@ -0,0 +1,542 @@
+use crate::adjustment::{
+    get_lagged_value, get_max_lag, push_history, Concept, Connection, Constraint, DynamicModel,
+    DEFAULT_TIME_STEP,
+};
+use crate::connection_function::ConnectionFunction;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::ops::{Add, Mul, Sub};
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Interval {
+    pub fn new(lower: f64, upper: f64) -> Self {
+        Self { lower, upper }
+    }
+    pub fn point(value: f64) -> Self {
+        Self {
+            lower: value,
+            upper: value,
+        }
+    }
+    pub fn from_constraint(constraint: &Constraint) -> Self {
+        Self {
+            lower: constraint.min_value,
+            upper: constraint.max_value,
+        }
+    }
+    pub fn get_width(&self) -> f64 {
+        self.upper - self.lower
+    }
+    fn apply_function(self, function: &ConnectionFunction) -> Self {
+        let mut values = vec![
+            function.apply(self.lower, None),
+            function.apply(self.upper, None),
+        ];
+        values.extend(
+            function
+                .get_breakpoints()
+                .iter()
+                .filter(|point| point.x > self.lower && point.x < self.upper)
+                .map(|point| point.y),
+        );
+        Self {
+            lower: values.iter().cloned().fold(f64::INFINITY, f64::min),
+            upper: values.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+    fn normalize(self) -> Self {
+        Self {
+            lower: Self::normalize_value(self.lower),
+            upper: Self::normalize_value(self.upper),
+        }
+    }
+    fn normalize_value(value: f64) -> f64 {
+        if value > 1.0 {
+            return 1.0;
+        }
+        if value <= 0.0 {
+            return 0.0;
+        }
+        value
+    }
+}
+
+impl Add for Interval {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self {
+            lower: self.lower + other.lower,
+            upper: self.upper + other.upper,
+        }
+    }
+}
+
+impl Sub for Interval {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self {
+            lower: self.lower - other.upper,
+            upper: self.upper - other.lower,
+        }
+    }
+}
+
+impl Mul for Interval {
+    type Output = Self;
+    fn mul(self, other: Self) -> Self {
+        let products = [
+            self.lower * other.lower,
+            self.lower * other.upper,
+            self.upper * other.lower,
+            self.upper * other.upper,
+        ];
+        Self {
+            lower: products.iter().cloned().fold(f64::INFINITY, f64::min),
+            upper: products.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+pub fn get_concept_intervals(
+    concepts_map: &HashMap<i32, Arc<Concept>>,
+    intervals: &HashMap<i32, Interval>,
+) -> HashMap<i32, Interval> {
+    concepts_map
+        .values()
+        .map(|concept| {
+            let interval = match (intervals.get(&concept.id), &concept.constraint) {
+                (Some(interval), _) => *interval,
+                (None, Some(constraint)) => Interval::from_constraint(constraint),
+                (None, None) => Interval::point(concept.value),
+            };
+            (concept.id, interval)
+        })
+        .collect()
+}
+
+pub fn get_connection_intervals(
+    connections_map: &HashMap<i32, Arc<Connection>>,
+    intervals: &HashMap<i32, Interval>,
+) -> HashMap<i32, Interval> {
+    connections_map
+        .values()
+        .map(|connection| {
+            let interval = match (intervals.get(&connection.id), &connection.constraint) {
+                (Some(interval), _) => *interval,
+                (None, Some(constraint)) => Interval::from_constraint(constraint),
+                (None, None) => Interval::point(connection.value),
+            };
+            (connection.id, interval)
+        })
+        .collect()
+}
+
+pub struct IntervalTimeSimulation {
+    max_model_time: i32,
+    current_time: i32,
+    concepts_map: HashMap<i32, Arc<Concept>>,
+    connections_map: HashMap<i32, Arc<Connection>>,
+    dynamic_model: DynamicModel,
+    previous_state: HashMap<i32, Interval>,
+    delta_state: HashMap<i32, Interval>,
+    state_history: VecDeque<HashMap<i32, Interval>>,
+    delta_history: VecDeque<HashMap<i32, Interval>>,
+    max_lag: usize,
+    connections: HashMap<i32, Interval>,
+    time_step: f64,
+}
+
+#[derive(Serialize)]
+pub struct IntervalTimeSimulationData {
+    pub time: i32,
+    pub state: HashMap<i32, Interval>,
+}
+
+impl IntervalTimeSimulation {
+    pub fn new(
+        max_model_time: i32,
+        concepts_map: HashMap<i32, Arc<Concept>>,
+        connections_map: HashMap<i32, Arc<Connection>>,
+        dynamic_model: DynamicModel,
+        concepts: HashMap<i32, Interval>,
+        connections: HashMap<i32, Interval>,
+    ) -> Self {
+        let previous_state = concepts;
+        let delta_state = previous_state.clone();
+        let max_lag = get_max_lag(&connections_map);
+        Self {
+            max_model_time,
+            current_time: 0,
+            concepts_map,
+            connections_map,
+            dynamic_model,
+            previous_state,
+            delta_state,
+            state_history: VecDeque::new(),
+            delta_history: VecDeque::new(),
+            max_lag,
+            connections,
+            time_step: DEFAULT_TIME_STEP,
+        }
+    }
+    pub fn set_time_step(&mut self, time_step: f64) {
+        self.time_step = time_step;
+    }
+    pub fn get_max_model_time(&self) -> i32 {
+        self.max_model_time
+    }
+    pub fn get_current_time(&self) -> i32 {
+        self.current_time
+    }
+    pub fn get_state(&self) -> HashMap<i32, Interval> {
+        self.previous_state.clone()
+    }
+    fn execute_next_value(
+        &self,
+        current_state: &mut HashMap<i32, Interval>,
+        concept_id: i32,
+        dynamic_model: &DynamicModel,
+        to_connections: &[(&Connection, Interval)],
+    ) {
+        let current_value = current_state.get_mut(&concept_id).unwrap();
+        let (source_state, source_history) = match dynamic_model {
+            DynamicModel::DeltaDelta | DynamicModel::ValueDelta => {
+                (&self.delta_state, &self.delta_history)
+            }
+            DynamicModel::DeltaValue | DynamicModel::ValueValue | DynamicModel::Continuous => {
+                (&self.previous_state, &self.state_history)
+            }
+        };
+        let sum = to_connections
+            .iter()
+            .map(|(connection, value)| {
+                let source_value = get_lagged_value(
+                    source_state,
+                    source_history,
+                    connection.source_id,
+                    connection.get_lag(),
+                );
+                *value * source_value.apply_function(&connection.function)
+            })
+            .fold(Interval::point(0.0), |sum, value| sum + value);
+        *current_value = match dynamic_model {
+            DynamicModel::DeltaDelta | DynamicModel::DeltaValue => {
+                (*current_value + sum).normalize()
+            }
+            DynamicModel::ValueDelta | DynamicModel::ValueValue => sum.normalize(),
+            DynamicModel::Continuous => unreachable!(),
+        };
+    }
+    // The classic Runge-Kutta step of A' = -λA + s is expanded into coefficients of A and of
+    // the stage inputs, so every interval occurs once and the step does not widen the enclosure
+    fn execute_continuous_step(
+        &self,
+        current_state: &mut HashMap<i32, Interval>,
+        concept_ids: &[i32],
+    ) {
+        let time_step = self.time_step;
+        let factors = HashMap::<i32, f64>::from_iter(concept_ids.iter().map(|concept_id| {
+            (
+                *concept_id,
+                -self.concepts_map[concept_id].get_decay_rate() * time_step,
+            )
+        }));
+        let s1 = self.calculate_inputs(&self.previous_state, concept_ids);
+        let y2 = self.get_stage_state(concept_ids, |concept_id, value| {
+            let z = factors[&concept_id];
+            Interval::point(1.0 + z / 2.0) * value
+                + Interval::point(time_step / 2.0) * s1[&concept_id]
+        });
+        let s2 = self.calculate_inputs(&y2, concept_ids);
+        let y3 = self.get_stage_state(concept_ids, |concept_id, value| {
+            let z = factors[&concept_id];
+            Interval::point(1.0 + z / 2.0 + z.powi(2) / 4.0) * value
+                + Interval::point(time_step / 2.0)
+                    * (Interval::point(z / 2.0) * s1[&concept_id] + s2[&concept_id])
+        });
+        let s3 = self.calculate_inputs(&y3, concept_ids);
+        let y4 = self.get_stage_state(concept_ids, |concept_id, value| {
+            let z = factors[&concept_id];
+            Interval::point(1.0 + z + z.powi(2) / 2.0 + z.powi(3) / 4.0) * value
+                + Interval::point(time_step)
+                    * (Interval::point(z.powi(2) / 4.0) * s1[&concept_id]
+                        + Interval::point(z / 2.0) * s2[&concept_id]
+                        + s3[&concept_id])
+        });
+        let s4 = self.calculate_inputs(&y4, concept_ids);
+        for concept_id in concept_ids {
+            let z = factors[concept_id];
+            let value =
+                Interval::point(1.0 + z + z.powi(2) / 2.0 + z.powi(3) / 6.0 + z.powi(4) / 24.0)
+                    * self.previous_state[concept_id]
+                    + Interval::point(time_step / 6.0)
+                        * (Interval::point(1.0 + z + z.powi(2) / 2.0 + z.powi(3) / 4.0)
+                            * s1[concept_id]
+                            + Interval::point(2.0 + z + z.powi(2) / 2.0) * s2[concept_id]
+                            + Interval::point(2.0 + z) * s3[concept_id]
+                            + s4[concept_id]);
+            current_state.insert(*concept_id, value.normalize());
+        }
+    }
+    fn calculate_inputs(
+        &self,
+        state: &HashMap<i32, Interval>,
+        concept_ids: &[i32],
+    ) -> HashMap<i32, Interval> {
+        concept_ids
+            .iter()
+            .map(|concept_id| {
+                let sum = self
+                    .get_to_connections(*concept_id)
+                    .iter()
+                    .map(|(connection, value)| {
+                        let source_value = match connection.get_lag() {
+                            0 => state[&connection.source_id],
+                            lag => get_lagged_value(
+                                &self.previous_state,
+                                &self.state_history,
+                                connection.source_id,
+                                lag,
+                            ),
+                        };
+                        *value * source_value.apply_function(&connection.function)
+                    })
+                    .fold(Interval::point(0.0), |sum, value| sum + value);
+                (*concept_id, sum.normalize())
+            })
+            .collect()
+    }
+    fn get_stage_state<F: Fn(i32, Interval) -> Interval>(
+        &self,
+        concept_ids: &[i32],
+        get_value: F,
+    ) -> HashMap<i32, Interval> {
+        let mut state = self.previous_state.clone();
+        for concept_id in concept_ids {
+            state.insert(
+                *concept_id,
+                get_value(*concept_id, self.previous_state[concept_id]),
+            );
+        }
+        state
+    }
+    fn get_to_connections(&self, concept_id: i32) -> Vec<(&Connection, Interval)> {
+        self.connections_map
+            .values()
+            .filter(|connection| connection.target_id == concept_id)
+            .map(|connection| {
+                let value = match self.connections.get(&connection.id) {
+                    Some(value) => *value,
+                    None => Interval::point(connection.value),
+                };
+                (connection.as_ref(), value)
+            })
+            .collect()
+    }
+}
+
+impl Iterator for IntervalTimeSimulation {
+    type Item = IntervalTimeSimulationData;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current_time > self.max_model_time {
+            panic!("The current time must be less or equal to the max model time");
+        }
+        let mut current_state = self.previous_state.clone();
+        let mut continuous_concepts = vec![];
+        for concept in self.concepts_map.values() {
+            let dynamic_model = concept
+                .dynamic_model
+                .as_ref()
+                .unwrap_or(&self.dynamic_model);
+            let to_connections = self.get_to_connections(concept.id);
+            // Source concepts have no input, so they keep their value in every model
+            if to_connections.is_empty() {
+                continue;
+            }
+            if let DynamicModel::Continuous = dynamic_model {
+                continuous_concepts.push(concept.id);
+                continue;
+            }
+            self.execute_next_value(
+                &mut current_state,
+                concept.id,
+                dynamic_model,
+                &to_connections,
+            )
+        }
+        if !continuous_concepts.is_empty() {
+            self.execute_continuous_step(&mut current_state, &continuous_concepts);
+        }
+        let delta_state = HashMap::from_iter(
+            current_state
+                .iter()
+                .map(|(k, v)| (*k, *v - self.previous_state[k])),
+        );
+        if self.max_lag > 0 {
+            push_history(&mut self.state_history, &self.previous_state, self.max_lag);
+            push_history(&mut self.delta_history, &self.delta_state, self.max_lag);
+        }
+        self.delta_state = delta_state;
+        self.previous_state = current_state;
+        self.current_time += 1;
+        if self.current_time <= self.max_model_time {
+            Some(IntervalTimeSimulationData {
+                time: self.current_time,
+                state: self.previous_state.clone(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjustment::tests::{get_concept, get_connection};
+    use crate::adjustment::TimeSimulation;
+
+    fn get_concepts() -> Vec<Arc<Concept>> {
+        vec![
+            get_concept(1, 0.8, None),
+            get_concept(2, 0.2, Some(0.5)),
+            get_concept(3, 0.4, None),
+        ]
+    }
+
+    fn get_connections() -> Vec<Arc<Connection>> {
+        vec![
+            get_connection(1, 0.6, 1, 2),
+            get_connection(2, -0.4, 3, 2),
+            get_connection(3, 0.9, 2, 3),
+        ]
+    }
+
+    fn get_scalar_states(
+        dynamic_model: DynamicModel,
+        concepts: &HashMap<i32, f64>,
+        connections: &HashMap<i32, f64>,
+    ) -> Vec<HashMap<i32, f64>> {
+        let mut time_simulation = TimeSimulation::new(
+            10,
+            get_concepts_map(),
+            get_connections_map(),
+            vec![],
+            dynamic_model,
+            concepts.clone(),
+            connections.clone(),
+        );
+        time_simulation.set_time_step(0.1);
+        time_simulation.map(|data| data.state).collect()
+    }
+
+    fn get_interval_states(
+        dynamic_model: DynamicModel,
+        concepts: HashMap<i32, Interval>,
+        connections: HashMap<i32, Interval>,
+    ) -> Vec<HashMap<i32, Interval>> {
+        let mut interval_time_simulation = IntervalTimeSimulation::new(
+            10,
+            get_concepts_map(),
+            get_connections_map(),
+            dynamic_model,
+            concepts,
+            connections,
+        );
+        interval_time_simulation.set_time_step(0.1);
+        interval_time_simulation.map(|data| data.state).collect()
+    }
+
+    fn get_concepts_map() -> HashMap<i32, Arc<Concept>> {
+        HashMap::from_iter(
+            get_concepts()
+                .into_iter()
+                .map(|concept| (concept.id, concept)),
+        )
+    }
+
+    fn get_connections_map() -> HashMap<i32, Arc<Connection>> {
+        HashMap::from_iter(
+            get_connections()
+                .into_iter()
+                .map(|connection| (connection.id, connection)),
+        )
+    }
+
+    fn get_dynamic_models() -> Vec<DynamicModel> {
+        vec![
+            DynamicModel::DeltaDelta,
+            DynamicModel::DeltaValue,
+            DynamicModel::ValueDelta,
+            DynamicModel::ValueValue,
+            DynamicModel::Continuous,
+        ]
+    }
+
+    #[test]
+    fn point_intervals_match_scalar_simulation() {
+        let concepts = HashMap::from_iter(
+            get_concepts()
+                .iter()
+                .map(|concept| (concept.id, concept.value)),
+        );
+        let connections = HashMap::from_iter(
+            get_connections()
+                .iter()
+                .map(|connection| (connection.id, connection.value)),
+        );
+        for dynamic_model in get_dynamic_models() {
+            let scalar_states = get_scalar_states(dynamic_model.clone(), &concepts, &connections);
+            let interval_states = get_interval_states(
+                dynamic_model.clone(),
+                get_concept_intervals(&get_concepts_map(), &HashMap::new()),
+                get_connection_intervals(&get_connections_map(), &HashMap::new()),
+            );
+            assert_eq!(scalar_states.len(), interval_states.len());
+            for (scalar_state, interval_state) in scalar_states.iter().zip(&interval_states) {
+                for (concept_id, value) in scalar_state {
+                    let interval = interval_state[concept_id];
+                    assert!((interval.lower - value).abs() < 1e-9);
+                    assert!((interval.upper - value).abs() < 1e-9);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn intervals_enclose_sampled_simulations() {
+        let concept_intervals = HashMap::from_iter([(1, Interval::new(0.6, 1.0))]);
+        let connection_intervals = HashMap::from_iter([(2, Interval::new(-0.6, -0.2))]);
+        for dynamic_model in get_dynamic_models() {
+            let interval_states = get_interval_states(
+                dynamic_model.clone(),
+                get_concept_intervals(&get_concepts_map(), &concept_intervals),
+                get_connection_intervals(&get_connections_map(), &connection_intervals),
+            );
+            for concept_value in [0.6, 0.7, 0.85, 1.0] {
+                for connection_value in [-0.6, -0.45, -0.2] {
+                    let concepts = HashMap::from_iter([(1, concept_value), (2, 0.2), (3, 0.4)]);
+                    let connections =
+                        HashMap::from_iter([(1, 0.6), (2, connection_value), (3, 0.9)]);
+                    let scalar_states =
+                        get_scalar_states(dynamic_model.clone(), &concepts, &connections);
+                    for (scalar_state, interval_state) in scalar_states.iter().zip(&interval_states)
+                    {
+                        for (concept_id, value) in scalar_state {
+                            let interval = interval_state[concept_id];
+                            assert!(interval.lower - 1e-9 <= *value);
+                            assert!(*value <= interval.upper + 1e-9);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,710 @@
+use crate::adjustment::{Concept, Connection, DynamicModel, TimeSimulation};
+use crate::interval::Interval;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BindingConstraintKind {
+    ControlMin,
+    ControlMax,
+    TargetMin,
+    TargetMax,
+    ActivationPiece,
+    FunctionPiece,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BindingConstraint {
+    pub kind: BindingConstraintKind,
+    pub concept_id: i32,
+    pub time: i32,
+    pub violation: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InverseSolution {
+    pub is_supported: bool,
+    pub is_feasible: bool,
+    pub concepts: HashMap<i32, f64>,
+    // Hull of the control ranges over the feasible linear pieces explored around the solution
+    pub feasible_region: HashMap<i32, Interval>,
+    pub error: f64,
+    pub binding_constraint: Option<BindingConstraint>,
+}
+
+pub struct InverseSolver {
+    concepts_map: HashMap<i32, Arc<Concept>>,
+    connections_map: HashMap<i32, Arc<Connection>>,
+    dynamic_model: DynamicModel,
+    control_concepts: Vec<Arc<Concept>>,
+    target_concepts: Vec<Arc<Concept>>,
+    time: i32,
+}
+
+#[derive(Clone)]
+struct Affine {
+    coefficients: Vec<f64>,
+    constant: f64,
+}
+
+struct LinearConstraint {
+    coefficients: Vec<f64>,
+    bound: f64,
+    kind: BindingConstraintKind,
+    concept_id: i32,
+    time: i32,
+}
+
+const MAX_PIECES: usize = 20;
+const PROJECTION_ITERATIONS: usize = 1000;
+const PROPAGATION_ITERATIONS: usize = 100;
+const FEASIBILITY_TOLERANCE: f64 = 0.000001;
+const PIECE_STEP: f64 = 0.000001;
+const CONVERGENCE_TOLERANCE: f64 = 0.000000000001;
+
+impl InverseSolver {
+    pub fn new(
+        concepts_map: HashMap<i32, Arc<Concept>>,
+        connections_map: HashMap<i32, Arc<Connection>>,
+        dynamic_model: DynamicModel,
+        time: i32,
+    ) -> Self {
+        let mut control_concepts = concepts_map
+            .values()
+            .filter(|concept| concept.is_control)
+            .cloned()
+            .collect::<Vec<_>>();
+        control_concepts.sort_by_key(|concept| concept.id);
+        let mut target_concepts = concepts_map
+            .values()
+            .filter(|concept| concept.is_target)
+            .cloned()
+            .collect::<Vec<_>>();
+        target_concepts.sort_by_key(|concept| concept.id);
+        Self {
+            concepts_map,
+            connections_map,
+            dynamic_model,
+            control_concepts,
+            target_concepts,
+            time,
+        }
+    }
+    // Only control concept values are solved for, and the continuous model is not piecewise linear
+    pub fn is_supported(&self) -> bool {
+        !matches!(self.dynamic_model, DynamicModel::Continuous)
+            && self
+                .concepts_map
+                .values()
+                .all(|concept| !matches!(concept.dynamic_model, Some(DynamicModel::Continuous)))
+            && self
+                .connections_map
+                .values()
+                .all(|connection| !connection.is_control && !connection.is_function_control)
+    }
+    pub fn solve(&self) -> InverseSolution {
+        if !self.is_supported() {
+            return InverseSolution {
+                is_supported: false,
+                is_feasible: false,
+                concepts: HashMap::new(),
+                feasible_region: HashMap::new(),
+                error: self.get_error(&HashMap::new()),
+                binding_constraint: None,
+            };
+        }
+        let origin = vec![0.0; self.control_concepts.len()];
+        let mut reference = self
+            .control_concepts
+            .iter()
+            .map(|concept| concept.value)
+            .collect::<Vec<_>>();
+        let mut best: Option<(f64, Vec<f64>, Vec<LinearConstraint>)> = None;
+        for _ in 0..MAX_PIECES {
+            let constraints = self.get_constraints(&reference);
+            let solution = Self::project(&origin, &constraints);
+            let violation = constraints
+                .iter()
+                .map(|constraint| constraint.get_violation(&solution))
+                .fold(0.0, f64::max);
+            let is_converged = violation <= FEASIBILITY_TOLERANCE
+                || solution
+                    .iter()
+                    .zip(&reference)
+                    .all(|(a, b)| (a - b).abs() <= CONVERGENCE_TOLERANCE);
+            if !matches!(&best, Some((best_violation, _, _)) if violation >= *best_violation) {
+                best = Some((violation, solution.clone(), constraints));
+            }
+            if is_converged {
+                break;
+            }
+            reference = solution;
+        }
+        let (violation, solution, constraints) = best.unwrap();
+        let concepts = HashMap::from_iter(
+            self.control_concepts
+                .iter()
+                .zip(&solution)
+                .map(|(concept, value)| (concept.id, value.clamp(0.0, 1.0))),
+        );
+        let is_feasible = violation <= FEASIBILITY_TOLERANCE;
+        let binding_constraint = if is_feasible {
+            None
+        } else {
+            constraints
+                .iter()
+                .max_by(|a, b| {
+                    a.get_violation(&solution)
+                        .total_cmp(&b.get_violation(&solution))
+                })
+                .map(|constraint| BindingConstraint {
+                    kind: constraint.kind,
+                    concept_id: constraint.concept_id,
+                    time: constraint.time,
+                    violation: constraint.get_violation(&solution),
+                })
+        };
+        let feasible_region = if is_feasible {
+            HashMap::from_iter(
+                self.control_concepts
+                    .iter()
+                    .zip(self.get_feasible_region(&solution, &constraints))
+                    .map(|(concept, interval)| (concept.id, interval)),
+            )
+        } else {
+            HashMap::new()
+        };
+        InverseSolution {
+            is_supported: true,
+            is_feasible,
+            error: self.get_error(&concepts),
+            concepts,
+            feasible_region,
+            binding_constraint,
+        }
+    }
+    // The solution piece is extended by stepping over each bound of the region into the
+    // neighbouring piece while that piece is feasible and widens the region
+    fn get_feasible_region(
+        &self,
+        solution: &[f64],
+        constraints: &[LinearConstraint],
+    ) -> Vec<Interval> {
+        let size = self.control_concepts.len();
+        let mut region = Self::propagate(constraints, size);
+        let mut pieces = 1;
+        for index in 0..size {
+            for is_lower in [true, false] {
+                while pieces < MAX_PIECES {
+                    let bound = if is_lower {
+                        region[index].lower - PIECE_STEP
+                    } else {
+                        region[index].upper + PIECE_STEP
+                    };
+                    if !(0.0..=1.0).contains(&bound) {
+                        break;
+                    }
+                    pieces += 1;
+                    let mut reference = solution.to_vec();
+                    reference[index] = bound;
+                    let constraints = self.get_constraints(&reference);
+                    let point = Self::project(&reference, &constraints);
+                    if constraints
+                        .iter()
+                        .any(|constraint| constraint.get_violation(&point) > FEASIBILITY_TOLERANCE)
+                    {
+                        break;
+                    }
+                    let piece_region = Self::propagate(&constraints, size);
+                    let is_widened = if is_lower {
+                        piece_region[index].lower < region[index].lower - CONVERGENCE_TOLERANCE
+                    } else {
+                        piece_region[index].upper > region[index].upper + CONVERGENCE_TOLERANCE
+                    };
+                    if !is_widened {
+                        break;
+                    }
+                    for (interval, piece_interval) in region.iter_mut().zip(&piece_region) {
+                        interval.lower = interval.lower.min(piece_interval.lower);
+                        interval.upper = interval.upper.max(piece_interval.upper);
+                    }
+                }
+            }
+        }
+        region
+    }
+    fn get_constraints(&self, reference: &[f64]) -> Vec<LinearConstraint> {
+        let size = self.control_concepts.len();
+        let mut constraints = vec![];
+        let mut initial_state = HashMap::<i32, Affine>::from_iter(
+            self.concepts_map
+                .values()
+                .map(|concept| (concept.id, Affine::constant(size, concept.value))),
+        );
+        for (index, concept) in self.control_concepts.iter().enumerate() {
+            let variable = Affine::variable(size, index);
+            let (min, max) = match &concept.constraint {
+                Some(constraint) => (constraint.get_min(0.0), constraint.get_max(1.0)),
+                None => (0.0, 1.0),
+            };
+            constraints.push(LinearConstraint::lower(
+                &variable,
+                min,
+                BindingConstraintKind::ControlMin,
+                concept.id,
+                0,
+            ));
+            constraints.push(LinearConstraint::upper(
+                &variable,
+                max,
+                BindingConstraintKind::ControlMax,
+                concept.id,
+                0,
+            ));
+            initial_state.insert(concept.id, variable);
+        }
+        let mut states = vec![initial_state.clone()];
+        let mut deltas = vec![initial_state];
+        for time in 1..=self.time.max(0) as usize {
+            let mut state = states[time - 1].clone();
+            for concept in self.concepts_map.values() {
+                let dynamic_model = concept
+                    .dynamic_model
+                    .as_ref()
+                    .unwrap_or(&self.dynamic_model);
+                let to_connections = self
+                    .connections_map
+                    .values()
+                    .filter(|connection| connection.target_id == concept.id)
+                    .collect::<Vec<_>>();
+                if to_connections.is_empty() {
+                    continue;
+                }
+                let (source_states, mut sum) = match dynamic_model {
+                    DynamicModel::DeltaDelta => (&deltas, states[time - 1][&concept.id].clone()),
+                    DynamicModel::DeltaValue => (&states, states[time - 1][&concept.id].clone()),
+                    DynamicModel::ValueDelta => (&deltas, Affine::constant(size, 0.0)),
+                    DynamicModel::ValueValue => (&states, Affine::constant(size, 0.0)),
+                    DynamicModel::Continuous => unreachable!(),
+                };
+                for connection in to_connections {
+                    let source_time = time.saturating_sub(1 + connection.get_lag());
+                    let source = &source_states[source_time][&connection.source_id];
+                    let piece = connection
+                        .function
+                        .get_linear_piece(source.evaluate(reference), None);
+                    if piece.lower.is_finite() {
+                        constraints.push(LinearConstraint::lower(
+                            source,
+                            piece.lower,
+                            BindingConstraintKind::FunctionPiece,
+                            concept.id,
+                            time as i32,
+                        ));
+                    }
+                    if piece.upper.is_finite() {
+                        constraints.push(LinearConstraint::upper(
+                            source,
+                            piece.upper,
+                            BindingConstraintKind::FunctionPiece,
+                            concept.id,
+                            time as i32,
+                        ));
+                    }
+                    sum = sum.add(
+                        &source
+                            .scale(piece.slope)
+                            .shift(piece.intercept)
+                            .scale(connection.value),
+                    );
+                }
+                let value = sum.evaluate(reference);
+                let next_value = if value > 1.0 {
+                    constraints.push(LinearConstraint::lower(
+                        &sum,
+                        1.0,
+                        BindingConstraintKind::ActivationPiece,
+                        concept.id,
+                        time as i32,
+                    ));
+                    Affine::constant(size, 1.0)
+                } else if value <= 0.0 {
+                    constraints.push(LinearConstraint::upper(
+                        &sum,
+                        0.0,
+                        BindingConstraintKind::ActivationPiece,
+                        concept.id,
+                        time as i32,
+                    ));
+                    Affine::constant(size, 0.0)
+                } else {
+                    constraints.push(LinearConstraint::lower(
+                        &sum,
+                        0.0,
+                        BindingConstraintKind::ActivationPiece,
+                        concept.id,
+                        time as i32,
+                    ));
+                    constraints.push(LinearConstraint::upper(
+                        &sum,
+                        1.0,
+                        BindingConstraintKind::ActivationPiece,
+                        concept.id,
+                        time as i32,
+                    ));
+                    sum
+                };
+                state.insert(concept.id, next_value);
+            }
+            let delta = HashMap::from_iter(
+                state
+                    .iter()
+                    .map(|(k, v)| (*k, v.add(&states[time - 1][k].scale(-1.0)))),
+            );
+            states.push(state);
+            deltas.push(delta);
+        }
+        let final_state = states.last().unwrap();
+        for concept in &self.target_concepts {
+            let target_value = concept.target_value.as_ref().unwrap();
+            constraints.push(LinearConstraint::lower(
+                &final_state[&concept.id],
+                target_value.get_min(),
+                BindingConstraintKind::TargetMin,
+                concept.id,
+                self.time,
+            ));
+            constraints.push(LinearConstraint::upper(
+                &final_state[&concept.id],
+                target_value.get_max(),
+                BindingConstraintKind::TargetMax,
+                concept.id,
+                self.time,
+            ));
+        }
+        constraints
+    }
+    fn project(point: &[f64], constraints: &[LinearConstraint]) -> Vec<f64> {
+        let mut solution = point.to_vec();
+        let mut increments = vec![vec![0.0; point.len()]; constraints.len()];
+        for _ in 0..PROJECTION_ITERATIONS {
+            let previous_solution = solution.clone();
+            for (constraint, increment) in constraints.iter().zip(increments.iter_mut()) {
+                let shifted = solution
+                    .iter()
+                    .zip(increment.iter())
+                    .map(|(value, increment)| value + increment)
+                    .collect::<Vec<_>>();
+                solution = constraint.project(&shifted);
+                for ((increment, shifted), value) in
+                    increment.iter_mut().zip(&shifted).zip(&solution)
+                {
+                    *increment = shifted - value;
+                }
+            }
+            if solution
+                .iter()
+                .zip(&previous_solution)
+                .all(|(a, b)| (a - b).abs() <= CONVERGENCE_TOLERANCE)
+            {
+                break;
+            }
+        }
+        solution
+    }
+    fn propagate(constraints: &[LinearConstraint], size: usize) -> Vec<Interval> {
+        let mut intervals = vec![Interval::new(0.0, 1.0); size];
+        for _ in 0..PROPAGATION_ITERATIONS {
+            let mut is_changed = false;
+            for constraint in constraints {
+                for j in 0..size {
+                    let coefficient = constraint.coefficients[j];
+                    if coefficient == 0.0 {
+                        continue;
+                    }
+                    let rest_min = constraint
+                        .coefficients
+                        .iter()
+                        .zip(&intervals)
+                        .enumerate()
+                        .filter(|(k, _)| *k != j)
+                        .map(|(_, (a, interval))| f64::min(a * interval.lower, a * interval.upper))
+                        .sum::<f64>();
+                    let bound = (constraint.bound - rest_min) / coefficient;
+                    let interval = &mut intervals[j];
+                    if coefficient > 0.0 && bound < interval.upper - CONVERGENCE_TOLERANCE {
+                        interval.upper = bound;
+                        is_changed = true;
+                    }
+                    if coefficient < 0.0 && bound > interval.lower + CONVERGENCE_TOLERANCE {
+                        interval.lower = bound;
+                        is_changed = true;
+                    }
+                }
+            }
+            if !is_changed {
+                break;
+            }
+        }
+        intervals
+    }
+    fn get_error(&self, concepts: &HashMap<i32, f64>) -> f64 {
+        if self.target_concepts.is_empty() || self.time < 1 {
+            return 0.0;
+        }
+        let mut state = HashMap::from_iter(
+            self.concepts_map
+                .values()
+                .map(|concept| (concept.id, concept.value)),
+        );
+        state.extend(concepts);
+        let time_simulation = TimeSimulation::new(
+            self.time,
+            self.concepts_map.clone(),
+            self.connections_map.clone(),
+            self.target_concepts.clone(),
+            self.dynamic_model.clone(),
+            state,
+            HashMap::new(),
+        );
+        time_simulation.last().map_or(0.0, |data| data.error)
+    }
+}
+
+impl Affine {
+    fn constant(size: usize, constant: f64) -> Self {
+        Self {
+            coefficients: vec![0.0; size],
+            constant,
+        }
+    }
+    fn variable(size: usize, index: usize) -> Self {
+        let mut coefficients = vec![0.0; size];
+        coefficients[index] = 1.0;
+        Self {
+            coefficients,
+            constant: 0.0,
+        }
+    }
+    fn evaluate(&self, point: &[f64]) -> f64 {
+        self.constant
+            + self
+                .coefficients
+                .iter()
+                .zip(point)
+                .map(|(a, b)| a * b)
+                .sum::<f64>()
+    }
+    fn add(&self, other: &Self) -> Self {
+        Self {
+            coefficients: self
+                .coefficients
+                .iter()
+                .zip(&other.coefficients)
+                .map(|(a, b)| a + b)
+                .collect(),
+            constant: self.constant + other.constant,
+        }
+    }
+    fn scale(&self, factor: f64) -> Self {
+        Self {
+            coefficients: self.coefficients.iter().map(|a| a * factor).collect(),
+            constant: self.constant * factor,
+        }
+    }
+    fn shift(&self, value: f64) -> Self {
+        Self {
+            coefficients: self.coefficients.clone(),
+            constant: self.constant + value,
+        }
+    }
+}
+
+impl LinearConstraint {
+    fn lower(
+        affine: &Affine,
+        min: f64,
+        kind: BindingConstraintKind,
+        concept_id: i32,
+        time: i32,
+    ) -> Self {
+        Self {
+            coefficients: affine.coefficients.iter().map(|a| -a).collect(),
+            bound: affine.constant - min,
+            kind,
+            concept_id,
+            time,
+        }
+    }
+    fn upper(
+        affine: &Affine,
+        max: f64,
+        kind: BindingConstraintKind,
+        concept_id: i32,
+        time: i32,
+    ) -> Self {
+        Self {
+            coefficients: affine.coefficients.clone(),
+            bound: max - affine.constant,
+            kind,
+            concept_id,
+            time,
+        }
+    }
+    fn get_violation(&self, point: &[f64]) -> f64 {
+        let value = self
+            .coefficients
+            .iter()
+            .zip(point)
+            .map(|(a, b)| a * b)
+            .sum::<f64>();
+        f64::max(value - self.bound, 0.0)
+    }
+    fn project(&self, point: &[f64]) -> Vec<f64> {
+        let violation = self.get_violation(point);
+        let norm = self.coefficients.iter().map(|a| a * a).sum::<f64>();
+        if violation == 0.0 || norm == 0.0 {
+            return point.to_vec();
+        }
+        point
+            .iter()
+            .zip(&self.coefficients)
+            .map(|(value, a)| value - violation / norm * a)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjustment::tests::{get_concept, get_connection};
+    use crate::adjustment::TargetValue;
+
+    use crate::connection_function::ConnectionFunction;
+
+    fn get_concepts_map(target_value: f64) -> HashMap<i32, Arc<Concept>> {
+        let mut control_concept = Arc::try_unwrap(get_concept(1, 0.5, None)).ok().unwrap();
+        control_concept.is_control = true;
+        let mut target_concept = Arc::try_unwrap(get_concept(2, target_value, None))
+            .ok()
+            .unwrap();
+        target_concept.is_target = true;
+        target_concept.target_value = Some(TargetValue {
+            min_value: 0.6,
+            include_min_value: true,
+            max_value: 0.8,
+            include_max_value: true,
+        });
+        HashMap::from_iter([
+            (1, Arc::new(control_concept)),
+            (2, Arc::new(target_concept)),
+        ])
+    }
+
+    fn get_solution(
+        weight: f64,
+        target_value: f64,
+        dynamic_model: DynamicModel,
+    ) -> (InverseSolution, f64) {
+        let concepts_map = get_concepts_map(target_value);
+        let connections_map = HashMap::from_iter([(1, get_connection(1, weight, 1, 2))]);
+        let inverse_solver = InverseSolver::new(
+            concepts_map.clone(),
+            connections_map.clone(),
+            dynamic_model.clone(),
+            3,
+        );
+        let inverse_solution = inverse_solver.solve();
+        let mut concepts = HashMap::from_iter([(1, 0.5), (2, target_value)]);
+        concepts.extend(inverse_solution.concepts.clone());
+        let target_concepts = vec![concepts_map[&2].clone()];
+        let time_simulation = TimeSimulation::new(
+            3,
+            concepts_map,
+            connections_map,
+            target_concepts,
+            dynamic_model,
+            concepts,
+            HashMap::new(),
+        );
+        let error = time_simulation.last().unwrap().error;
+        (inverse_solution, error)
+    }
+
+    #[test]
+    fn feasible_solution_reaches_targets() {
+        let (inverse_solution, error) = get_solution(0.8, 0.0, DynamicModel::ValueValue);
+        assert!(inverse_solution.is_supported);
+        assert!(inverse_solution.is_feasible);
+        assert!(inverse_solution.binding_constraint.is_none());
+        assert!((inverse_solution.concepts[&1] - 0.75).abs() < 1e-5);
+        let interval = inverse_solution.feasible_region[&1];
+        assert!((interval.lower - 0.75).abs() < 1e-5);
+        assert!((interval.upper - 1.0).abs() < 1e-5);
+        assert!(error < 1e-5);
+    }
+
+    #[test]
+    fn feasible_solution_reaches_targets_in_delta_model() {
+        // The target accumulates the input, so it reaches 0.6 from 0.0 over three steps
+        let (inverse_solution, error) = get_solution(0.5, 0.0, DynamicModel::DeltaValue);
+        assert!(inverse_solution.is_feasible);
+        assert!((inverse_solution.concepts[&1] - 0.4).abs() < 1e-5);
+        assert!(error < 1e-5);
+    }
+
+    #[test]
+    fn infeasible_solution_reports_binding_constraint() {
+        let (inverse_solution, error) = get_solution(0.4, 0.0, DynamicModel::ValueValue);
+        assert!(inverse_solution.is_supported);
+        assert!(!inverse_solution.is_feasible);
+        assert!(inverse_solution.feasible_region.is_empty());
+        let binding_constraint = inverse_solution.binding_constraint.unwrap();
+        // The alternating projection spreads the violation over both conflicting bounds
+        assert!(matches!(
+            (binding_constraint.kind, binding_constraint.concept_id),
+            (BindingConstraintKind::ControlMax, 1) | (BindingConstraintKind::TargetMin, 2)
+        ));
+        assert!(binding_constraint.violation > 0.0);
+        assert!(error > 0.0);
+    }
+
+    #[test]
+    fn continuous_model_is_not_supported() {
+        let (inverse_solution, _) = get_solution(0.8, 0.0, DynamicModel::Continuous);
+        assert!(!inverse_solution.is_supported);
+        assert!(!inverse_solution.is_feasible);
+        assert!(inverse_solution.concepts.is_empty());
+    }
+
+    #[test]
+    fn feasible_region_spans_several_pieces() {
+        let mut concepts_map = get_concepts_map(0.0);
+        let mut target_concept = Arc::try_unwrap(concepts_map.remove(&2).unwrap())
+            .ok()
+            .unwrap();
+        target_concept.target_value = Some(TargetValue {
+            min_value: 0.0,
+            include_min_value: true,
+            max_value: 0.8,
+            include_max_value: true,
+        });
+        concepts_map.insert(2, Arc::new(target_concept));
+        let mut connection = Arc::try_unwrap(get_connection(1, 1.0, 1, 2)).ok().unwrap();
+        connection.function = ConnectionFunction::Threshold { threshold: 0.5 };
+        let connections_map = HashMap::from_iter([(1, Arc::new(connection))]);
+        let inverse_solver =
+            InverseSolver::new(concepts_map, connections_map, DynamicModel::ValueValue, 3);
+        let inverse_solution = inverse_solver.solve();
+        assert!(inverse_solution.is_feasible);
+        // Control values below the threshold keep the target at zero and above it pass through,
+        // so the region covers both pieces of the threshold function
+        let interval = inverse_solution.feasible_region[&1];
+        assert!(interval.lower.abs() < 1e-5);
+        assert!((interval.upper - 0.8).abs() < 1e-5);
+    }
+}
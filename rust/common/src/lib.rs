@@ -1 +1,6 @@
 pub mod adjustment;
+pub mod connection_function;
+pub mod interval;
+pub mod inverse;
+pub mod linguistic;
+pub mod stability;
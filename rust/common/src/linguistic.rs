@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FuzzyNumber {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefuzzificationMethod {
+    #[default]
+    Centroid,
+    MeanOfMaxima,
+}
+
+impl FuzzyNumber {
+    pub fn triangular(a: f64, b: f64, c: f64) -> Self {
+        Self { a, b, c: b, d: c }
+    }
+    pub fn trapezoidal(a: f64, b: f64, c: f64, d: f64) -> Self {
+        Self { a, b, c, d }
+    }
+    pub fn is_valid(&self) -> bool {
+        self.a <= self.b && self.b <= self.c && self.c <= self.d
+    }
+    pub fn is_triangular(&self) -> bool {
+        self.b == self.c
+    }
+    pub fn get_membership(&self, x: f64) -> f64 {
+        if x < self.a || x > self.d {
+            0.0
+        } else if x < self.b {
+            (x - self.a) / (self.b - self.a)
+        } else if x <= self.c {
+            1.0
+        } else {
+            (self.d - x) / (self.d - self.c)
+        }
+    }
+    pub fn aggregate(fuzzy_numbers: &[FuzzyNumber]) -> Option<Self> {
+        if fuzzy_numbers.is_empty() {
+            return None;
+        }
+        let count = fuzzy_numbers.len() as f64;
+        Some(Self {
+            a: fuzzy_numbers.iter().map(|n| n.a).sum::<f64>() / count,
+            b: fuzzy_numbers.iter().map(|n| n.b).sum::<f64>() / count,
+            c: fuzzy_numbers.iter().map(|n| n.c).sum::<f64>() / count,
+            d: fuzzy_numbers.iter().map(|n| n.d).sum::<f64>() / count,
+        })
+    }
+    pub fn defuzzify(&self, method: &DefuzzificationMethod) -> f64 {
+        match method {
+            DefuzzificationMethod::Centroid => self.get_centroid(),
+            DefuzzificationMethod::MeanOfMaxima => (self.b + self.c) / 2.0,
+        }
+    }
+    fn get_centroid(&self) -> f64 {
+        let denominator = 3.0 * (self.d + self.c - self.a - self.b);
+        if denominator == 0.0 {
+            return self.a;
+        }
+        (self.d.powi(2) + self.c.powi(2) + self.c * self.d
+            - self.a.powi(2)
+            - self.b.powi(2)
+            - self.a * self.b)
+            / denominator
+    }
+}
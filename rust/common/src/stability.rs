@@ -0,0 +1,354 @@
+use crate::adjustment::{Concept, Connection, DynamicModel};
+use crate::connection_function::ConnectionFunction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Activation {
+    #[default]
+    Clamp,
+    Sigmoid {
+        steepness: f64,
+    },
+    Tanh {
+        steepness: f64,
+    },
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StabilityAnalysis {
+    pub spectral_radius: f64,
+    pub spectral_norm: f64,
+    pub frobenius_norm: f64,
+    pub row_sum_norm: f64,
+    pub column_sum_norm: f64,
+    pub lipschitz_constant: f64,
+    pub contraction_factor: f64,
+    pub is_continuous: bool,
+    pub is_contraction: bool,
+}
+
+pub struct WeightMatrix {
+    pub concept_ids: Vec<i32>,
+    pub values: Vec<Vec<f64>>,
+    pub is_continuous: bool,
+}
+
+const POWER_ITERATIONS: usize = 500;
+const POWER_TOLERANCE: f64 = 1e-12;
+const SQUARINGS: usize = 10;
+
+impl Activation {
+    pub fn get_lipschitz_constant(&self) -> f64 {
+        match self {
+            Self::Clamp => 1.0,
+            Self::Sigmoid { steepness } => steepness / 4.0,
+            Self::Tanh { steepness } => *steepness,
+        }
+    }
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Clamp => true,
+            Self::Sigmoid { steepness } | Self::Tanh { steepness } => *steepness > 0.0,
+        }
+    }
+}
+
+impl WeightMatrix {
+    // Rows follow the update rule of each concept: W for value models, I + W for delta models
+    // and I + dt(W - λI) for the continuous model. Source concepts keep their value, so they
+    // are inputs rather than state and are left out.
+    pub fn new(
+        concepts_map: &HashMap<i32, Arc<Concept>>,
+        connections_map: &HashMap<i32, Arc<Connection>>,
+        connections: &HashMap<i32, f64>,
+        function_parameters: &HashMap<i32, f64>,
+        dynamic_model: &DynamicModel,
+        time_step: f64,
+    ) -> Self {
+        let mut concept_ids = concepts_map
+            .keys()
+            .filter(|concept_id| {
+                connections_map
+                    .values()
+                    .any(|connection| connection.target_id == **concept_id)
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        concept_ids.sort();
+        let indexes = HashMap::<i32, usize>::from_iter(
+            concept_ids
+                .iter()
+                .enumerate()
+                .map(|(index, concept_id)| (*concept_id, index)),
+        );
+        let is_linear = connections_map
+            .values()
+            .all(|connection| matches!(connection.function, ConnectionFunction::Linear));
+        let mut is_continuous = true;
+        let mut values = vec![vec![0.0; concept_ids.len()]; concept_ids.len()];
+        for connection in connections_map.values() {
+            let source_index = match indexes.get(&connection.source_id) {
+                Some(source_index) => *source_index,
+                None => continue,
+            };
+            let target_index = indexes[&connection.target_id];
+            let value = match connections.get(&connection.id) {
+                Some(value) => *value,
+                None => connection.value,
+            };
+            let (_, scale) = Self::get_update_coefficients(
+                &concepts_map[&connection.target_id],
+                dynamic_model,
+                time_step,
+            );
+            values[target_index][source_index] = if is_linear {
+                scale * value
+            } else {
+                let lipschitz_constant = connection
+                    .function
+                    .get_lipschitz_constant(function_parameters.get(&connection.id).cloned());
+                if lipschitz_constant.is_none() && value != 0.0 {
+                    is_continuous = false;
+                }
+                scale * value.abs() * lipschitz_constant.unwrap_or(1.0)
+            };
+        }
+        for (index, concept_id) in concept_ids.iter().enumerate() {
+            let (identity, _) =
+                Self::get_update_coefficients(&concepts_map[concept_id], dynamic_model, time_step);
+            // Nonlinear entries are bounds of absolute values, so the diagonal is bounded too
+            values[index][index] = if is_linear {
+                identity + values[index][index]
+            } else {
+                identity.abs() + values[index][index]
+            };
+        }
+        Self {
+            concept_ids,
+            values,
+            is_continuous,
+        }
+    }
+    fn get_update_coefficients(
+        concept: &Concept,
+        dynamic_model: &DynamicModel,
+        time_step: f64,
+    ) -> (f64, f64) {
+        match concept.dynamic_model.as_ref().unwrap_or(dynamic_model) {
+            DynamicModel::ValueDelta | DynamicModel::ValueValue => (0.0, 1.0),
+            DynamicModel::DeltaDelta | DynamicModel::DeltaValue => (1.0, 1.0),
+            DynamicModel::Continuous => (1.0 - time_step * concept.get_decay_rate(), time_step),
+        }
+    }
+    pub fn analyze(&self, activation: &Activation) -> StabilityAnalysis {
+        let spectral_norm = self.get_spectral_norm();
+        let row_sum_norm = self.get_row_sum_norm();
+        let column_sum_norm = self.get_column_sum_norm();
+        let lipschitz_constant = activation.get_lipschitz_constant();
+        let contraction_factor =
+            lipschitz_constant * spectral_norm.min(row_sum_norm).min(column_sum_norm);
+        StabilityAnalysis {
+            spectral_radius: self.get_spectral_radius(),
+            spectral_norm,
+            frobenius_norm: Self::get_frobenius_norm(&self.values),
+            row_sum_norm,
+            column_sum_norm,
+            lipschitz_constant,
+            contraction_factor,
+            is_continuous: self.is_continuous,
+            is_contraction: self.is_continuous && contraction_factor < 1.0,
+        }
+    }
+    pub fn is_contraction(&self, activation: &Activation) -> bool {
+        if !self.is_continuous {
+            return false;
+        }
+        let lipschitz_constant = activation.get_lipschitz_constant();
+        if lipschitz_constant * self.get_row_sum_norm().min(self.get_column_sum_norm()) < 1.0 {
+            return true;
+        }
+        lipschitz_constant * self.get_spectral_norm() < 1.0
+    }
+    pub fn get_row_sum_norm(&self) -> f64 {
+        self.values
+            .iter()
+            .map(|row| row.iter().map(|value| value.abs()).sum::<f64>())
+            .fold(0.0, f64::max)
+    }
+    pub fn get_column_sum_norm(&self) -> f64 {
+        (0..self.values.len())
+            .map(|j| self.values.iter().map(|row| row[j].abs()).sum::<f64>())
+            .fold(0.0, f64::max)
+    }
+    pub fn get_spectral_norm(&self) -> f64 {
+        let size = self.values.len();
+        if size == 0 {
+            return 0.0;
+        }
+        let mut vector = (0..size)
+            .map(|i| 1.0 + i as f64 / size as f64)
+            .collect::<Vec<_>>();
+        let mut norm = Self::get_vector_norm(&vector);
+        vector.iter_mut().for_each(|value| *value /= norm);
+        let mut eigenvalue = 0.0;
+        for _ in 0..POWER_ITERATIONS {
+            let product = Self::multiply_vector(&self.values, &vector);
+            let mut next_vector = vec![0.0; size];
+            for (row, product_value) in self.values.iter().zip(&product) {
+                for (next_value, value) in next_vector.iter_mut().zip(row) {
+                    *next_value += value * product_value;
+                }
+            }
+            norm = Self::get_vector_norm(&next_vector);
+            if norm == 0.0 {
+                return 0.0;
+            }
+            next_vector.iter_mut().for_each(|value| *value /= norm);
+            vector = next_vector;
+            let is_converged = (norm - eigenvalue).abs() <= POWER_TOLERANCE * norm;
+            eigenvalue = norm;
+            if is_converged {
+                break;
+            }
+        }
+        eigenvalue.sqrt()
+    }
+    pub fn get_spectral_radius(&self) -> f64 {
+        let mut matrix = self.values.clone();
+        let mut log_norm = 0.0;
+        for _ in 0..SQUARINGS {
+            let norm = Self::get_frobenius_norm(&matrix);
+            if norm == 0.0 {
+                return 0.0;
+            }
+            matrix
+                .iter_mut()
+                .for_each(|row| row.iter_mut().for_each(|value| *value /= norm));
+            log_norm = 2.0 * (log_norm + norm.ln());
+            matrix = Self::multiply(&matrix, &matrix);
+        }
+        let norm = Self::get_frobenius_norm(&matrix);
+        if norm == 0.0 {
+            return 0.0;
+        }
+        ((log_norm + norm.ln()) / 2_f64.powi(SQUARINGS as i32)).exp()
+    }
+    fn get_frobenius_norm(matrix: &[Vec<f64>]) -> f64 {
+        matrix
+            .iter()
+            .flat_map(|row| row.iter())
+            .map(|value| value * value)
+            .sum::<f64>()
+            .sqrt()
+    }
+    fn get_vector_norm(vector: &[f64]) -> f64 {
+        vector.iter().map(|value| value * value).sum::<f64>().sqrt()
+    }
+    fn multiply_vector(matrix: &[Vec<f64>], vector: &[f64]) -> Vec<f64> {
+        matrix
+            .iter()
+            .map(|row| row.iter().zip(vector).map(|(a, b)| a * b).sum())
+            .collect()
+    }
+    fn multiply(left: &[Vec<f64>], right: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        let size = left.len();
+        let mut product = vec![vec![0.0; size]; size];
+        for i in 0..size {
+            for k in 0..size {
+                let value = left[i][k];
+                if value == 0.0 {
+                    continue;
+                }
+                for j in 0..size {
+                    product[i][j] += value * right[k][j];
+                }
+            }
+        }
+        product
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adjustment::tests::{get_concept, get_connection};
+
+    fn get_weight_matrix(
+        weights: &[f64],
+        dynamic_model: DynamicModel,
+        time_step: f64,
+    ) -> WeightMatrix {
+        // The concepts form a cycle, so none of them is a source concept
+        let size = weights.len() as i32;
+        let concepts_map = HashMap::from_iter(
+            (1..=size).map(|concept_id| (concept_id, get_concept(concept_id, 0.5, None))),
+        );
+        let connections_map =
+            HashMap::from_iter(weights.iter().enumerate().map(|(index, value)| {
+                let id = index as i32 + 1;
+                (id, get_connection(id, *value, id, id % size + 1))
+            }));
+        WeightMatrix::new(
+            &concepts_map,
+            &connections_map,
+            &HashMap::new(),
+            &HashMap::new(),
+            &dynamic_model,
+            time_step,
+        )
+    }
+
+    #[test]
+    fn small_weights_are_contraction() {
+        let weight_matrix = get_weight_matrix(&[0.5, -0.3, 0.4], DynamicModel::ValueValue, 1.0);
+        assert!(weight_matrix.is_contraction(&Activation::Clamp));
+        assert!(weight_matrix.analyze(&Activation::Clamp).is_contraction);
+        assert!((weight_matrix.get_spectral_norm() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn large_weights_are_not_contraction() {
+        let weight_matrix = get_weight_matrix(&[1.0, -1.0, 1.0], DynamicModel::ValueValue, 1.0);
+        assert!(!weight_matrix.is_contraction(&Activation::Clamp));
+        assert!(weight_matrix.is_contraction(&Activation::Sigmoid { steepness: 2.0 }));
+        assert!((weight_matrix.get_spectral_radius() - 1.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn delta_model_adds_identity() {
+        let weight_matrix = get_weight_matrix(&[0.1, 0.1], DynamicModel::DeltaValue, 1.0);
+        assert_eq!(weight_matrix.values, vec![vec![1.0, 0.1], vec![0.1, 1.0]]);
+        assert!(!weight_matrix.is_contraction(&Activation::Clamp));
+    }
+
+    #[test]
+    fn continuous_model_scales_by_time_step() {
+        let weight_matrix = get_weight_matrix(&[0.5, 0.5], DynamicModel::Continuous, 0.1);
+        assert!((weight_matrix.values[0][0] - 0.9).abs() < 1e-12);
+        assert!((weight_matrix.values[0][1] - 0.05).abs() < 1e-12);
+        assert!(weight_matrix.is_contraction(&Activation::Clamp));
+    }
+
+    #[test]
+    fn source_concepts_are_excluded() {
+        let concepts_map = HashMap::from_iter([
+            (1, get_concept(1, 0.5, None)),
+            (2, get_concept(2, 0.5, None)),
+        ]);
+        let connections_map = HashMap::from_iter([(1, get_connection(1, 2.0, 1, 2))]);
+        let weight_matrix = WeightMatrix::new(
+            &concepts_map,
+            &connections_map,
+            &HashMap::new(),
+            &HashMap::new(),
+            &DynamicModel::ValueValue,
+            1.0,
+        );
+        assert_eq!(weight_matrix.concept_ids, vec![2]);
+        assert!(weight_matrix.is_contraction(&Activation::Clamp));
+    }
+}
@@ -1,8 +1,12 @@
 use async_trait::async_trait;
 use fuzzy_cognitive_model_common::adjustment::{
-    AdjustmentModel, Generation, Individual, SaveResult, TimeSimulation,
+    AdjustmentModel, Generation, Individual, SaveResult, TimeSimulation, DEFAULT_TIME_STEP,
 };
+use fuzzy_cognitive_model_common::interval::IntervalTimeSimulation;
+use fuzzy_cognitive_model_common::inverse::InverseSolver;
+use fuzzy_cognitive_model_common::stability::WeightMatrix;
 use js_sys::Function;
+use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
 struct SaveResultClient {
@@ -120,6 +124,9 @@ impl TimeSimulationExecutor {
     pub fn get_error(&self) -> f64 {
         self.time_simulation.get_error()
     }
+    pub fn set_time_step(&mut self, time_step: f64) -> () {
+        self.time_simulation.set_time_step(time_step);
+    }
     pub fn get_state(&self) -> JsValue {
         serde_wasm_bindgen::to_value(&self.time_simulation.get_state()).unwrap()
     }
@@ -127,3 +134,84 @@ impl TimeSimulationExecutor {
         serde_wasm_bindgen::to_value(&self.time_simulation.next()).unwrap()
     }
 }
+
+#[wasm_bindgen]
+pub struct IntervalTimeSimulationExecutor {
+    interval_time_simulation: IntervalTimeSimulation,
+}
+
+#[wasm_bindgen]
+impl IntervalTimeSimulationExecutor {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        max_model_time: JsValue,
+        concepts_map: JsValue,
+        connections_map: JsValue,
+        dynamic_model: JsValue,
+        concepts: JsValue,
+        connections: JsValue,
+    ) -> Self {
+        Self {
+            interval_time_simulation: IntervalTimeSimulation::new(
+                serde_wasm_bindgen::from_value(max_model_time).unwrap(),
+                serde_wasm_bindgen::from_value(concepts_map).unwrap(),
+                serde_wasm_bindgen::from_value(connections_map).unwrap(),
+                serde_wasm_bindgen::from_value(dynamic_model).unwrap(),
+                serde_wasm_bindgen::from_value(concepts).unwrap(),
+                serde_wasm_bindgen::from_value(connections).unwrap(),
+            ),
+        }
+    }
+    pub fn get_max_model_time(&self) -> i32 {
+        self.interval_time_simulation.get_max_model_time()
+    }
+    pub fn get_current_time(&self) -> i32 {
+        self.interval_time_simulation.get_current_time()
+    }
+    pub fn set_time_step(&mut self, time_step: f64) -> () {
+        self.interval_time_simulation.set_time_step(time_step);
+    }
+    pub fn get_state(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.interval_time_simulation.get_state()).unwrap()
+    }
+    pub fn next(&mut self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.interval_time_simulation.next()).unwrap()
+    }
+}
+
+#[wasm_bindgen]
+pub fn analyze_stability(
+    concepts_map: JsValue,
+    connections_map: JsValue,
+    dynamic_model: JsValue,
+    time_step: JsValue,
+    activation: JsValue,
+) -> JsValue {
+    let time_step: Option<f64> = serde_wasm_bindgen::from_value(time_step).unwrap();
+    let weight_matrix = WeightMatrix::new(
+        &serde_wasm_bindgen::from_value(concepts_map).unwrap(),
+        &serde_wasm_bindgen::from_value(connections_map).unwrap(),
+        &HashMap::new(),
+        &HashMap::new(),
+        &serde_wasm_bindgen::from_value(dynamic_model).unwrap(),
+        time_step.unwrap_or(DEFAULT_TIME_STEP),
+    );
+    let activation = serde_wasm_bindgen::from_value(activation).unwrap();
+    serde_wasm_bindgen::to_value(&weight_matrix.analyze(&activation)).unwrap()
+}
+
+#[wasm_bindgen]
+pub fn solve_inverse(
+    concepts_map: JsValue,
+    connections_map: JsValue,
+    dynamic_model: JsValue,
+    time: JsValue,
+) -> JsValue {
+    let inverse_solver = InverseSolver::new(
+        serde_wasm_bindgen::from_value(concepts_map).unwrap(),
+        serde_wasm_bindgen::from_value(connections_map).unwrap(),
+        serde_wasm_bindgen::from_value(dynamic_model).unwrap(),
+        serde_wasm_bindgen::from_value(time).unwrap(),
+    );
+    serde_wasm_bindgen::to_value(&inverse_solver.solve()).unwrap()
+}